@@ -0,0 +1,294 @@
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use std::convert::TryInto;
+use std::path::PathBuf;
+
+use triblespace::prelude::blobschemas::LongString;
+use triblespace::prelude::blobschemas::SimpleArchive;
+use triblespace::prelude::BlobStore;
+use triblespace::prelude::BlobStoreGet;
+use triblespace::prelude::BranchStore;
+use triblespace::prelude::View;
+use triblespace_core::id::Id;
+use triblespace_core::repo::bundle::{self, Bundle, ImportOutcome};
+use triblespace_core::repo::pile::Pile;
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::schemas::hash::{Blake3, Handle, Hash};
+use triblespace_core::value::Value;
+
+type CommitHandle = Value<Handle<Blake3, SimpleArchive>>;
+
+#[derive(Parser)]
+pub enum Command {
+    /// Write branches and the blobs they reach into a bundle file.
+    ///
+    /// With `--base`, commits already reachable from the base are left out
+    /// and the importing pile must hold them (like `git bundle A..B`).
+    Create {
+        /// Path to the pile file to read
+        pile: PathBuf,
+        /// Path of the bundle file to write
+        out: PathBuf,
+        /// Branch to include (name or hex ID). Repeatable. Defaults to every
+        /// non-tracking branch.
+        #[arg(long)]
+        branch: Vec<String>,
+        /// Commit the receiver already has: a commit handle ("blake3:HEX")
+        /// or a branch name/hex ID standing for that branch's head. Repeatable.
+        #[arg(long)]
+        base: Vec<String>,
+    },
+    /// Check a bundle's hashes and signatures and list what it carries.
+    Verify {
+        /// Path to the bundle file
+        bundle: PathBuf,
+    },
+    /// Import a bundle into a pile.
+    ///
+    /// Missing branches are created and branches the bundle is ahead of are
+    /// fast-forwarded. Diverged branches are left alone; their bundled heads
+    /// land in tracking branches instead, ready for `trible pile merge`.
+    Import {
+        /// Path to the pile file to modify
+        pile: PathBuf,
+        /// Path to the bundle file
+        bundle: PathBuf,
+    },
+}
+
+pub fn run(cmd: Command) -> Result<()> {
+    match cmd {
+        Command::Create {
+            pile,
+            out,
+            branch,
+            base,
+        } => create(pile, out, branch, base),
+        Command::Verify { bundle } => verify(bundle),
+        Command::Import { pile, bundle } => import(pile, bundle),
+    }
+}
+
+fn commit_hex(handle: CommitHandle) -> String {
+    let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+    hash.from_value()
+}
+
+fn parse_branch_id_hex(raw: &str) -> Option<Id> {
+    let bytes = hex::decode(raw.trim()).ok()?;
+    let arr: [u8; 16] = bytes.as_slice().try_into().ok()?;
+    Id::new(arr)
+}
+
+fn parse_commit_handle(raw: &str) -> Option<CommitHandle> {
+    let hex = raw
+        .trim()
+        .strip_prefix("blake3:")
+        .unwrap_or(raw.trim());
+    let bytes = hex::decode(hex).ok()?;
+    let arr: [u8; 32] = bytes.as_slice().try_into().ok()?;
+    Some(Value::new(arr))
+}
+
+/// Reads the branch name and head commit recorded in a branch's metadata.
+fn read_branch(pile: &mut Pile<Blake3>, id: Id) -> Result<(Option<String>, Option<CommitHandle>)> {
+    let reader = pile.reader().map_err(|e| anyhow!("pile reader: {e:?}"))?;
+    let Some(meta_handle) = pile.head(id).map_err(|e| anyhow!("branch head: {e:?}"))? else {
+        bail!("branch not found: {id:X}");
+    };
+    let meta: TribleSet = reader
+        .get::<TribleSet, SimpleArchive>(meta_handle)
+        .map_err(|e| anyhow!("branch metadata: {e:?}"))?;
+
+    let name_attr = triblespace_core::metadata::name.id();
+    let head_attr = triblespace_core::repo::head.id();
+    let mut name = None;
+    let mut head = None;
+    for t in meta.iter() {
+        if t.a() == &name_attr {
+            let handle: Value<Handle<Blake3, LongString>> = *t.v();
+            let view: View<str> = reader
+                .get(handle)
+                .map_err(|e| anyhow!("branch name blob: {e:?}"))?;
+            name = Some(view.to_string());
+        } else if t.a() == &head_attr {
+            head = Some(*t.v::<Handle<Blake3, SimpleArchive>>());
+        }
+    }
+    Ok((name, head))
+}
+
+/// Resolves a branch given by hex ID or by (unique) name.
+fn resolve_branch(pile: &mut Pile<Blake3>, branches: &[Id], raw: &str) -> Result<Id> {
+    if let Some(id) = parse_branch_id_hex(raw) {
+        if branches.contains(&id) {
+            return Ok(id);
+        }
+    }
+    let mut matches = Vec::new();
+    for &id in branches {
+        if read_branch(pile, id)?.0.as_deref() == Some(raw) {
+            matches.push(id);
+        }
+    }
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => bail!("no branch named or identified by {raw}"),
+        _ => bail!("branch name {raw} is ambiguous; use the hex ID"),
+    }
+}
+
+fn create(pile_path: PathBuf, out: PathBuf, branch: Vec<String>, base: Vec<String>) -> Result<()> {
+    let mut pile: Pile<Blake3> = Pile::open(&pile_path)?;
+    let res = (|| -> Result<()> {
+        pile.restore().map_err(|e| anyhow!("restore pile: {e:?}"))?;
+        let all: Vec<Id> = pile
+            .branches()
+            .map_err(|e| anyhow!("branches: {e:?}"))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("branch iter: {e:?}"))?;
+
+        let mut selected = Vec::new();
+        if branch.is_empty() {
+            for &id in &all {
+                if !triblespace_net::tracking::is_tracking_branch(&mut pile, id) {
+                    selected.push(id);
+                }
+            }
+        } else {
+            for raw in &branch {
+                selected.push(resolve_branch(&mut pile, &all, raw)?);
+            }
+        }
+        if selected.is_empty() {
+            bail!("no branches to bundle");
+        }
+
+        let mut bases = Vec::new();
+        for raw in &base {
+            let handle = match parse_commit_handle(raw) {
+                Some(handle) => handle,
+                None => {
+                    let id = resolve_branch(&mut pile, &all, raw)?;
+                    match read_branch(&mut pile, id)?.1 {
+                        Some(head) => head,
+                        None => continue,
+                    }
+                }
+            };
+            bases.push(handle);
+        }
+
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::io::BufWriter::new(std::fs::File::create(&out)?);
+        let manifest = bundle::create_bundle(&mut pile, &selected, &bases, file)
+            .map_err(|e| anyhow!("create bundle: {e:?}"))?;
+
+        let written = Bundle::open(&out)?;
+        println!(
+            "Wrote {}: {} branch(es), {} base(s), {} blob(s), {} bytes",
+            out.display(),
+            manifest.branches.len(),
+            manifest.bases.len(),
+            written.reader().len(),
+            std::fs::metadata(&out)?.len()
+        );
+        Ok(())
+    })();
+    let close_res = pile.close().map_err(|e| anyhow!("{e:?}"));
+    res.and(close_res)
+}
+
+fn verify(path: PathBuf) -> Result<()> {
+    let bundle = Bundle::open(&path)?;
+    let verification = bundle.verify()?;
+    println!(
+        "{}: {} blob(s), {} branch(es)",
+        path.display(),
+        bundle.reader().len(),
+        verification.branches.len()
+    );
+    for branch in &verification.branches {
+        let name = branch.name.as_deref().unwrap_or("<unnamed>");
+        let head = branch
+            .head
+            .map(|h| format!("blake3:{}", commit_hex(h)))
+            .unwrap_or_else(|| "-".to_string());
+        let signer = branch
+            .signed_by
+            .map(|k| hex::encode(k.as_bytes()))
+            .unwrap_or_else(|| "unsigned".to_string());
+        println!(
+            "- {name} ({:X}) head={head} commits={} signed_by={signer}",
+            branch.id, branch.commits
+        );
+    }
+    if verification.prerequisites.is_empty() {
+        println!("No prerequisites.");
+    } else {
+        println!("Requires {} blob(s):", verification.prerequisites.len());
+        for handle in &verification.prerequisites {
+            println!("- blake3:{}", hex::encode(handle.raw));
+        }
+    }
+    Ok(())
+}
+
+fn import(pile_path: PathBuf, bundle_path: PathBuf) -> Result<()> {
+    let bundle = Bundle::open(&bundle_path)?;
+    let mut pile: Pile<Blake3> = Pile::open(&pile_path)?;
+    let res = (|| -> Result<()> {
+        pile.restore().map_err(|e| anyhow!("restore pile: {e:?}"))?;
+        let imported = bundle::import_bundle(&mut pile, &bundle).map_err(|e| match e {
+            bundle::ImportBundleError::Bundle(e) => anyhow!("invalid bundle: {e}"),
+            bundle::ImportBundleError::MissingPrerequisite(h) => anyhow!(
+                "pile is missing prerequisite blake3:{}; import the bundle it was cut against first",
+                hex::encode(h.raw)
+            ),
+            e => anyhow!("import bundle: {e:?}"),
+        })?;
+
+        for entry in imported {
+            let branch = &entry.branch;
+            let name = branch
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{:X}", branch.id));
+            let label = format!("{name} ({:X})", branch.id);
+            match entry.outcome {
+                ImportOutcome::Created => println!("created {label}"),
+                ImportOutcome::UpToDate => println!("up to date {label}"),
+                ImportOutcome::FastForwarded { from } => println!(
+                    "fast-forwarded {label} from {}",
+                    from.map(|h| format!("blake3:{}", commit_hex(h)))
+                        .unwrap_or_else(|| "empty".to_string())
+                ),
+                ImportOutcome::Diverged { local } => {
+                    let Some(signer) = branch.signed_by else {
+                        println!(
+                            "diverged {label}: local head blake3:{} kept; bundled head is unsigned, not tracked",
+                            commit_hex(local)
+                        );
+                        continue;
+                    };
+                    let tracking = triblespace_net::tracking::ensure_tracking_branch(
+                        &mut pile,
+                        branch.id,
+                        &branch.metadata.raw,
+                        &name,
+                        signer.as_bytes(),
+                    )
+                    .ok_or_else(|| anyhow!("failed to record tracking branch for {label}"))?;
+                    println!(
+                        "diverged {label}: bundled head tracked as {tracking:X}; merge it to reconcile"
+                    );
+                }
+            }
+        }
+        Ok(())
+    })();
+    let close_res = pile.close().map_err(|e| anyhow!("{e:?}"));
+    res.and(close_res)
+}
@@ -0,0 +1,121 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use triblespace_core::id::Id;
+use triblespace_core::import::json_stream::{JsonStreamImporter, DEFAULT_COMMIT_EVERY};
+use triblespace_core::repo::pile::Pile;
+use triblespace_core::repo::Repository;
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::schemas::hash::Blake3;
+
+use super::signing::load_signing_key;
+
+#[derive(Parser)]
+pub enum Command {
+    /// Stream JSON into a branch, committing and pushing every chunk.
+    ///
+    /// The input is NDJSON, concatenated JSON, or one top-level array
+    /// whose elements are imported as separate records.
+    Json {
+        /// Path to the pile file to modify
+        pile: PathBuf,
+        /// Branch identifier (hex encoded)
+        branch: String,
+        /// JSON file to import, or `-` for standard input
+        input: PathBuf,
+        /// Keep field order and primitive records with the lossless tree importer
+        #[arg(long)]
+        lossless: bool,
+        /// Number of records per commit
+        #[arg(long, default_value_t = DEFAULT_COMMIT_EVERY)]
+        commit_every: usize,
+        /// Optional signing key path. The file should contain a 64-char hex seed.
+        #[arg(long)]
+        signing_key: Option<PathBuf>,
+    },
+}
+
+pub fn run(cmd: Command) -> Result<()> {
+    match cmd {
+        Command::Json {
+            pile,
+            branch,
+            input,
+            lossless,
+            commit_every,
+            signing_key,
+        } => {
+            let reader: Box<dyn Read> = if input.as_os_str() == "-" {
+                Box::new(std::io::stdin().lock())
+            } else {
+                Box::new(
+                    File::open(&input)
+                        .map_err(|e| anyhow!("open {}: {e}", input.display()))?,
+                )
+            };
+            let mut stream =
+                JsonStreamImporter::<_, Blake3>::new(reader, None).with_commit_every(commit_every);
+            if lossless {
+                stream = stream.lossless();
+            }
+            import_json(pile, branch, stream, signing_key)
+        }
+    }
+}
+
+fn import_json<R: Read>(
+    pile_path: PathBuf,
+    branch: String,
+    mut stream: JsonStreamImporter<R, Blake3>,
+    signing_key: Option<PathBuf>,
+) -> Result<()> {
+    let key = load_signing_key(&signing_key)?;
+    let pile: Pile<Blake3> = Pile::open(&pile_path)?;
+    let mut repo = Repository::new(pile, key, TribleSet::new())?;
+
+    let res = (|| -> Result<(), anyhow::Error> {
+        repo.storage_mut()
+            .refresh()
+            .map_err(|e| anyhow!("refresh pile: {e:?}"))?;
+        let branch_id = parse_branch_id_hex(&branch)?;
+        let mut ws = repo
+            .pull(branch_id)
+            .map_err(|e| anyhow!("pull branch: {e:?}"))?;
+
+        // Pushing every chunk uploads its blobs and empties the workspace,
+        // so memory stays bounded by one chunk.
+        while let Some(progress) = stream.import_chunk(&mut ws)? {
+            repo.push(&mut ws)
+                .map_err(|e| anyhow!("push failed: {e:?}"))?;
+            eprintln!(
+                "imported {} records ({} bytes) in {} commits",
+                progress.records, progress.bytes, progress.commits
+            );
+        }
+        let progress = stream.progress();
+        println!(
+            "imported {} records in {} commits to branch {branch_id:X}",
+            progress.records, progress.commits
+        );
+        Ok(())
+    })();
+
+    let close_res = repo
+        .into_storage()
+        .close()
+        .map_err(|e| anyhow!("{e:?}"));
+    res.and(close_res)
+}
+
+fn parse_branch_id_hex(raw: &str) -> Result<Id> {
+    let bytes = hex::decode(raw.trim())?;
+    let arr: [u8; 16] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("branch id must be 16 bytes (32 hex chars)"))?;
+    Id::new(arr).ok_or_else(|| anyhow!("branch id cannot be nil"))
+}
@@ -5,10 +5,13 @@ use std::path::PathBuf;
 
 pub mod blob;
 pub mod branch;
+mod bundle;
 mod diagnose;
+mod import;
 mod merge;
 mod migrate;
 pub mod net;
+mod parquet;
 mod signing;
 mod squash;
 
@@ -24,6 +27,11 @@ pub enum PileCommand {
         #[command(subcommand)]
         cmd: blob::Command,
     },
+    /// Offline transfer of branches via self-contained bundle files.
+    Bundle {
+        #[command(subcommand)]
+        cmd: bundle::Command,
+    },
     /// Merge source branch heads into a target branch.
     Merge {
         /// Path to the pile file to modify
@@ -50,6 +58,11 @@ pub enum PileCommand {
         #[command(subcommand)]
         cmd: diagnose::Command,
     },
+    /// Bulk ingest of external data formats into a branch.
+    Import {
+        #[command(subcommand)]
+        cmd: import::Command,
+    },
     /// Migrate legacy pile metadata to the current schemas.
     Migrate {
         /// Path to the pile file to modify
@@ -57,6 +70,11 @@ pub enum PileCommand {
         #[command(subcommand)]
         cmd: migrate::Command,
     },
+    /// Columnar export of branch contents as Parquet tables.
+    Parquet {
+        #[command(subcommand)]
+        cmd: parquet::Command,
+    },
     /// Distributed pile sync over iroh (p2p QUIC connections).
     Net {
         #[command(subcommand)]
@@ -89,6 +107,7 @@ pub fn run(cmd: PileCommand) -> Result<()> {
     match cmd {
         PileCommand::Branch { cmd } => branch::run(cmd),
         PileCommand::Blob { cmd } => blob::run(cmd),
+        PileCommand::Bundle { cmd } => bundle::run(cmd),
         PileCommand::Merge {
             pile,
             target,
@@ -116,7 +135,9 @@ pub fn run(cmd: PileCommand) -> Result<()> {
             Ok(())
         }
         PileCommand::Net { cmd } => net::run(cmd),
+        PileCommand::Parquet { cmd } => parquet::run(cmd),
         PileCommand::Diagnose { cmd } => diagnose::run(cmd),
+        PileCommand::Import { cmd } => import::run(cmd),
         PileCommand::Migrate { pile, cmd } => migrate::run(pile, cmd),
         PileCommand::Squash {
             source,
@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use triblespace::prelude::BlobStore;
+use triblespace_core::export::arrow::{entities_to_record_batch, write_parquet};
+use triblespace_core::id::Id;
+use triblespace_core::repo::pile::Pile;
+use triblespace_core::repo::Repository;
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::schemas::hash::Blake3;
+
+use super::signing::load_signing_key;
+
+#[derive(Parser)]
+pub enum Command {
+    /// Write the entities of a branch as a Parquet table.
+    ///
+    /// Each entity becomes a row with an `id` column and one column per
+    /// described attribute it uses, typed by the attribute's value schema.
+    /// Attributes in schemas without a columnar type are left out.
+    Export {
+        /// Path to the pile file to read
+        pile: PathBuf,
+        /// Branch identifier (hex encoded)
+        branch: String,
+        /// Path of the Parquet file to write
+        output: PathBuf,
+    },
+}
+
+pub fn run(cmd: Command) -> Result<()> {
+    match cmd {
+        Command::Export {
+            pile,
+            branch,
+            output,
+        } => export(pile, branch, output),
+    }
+}
+
+fn export(pile_path: PathBuf, branch: String, output: PathBuf) -> Result<()> {
+    let key = load_signing_key(&None)?;
+    let pile: Pile<Blake3> = Pile::open(&pile_path)?;
+    let mut repo = Repository::new(pile, key, TribleSet::new())?;
+
+    let res = (|| -> Result<(), anyhow::Error> {
+        repo.storage_mut()
+            .refresh()
+            .map_err(|e| anyhow!("refresh pile: {e:?}"))?;
+        let branch_id = parse_branch_id_hex(&branch)?;
+        let mut ws = repo
+            .pull(branch_id)
+            .map_err(|e| anyhow!("pull branch: {e:?}"))?;
+        let (data, metadata) = ws
+            .checkout_with_metadata(..)
+            .map_err(|e| anyhow!("checkout: {e:?}"))?;
+
+        let mut set = data.clone();
+        set += metadata;
+        let reader = repo
+            .storage_mut()
+            .reader()
+            .map_err(|e| anyhow!("pile reader error: {e:?}"))?;
+        let batch = entities_to_record_batch(&set, data.iter().map(|t| *t.e()), &reader)
+            .map_err(|e| anyhow!("export: {e}"))?;
+
+        let file = File::create(&output)
+            .map_err(|e| anyhow!("create {}: {e}", output.display()))?;
+        write_parquet(&batch, BufWriter::new(file)).map_err(|e| anyhow!("write parquet: {e}"))?;
+        println!(
+            "wrote {} rows and {} columns to {}",
+            batch.num_rows(),
+            batch.num_columns(),
+            output.display()
+        );
+        Ok(())
+    })();
+
+    let close_res = repo
+        .into_storage()
+        .close()
+        .map_err(|e| anyhow!("{e:?}"));
+    res.and(close_res)
+}
+
+fn parse_branch_id_hex(raw: &str) -> Result<Id> {
+    let bytes = hex::decode(raw.trim())?;
+    let arr: [u8; 16] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("branch id must be 16 bytes (32 hex chars)"))?;
+    Id::new(arr).ok_or_else(|| anyhow!("branch id cannot be nil"))
+}
@@ -1,6 +1,7 @@
 //! `trible team` — capability-based team membership management.
 //!
-//! Issues, lists, and revokes capabilities for a triblespace team.
+//! Issues, renews, inspects, lists, and revokes capabilities for a
+//! triblespace team.
 //! Capabilities are signed delegations chained from a single team root
 //! keypair; possessing a leaf capability handle authorises a peer to
 //! connect to the team's mesh under the cap's scope.
@@ -42,6 +43,9 @@ pub enum Command {
         /// alongside the pile, generated if missing).
         #[arg(long)]
         key: Option<PathBuf>,
+        /// How long the founder's cap is valid (e.g. "30 days", "12 h").
+        #[arg(long, default_value = "30 days")]
+        valid_for: String,
     },
     /// Issue a capability for a teammate, delegating from the running
     /// node's own cap.
@@ -68,6 +72,54 @@ pub enum Command {
         /// Scope to grant. Must be a subset of the issuer's own scope.
         #[arg(long, value_enum, default_value = "read")]
         scope: ScopeArg,
+        /// How long the cap is valid (e.g. "30 days", "12 h"). The cap
+        /// is only honoured while the issuer's own cap is, too.
+        #[arg(long, default_value = "30 days")]
+        valid_for: String,
+    },
+    /// Re-issue a capability with a fresh validity window, keeping its
+    /// subject and scope. Must be run by the cap's original issuer: the
+    /// team root (via `--team-root-secret`) for the founder's cap, the
+    /// delegating node (via `--key` and its own `--issuer-cap`)
+    /// otherwise.
+    Renew {
+        /// The cap to renew (hex of its sig handle).
+        cap: String,
+        /// Path to the local pile file.
+        #[arg(long)]
+        pile: PathBuf,
+        /// Team root pubkey (hex). Used to verify the issuer's own cap
+        /// before signing the renewal.
+        #[arg(long, env = "TRIBLE_TEAM_ROOT")]
+        team_root: Option<String>,
+        /// The issuer's own current cap (hex of its sig handle). The
+        /// renewed cap chains off it. Not needed for root-issued caps.
+        #[arg(long, env = "TRIBLE_TEAM_CAP")]
+        issuer_cap: Option<String>,
+        /// Issuer's signing key path (defaults to the conventional
+        /// location next to the pile).
+        #[arg(long)]
+        key: Option<PathBuf>,
+        /// Team root secret key (hex), for renewing caps the team root
+        /// issued directly.
+        #[arg(long, env = "TRIBLE_TEAM_ROOT_SECRET")]
+        team_root_secret: Option<String>,
+        /// How long the renewed cap is valid (e.g. "30 days", "12 h").
+        #[arg(long, default_value = "30 days")]
+        valid_for: String,
+    },
+    /// Show a capability's subject, issuer, scope and validity window.
+    /// With a team root, also verifies the chain and reports how long it
+    /// is honoured for.
+    Inspect {
+        /// The cap to inspect (hex of its sig handle).
+        cap: String,
+        /// Path to the local pile file.
+        #[arg(long)]
+        pile: PathBuf,
+        /// Team root pubkey (hex) to verify the chain against.
+        #[arg(long, env = "TRIBLE_TEAM_ROOT")]
+        team_root: Option<String>,
     },
     /// Issue a revocation for a pubkey. Must be signed by the team
     /// root keypair (loaded from `--team-root-secret` /
@@ -113,7 +165,7 @@ impl ScopeArg {
 
 pub fn run(cmd: Command) -> Result<()> {
     match cmd {
-        Command::Create { pile, key } => run_create(pile, key),
+        Command::Create { pile, key, valid_for } => run_create(pile, key, valid_for),
         Command::Invite {
             pile,
             team_root,
@@ -121,7 +173,18 @@ pub fn run(cmd: Command) -> Result<()> {
             key,
             invitee,
             scope,
-        } => run_invite(pile, team_root, cap, key, invitee, scope),
+            valid_for,
+        } => run_invite(pile, team_root, cap, key, invitee, scope, valid_for),
+        Command::Renew {
+            cap,
+            pile,
+            team_root,
+            issuer_cap,
+            key,
+            team_root_secret,
+            valid_for,
+        } => run_renew(pile, cap, team_root, issuer_cap, key, team_root_secret, valid_for),
+        Command::Inspect { cap, pile, team_root } => run_inspect(pile, cap, team_root),
         Command::Revoke {
             pile,
             team_root_secret,
@@ -154,6 +217,16 @@ fn load_or_generate_signing_key(
     triblespace_net::identity::load_or_create_key(&path, &parent)
 }
 
+/// Like [`load_or_generate_signing_key`], but a missing key file is an
+/// error — for commands that must sign with a key that already exists.
+fn load_existing_signing_key(path: Option<PathBuf>, pile_path: &std::path::Path) -> Result<SigningKey> {
+    let parent = pile_path
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| std::path::PathBuf::from("."));
+    triblespace_net::identity::load_key(&path, &parent)
+}
+
 fn fresh_signing_key() -> Result<SigningKey> {
     let mut seed = [0u8; 32];
     getrandom::fill(&mut seed).map_err(|e| anyhow!("generate key: {e}"))?;
@@ -187,11 +260,14 @@ fn parse_handle_hex(s: &str) -> Result<Value<Handle<Blake3, SimpleArchive>>> {
     Ok(Value::new(raw))
 }
 
-fn now_plus_30_days() -> Value<triblespace_core::value::schemas::time::NsTAIInterval> {
-    use triblespace_core::value::TryToValue;
-    let now = hifitime::Epoch::now().expect("system time");
-    let later = now + hifitime::Duration::from_seconds(30.0 * 86400.0);
-    (now, later).try_to_value().expect("valid interval")
+fn parse_lifetime(s: &str) -> Result<hifitime::Duration> {
+    use std::str::FromStr;
+    let lifetime = hifitime::Duration::from_str(s.trim())
+        .map_err(|e| anyhow!("parse duration '{s}': {e}"))?;
+    if lifetime <= hifitime::Duration::ZERO {
+        return Err(anyhow!("validity must be positive, got '{s}'"));
+    }
+    Ok(lifetime)
 }
 
 fn store_blob(pile: &mut PileBlake3, blob: Blob<SimpleArchive>) -> Result<()> {
@@ -236,6 +312,58 @@ fn fetch_cap_blob_pair(
     Ok((cap_blob, sig_blob))
 }
 
+fn verify_cap_in_pile(
+    pile: &mut PileBlake3,
+    team_root: VerifyingKey,
+    sig_handle: Value<Handle<Blake3, SimpleArchive>>,
+    subject: VerifyingKey,
+    revoked: &std::collections::HashSet<VerifyingKey>,
+) -> Result<capability::VerifiedCapability> {
+    use triblespace_core::repo::BlobStore;
+    use triblespace_core::repo::BlobStoreGet;
+
+    let reader = pile
+        .reader()
+        .map_err(|e| anyhow!("pile reader: {e:?}"))?;
+    capability::verify_chain(team_root, sig_handle, subject, revoked, |h| {
+        reader.get::<Blob<SimpleArchive>, SimpleArchive>(h).ok()
+    })
+    .map_err(|e| anyhow!("{e}"))
+}
+
+/// Pubkeys revoked by the team root, from the revocation blobs in the
+/// pile.
+fn pile_revocations(
+    pile: &mut PileBlake3,
+    team_root: VerifyingKey,
+) -> Result<std::collections::HashSet<VerifyingKey>> {
+    use triblespace_core::repo::BlobStore;
+    use triblespace_core::repo::BlobStoreGet;
+    use triblespace_core::repo::BlobStoreList;
+
+    let reader = pile
+        .reader()
+        .map_err(|e| anyhow!("pile reader: {e:?}"))?;
+    let blobs = reader.blobs().filter_map(|h| {
+        let handle: Value<Handle<Blake3, SimpleArchive>> = Value::new(h.ok()?.raw);
+        reader.get::<Blob<SimpleArchive>, SimpleArchive>(handle).ok()
+    });
+    let authorised = std::collections::HashSet::from([team_root]);
+    Ok(capability::build_revocation_set(
+        &authorised,
+        capability::extract_revocation_pairs(blobs),
+    ))
+}
+
+fn format_remaining(until: hifitime::Epoch) -> String {
+    let now = hifitime::Epoch::now().expect("system time");
+    if until >= now {
+        format!("{} left", (until - now).round(hifitime::Duration::from_seconds(1.0)))
+    } else {
+        format!("expired {} ago", (now - until).round(hifitime::Duration::from_seconds(1.0)))
+    }
+}
+
 fn print_warning_box(lines: &[&str]) {
     let max = lines.iter().map(|l| l.len()).max().unwrap_or(0);
     let bar = "═".repeat(max + 2);
@@ -248,7 +376,8 @@ fn print_warning_box(lines: &[&str]) {
 
 // ── Subcommands ─────────────────────────────────────────────────────
 
-fn run_create(pile_path: PathBuf, key: Option<PathBuf>) -> Result<()> {
+fn run_create(pile_path: PathBuf, key: Option<PathBuf>, valid_for: String) -> Result<()> {
+    let lifetime = parse_lifetime(&valid_for)?;
     let mut pile = open_pile(&pile_path)?;
     let founder_key = load_or_generate_signing_key(key, &pile_path)?;
 
@@ -272,7 +401,7 @@ fn run_create(pile_path: PathBuf, key: Option<PathBuf>) -> Result<()> {
         None,
         scope_root,
         scope_facts,
-        now_plus_30_days(),
+        capability::valid_for(lifetime),
     )
     .map_err(|e| anyhow!("build founder cap: {e:?}"))?;
 
@@ -313,7 +442,9 @@ fn run_invite(
     key: Option<PathBuf>,
     invitee_hex: String,
     scope: ScopeArg,
+    valid_for: String,
 ) -> Result<()> {
+    let lifetime = parse_lifetime(&valid_for)?;
     let mut pile = open_pile(&pile_path)?;
     let issuer_key = load_or_generate_signing_key(key, &pile_path)?;
     let team_root = parse_pubkey_hex(&team_root_hex)?;
@@ -366,7 +497,7 @@ fn run_invite(
         Some((parent_cap_blob, parent_sig_blob)),
         scope_root,
         scope_facts,
-        now_plus_30_days(),
+        capability::valid_for(lifetime),
     )
     .map_err(|e| anyhow!("build invitee cap: {e:?}"))?;
 
@@ -387,6 +518,155 @@ fn run_invite(
     Ok(())
 }
 
+fn run_renew(
+    pile_path: PathBuf,
+    cap_hex: String,
+    team_root_hex: Option<String>,
+    issuer_cap_hex: Option<String>,
+    key: Option<PathBuf>,
+    team_root_secret_hex: Option<String>,
+    valid_for: String,
+) -> Result<()> {
+    let lifetime = parse_lifetime(&valid_for)?;
+    let mut pile = open_pile(&pile_path)?;
+    let (cap_blob, _) = fetch_cap_blob_pair(&mut pile, parse_handle_hex(&cap_hex)?)?;
+    let info = capability::inspect_capability(&cap_blob)
+        .map_err(|e| anyhow!("parse cap: {e:?}"))?;
+
+    // Root-issued caps are renewed by the team root itself; everything
+    // else by the delegating node, chaining off its own current cap.
+    // An explicit team root secret that didn't issue the cap is a
+    // mistake, not a hint to fall back to the node key.
+    let team_root_key = team_root_secret_hex
+        .as_deref()
+        .map(parse_secret_hex)
+        .transpose()?;
+    if let Some(root) = &team_root_key {
+        if root.verifying_key() != info.issuer {
+            let _ = pile.close();
+            return Err(anyhow!(
+                "cap was issued by {}, not by the given team root; renew it with the issuer's key",
+                hex::encode(info.issuer.to_bytes()),
+            ));
+        }
+    }
+    let (issuer_key, parent) = match team_root_key {
+        Some(root) => (root, None),
+        None => {
+            // Never create key material here: a freshly generated key
+            // could not have issued the cap anyway.
+            let issuer_key = match load_existing_signing_key(key, &pile_path) {
+                Ok(issuer_key) => issuer_key,
+                Err(err) => {
+                    let _ = pile.close();
+                    return Err(err);
+                }
+            };
+            if issuer_key.verifying_key() != info.issuer {
+                let _ = pile.close();
+                return Err(anyhow!(
+                    "cap was issued by {}; renew it with that key (or the team root secret)",
+                    hex::encode(info.issuer.to_bytes()),
+                ));
+            }
+            let team_root = parse_pubkey_hex(
+                team_root_hex
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("--team-root is required to renew a delegated cap"))?,
+            )?;
+            let issuer_cap = parse_handle_hex(
+                issuer_cap_hex
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("--issuer-cap is required to renew a delegated cap"))?,
+            )?;
+            // Same rule as `invite`: don't sign off an invalid/expired cap.
+            let revoked = pile_revocations(&mut pile, team_root)?;
+            verify_cap_in_pile(&mut pile, team_root, issuer_cap, issuer_key.verifying_key(), &revoked)
+                .map_err(|e| anyhow!("issuer's cap does not verify: {e}"))?;
+            let parent = fetch_cap_blob_pair(&mut pile, issuer_cap)?;
+            (issuer_key, Some(parent))
+        }
+    };
+
+    let (renewed_cap, renewed_sig) = capability::renew_capability(
+        &issuer_key,
+        &cap_blob,
+        parent,
+        capability::valid_for(lifetime),
+    )
+    .map_err(|e| anyhow!("renew cap: {e:?}"))?;
+    let not_after = capability::inspect_capability(&renewed_cap)
+        .map_err(|e| anyhow!("parse renewed cap: {e:?}"))?
+        .not_after;
+    let sig_handle: Value<Handle<Blake3, SimpleArchive>> = renewed_sig.get_handle();
+
+    store_blob(&mut pile, renewed_cap)?;
+    store_blob(&mut pile, renewed_sig)?;
+    let _ = pile.close();
+
+    println!("renewed cap (sig): {}", hex::encode(sig_handle.raw));
+    println!("valid until:       {not_after}");
+    println!();
+    println!("Share with {}:", hex::encode(info.subject.to_bytes()));
+    println!("  TRIBLE_TEAM_CAP={}", hex::encode(sig_handle.raw));
+
+    Ok(())
+}
+
+fn run_inspect(pile_path: PathBuf, cap_hex: String, team_root_hex: Option<String>) -> Result<()> {
+    let mut pile = open_pile(&pile_path)?;
+    let sig_handle = parse_handle_hex(&cap_hex)?;
+    let (cap_blob, _) = fetch_cap_blob_pair(&mut pile, sig_handle)?;
+    let info = capability::inspect_capability(&cap_blob)
+        .map_err(|e| anyhow!("parse cap: {e:?}"))?;
+    let cap_handle: Value<Handle<Blake3, SimpleArchive>> = cap_blob.get_handle();
+
+    let mut permissions: Vec<&str> = info
+        .permissions
+        .iter()
+        .map(|perm| match *perm {
+            p if p == capability::PERM_READ => "read",
+            p if p == capability::PERM_WRITE => "write",
+            p if p == capability::PERM_ADMIN => "admin",
+            _ => "unknown",
+        })
+        .collect();
+    permissions.sort_unstable();
+    let branches = match &info.branches {
+        None => "all".to_string(),
+        Some(set) => set.iter().map(|b| format!("{b:X}")).collect::<Vec<_>>().join(", "),
+    };
+
+    println!("cap (sig):   {}", hex::encode(sig_handle.raw));
+    println!("cap blob:    {}", hex::encode(cap_handle.raw));
+    println!("subject:     {}", hex::encode(info.subject.to_bytes()));
+    println!("issuer:      {}", hex::encode(info.issuer.to_bytes()));
+    match info.parent {
+        Some(parent) => println!("parent:      {}", hex::encode(parent.raw)),
+        None => println!("parent:      none (issued by the team root)"),
+    }
+    println!("permissions: {}", permissions.join(", "));
+    println!("branches:    {branches}");
+    println!("not before:  {}", info.not_before);
+    println!("not after:   {} ({})", info.not_after, format_remaining(info.not_after));
+
+    if let Some(team_root_hex) = team_root_hex {
+        let team_root = parse_pubkey_hex(&team_root_hex)?;
+        let revoked = pile_revocations(&mut pile, team_root)?;
+        match verify_cap_in_pile(&mut pile, team_root, sig_handle, info.subject, &revoked) {
+            Ok(verified) => println!(
+                "chain:       valid until {} ({})",
+                verified.not_after,
+                format_remaining(verified.not_after),
+            ),
+            Err(e) => println!("chain:       rejected: {e}"),
+        }
+    }
+    let _ = pile.close();
+
+    Ok(())
+}
+
 fn run_revoke(
     pile_path: PathBuf,
     team_root_secret_hex: String,
@@ -0,0 +1,169 @@
+use assert_cmd::Command;
+use ed25519_dalek::SigningKey;
+use std::path::Path;
+use tempfile::tempdir;
+use triblespace::prelude::*;
+use triblespace_core::id::Id;
+use triblespace_core::metadata;
+use triblespace_core::repo::pile::Pile;
+use triblespace_core::repo::{CommitHandle, Repository};
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::schemas::hash::Blake3;
+use triblespace_core::value::schemas::hash::Handle;
+use triblespace_core::value::Value;
+
+fn random_signing_key() -> SigningKey {
+    let mut seed = [0u8; 32];
+    getrandom::fill(&mut seed).expect("getrandom");
+    SigningKey::from_bytes(&seed)
+}
+
+fn open_repo(path: &Path) -> Repository<Pile<Blake3>> {
+    let mut pile: Pile<Blake3> = Pile::open(path).unwrap();
+    pile.restore().unwrap();
+    Repository::new(pile, random_signing_key(), TribleSet::new()).unwrap()
+}
+
+/// Appends a commit to `branch` and returns the new head.
+fn commit(path: &Path, branch: Id, label: &str) -> CommitHandle {
+    let mut repo = open_repo(path);
+    let mut ws = repo.pull(branch).expect("pull");
+    let name = ws.put::<blobschemas::LongString, _>(label.to_string());
+    ws.commit(entity! { &ufoid() @ metadata::name: name }, label);
+    repo.push(&mut ws).expect("push");
+    let head = ws.head().expect("head");
+    repo.close().unwrap();
+    head
+}
+
+fn head_of(path: &Path, branch: Id) -> Option<CommitHandle> {
+    let mut repo = open_repo(path);
+    let head = repo.pull(branch).expect("pull").head();
+    repo.close().unwrap();
+    head
+}
+
+fn handle_arg(handle: CommitHandle) -> String {
+    let hash: Value<triblespace_core::value::schemas::hash::Hash<Blake3>> = Handle::to_hash(handle);
+    hash.from_value()
+}
+
+fn trible(args: &[&str]) -> String {
+    let out = Command::cargo_bin("trible")
+        .unwrap()
+        .args(args)
+        .output()
+        .expect("run trible");
+    assert!(
+        out.status.success(),
+        "trible {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn bundles_carry_branches_between_piles() {
+    let dir = tempdir().unwrap();
+    let source = dir.path().join("source.pile");
+    let target = dir.path().join("target.pile");
+    std::fs::File::create(&source).unwrap();
+    std::fs::File::create(&target).unwrap();
+    let (source_s, target_s) = (source.to_str().unwrap(), target.to_str().unwrap());
+
+    let branch = {
+        let mut repo = open_repo(&source);
+        let id = *repo.create_branch("main", None).expect("create branch");
+        repo.close().unwrap();
+        id
+    };
+    let base = commit(&source, branch, "first");
+
+    // Full bundle into an empty pile creates the branch.
+    let full = dir.path().join("full.bundle");
+    trible(&["pile", "bundle", "create", source_s, full.to_str().unwrap()]);
+    let verified = trible(&["pile", "bundle", "verify", full.to_str().unwrap()]);
+    assert!(verified.contains("main"), "{verified}");
+    assert!(verified.contains("No prerequisites."), "{verified}");
+    let out = trible(&["pile", "bundle", "import", target_s, full.to_str().unwrap()]);
+    assert!(out.contains("created main"), "{out}");
+    assert_eq!(head_of(&target, branch), Some(base));
+
+    // A thin bundle cut against the old head fast-forwards the copy.
+    let head = commit(&source, branch, "second");
+    let thin = dir.path().join("thin.bundle");
+    trible(&[
+        "pile",
+        "bundle",
+        "create",
+        source_s,
+        thin.to_str().unwrap(),
+        "--branch",
+        "main",
+        "--base",
+        &handle_arg(base),
+    ]);
+    let verified = trible(&["pile", "bundle", "verify", thin.to_str().unwrap()]);
+    assert!(verified.contains(&handle_arg(base)), "{verified}");
+    let out = trible(&["pile", "bundle", "import", target_s, thin.to_str().unwrap()]);
+    assert!(out.contains("fast-forwarded main"), "{out}");
+    assert_eq!(head_of(&target, branch), Some(head));
+
+    // Once the copy has its own commits, the bundled head lands in a
+    // tracking branch and the local branch is left alone.
+    let local = commit(&target, branch, "local");
+    commit(&source, branch, "remote");
+    let diverged = dir.path().join("diverged.bundle");
+    trible(&["pile", "bundle", "create", source_s, diverged.to_str().unwrap()]);
+    let out = trible(&["pile", "bundle", "import", target_s, diverged.to_str().unwrap()]);
+    assert!(out.contains("diverged main"), "{out}");
+    assert_eq!(head_of(&target, branch), Some(local));
+
+    let mut pile: Pile<Blake3> = Pile::open(&target).unwrap();
+    pile.restore().unwrap();
+    let tracking = triblespace_net::tracking::find_tracking_branch(&mut pile, branch);
+    pile.close().unwrap();
+    assert!(tracking.is_some());
+}
+
+#[test]
+fn import_refuses_bundles_without_their_base() {
+    let dir = tempdir().unwrap();
+    let source = dir.path().join("source.pile");
+    let target = dir.path().join("target.pile");
+    std::fs::File::create(&source).unwrap();
+    std::fs::File::create(&target).unwrap();
+
+    let branch = {
+        let mut repo = open_repo(&source);
+        let id = *repo.create_branch("main", None).expect("create branch");
+        repo.close().unwrap();
+        id
+    };
+    let base = commit(&source, branch, "first");
+    commit(&source, branch, "second");
+
+    let thin = dir.path().join("thin.bundle");
+    trible(&[
+        "pile",
+        "bundle",
+        "create",
+        source.to_str().unwrap(),
+        thin.to_str().unwrap(),
+        "--base",
+        &handle_arg(base),
+    ]);
+    let out = Command::cargo_bin("trible")
+        .unwrap()
+        .args(["pile", "bundle", "import", target.to_str().unwrap(), thin.to_str().unwrap()])
+        .output()
+        .expect("run trible");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("prerequisite"));
+
+    let mut pile: Pile<Blake3> = Pile::open(&target).unwrap();
+    pile.restore().unwrap();
+    let head = pile.head(branch).unwrap();
+    pile.close().unwrap();
+    assert!(head.is_none());
+}
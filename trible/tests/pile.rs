@@ -521,3 +521,122 @@ fn pile_branch_create_outputs_id() {
         .success()
         .stdout(predicate::str::is_match("^[A-F0-9]{32}\\t-\\tmain\\n$").unwrap());
 }
+
+#[test]
+fn parquet_export_writes_described_attributes() {
+    use triblespace::prelude::blobschemas::{LongString, SimpleArchive};
+    use triblespace::prelude::valueschemas::{GenId, ShortString};
+    use triblespace::prelude::*;
+    use triblespace_core::blob::MemoryBlobStore;
+    use triblespace_core::import::arrow::ArrowImporter;
+    use triblespace_core::metadata;
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("parquet_test.pile");
+    let output = dir.path().join("books.parquet");
+    std::fs::File::create(&path).unwrap();
+
+    let title = Attribute::<ShortString>::from_name("title");
+    let branch_id = {
+        let pile: Pile<Blake3> = Pile::open(&path).unwrap();
+        let mut repo = Repository::new(pile, random_signing_key(), TribleSet::new()).unwrap();
+        let branch_id = repo.create_branch("main", None).expect("create branch");
+        let mut ws = repo.pull(*branch_id).expect("pull");
+
+        let name = ws.put::<LongString, _>("title".to_string());
+        let title_id = title.id();
+        let described = TribleSet::from(entity! { ExclusiveId::force_ref(&title_id) @
+            metadata::name: name,
+            metadata::value_schema: GenId::value_from(ShortString::ID),
+        });
+        let described = ws.put::<SimpleArchive, _>(described);
+        let mut content = TribleSet::new();
+        content += entity! { &ufoid() @ title: "Dune" };
+        content += entity! { &ufoid() @ title: "Emma" };
+        ws.commit_with_metadata(content, described, "seed");
+
+        let push_res = repo.try_push(&mut ws).expect("push");
+        assert!(push_res.is_none(), "unexpected push conflict");
+        repo.into_storage().close().unwrap();
+        *branch_id
+    };
+
+    Command::cargo_bin("trible")
+        .unwrap()
+        .args([
+            "pile",
+            "parquet",
+            "export",
+            path.to_str().unwrap(),
+            &format!("{branch_id:X}"),
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("wrote 2 rows and 2 columns"));
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let rows = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .skip_column("id")
+        .import_parquet(std::fs::File::open(&output).unwrap())
+        .expect("read parquet");
+    let facts = rows.facts();
+    let mut titles: Vec<String> = find!((t: String), pattern!(facts, [{ title: ?t }]))
+        .map(|(t,)| t)
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Dune", "Emma"]);
+}
+
+#[test]
+fn import_json_streams_chunks_into_branch() {
+    use triblespace::prelude::blobschemas::LongString;
+    use triblespace::prelude::valueschemas::Handle;
+    use triblespace::prelude::*;
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("import_test.pile");
+    let input = dir.path().join("events.ndjson");
+    std::fs::File::create(&path).unwrap();
+    std::fs::write(
+        &input,
+        "{\"user\": \"ada\"}\n{\"user\": \"bob\"}\n{\"user\": \"cy\"}\n",
+    )
+    .unwrap();
+
+    let branch_id = {
+        let pile: Pile<Blake3> = Pile::open(&path).unwrap();
+        let mut repo = Repository::new(pile, random_signing_key(), TribleSet::new()).unwrap();
+        let branch_id = repo.create_branch("main", None).expect("create branch");
+        repo.into_storage().close().unwrap();
+        *branch_id
+    };
+
+    Command::cargo_bin("trible")
+        .unwrap()
+        .args([
+            "pile",
+            "import",
+            "json",
+            path.to_str().unwrap(),
+            &format!("{branch_id:X}"),
+            input.to_str().unwrap(),
+            "--commit-every",
+            "2",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("imported 3 records in 2 commits"));
+
+    let pile: Pile<Blake3> = Pile::open(&path).unwrap();
+    let mut repo = Repository::new(pile, random_signing_key(), TribleSet::new()).unwrap();
+    let mut ws = repo.pull(branch_id).expect("pull");
+    let checkout = ws.checkout(..).expect("checkout");
+    let user = Attribute::<Handle<Blake3, LongString>>::from_name("user");
+    let facts = checkout.facts();
+    assert_eq!(
+        find!((e: Id), pattern!(facts, [{ ?e @ user: _?name }])).count(),
+        3
+    );
+    repo.into_storage().close().unwrap();
+}
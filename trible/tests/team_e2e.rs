@@ -272,3 +272,70 @@ fn invite_rejects_invalid_issuer_cap() {
         .assert()
         .failure();
 }
+
+#[test]
+fn renew_rejects_foreign_team_root_and_never_writes_a_key() {
+    let dir = tempdir().expect("tempdir");
+    let pile_path = dir.path().join("team.pile");
+    std::fs::File::create(&pile_path).expect("create pile file");
+    let founder_key_path = dir.path().join("founder.key");
+    let missing_key_path = dir.path().join("missing.key");
+
+    let create = Command::cargo_bin("trible")
+        .unwrap()
+        .args([
+            "team",
+            "create",
+            "--pile",
+            pile_path.to_str().unwrap(),
+            "--key",
+            founder_key_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    let (team_root, _team_root_secret, founder_cap_sig) = parse_create_output(
+        std::str::from_utf8(&create.get_output().stdout).unwrap(),
+    );
+
+    // A team root secret that didn't issue the cap is an error, not a
+    // silent fall back to the node key.
+    let foreign_secret = "11".repeat(32);
+    Command::cargo_bin("trible")
+        .unwrap()
+        .args([
+            "team",
+            "renew",
+            &founder_cap_sig,
+            "--pile",
+            pile_path.to_str().unwrap(),
+            "--team-root-secret",
+            &foreign_secret,
+            "--key",
+            missing_key_path.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("not by the given team root"));
+
+    // Without the secret, renew needs an existing issuer key and must
+    // not generate one.
+    Command::cargo_bin("trible")
+        .unwrap()
+        .env_remove("TRIBLE_TEAM_ROOT_SECRET")
+        .args([
+            "team",
+            "renew",
+            &founder_cap_sig,
+            "--pile",
+            pile_path.to_str().unwrap(),
+            "--team-root",
+            &team_root,
+            "--issuer-cap",
+            &founder_cap_sig,
+            "--key",
+            missing_key_path.to_str().unwrap(),
+        ])
+        .assert()
+        .failure();
+    assert!(!missing_key_path.exists(), "renew must not write key material");
+}
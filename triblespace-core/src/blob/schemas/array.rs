@@ -5,6 +5,11 @@
 //! samples, embeddings) come from the TribleSpace attributes that reference
 //! the blob, not the schema itself — same as `LongString` being structural
 //! rather than semantic.
+//!
+//! For N-dimensional data, the
+//! [`tensor`](crate::blob::schemas::array::tensor) module defines a
+//! standard convention of shape, stride and dtype attributes and a
+//! [`Tensor`](crate::blob::schemas::array::Tensor) view.
 
 use core::marker::PhantomData;
 
@@ -21,6 +26,10 @@ use crate::repo::BlobStore;
 use crate::trible::Fragment;
 use crate::value::schemas::hash::Blake3;
 
+pub mod tensor;
+
+pub use tensor::Tensor;
+
 /// Maps a schema element marker to its native Rust type.
 ///
 /// Implement this for zero-sized marker types (e.g. `F32`, `BF16`, `I8`)
@@ -36,7 +45,8 @@ pub trait ArrayElement: ConstId + 'static {
 /// The blob schema ID is derived at compile time from T's ConstId via
 /// const_blake3, following the same pattern as `Handle<H, S>`.
 ///
-/// Shape metadata lives in TribleSpace triples, not in the blob.
+/// Shape metadata lives in TribleSpace triples, not in the blob; see
+/// [`tensor`] for the standard attributes.
 /// Use `View<[T::Native]>` for zero-copy access via `TryFromBlob`.
 pub struct Array<T: ArrayElement>(PhantomData<T>);
 
@@ -132,6 +142,18 @@ pub mod elements {
         "FA3AD8DEC844D5F409AB728269B7A3FE",
         "64-bit IEEE-754 float."
     );
+    impl_array_element!(
+        F16,
+        half::f16,
+        "FEA96B75117BDC3F6F8A5C4483E02D7E",
+        "16-bit IEEE-754 half-precision float."
+    );
+    impl_array_element!(
+        BF16,
+        half::bf16,
+        "12651C7D404BAA054E19836BBB4815B5",
+        "16-bit bfloat16 float."
+    );
     impl_array_element!(
         U8,
         u8,
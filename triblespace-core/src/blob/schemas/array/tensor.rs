@@ -0,0 +1,401 @@
+//! N-dimensional tensors stored as [`Array`] blobs.
+//!
+//! A tensor is an entity carrying the tensor attributes from
+//! [`metadata`]:
+//!
+//! - `metadata::tensor_data`: the `Array<T>` blob holding the elements,
+//! - `metadata::tensor_dtype`: the id of the element type `T`,
+//! - `metadata::tensor_shape`: an `Array<U64>` with the extent of each
+//!   dimension, outermost first,
+//! - `metadata::tensor_strides` (optional): an `Array<U64>` with the element
+//!   stride of each dimension. Without it the elements are contiguous in
+//!   row-major order.
+//!
+//! [`tensor_entity`] stores a tensor under this convention and
+//! [`Tensor::load`] reads one back. The [`Tensor`] view indexes the element
+//! blob in place, so a tensor loaded from a pile reads straight from the
+//! memory-mapped file.
+//!
+//! ```rust,ignore
+//! let tensor = ufoid();
+//! let mut facts = tensor_entity::<F32, _>(&mut blobs, &tensor, weights, &[768, 3072])?;
+//! facts += entity! { &tensor @ metadata::name: blobs.put("mlp.up.weight")? };
+//!
+//! let view = Tensor::<F32>::load(&facts, *tensor, &reader)?;
+//! let w = view.get(&[3, 7]);
+//! ```
+
+use std::fmt;
+
+use anybytes::View;
+
+use super::elements::U64;
+use super::{Array, ArrayElement};
+use crate::blob::schemas::UnknownBlob;
+use crate::id::{ExclusiveId, Id};
+use crate::macros::entity;
+use crate::prelude::{find, pattern};
+use crate::metadata;
+use crate::repo::{BlobStoreGet, BlobStorePut};
+use crate::trible::{Fragment, TribleSet};
+use crate::value::schemas::hash::{Blake3, Handle};
+use crate::value::Value;
+
+/// Error raised when a tensor's metadata or elements do not fit together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TensorError {
+    /// The entity lacks the named tensor attribute.
+    MissingAttribute(&'static str),
+    /// The tensor stores elements of another type than requested.
+    DtypeMismatch {
+        /// The requested element type.
+        expected: Id,
+        /// The element type recorded on the entity.
+        found: Id,
+    },
+    /// The shape and the strides have different numbers of dimensions.
+    RankMismatch {
+        /// Dimensions in the shape.
+        shape: usize,
+        /// Dimensions in the strides.
+        strides: usize,
+    },
+    /// The layout addresses `required` elements but the data holds `len`.
+    /// Sizes that overflow `usize` report `usize::MAX`.
+    LengthMismatch {
+        /// Elements the layout needs.
+        required: usize,
+        /// Elements in the data.
+        len: usize,
+    },
+    /// A blob could not be loaded.
+    Blob(String),
+}
+
+impl fmt::Display for TensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TensorError::MissingAttribute(attr) => write!(f, "tensor has no {attr}"),
+            TensorError::DtypeMismatch { expected, found } => {
+                write!(f, "tensor stores {found:X} elements, expected {expected:X}")
+            }
+            TensorError::RankMismatch { shape, strides } => write!(
+                f,
+                "tensor shape has {shape} dimensions but strides have {strides}"
+            ),
+            TensorError::LengthMismatch { required, len } => write!(
+                f,
+                "tensor layout needs {required} elements but the data holds {len}"
+            ),
+            TensorError::Blob(message) => write!(f, "failed to load tensor blob: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TensorError {}
+
+/// A zero-copy N-dimensional view over the elements of an [`Array`] blob.
+pub struct Tensor<T: ArrayElement> {
+    data: View<[T::Native]>,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl<T: ArrayElement> Tensor<T> {
+    /// A row-major view of `data` with the given `shape`, which must cover
+    /// every element exactly.
+    pub fn new(data: View<[T::Native]>, shape: Vec<usize>) -> Result<Self, TensorError> {
+        let required = element_count(&shape).unwrap_or(usize::MAX);
+        if required != data.len() {
+            return Err(TensorError::LengthMismatch {
+                required,
+                len: data.len(),
+            });
+        }
+        let strides = row_major_strides(&shape);
+        Ok(Tensor {
+            data,
+            shape,
+            strides,
+        })
+    }
+
+    /// A view of `data` that finds element `index` at offset
+    /// `sum(index[i] * strides[i])`. Every offset must lie within `data`,
+    /// and the element count of `shape` must fit in a `usize`.
+    pub fn with_strides(
+        data: View<[T::Native]>,
+        shape: Vec<usize>,
+        strides: Vec<usize>,
+    ) -> Result<Self, TensorError> {
+        if shape.len() != strides.len() {
+            return Err(TensorError::RankMismatch {
+                shape: shape.len(),
+                strides: strides.len(),
+            });
+        }
+        let required = element_count(&shape)
+            .and_then(|_| extent(&shape, &strides))
+            .unwrap_or(usize::MAX);
+        if required > data.len() {
+            return Err(TensorError::LengthMismatch {
+                required,
+                len: data.len(),
+            });
+        }
+        Ok(Tensor {
+            data,
+            shape,
+            strides,
+        })
+    }
+
+    /// Loads the tensor described by `tensor` in `set`, fetching its blobs
+    /// from `store`.
+    pub fn load<B>(set: &TribleSet, tensor: Id, store: &B) -> Result<Self, TensorError>
+    where
+        B: BlobStoreGet<Blake3>,
+    {
+        let dtype = find!(dtype: Id, pattern!(set, [{ tensor @ metadata::tensor_dtype: ?dtype }]))
+            .next()
+            .ok_or(TensorError::MissingAttribute("tensor_dtype"))?;
+        if dtype != T::ID {
+            return Err(TensorError::DtypeMismatch {
+                expected: T::ID,
+                found: dtype,
+            });
+        }
+        let data = find!(
+            data: Value<Handle<Blake3, UnknownBlob>>,
+            pattern!(set, [{ tensor @ metadata::tensor_data: ?data }])
+        )
+        .next()
+        .ok_or(TensorError::MissingAttribute("tensor_data"))?;
+        let shape = find!(
+            shape: Value<Handle<Blake3, Array<U64>>>,
+            pattern!(set, [{ tensor @ metadata::tensor_shape: ?shape }])
+        )
+        .next()
+        .ok_or(TensorError::MissingAttribute("tensor_shape"))?;
+        let strides = find!(
+            strides: Value<Handle<Blake3, Array<U64>>>,
+            pattern!(set, [{ tensor @ metadata::tensor_strides: ?strides }])
+        )
+        .next();
+
+        let data: View<[T::Native]> = store
+            .get::<_, Array<T>>(data.transmute())
+            .map_err(|e| TensorError::Blob(e.to_string()))?;
+        let shape = load_dims(store, shape, data.len())?;
+        match strides {
+            Some(strides) => {
+                let strides = load_dims(store, strides, data.len())?;
+                Tensor::with_strides(data, shape, strides)
+            }
+            None => Tensor::new(data, shape),
+        }
+    }
+
+    /// The extent of each dimension.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The element stride of each dimension.
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// The number of dimensions.
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// The number of elements the tensor addresses.
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Whether some dimension is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The underlying element blob.
+    pub fn data(&self) -> &View<[T::Native]> {
+        &self.data
+    }
+
+    /// The elements in row-major order, if they are stored that way.
+    pub fn as_slice(&self) -> Option<&[T::Native]> {
+        (self.strides == row_major_strides(&self.shape)).then(|| &self.data[..self.len()])
+    }
+
+    /// The element at `index`, or `None` if the index has the wrong number
+    /// of dimensions or lies outside the shape.
+    pub fn get(&self, index: &[usize]) -> Option<&T::Native> {
+        if index.len() != self.shape.len() {
+            return None;
+        }
+        let mut offset = 0;
+        for ((&i, &extent), &stride) in index.iter().zip(&self.shape).zip(&self.strides) {
+            if i >= extent {
+                return None;
+            }
+            offset += i * stride;
+        }
+        self.data.get(offset)
+    }
+}
+
+/// Stores `data` as an `Array<T>` blob and returns the attributes describing
+/// it as a row-major tensor of `shape` on `tensor`.
+///
+/// # Panics
+///
+/// Panics if `shape` does not cover `data.len()` elements exactly.
+pub fn tensor_entity<T, B>(
+    blobs: &mut B,
+    tensor: &ExclusiveId,
+    data: Vec<T::Native>,
+    shape: &[usize],
+) -> Result<Fragment, B::PutError>
+where
+    T: ArrayElement,
+    B: BlobStorePut<Blake3>,
+{
+    assert_eq!(
+        element_count(shape),
+        Some(data.len()),
+        "tensor shape does not match the element count"
+    );
+    let data = blobs.put::<Array<T>, _>(data)?;
+    let shape: Vec<u64> = shape.iter().map(|&d| d as u64).collect();
+    Ok(entity! { tensor @
+        metadata::tensor_data: data.transmute(),
+        metadata::tensor_dtype: T::ID,
+        metadata::tensor_shape: blobs.put(shape)?,
+    })
+}
+
+/// The number of elements in `shape`, or `None` on overflow.
+pub(crate) fn element_count(shape: &[usize]) -> Option<usize> {
+    if shape.contains(&0) {
+        return Some(0);
+    }
+    shape.iter().try_fold(1usize, |n, &d| n.checked_mul(d))
+}
+
+/// Strides of a contiguous row-major layout of `shape`.
+pub(crate) fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1usize; shape.len()];
+    for i in (1..shape.len()).rev() {
+        strides[i - 1] = strides[i].saturating_mul(shape[i]);
+    }
+    strides
+}
+
+/// One past the largest offset the layout addresses, or `None` on overflow.
+fn extent(shape: &[usize], strides: &[usize]) -> Option<usize> {
+    if shape.contains(&0) {
+        return Some(0);
+    }
+    shape
+        .iter()
+        .zip(strides)
+        .try_fold(1usize, |end, (&d, &s)| end.checked_add((d - 1).checked_mul(s)?))
+}
+
+fn load_dims<B: BlobStoreGet<Blake3>>(
+    store: &B,
+    handle: Value<Handle<Blake3, Array<U64>>>,
+    len: usize,
+) -> Result<Vec<usize>, TensorError> {
+    let dims: View<[u64]> = store
+        .get(handle)
+        .map_err(|e| TensorError::Blob(e.to_string()))?;
+    dims.iter()
+        .map(|&d| {
+            usize::try_from(d).map_err(|_| TensorError::LengthMismatch {
+                required: usize::MAX,
+                len,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::schemas::array::elements::{BF16, F32};
+    use crate::blob::MemoryBlobStore;
+    use crate::id::ufoid;
+    use crate::metadata::ConstId;
+    use crate::repo::BlobStore;
+    use anybytes::Bytes;
+
+    #[test]
+    fn loads_and_indexes_tensors() {
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let tensor = ufoid();
+        let data: Vec<f32> = (0..24).map(|i| i as f32).collect();
+        let facts = tensor_entity::<F32, _>(&mut blobs, &tensor, data, &[2, 3, 4]).unwrap();
+        let reader = blobs.reader().unwrap();
+
+        let view = Tensor::<F32>::load(&facts, *tensor, &reader).unwrap();
+        assert_eq!(view.shape(), [2, 3, 4]);
+        assert_eq!(view.strides(), [12, 4, 1]);
+        assert_eq!(view.get(&[1, 2, 3]), Some(&23.0));
+        assert_eq!(view.get(&[0, 1, 2]), Some(&6.0));
+        assert_eq!(view.get(&[2, 0, 0]), None);
+        assert_eq!(view.get(&[0, 0]), None);
+        assert_eq!(view.as_slice().map(<[f32]>::len), Some(24));
+
+        assert_eq!(
+            Tensor::<BF16>::load(&facts, *tensor, &reader).err(),
+            Some(TensorError::DtypeMismatch {
+                expected: BF16::ID,
+                found: F32::ID,
+            })
+        );
+    }
+
+    #[test]
+    fn strided_views_transpose_without_copying() {
+        let data: View<[f32]> = Bytes::from_source(vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .view()
+            .unwrap();
+        // The 2x3 matrix [[1, 2, 3], [4, 5, 6]] read as its 3x2 transpose.
+        let transposed = Tensor::<F32>::with_strides(data.clone(), vec![3, 2], vec![1, 3]).unwrap();
+        assert_eq!(transposed.get(&[2, 0]), Some(&3.0));
+        assert_eq!(transposed.get(&[2, 1]), Some(&6.0));
+        assert_eq!(transposed.as_slice(), None);
+
+        assert_eq!(
+            Tensor::<F32>::with_strides(data.clone(), vec![3, 2], vec![2, 3]).err(),
+            Some(TensorError::LengthMismatch {
+                required: 8,
+                len: 6
+            })
+        );
+        // Zero strides keep the offsets in range, but the element count
+        // of the shape would overflow `len`.
+        assert_eq!(
+            Tensor::<F32>::with_strides(data.clone(), vec![usize::MAX, 2], vec![0, 1]).err(),
+            Some(TensorError::LengthMismatch {
+                required: usize::MAX,
+                len: 6
+            })
+        );
+        let broadcast = Tensor::<F32>::with_strides(data.clone(), vec![4, 3], vec![0, 1]).unwrap();
+        assert_eq!(broadcast.len(), 12);
+        assert_eq!(broadcast.get(&[3, 2]), Some(&3.0));
+        assert_eq!(
+            Tensor::<F32>::new(data, vec![4, 2]).err(),
+            Some(TensorError::LengthMismatch {
+                required: 8,
+                len: 6
+            })
+        );
+    }
+}
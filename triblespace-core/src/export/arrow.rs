@@ -0,0 +1,693 @@
+//! TribleSpace → Arrow exporter.
+//!
+//! Builds Arrow [`RecordBatch`]es from query results or entities, for
+//! columnar analytics tools:
+//!
+//! - [`rows_to_record_batch`] turns the tuples of a `find!` projection into
+//!   one row each, under the given headers. Any tuple of [`ArrowField`]s is a
+//!   row, and each field type picks its column type (`String` is `Utf8`,
+//!   `i64` is `Int64`, `Id` a dictionary of 16-byte values, and so on).
+//! - [`entities_to_record_batch`] writes one row per entity, with an `id`
+//!   column followed by one column per described attribute
+//!   (`metadata::name` + `metadata::value_schema`) the entities use, sorted
+//!   by name. The column type follows the value schema: `Utf8` for short
+//!   and long strings, `Int64`/`UInt64` for 256-bit integers, `Float64`,
+//!   `Boolean`, a `{start, end}` struct of UTC timestamps for
+//!   `NsTAIInterval`, and `Dictionary(Int32, FixedSizeBinary(16))` for ids.
+//!   A column becomes a list when an entity has several values for it.
+//!   Values that don't convert, such as an inverted interval, become nulls.
+//!   Attributes in other schemas are left out. Each field of a schema the
+//!   importer reads back (strings, `I256BE`, `F64`, `Boolean`,
+//!   `NsTAIInterval` and ids) records its attribute and schema in its
+//!   metadata, so [`ArrowImporter`](crate::import::arrow::ArrowImporter)
+//!   reads it back into the same attribute. `U256BE` columns and
+//!   text-rendered schemas (UUIDs, addresses, geo points, dates) carry no
+//!   such tags: they import like any untagged column, into attributes
+//!   derived from the field name under the inferred integer or string
+//!   schema.
+//!
+//! With the `parquet` feature, [`write_parquet`] stores a batch as a Parquet
+//! file.
+//!
+//! ```rust,ignore
+//! let rows = find!((title: String, pages: i64), pattern!(&facts, [{ title: ?title, pages: ?pages }]));
+//! let batch = rows_to_record_batch(&["title", "pages"], rows)?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use anybytes::View;
+use arrow_array::builder::{
+    ArrayBuilder, BooleanBuilder, Decimal128Builder, FixedSizeBinaryDictionaryBuilder,
+    Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder, Int8Builder,
+    StringBuilder, TimestampNanosecondBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+    UInt8Builder,
+};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, ListArray, RecordBatch, StructArray, TimestampNanosecondArray};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{ArrowError, DataType, Field, Fields, Schema, TimeUnit};
+use hifitime::{Duration, Epoch};
+
+use crate::blob::schemas::longstring::LongString;
+use crate::id::Id;
+use crate::import::arrow::{ATTRIBUTE_KEY, VALUE_SCHEMA_KEY};
+use crate::import::csv::ColumnSchema;
+use crate::metadata;
+use crate::metadata::ConstId;
+use crate::prelude::{find, pattern};
+use crate::query::TriblePattern;
+use crate::repo::BlobStoreGet;
+use crate::trible::TribleSet;
+use crate::value::schemas::boolean::Boolean;
+use crate::value::schemas::f64::F64;
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, Handle, Hash};
+use crate::value::schemas::iu256::{I256BE, U256BE};
+use crate::value::schemas::shortstring::ShortString;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::schemas::UnknownValue;
+use crate::value::{RawValue, ToValue, Value, ValueSchema};
+use crate::{and, temp};
+
+use super::ExportError;
+
+/// A value that can be appended to an Arrow column.
+pub trait ArrowField {
+    /// The builder of the column.
+    type Builder: ArrayBuilder;
+
+    /// A new, empty column builder.
+    fn builder() -> Self::Builder;
+
+    /// Append the value to `builder`.
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError>;
+
+    /// Append a null to `builder`.
+    fn append_null(builder: &mut Self::Builder);
+}
+
+/// A tuple of [`ArrowField`]s forming one row.
+pub trait ArrowRecord {
+    /// The column builders, one per field.
+    type Builders;
+
+    /// New, empty column builders.
+    fn builders() -> Self::Builders;
+
+    /// Append every field to its column.
+    fn append(&self, builders: &mut Self::Builders) -> Result<(), ArrowError>;
+
+    /// Finish the columns.
+    fn finish(builders: Self::Builders) -> Vec<ArrayRef>;
+}
+
+impl<T: ArrowField + ?Sized> ArrowField for &T {
+    type Builder = T::Builder;
+
+    fn builder() -> Self::Builder {
+        T::builder()
+    }
+
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+        (**self).append(builder)
+    }
+
+    fn append_null(builder: &mut Self::Builder) {
+        T::append_null(builder);
+    }
+}
+
+impl<T: ArrowField> ArrowField for Option<T> {
+    type Builder = T::Builder;
+
+    fn builder() -> Self::Builder {
+        T::builder()
+    }
+
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+        match self {
+            Some(value) => value.append(builder),
+            None => {
+                T::append_null(builder);
+                Ok(())
+            }
+        }
+    }
+
+    fn append_null(builder: &mut Self::Builder) {
+        T::append_null(builder);
+    }
+}
+
+macro_rules! text_fields {
+    ($($t:ty),*) => {$(
+        impl ArrowField for $t {
+            type Builder = StringBuilder;
+
+            fn builder() -> Self::Builder {
+                StringBuilder::new()
+            }
+
+            fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+                builder.append_value(AsRef::<str>::as_ref(self));
+                Ok(())
+            }
+
+            fn append_null(builder: &mut Self::Builder) {
+                builder.append_null();
+            }
+        }
+    )*};
+}
+
+text_fields!(str, String, View<str>);
+
+macro_rules! primitive_fields {
+    ($($t:ty => $builder:ty),*) => {$(
+        impl ArrowField for $t {
+            type Builder = $builder;
+
+            fn builder() -> Self::Builder {
+                <$builder>::new()
+            }
+
+            fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+                builder.append_value(*self);
+                Ok(())
+            }
+
+            fn append_null(builder: &mut Self::Builder) {
+                builder.append_null();
+            }
+        }
+    )*};
+}
+
+primitive_fields!(
+    bool => BooleanBuilder,
+    i8 => Int8Builder,
+    i16 => Int16Builder,
+    i32 => Int32Builder,
+    i64 => Int64Builder,
+    u8 => UInt8Builder,
+    u16 => UInt16Builder,
+    u32 => UInt32Builder,
+    u64 => UInt64Builder,
+    f32 => Float32Builder,
+    f64 => Float64Builder
+);
+
+/// `Decimal128(38, 0)`, which holds every `i128`.
+impl ArrowField for i128 {
+    type Builder = Decimal128Builder;
+
+    fn builder() -> Self::Builder {
+        Decimal128Builder::new()
+            .with_precision_and_scale(38, 0)
+            .expect("valid decimal precision")
+    }
+
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+        builder.append_value(*self);
+        Ok(())
+    }
+
+    fn append_null(builder: &mut Self::Builder) {
+        builder.append_null();
+    }
+}
+
+/// `Dictionary(Int32, FixedSizeBinary(16))`, storing each distinct id once.
+impl ArrowField for Id {
+    type Builder = FixedSizeBinaryDictionaryBuilder<Int32Type>;
+
+    fn builder() -> Self::Builder {
+        FixedSizeBinaryDictionaryBuilder::new(16)
+    }
+
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+        builder.append(self.raw()).map(|_| ())
+    }
+
+    fn append_null(builder: &mut Self::Builder) {
+        builder.append_null();
+    }
+}
+
+/// A nanosecond UTC timestamp.
+impl ArrowField for Epoch {
+    type Builder = TimestampNanosecondBuilder;
+
+    fn builder() -> Self::Builder {
+        TimestampNanosecondBuilder::new().with_timezone("UTC")
+    }
+
+    fn append(&self, builder: &mut Self::Builder) -> Result<(), ArrowError> {
+        let ns = unix_nanos(*self).ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!("{self} is outside the timestamp range"))
+        })?;
+        builder.append_value(ns);
+        Ok(())
+    }
+
+    fn append_null(builder: &mut Self::Builder) {
+        builder.append_null();
+    }
+}
+
+macro_rules! tuple_records {
+    ($($t:ident . $i:tt),+) => {
+        impl<$($t: ArrowField),+> ArrowRecord for ($($t,)+) {
+            type Builders = ($($t::Builder,)+);
+
+            fn builders() -> Self::Builders {
+                ($($t::builder(),)+)
+            }
+
+            fn append(&self, builders: &mut Self::Builders) -> Result<(), ArrowError> {
+                $(self.$i.append(&mut builders.$i)?;)+
+                Ok(())
+            }
+
+            fn finish(mut builders: Self::Builders) -> Vec<ArrayRef> {
+                vec![$(ArrayBuilder::finish(&mut builders.$i)),+]
+            }
+        }
+    };
+}
+
+tuple_records!(A.0);
+tuple_records!(A.0, B.1);
+tuple_records!(A.0, B.1, C.2);
+tuple_records!(A.0, B.1, C.2, D.3);
+tuple_records!(A.0, B.1, C.2, D.3, E.4);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
+/// Build a batch with one row per tuple in `rows`, in order, under
+/// `headers`. All columns are nullable.
+///
+/// Fails if `headers` and the tuples differ in length, or a value does not
+/// fit its column type.
+pub fn rows_to_record_batch<R: ArrowRecord>(
+    headers: &[&str],
+    rows: impl IntoIterator<Item = R>,
+) -> Result<RecordBatch, ArrowError> {
+    let mut builders = R::builders();
+    for row in rows {
+        row.append(&mut builders)?;
+    }
+    let columns = R::finish(builders);
+    if headers.len() != columns.len() {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "{} headers for {} columns",
+            headers.len(),
+            columns.len()
+        )));
+    }
+    let fields: Vec<Field> = headers
+        .iter()
+        .zip(&columns)
+        .map(|(name, column)| Field::new(*name, column.data_type().clone(), true))
+        .collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Build a batch with one row per entity in `entities` (duplicates
+/// dropped), with the values `set` holds for them. `store` must hold the
+/// set's long strings and attribute names.
+///
+/// Fails with [`ExportError::Malformed`] when an integer does not fit 64
+/// bits or a time lies outside the nanosecond timestamp range.
+pub fn entities_to_record_batch(
+    set: &TribleSet,
+    entities: impl IntoIterator<Item = Id>,
+    store: &impl BlobStoreGet<Blake3>,
+) -> Result<RecordBatch, ExportError> {
+    let mut ctx = ColumnCtx {
+        store,
+        strings: HashMap::new(),
+    };
+
+    let mut described: HashMap<Id, (Value<Handle<Blake3, LongString>>, Id)> = HashMap::new();
+    for (attr, name, schema) in find!(
+        (attr: Id, name: Value<Handle<Blake3, LongString>>, schema: Id),
+        pattern!(set, [{ ?attr @ metadata::name: ?name, metadata::value_schema: ?schema }])
+    ) {
+        if exports(schema) {
+            described.insert(attr, (name, schema));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+    let mut cells: BTreeMap<Id, Vec<Vec<Value<UnknownValue>>>> = BTreeMap::new();
+    for entity in entities {
+        if !seen.insert(entity) {
+            continue;
+        }
+        for (attr, value) in find!(
+            (attr: Id, value: Value<UnknownValue>),
+            temp!((e), and!(e.is(entity.to_value()), set.pattern(e, attr, value)))
+        ) {
+            if !described.contains_key(&attr) {
+                continue;
+            }
+            let column = cells.entry(attr).or_default();
+            column.resize_with(ids.len() + 1, Vec::new);
+            column[ids.len()].push(value);
+        }
+        ids.push(entity);
+    }
+
+    let mut columns = Vec::with_capacity(cells.len());
+    for (attr, mut column) in cells {
+        let (name, schema) = described[&attr];
+        let name = ctx.resolve(name)?.to_string();
+        column.resize_with(ids.len(), Vec::new);
+        for values in &mut column {
+            values.sort_by_key(|value| value.raw);
+        }
+        let (mut field, array) = ctx.column(&ids, schema, column)?;
+        // Only tag columns the importer can read back into `attr`.
+        if ColumnSchema::from_id(schema).is_some() {
+            field = field.with_metadata(HashMap::from([
+                (ATTRIBUTE_KEY.to_owned(), format!("{attr:X}")),
+                (VALUE_SCHEMA_KEY.to_owned(), format!("{schema:X}")),
+            ]));
+        }
+        columns.push((name, field, array));
+    }
+    columns.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+    let id_column = ctx.leaf(
+        GenId::ID,
+        ids.iter().map(|id| Some((*id, GenId::value_from(*id).transmute()))),
+    )?;
+    let mut fields = vec![Field::new("id", id_column.data_type().clone(), false)];
+    let mut arrays = vec![id_column];
+    for (name, field, array) in columns {
+        fields.push(field.with_name(name));
+        arrays.push(array);
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+        .expect("columns match their fields"))
+}
+
+/// Write `batch` as a Parquet file to `out`.
+///
+/// Parquet dictionary-encodes pages itself and cannot store dictionaries of
+/// fixed-size values, so id columns are written as plain
+/// `FixedSizeBinary(16)`.
+#[cfg(feature = "parquet")]
+pub fn write_parquet(
+    batch: &RecordBatch,
+    out: impl std::io::Write + Send,
+) -> Result<(), parquet::errors::ParquetError> {
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut arrays = Vec::with_capacity(batch.num_columns());
+    for (field, array) in batch.schema_ref().fields().iter().zip(batch.columns()) {
+        let array = undictionary(array);
+        fields.push(field.as_ref().clone().with_data_type(array.data_type().clone()));
+        arrays.push(array);
+    }
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(out, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// `array` with dictionaries of fixed-size values, also inside lists,
+/// replaced by their values.
+#[cfg(feature = "parquet")]
+fn undictionary(array: &ArrayRef) -> ArrayRef {
+    use arrow_array::cast::AsArray;
+    use arrow_array::{Array, FixedSizeBinaryArray};
+
+    match array.data_type() {
+        DataType::Dictionary(_, values) if matches!(**values, DataType::FixedSizeBinary(_)) => {
+            let dictionary = array.as_any_dictionary();
+            let values = dictionary.values().as_fixed_size_binary();
+            let keys = if values.is_empty() {
+                vec![0; array.len()]
+            } else {
+                dictionary.normalized_keys()
+            };
+            let items = keys
+                .into_iter()
+                .enumerate()
+                .map(|(i, key)| array.is_valid(i).then(|| values.value(key)));
+            Arc::new(
+                FixedSizeBinaryArray::try_from_sparse_iter_with_size(items, values.value_length())
+                    .expect("values of the dictionary's width"),
+            )
+        }
+        DataType::List(field) => {
+            let list = array.as_list::<i32>();
+            let values = undictionary(list.values());
+            let field = field.as_ref().clone().with_data_type(values.data_type().clone());
+            Arc::new(ListArray::new(
+                Arc::new(field),
+                list.offsets().clone(),
+                values,
+                list.nulls().cloned(),
+            ))
+        }
+        _ => array.clone(),
+    }
+}
+
+/// Whether [`ColumnCtx::column`] can export values of `schema`.
+fn exports(schema: Id) -> bool {
+    [
+        GenId::ID,
+        ShortString::ID,
+        Handle::<Blake3, LongString>::ID,
+        I256BE::ID,
+        U256BE::ID,
+        F64::ID,
+        Boolean::ID,
+        NsTAIInterval::ID,
+    ]
+    .contains(&schema)
+        || super::displays_text(schema)
+}
+
+/// Nanoseconds of `epoch` since the Unix epoch, if they fit an `i64`.
+fn unix_nanos(epoch: Epoch) -> Option<i64> {
+    i64::try_from(epoch.to_unix_duration().total_nanoseconds()).ok()
+}
+
+struct ColumnCtx<'a, Store: BlobStoreGet<Blake3>> {
+    store: &'a Store,
+    strings: HashMap<RawValue, View<str>>,
+}
+
+impl<Store: BlobStoreGet<Blake3>> ColumnCtx<'_, Store> {
+    /// The field (unnamed) and array of a column with the values `column`
+    /// holds for each of `ids`.
+    fn column(
+        &mut self,
+        ids: &[Id],
+        schema: Id,
+        column: Vec<Vec<Value<UnknownValue>>>,
+    ) -> Result<(Field, ArrayRef), ExportError> {
+        if column.iter().all(|values| values.len() <= 1) {
+            let items = ids
+                .iter()
+                .zip(column)
+                .map(|(id, values)| values.first().map(|value| (*id, *value)));
+            let array = self.leaf(schema, items)?;
+            return Ok((Field::new("", array.data_type().clone(), true), array));
+        }
+
+        let nulls = NullBuffer::from_iter(column.iter().map(|values| !values.is_empty()));
+        let offsets = OffsetBuffer::from_lengths(column.iter().map(Vec::len));
+        let items = ids.iter().zip(&column).flat_map(|(id, values)| {
+            values.iter().map(move |value| Some((*id, *value)))
+        });
+        // Values that fail to convert become null elements, as they
+        // become null cells in single-valued columns.
+        let values = self.leaf(schema, items)?;
+        let element = Arc::new(Field::new_list_field(values.data_type().clone(), true));
+        let array: ArrayRef = Arc::new(ListArray::new(element, offsets, values, Some(nulls)));
+        Ok((Field::new("", array.data_type().clone(), true), array))
+    }
+
+    /// An array of `items` under `schema`, each with its entity for errors.
+    fn leaf(
+        &mut self,
+        schema: Id,
+        items: impl Iterator<Item = Option<(Id, Value<UnknownValue>)>>,
+    ) -> Result<ArrayRef, ExportError> {
+        fn build<B: ArrayBuilder, T>(
+            mut builder: B,
+            items: impl Iterator<Item = Option<(Id, Value<UnknownValue>)>>,
+            mut convert: impl FnMut(Id, Value<UnknownValue>) -> Result<Option<T>, ExportError>,
+            mut append: impl FnMut(&mut B, Option<T>),
+        ) -> Result<ArrayRef, ExportError> {
+            for item in items {
+                let value = match item {
+                    Some((entity, value)) => convert(entity, value)?,
+                    None => None,
+                };
+                append(&mut builder, value);
+            }
+            Ok(builder.finish())
+        }
+        let too_large = |entity| ExportError::Malformed {
+            entity,
+            reason: "integer does not fit 64 bits",
+        };
+
+        if schema == GenId::ID {
+            let mut builder = Id::builder();
+            for item in items {
+                let id = item.and_then(|(entity, value)| {
+                    let id = value.transmute::<GenId>().try_from_value::<Id>().ok()?;
+                    Some((entity, id))
+                });
+                match id {
+                    Some((entity, id)) => {
+                        id.append(&mut builder)
+                            .map_err(|_| ExportError::Malformed {
+                                entity,
+                                reason: "more than 2^31 distinct ids in a column",
+                            })?
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Ok(ArrayBuilder::finish(&mut builder))
+        } else if schema == ShortString::ID {
+            build(
+                StringBuilder::new(),
+                items,
+                |_, value| Ok(value.transmute::<ShortString>().try_from_value::<String>().ok()),
+                |builder, text| builder.append_option(text),
+            )
+        } else if schema == Handle::<Blake3, LongString>::ID {
+            build(
+                StringBuilder::new(),
+                items,
+                |_, value| self.resolve(value.transmute()).map(Some),
+                |builder, text| builder.append_option(text.as_ref().map(|text| text.as_ref())),
+            )
+        } else if schema == I256BE::ID {
+            build(
+                Int64Builder::new(),
+                items,
+                |entity, value| {
+                    let n: ethnum::I256 = value.transmute::<I256BE>().from_value();
+                    i64::try_from(n).map(Some).map_err(|_| too_large(entity))
+                },
+                |builder, n| builder.append_option(n),
+            )
+        } else if schema == U256BE::ID {
+            build(
+                UInt64Builder::new(),
+                items,
+                |entity, value| {
+                    let n: ethnum::U256 = value.transmute::<U256BE>().from_value();
+                    u64::try_from(n).map(Some).map_err(|_| too_large(entity))
+                },
+                |builder, n| builder.append_option(n),
+            )
+        } else if schema == F64::ID {
+            build(
+                Float64Builder::new(),
+                items,
+                |_, value| Ok(Some(value.transmute::<F64>().from_value::<f64>())),
+                |builder, n| builder.append_option(n),
+            )
+        } else if schema == Boolean::ID {
+            build(
+                BooleanBuilder::new(),
+                items,
+                |_, value| Ok(value.transmute::<Boolean>().try_from_value::<bool>().ok()),
+                |builder, b| builder.append_option(b),
+            )
+        } else if super::displays_text(schema) {
+            build(
+                StringBuilder::new(),
+                items,
+                |_, value| Ok(super::display_text(schema, value)),
+                |builder, text| builder.append_option(text),
+            )
+        } else {
+            debug_assert_eq!(schema, NsTAIInterval::ID);
+            let (mut lower, mut upper) = (Vec::new(), Vec::new());
+            for item in items {
+                let bounds = match item {
+                    Some((entity, value)) => interval_nanos(entity, value)?,
+                    None => None,
+                };
+                lower.push(bounds.map(|(lower, _)| lower));
+                upper.push(bounds.map(|(_, upper)| upper));
+            }
+            let nulls = NullBuffer::from_iter(lower.iter().map(Option::is_some));
+            let timestamps = |nanos: Vec<Option<i64>>| -> ArrayRef {
+                Arc::new(TimestampNanosecondArray::from(nanos).with_timezone("UTC"))
+            };
+            let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()));
+            let fields = Fields::from(vec![
+                Field::new("start", timestamp.clone(), true),
+                Field::new("end", timestamp, true),
+            ]);
+            Ok(Arc::new(StructArray::new(
+                fields,
+                vec![timestamps(lower), timestamps(upper)],
+                Some(nulls),
+            )))
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        handle: Value<Handle<Blake3, LongString>>,
+    ) -> Result<View<str>, ExportError> {
+        if let Some(cached) = self.strings.get(&handle.raw) {
+            return Ok(cached.clone());
+        }
+        let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+        let text: View<str> = self
+            .store
+            .get::<View<str>, LongString>(handle)
+            .map_err(|err| ExportError::BlobStore {
+                hash: hex::encode(hash.raw),
+                source: err.to_string(),
+            })?;
+        self.strings.insert(handle.raw, text.clone());
+        Ok(text)
+    }
+}
+
+/// The bounds of an interval as Unix nanoseconds, `None` if it is inverted.
+fn interval_nanos(
+    entity: Id,
+    value: Value<UnknownValue>,
+) -> Result<Option<(i64, i64)>, ExportError> {
+    let Ok((lower, upper)) = value
+        .transmute::<NsTAIInterval>()
+        .try_from_value::<(i128, i128)>()
+    else {
+        return Ok(None);
+    };
+    let nanos = |ns| {
+        unix_nanos(Epoch::from_tai_duration(Duration::from_total_nanoseconds(ns))).ok_or(
+            ExportError::Malformed {
+                entity,
+                reason: "time outside the 64-bit nanosecond range",
+            },
+        )
+    };
+    Ok(Some((nanos(lower)?, nanos(upper)?)))
+}
@@ -0,0 +1,466 @@
+//! TribleSpace → CSV exporter.
+//!
+//! [`CsvWriter`] flattens query results or entities into CSV text:
+//!
+//! - [`CsvWriter::write_rows`] writes the tuples of a `find!` projection,
+//!   one row each, under the given headers. Any tuple of [`CsvField`]s is a
+//!   row.
+//! - [`CsvWriter::write_entities`] writes one row per entity, with an `id`
+//!   column followed by one column per described attribute
+//!   (`metadata::name` + `metadata::value_schema`) the entities use, sorted
+//!   by name. Cells render by schema as [`crate::import::csv`] reads them
+//!   back: text, integers, floats, `true`/`false`, and timestamps (or
+//!   `start/end` intervals). Entity references are hex ids. An attribute
+//!   with several values for one entity gets them sorted, one per line in
+//!   the cell. Attributes in other schemas are left out.
+//!
+//! Fields are quoted when they contain the delimiter, a quote or a line
+//! break.
+//!
+//! ```rust,ignore
+//! let rows = find!((title: String, pages: i128), pattern!(&facts, [{ title: ?title, pages: ?pages }]));
+//! CsvWriter::new().write_rows(&["title", "pages"], rows, &mut out);
+//! ```
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+
+use anybytes::View;
+use hifitime::{Duration, Epoch, TimeScale};
+use num_rational::Ratio;
+use ryu::Buffer;
+
+use crate::and;
+use crate::blob::schemas::longstring::LongString;
+use crate::id::Id;
+use crate::metadata;
+use crate::metadata::ConstId;
+use crate::prelude::{find, pattern};
+use crate::query::TriblePattern;
+use crate::repo::BlobStoreGet;
+use crate::temp;
+use crate::trible::TribleSet;
+use crate::value::schemas::boolean::Boolean;
+use crate::value::schemas::f64::F64;
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, Handle, Hash};
+use crate::value::schemas::iu256::{I256BE, U256BE};
+use crate::value::schemas::r256::R256BE;
+use crate::value::schemas::shortstring::ShortString;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::schemas::UnknownValue;
+use crate::value::{RawValue, ToValue, Value};
+
+use super::ExportError;
+
+/// A value that can be written as one CSV field.
+pub trait CsvField {
+    /// Append the unquoted text of the field to `out`.
+    fn write_field(&self, out: &mut String);
+}
+
+/// A tuple of [`CsvField`]s forming one CSV row.
+pub trait CsvRecord {
+    /// Append the text of every field to `fields`.
+    fn write_record(&self, fields: &mut Vec<String>);
+}
+
+impl<T: CsvField + ?Sized> CsvField for &T {
+    fn write_field(&self, out: &mut String) {
+        (**self).write_field(out);
+    }
+}
+
+impl<T: CsvField> CsvField for Option<T> {
+    fn write_field(&self, out: &mut String) {
+        if let Some(value) = self {
+            value.write_field(out);
+        }
+    }
+}
+
+impl CsvField for str {
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self);
+    }
+}
+
+impl CsvField for String {
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self);
+    }
+}
+
+impl CsvField for View<str> {
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self.as_ref());
+    }
+}
+
+impl CsvField for Id {
+    fn write_field(&self, out: &mut String) {
+        let _ = write!(out, "{self:x}");
+    }
+}
+
+impl CsvField for f64 {
+    fn write_field(&self, out: &mut String) {
+        if self.is_finite() {
+            out.push_str(Buffer::new().format_finite(*self));
+        } else if self.is_nan() {
+            out.push_str("NaN");
+        } else if *self > 0.0 {
+            out.push_str("inf");
+        } else {
+            out.push_str("-inf");
+        }
+    }
+}
+
+impl CsvField for f32 {
+    fn write_field(&self, out: &mut String) {
+        f64::from(*self).write_field(out);
+    }
+}
+
+impl CsvField for Epoch {
+    fn write_field(&self, out: &mut String) {
+        let _ = write!(out, "{self}");
+    }
+}
+
+/// A point in time when both ends are equal, `start/end` otherwise.
+impl CsvField for (Epoch, Epoch) {
+    fn write_field(&self, out: &mut String) {
+        self.0.write_field(out);
+        if self.0 != self.1 {
+            out.push('/');
+            self.1.write_field(out);
+        }
+    }
+}
+
+/// `numerator/denominator`, or just the numerator for integers.
+impl CsvField for Ratio<i128> {
+    fn write_field(&self, out: &mut String) {
+        if self.is_integer() {
+            let _ = write!(out, "{}", self.numer());
+        } else {
+            let _ = write!(out, "{}/{}", self.numer(), self.denom());
+        }
+    }
+}
+
+macro_rules! display_fields {
+    ($($t:ty),*) => {$(
+        impl CsvField for $t {
+            fn write_field(&self, out: &mut String) {
+                let _ = write!(out, "{self}");
+            }
+        }
+    )*};
+}
+
+display_fields!(
+    bool,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    ethnum::I256,
+    ethnum::U256
+);
+
+macro_rules! tuple_records {
+    ($($t:ident . $i:tt),+) => {
+        impl<$($t: CsvField),+> CsvRecord for ($($t,)+) {
+            fn write_record(&self, fields: &mut Vec<String>) {
+                $(
+                    let mut field = String::new();
+                    self.$i.write_field(&mut field);
+                    fields.push(field);
+                )+
+            }
+        }
+    };
+}
+
+tuple_records!(A.0);
+tuple_records!(A.0, B.1);
+tuple_records!(A.0, B.1, C.2);
+tuple_records!(A.0, B.1, C.2, D.3);
+tuple_records!(A.0, B.1, C.2, D.3, E.4);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10);
+tuple_records!(A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7, I.8, J.9, K.10, L.11);
+
+/// Writes CSV with a header row.
+#[derive(Debug, Clone)]
+pub struct CsvWriter {
+    delimiter: char,
+}
+
+impl Default for CsvWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvWriter {
+    /// A writer separating fields with `,`.
+    pub fn new() -> Self {
+        Self { delimiter: ',' }
+    }
+
+    /// Separate fields with `delimiter` instead of `,`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write `headers`, then one row per tuple in `rows`, returning the
+    /// number of rows written. Rows are written as given, so sort the
+    /// projection first if the output should be deterministic.
+    pub fn write_rows<R: CsvRecord>(
+        &self,
+        headers: &[&str],
+        rows: impl IntoIterator<Item = R>,
+        out: &mut impl FmtWrite,
+    ) -> usize {
+        self.write_record(headers.iter().copied(), out);
+        let mut fields = Vec::new();
+        let mut count = 0;
+        for row in rows {
+            fields.clear();
+            row.write_record(&mut fields);
+            self.write_record(fields.iter().map(String::as_str), out);
+            count += 1;
+        }
+        count
+    }
+
+    /// Write one row per entity in `entities` (duplicates dropped), with the
+    /// values `set` holds for them, returning the number of rows written.
+    /// `store` must hold the set's long strings and attribute names.
+    pub fn write_entities(
+        &self,
+        set: &TribleSet,
+        entities: impl IntoIterator<Item = Id>,
+        store: &impl BlobStoreGet<Blake3>,
+        out: &mut impl FmtWrite,
+    ) -> Result<usize, ExportError> {
+        let mut ctx = CellCtx {
+            store,
+            strings: HashMap::new(),
+        };
+
+        let mut described: HashMap<Id, (Value<Handle<Blake3, LongString>>, Id)> = HashMap::new();
+        for (attr, name, schema) in find!(
+            (attr: Id, name: Value<Handle<Blake3, LongString>>, schema: Id),
+            pattern!(set, [{ ?attr @ metadata::name: ?name, metadata::value_schema: ?schema }])
+        ) {
+            if renders(schema) {
+                described.insert(attr, (name, schema));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut rows: Vec<(Id, BTreeMap<Id, Vec<String>>)> = Vec::new();
+        let mut columns: HashMap<Id, String> = HashMap::new();
+        for entity in entities {
+            if !seen.insert(entity) {
+                continue;
+            }
+            let mut cells: BTreeMap<Id, Vec<String>> = BTreeMap::new();
+            for (attr, value) in find!(
+                (attr: Id, value: Value<UnknownValue>),
+                temp!((e), and!(e.is(entity.to_value()), set.pattern(e, attr, value)))
+            ) {
+                let Some((name, schema)) = described.get(&attr) else {
+                    continue;
+                };
+                if let Entry::Vacant(column) = columns.entry(attr) {
+                    column.insert(ctx.resolve(*name)?.to_string());
+                }
+                let mut cell = String::new();
+                ctx.write_value(*schema, value, &mut cell)?;
+                cells.entry(attr).or_default().push(cell);
+            }
+            rows.push((entity, cells));
+        }
+
+        let mut columns: Vec<(String, Id)> =
+            columns.into_iter().map(|(attr, name)| (name, attr)).collect();
+        columns.sort();
+
+        let headers = std::iter::once("id").chain(columns.iter().map(|(name, _)| name.as_str()));
+        self.write_record(headers, out);
+        let mut fields = Vec::with_capacity(columns.len() + 1);
+        for (entity, mut cells) in rows {
+            fields.clear();
+            fields.push(format!("{entity:x}"));
+            for (_, attr) in &columns {
+                let mut values = cells.remove(attr).unwrap_or_default();
+                values.sort();
+                fields.push(values.join("\n"));
+            }
+            self.write_record(fields.iter().map(String::as_str), out);
+        }
+        Ok(seen.len())
+    }
+
+    fn write_record<'f>(&self, fields: impl IntoIterator<Item = &'f str>, out: &mut impl FmtWrite) {
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                let _ = out.write_char(self.delimiter);
+            }
+            self.write_escaped(field, out);
+        }
+        let _ = out.write_char('\n');
+    }
+
+    fn write_escaped(&self, field: &str, out: &mut impl FmtWrite) {
+        let quote = field
+            .chars()
+            .any(|c| c == self.delimiter || c == '"' || c == '\n' || c == '\r');
+        if !quote {
+            let _ = out.write_str(field);
+            return;
+        }
+        let _ = out.write_char('"');
+        for (i, part) in field.split('"').enumerate() {
+            if i > 0 {
+                let _ = out.write_str("\"\"");
+            }
+            let _ = out.write_str(part);
+        }
+        let _ = out.write_char('"');
+    }
+}
+
+/// Whether [`CellCtx::write_value`] can render values of `schema`.
+fn renders(schema: Id) -> bool {
+    [
+        GenId::ID,
+        ShortString::ID,
+        Handle::<Blake3, LongString>::ID,
+        I256BE::ID,
+        U256BE::ID,
+        R256BE::ID,
+        F64::ID,
+        Boolean::ID,
+        NsTAIInterval::ID,
+    ]
+    .contains(&schema)
+        || super::displays_text(schema)
+}
+
+struct CellCtx<'a, Store: BlobStoreGet<Blake3>> {
+    store: &'a Store,
+    strings: HashMap<RawValue, View<str>>,
+}
+
+impl<Store: BlobStoreGet<Blake3>> CellCtx<'_, Store> {
+    fn write_value(
+        &mut self,
+        schema: Id,
+        value: Value<UnknownValue>,
+        out: &mut String,
+    ) -> Result<(), ExportError> {
+        if schema == GenId::ID {
+            if let Ok(id) = value.transmute::<GenId>().try_from_value::<Id>() {
+                id.write_field(out);
+            }
+        } else if schema == ShortString::ID {
+            if let Ok(text) = value.transmute::<ShortString>().try_from_value::<String>() {
+                out.push_str(&text);
+            }
+        } else if schema == Handle::<Blake3, LongString>::ID {
+            let text = self.resolve(value.transmute())?;
+            out.push_str(text.as_ref());
+        } else if schema == I256BE::ID {
+            let n: ethnum::I256 = value.transmute::<I256BE>().from_value();
+            n.write_field(out);
+        } else if schema == U256BE::ID {
+            let n: ethnum::U256 = value.transmute::<U256BE>().from_value();
+            n.write_field(out);
+        } else if schema == R256BE::ID {
+            if let Ok(ratio) = value.transmute::<R256BE>().try_from_value::<Ratio<i128>>() {
+                ratio.write_field(out);
+            }
+        } else if schema == F64::ID {
+            let n: f64 = value.transmute::<F64>().from_value();
+            n.write_field(out);
+        } else if schema == Boolean::ID {
+            if let Ok(b) = value.transmute::<Boolean>().try_from_value::<bool>() {
+                b.write_field(out);
+            }
+        } else if schema == NsTAIInterval::ID {
+            if let Ok((lower, upper)) = value
+                .transmute::<NsTAIInterval>()
+                .try_from_value::<(i128, i128)>()
+            {
+                let epoch = |ns| {
+                    Epoch::from_tai_duration(Duration::from_total_nanoseconds(ns))
+                        .to_time_scale(TimeScale::UTC)
+                };
+                (epoch(lower), epoch(upper)).write_field(out);
+            }
+        } else if let Some(text) = super::display_text(schema, value) {
+            out.push_str(&text);
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        &mut self,
+        handle: Value<Handle<Blake3, LongString>>,
+    ) -> Result<View<str>, ExportError> {
+        if let Some(cached) = self.strings.get(&handle.raw) {
+            return Ok(cached.clone());
+        }
+        let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+        let text: View<str> = self
+            .store
+            .get::<View<str>, LongString>(handle)
+            .map_err(|err| ExportError::BlobStore {
+                hash: hex::encode(hash.raw),
+                source: err.to_string(),
+            })?;
+        self.strings.insert(handle.raw, text.clone());
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsvWriter;
+
+    #[test]
+    fn quotes_only_when_needed() {
+        let mut out = String::new();
+        let rows = [("plain", 1.5f64), ("a,b", 2.0), ("say \"hi\"", f64::NAN), ("two\nlines", -0.0)];
+        CsvWriter::new().write_rows(&["text", "n"], rows, &mut out);
+        assert_eq!(
+            out,
+            "text,n\nplain,1.5\n\"a,b\",2.0\n\"say \"\"hi\"\"\",NaN\n\"two\nlines\",-0.0\n"
+        );
+
+        let mut out = String::new();
+        CsvWriter::new()
+            .with_delimiter(';')
+            .write_rows(&["a;b"], [("a,b",)], &mut out);
+        assert_eq!(out, "\"a;b\"\na,b\n");
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::{HashMap, HashSet};
-use std::fmt;
 use std::fmt::Write as FmtWrite;
 
 use crate::and;
@@ -23,37 +22,7 @@ use crate::value::Value;
 use anybytes::View;
 use ryu::Buffer;
 
-/// Error returned by [`export_to_json`].
-#[derive(Debug)]
-pub enum ExportError {
-    /// The blob handle has no corresponding entry in the blob store.
-    MissingBlob {
-        /// Hex-encoded hash of the missing blob.
-        hash: String,
-    },
-    /// The blob store returned an error while loading the blob.
-    BlobStore {
-        /// Hex-encoded hash of the blob.
-        hash: String,
-        /// Stringified underlying error.
-        source: String,
-    },
-}
-
-impl fmt::Display for ExportError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::MissingBlob { hash } => {
-                write!(f, "missing blob for handle hash {hash}")
-            }
-            Self::BlobStore { hash, source } => {
-                write!(f, "failed to load blob {hash}: {source}")
-            }
-        }
-    }
-}
-
-impl std::error::Error for ExportError {}
+pub use super::ExportError;
 
 /// Streamed exporter that writes JSON text directly (avoids serde_json Numbers).
 pub fn export_to_json(
@@ -212,11 +181,14 @@ fn render_schema_value(
         write_escaped_str(text.as_ref(), out);
         return Ok(());
     }
+    if let Some(text) = super::display_text(schema, value) {
+        write_escaped_str(&text, out);
+    }
 
     Ok(())
 }
 
-fn write_escaped_str(text: &str, out: &mut impl FmtWrite) {
+pub(super) fn write_escaped_str(text: &str, out: &mut impl FmtWrite) {
     let _ = out.write_char('"');
     let bytes = text.as_bytes();
     let mut idx = 0;
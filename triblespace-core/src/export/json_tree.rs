@@ -0,0 +1,263 @@
+//! Lossless JSON exporter, the inverse of [`crate::import::json_tree`].
+//!
+//! [`export_json_tree`] walks the node/entry graph written by
+//! [`JsonTreeImporter`](crate::import::json_tree::JsonTreeImporter) and
+//! rebuilds the document: object fields in their original order (duplicate
+//! keys included), array elements by index, numbers from their raw text,
+//! and primitive roots. Output is compact, and strings are escaped the same
+//! way as [`export_to_json`](super::json::export_to_json), so a compact
+//! document using those escapes exports back to exactly the text it was
+//! imported from. Insignificant whitespace and alternative escapes
+//! (`\/`, `\u00e9`) are not recorded by the importer and normalise away.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+
+use anybytes::View;
+
+use crate::blob::schemas::longstring::LongString;
+use crate::id::Id;
+use crate::import::json_tree::{
+    array_index, array_parent, array_value, boolean, field_index, field_name, field_parent,
+    field_value, kind, kind_array, kind_array_entry, kind_bool, kind_field, kind_null,
+    kind_number, kind_object, kind_string, number_raw, string,
+};
+use crate::prelude::{find, pattern};
+use crate::repo::BlobStoreGet;
+use crate::trible::TribleSet;
+use crate::value::schemas::hash::{Blake3, Handle, Hash};
+use crate::value::{RawValue, Value};
+
+use super::json::write_escaped_str;
+use super::ExportError;
+
+/// Write the JSON value rooted at `root` (the root of a
+/// [`JsonTreeImporter`](crate::import::json_tree::JsonTreeImporter)
+/// fragment) as compact JSON text. `store` must hold the string, number and
+/// field-name blobs of the import.
+///
+/// Fails with [`ExportError::Malformed`] when a node lacks its kind or
+/// payload, an entry index repeats, or the graph is cyclic.
+pub fn export_json_tree(
+    merged: &TribleSet,
+    root: Id,
+    store: &impl BlobStoreGet<Blake3>,
+    out: &mut impl FmtWrite,
+) -> Result<(), ExportError> {
+    let mut ctx = TreeCtx {
+        merged,
+        store,
+        strings: HashMap::new(),
+        ancestors: HashSet::new(),
+    };
+    ctx.write_node(root, out)
+}
+
+type TextHandle = Value<Handle<Blake3, LongString>>;
+
+struct TreeCtx<'a, Store: BlobStoreGet<Blake3>> {
+    merged: &'a TribleSet,
+    store: &'a Store,
+    strings: HashMap<RawValue, View<str>>,
+    ancestors: HashSet<Id>,
+}
+
+impl<Store: BlobStoreGet<Blake3>> TreeCtx<'_, Store> {
+    fn write_node(&mut self, node: Id, out: &mut impl FmtWrite) -> Result<(), ExportError> {
+        let merged = self.merged;
+        let malformed = |reason| ExportError::Malformed {
+            entity: node,
+            reason,
+        };
+        let node_kind = single(find!(
+            (k: Id),
+            pattern!(merged, [{ node @ kind: ?k }])
+        ))
+        .map_err(|many| malformed(if many { "multiple kinds" } else { "missing kind" }))?;
+
+        if node_kind == kind_null {
+            let _ = out.write_str("null");
+        } else if node_kind == kind_bool {
+            let b = single(find!(
+                (b: bool),
+                pattern!(merged, [{ node @ boolean: ?b }])
+            ))
+            .map_err(|_| malformed("boolean node without a single value"))?;
+            let _ = out.write_str(if b { "true" } else { "false" });
+        } else if node_kind == kind_string {
+            let handle = single(find!(
+                (h: Value<Handle<Blake3, LongString>>),
+                pattern!(merged, [{ node @ string: ?h }])
+            ))
+            .map_err(|_| malformed("string node without a single value"))?;
+            let text = self.resolve(handle)?;
+            write_escaped_str(text.as_ref(), out);
+        } else if node_kind == kind_number {
+            let handle = single(find!(
+                (h: Value<Handle<Blake3, LongString>>),
+                pattern!(merged, [{ node @ number_raw: ?h }])
+            ))
+            .map_err(|_| malformed("number node without a single value"))?;
+            let text = self.resolve(handle)?;
+            let _ = out.write_str(text.as_ref());
+        } else if node_kind == kind_object {
+            let mut fields: Vec<(u64, TextHandle, Id)> = find!(
+                (index: u64, name: Value<Handle<Blake3, LongString>>, value: Id),
+                pattern!(merged, [{
+                    _?entry @
+                    kind: kind_field,
+                    field_parent: node,
+                    field_index: ?index,
+                    field_name: ?name,
+                    field_value: ?value,
+                }])
+            )
+            .collect();
+            fields.sort_by_key(|(index, _, _)| *index);
+            if fields.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(malformed("repeated field index"));
+            }
+
+            self.enter(node)?;
+            let _ = out.write_char('{');
+            for (i, (_, name, value)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    let _ = out.write_char(',');
+                }
+                let name = self.resolve(name)?;
+                write_escaped_str(name.as_ref(), out);
+                let _ = out.write_char(':');
+                self.write_node(value, out)?;
+            }
+            let _ = out.write_char('}');
+            self.ancestors.remove(&node);
+        } else if node_kind == kind_array {
+            let mut entries: Vec<(u64, Id)> = find!(
+                (index: u64, value: Id),
+                pattern!(merged, [{
+                    _?entry @
+                    kind: kind_array_entry,
+                    array_parent: node,
+                    array_index: ?index,
+                    array_value: ?value,
+                }])
+            )
+            .collect();
+            entries.sort_by_key(|(index, _)| *index);
+            if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(malformed("repeated array index"));
+            }
+
+            self.enter(node)?;
+            let _ = out.write_char('[');
+            for (i, (_, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    let _ = out.write_char(',');
+                }
+                self.write_node(value, out)?;
+            }
+            let _ = out.write_char(']');
+            self.ancestors.remove(&node);
+        } else {
+            return Err(malformed("not a JSON value node"));
+        }
+        Ok(())
+    }
+
+    /// Mark `node` as being written, rejecting cycles.
+    fn enter(&mut self, node: Id) -> Result<(), ExportError> {
+        if self.ancestors.insert(node) {
+            Ok(())
+        } else {
+            Err(ExportError::Malformed {
+                entity: node,
+                reason: "node contains itself",
+            })
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        handle: Value<Handle<Blake3, LongString>>,
+    ) -> Result<View<str>, ExportError> {
+        if let Some(cached) = self.strings.get(&handle.raw) {
+            return Ok(cached.clone());
+        }
+        let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+        let text: View<str> = self
+            .store
+            .get::<View<str>, LongString>(handle)
+            .map_err(|err| ExportError::BlobStore {
+                hash: hex::encode(hash.raw),
+                source: err.to_string(),
+            })?;
+        self.strings.insert(handle.raw, text.clone());
+        Ok(text)
+    }
+}
+
+/// The only item of `iter`; `Err(true)` if there are several, `Err(false)`
+/// if there are none.
+fn single<T, I: Iterator<Item = (T,)>>(mut iter: I) -> Result<T, bool> {
+    let (first,) = iter.next().ok_or(false)?;
+    match iter.next() {
+        Some(_) => Err(true),
+        None => Ok(first),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::export_json_tree;
+    use crate::blob::MemoryBlobStore;
+    use crate::export::ExportError;
+    use crate::import::json_tree::JsonTreeImporter;
+    use crate::repo::BlobStore;
+    use crate::value::schemas::hash::Blake3;
+
+    fn roundtrip(input: &str) -> String {
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let fragment = JsonTreeImporter::<_, Blake3>::new(&mut blobs, None)
+            .import_str(input)
+            .unwrap();
+        let root = fragment.root().expect("rooted fragment");
+        let mut out = String::new();
+        export_json_tree(fragment.facts(), root, &blobs.reader().unwrap(), &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn reproduces_compact_documents() {
+        for input in [
+            r#"{"b":1,"a":[true,false,null],"b":1.50e+3}"#,
+            r#"[[],{},"",[[0]],-0.0]"#,
+            r#""café \"quoted\"\n""#,
+            "12345678901234567890123456789",
+            "null",
+        ] {
+            assert_eq!(roundtrip(input), input);
+        }
+    }
+
+    #[test]
+    fn drops_insignificant_whitespace() {
+        assert_eq!(
+            roundtrip("{ \"a\" : [ 1 , 2 ] ,\n \"b\" : { } }"),
+            r#"{"a":[1,2],"b":{}}"#
+        );
+    }
+
+    #[test]
+    fn unknown_root_is_malformed() {
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let mut out = String::new();
+        let err = export_json_tree(
+            &Default::default(),
+            *crate::id::rngid(),
+            &blobs.reader().unwrap(),
+            &mut out,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ExportError::Malformed { .. }));
+    }
+}
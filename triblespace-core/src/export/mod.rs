@@ -1,4 +1,113 @@
 //! Export utilities for serialising trible data into external formats.
 
+use std::fmt;
+
+use crate::id::Id;
+use crate::metadata::ConstId;
+use crate::value::schemas::calendar::LocalDate;
+use crate::value::schemas::geopoint::GeoPoint;
+use crate::value::schemas::net::{Ipv4Addr, Ipv6Addr, MacAddr, SocketAddr};
+use crate::value::schemas::uuid::Uuid;
+use crate::value::schemas::UnknownValue;
+use crate::value::Value;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "csv")]
+pub mod csv;
 /// JSON export utilities for trible data.
 pub mod json;
+pub mod json_tree;
+pub mod rdf;
+pub mod safetensors;
+
+/// Error returned by the exporters in this module.
+#[derive(Debug)]
+pub enum ExportError {
+    /// The blob handle has no corresponding entry in the blob store.
+    MissingBlob {
+        /// Hex-encoded hash of the missing blob.
+        hash: String,
+    },
+    /// The blob store returned an error while loading the blob.
+    BlobStore {
+        /// Hex-encoded hash of the blob.
+        hash: String,
+        /// Stringified underlying error.
+        source: String,
+    },
+    /// An entity does not have the structure the exporter expects.
+    Malformed {
+        /// The offending entity.
+        entity: Id,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBlob { hash } => {
+                write!(f, "missing blob for handle hash {hash}")
+            }
+            Self::BlobStore { hash, source } => {
+                write!(f, "failed to load blob {hash}: {source}")
+            }
+            Self::Malformed { entity, reason } => {
+                write!(f, "malformed entity {entity:x}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// The conventional text form of values whose schema exporters render as
+/// plain strings (UUIDs, network addresses, `lat,lon` geo points and
+/// `YYYY-MM-DD` dates), or `None` for any other schema and for malformed
+/// values.
+pub(crate) fn display_text(schema: Id, value: Value<UnknownValue>) -> Option<String> {
+    if schema == Uuid::ID {
+        let uuid: uuid::Uuid = value.transmute::<Uuid>().try_from_value().ok()?;
+        Some(uuid.hyphenated().to_string())
+    } else if schema == Ipv4Addr::ID {
+        let addr: std::net::Ipv4Addr = value.transmute::<Ipv4Addr>().try_from_value().ok()?;
+        Some(addr.to_string())
+    } else if schema == Ipv6Addr::ID {
+        let addr: std::net::IpAddr = value.transmute::<Ipv6Addr>().try_from_value().ok()?;
+        Some(addr.to_string())
+    } else if schema == SocketAddr::ID {
+        let addr: std::net::SocketAddr =
+            value.transmute::<SocketAddr>().try_from_value().ok()?;
+        Some(addr.to_string())
+    } else if schema == MacAddr::ID {
+        let octets: [u8; 6] = value.transmute::<MacAddr>().try_from_value().ok()?;
+        let hex: Vec<String> = octets.iter().map(|b| format!("{b:02x}")).collect();
+        Some(hex.join(":"))
+    } else if schema == GeoPoint::ID {
+        let (lat, lon): (f64, f64) = value.transmute::<GeoPoint>().try_from_value().ok()?;
+        Some(format!("{lat},{lon}"))
+    } else if schema == LocalDate::ID {
+        let (year, month, day): (i32, u8, u8) =
+            value.transmute::<LocalDate>().try_from_value().ok()?;
+        Some(format!("{year:04}-{month:02}-{day:02}"))
+    } else {
+        None
+    }
+}
+
+/// Whether [`display_text`] renders values of `schema`.
+#[cfg(feature = "csv")]
+pub(crate) fn displays_text(schema: Id) -> bool {
+    [
+        Uuid::ID,
+        Ipv4Addr::ID,
+        Ipv6Addr::ID,
+        SocketAddr::ID,
+        MacAddr::ID,
+        GeoPoint::ID,
+        LocalDate::ID,
+    ]
+    .contains(&schema)
+}
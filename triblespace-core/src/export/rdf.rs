@@ -0,0 +1,653 @@
+//! TribleSpace → RDF exporter.
+//!
+//! The inverse of [`crate::import::ntriples`]: [`RdfWriter`] serialises a
+//! [`TribleSet`] as N-Triples, Turtle or N-Quads. Entities that carry an
+//! [`rdf_uri`] edge are written under that URI; every other entity is a
+//! blank node labelled with its hex id.
+//!
+//! Predicate IRIs come from the attribute descriptions in the exported set
+//! (`metadata::name` + `metadata::value_schema`, which the RDF importers
+//! emit for every predicate they see) or from IRIs registered with
+//! [`RdfWriter::with_predicate`] for data imported without them; an
+//! attribute without a name is written as `urn:trible:<hex id>`. Values map
+//! back to RDF terms by schema:
+//!
+//! - `GenId` → the referenced entity
+//! - `Handle<Blake3, LongString>` / `ShortString` → plain string literal,
+//!   language-tagged when the attribute is named `"<predicate> @<tag>"`
+//! - `I256BE` → `xsd:integer`
+//! - `U256BE` → `xsd:nonNegativeInteger`
+//! - `R256BE` → `xsd:decimal`, or `owl:rational` when the ratio has no
+//!   finite decimal expansion
+//! - `F64` → `xsd:double`
+//! - `Boolean` → `xsd:boolean`
+//!
+//! Attributes with any other schema are skipped. The `rdf_uri` edges and the
+//! predicate descriptions themselves are bookkeeping rather than statements
+//! and are not written, so an imported N-Triples file exports back to the
+//! statements it was read from (up to blank-node labels).
+//!
+//! N-Quads output takes an optional graph IRI per call; [`commit_graph`] and
+//! [`branch_graph`] name a commit's content or a branch's checkout so a
+//! history can be written as one named graph per commit:
+//!
+//! ```rust,ignore
+//! let mut writer = RdfWriter::new(&reader);
+//! for raw in ws.checkout(..)?.commits().iter() {
+//!     let commit = Value::new(*raw);
+//!     let facts = ws.checkout(commit)?;
+//!     writer.write_nquads(&facts, Some(&commit_graph(commit)), &mut out)?;
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+
+use anybytes::View;
+use num_rational::Ratio;
+use ryu::Buffer;
+
+use crate::attribute::Attribute;
+use crate::blob::schemas::longstring::LongString;
+use crate::id::Id;
+use crate::import::rdf_uri;
+use crate::metadata;
+use crate::metadata::ConstId;
+use crate::prelude::{find, pattern};
+use crate::repo::{BlobStoreGet, CommitHandle};
+use crate::trible::TribleSet;
+use crate::value::schemas::boolean::Boolean;
+use crate::value::schemas::f64::F64;
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, Handle, Hash};
+use crate::value::schemas::iu256::{I256BE, U256BE};
+use crate::value::schemas::r256::R256BE;
+use crate::value::schemas::shortstring::ShortString;
+use crate::value::schemas::UnknownValue;
+use crate::value::{RawValue, Value, ValueSchema};
+
+use super::ExportError;
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_NON_NEGATIVE_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#nonNegativeInteger";
+const XSD_DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const OWL_RATIONAL: &str = "http://www.w3.org/2002/07/owl#rational";
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// IRI for an attribute without a name.
+fn fallback_iri(id: Id) -> String {
+    format!("urn:trible:{id:x}")
+}
+
+/// Graph IRI naming the content of `commit` in N-Quads output.
+pub fn commit_graph(commit: CommitHandle) -> String {
+    format!("urn:trible:commit:{}", hex::encode(commit.raw))
+}
+
+/// Graph IRI naming the checkout of `branch` in N-Quads output.
+pub fn branch_graph(branch: Id) -> String {
+    format!("urn:trible:branch:{branch:x}")
+}
+
+// ── Statements ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Term {
+    Iri(String),
+    Blank(Id),
+    Literal {
+        lexical: String,
+        datatype: Option<&'static str>,
+        language: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Statement {
+    subject: Term,
+    predicate: String,
+    object: Term,
+}
+
+/// Serialises [`TribleSet`]s as RDF.
+///
+/// The writer borrows the blob store that holds the set's string blobs
+/// (URIs, predicate names, text literals) and caches every string it
+/// loads, so reuse one writer across graphs of the same dataset.
+/// Statements are written sorted and deduplicated, so the output is
+/// deterministic for a given set.
+pub struct RdfWriter<'a, Store: BlobStoreGet<Blake3>> {
+    store: &'a Store,
+    prefixes: Vec<(String, String)>,
+    predicates: HashMap<Id, (String, Id)>,
+    strings: HashMap<RawValue, View<str>>,
+}
+
+impl<'a, Store: BlobStoreGet<Blake3>> RdfWriter<'a, Store> {
+    /// Create a writer reading blobs from `store`. The `xsd` prefix is
+    /// predeclared for Turtle output.
+    pub fn new(store: &'a Store) -> Self {
+        Self {
+            store,
+            prefixes: vec![("xsd".to_owned(), XSD.to_owned())],
+            predicates: HashMap::new(),
+            strings: HashMap::new(),
+        }
+    }
+
+    /// Declare a Turtle prefix: IRIs starting with `namespace` are
+    /// abbreviated to `prefix:local` where the local part allows it.
+    /// Re-declaring a prefix replaces its namespace.
+    pub fn with_prefix(mut self, prefix: &str, namespace: &str) -> Self {
+        self.prefixes.retain(|(p, _)| p != prefix);
+        self.prefixes.push((prefix.to_owned(), namespace.to_owned()));
+        self
+    }
+
+    /// Register `iri` as a predicate, covering the attribute
+    /// [`Attribute::from_name`] derives from it under every exportable
+    /// schema. Needed for sets that lack the predicate's description.
+    pub fn with_predicate(mut self, iri: &str) -> Self {
+        self.register::<GenId>(iri);
+        self.register::<Handle<Blake3, LongString>>(iri);
+        self.register::<ShortString>(iri);
+        self.register::<I256BE>(iri);
+        self.register::<U256BE>(iri);
+        self.register::<R256BE>(iri);
+        self.register::<F64>(iri);
+        self.register::<Boolean>(iri);
+        self
+    }
+
+    fn register<S: ValueSchema>(&mut self, iri: &str) {
+        let attr = Attribute::<S>::from_name(iri);
+        self.predicates.insert(attr.id(), (iri.to_owned(), S::ID));
+    }
+
+    /// Write `set` as N-Triples, returning the number of statements.
+    pub fn write_ntriples(
+        &mut self,
+        set: &TribleSet,
+        out: &mut impl FmtWrite,
+    ) -> Result<usize, ExportError> {
+        self.write_nquads(set, None, out)
+    }
+
+    /// Write `set` as N-Quads in the named graph `graph` (the default
+    /// graph for `None`, which makes the output plain N-Triples),
+    /// returning the number of statements.
+    pub fn write_nquads(
+        &mut self,
+        set: &TribleSet,
+        graph: Option<&str>,
+        out: &mut impl FmtWrite,
+    ) -> Result<usize, ExportError> {
+        let statements = self.statements(set)?;
+        for statement in &statements {
+            self.write_term(&statement.subject, false, out);
+            let _ = out.write_char(' ');
+            write_iri(&statement.predicate, out);
+            let _ = out.write_char(' ');
+            self.write_term(&statement.object, false, out);
+            if let Some(graph) = graph {
+                let _ = out.write_char(' ');
+                write_iri(graph, out);
+            }
+            let _ = out.write_str(" .\n");
+        }
+        Ok(statements.len())
+    }
+
+    /// Write `set` as Turtle: the declared prefixes, then one block per
+    /// subject with its predicates and objects grouped. Returns the number
+    /// of statements.
+    pub fn write_turtle(
+        &mut self,
+        set: &TribleSet,
+        out: &mut impl FmtWrite,
+    ) -> Result<usize, ExportError> {
+        let statements = self.statements(set)?;
+        for (prefix, namespace) in &self.prefixes {
+            let _ = write!(out, "@prefix {prefix}: ");
+            write_iri(namespace, out);
+            let _ = out.write_str(" .\n");
+        }
+
+        let mut previous: Option<&Statement> = None;
+        for statement in &statements {
+            match previous {
+                Some(p) if p.subject == statement.subject && p.predicate == statement.predicate => {
+                    let _ = out.write_str(", ");
+                }
+                Some(p) if p.subject == statement.subject => {
+                    let _ = out.write_str(" ;\n    ");
+                    self.write_turtle_predicate(&statement.predicate, out);
+                    let _ = out.write_char(' ');
+                }
+                _ => {
+                    if previous.is_some() {
+                        let _ = out.write_str(" .\n");
+                    }
+                    let _ = out.write_char('\n');
+                    self.write_term(&statement.subject, true, out);
+                    let _ = out.write_char(' ');
+                    self.write_turtle_predicate(&statement.predicate, out);
+                    let _ = out.write_char(' ');
+                }
+            }
+            self.write_term(&statement.object, true, out);
+            previous = Some(statement);
+        }
+        if previous.is_some() {
+            let _ = out.write_str(" .\n");
+        }
+        Ok(statements.len())
+    }
+
+    /// Write `term`, abbreviating IRIs through the prefixes if `turtle`.
+    fn write_term(&self, term: &Term, turtle: bool, out: &mut impl FmtWrite) {
+        let iri = |iri: &str, out: &mut _| {
+            if turtle {
+                self.write_turtle_iri(iri, out);
+            } else {
+                write_iri(iri, out);
+            }
+        };
+        match term {
+            Term::Iri(i) => iri(i, out),
+            Term::Blank(id) => {
+                let _ = write!(out, "_:{id:x}");
+            }
+            Term::Literal {
+                lexical,
+                datatype,
+                language,
+            } => {
+                write_literal(lexical, out);
+                if let Some(language) = language {
+                    let _ = write!(out, "@{language}");
+                } else if let Some(datatype) = datatype {
+                    let _ = out.write_str("^^");
+                    iri(datatype, out);
+                }
+            }
+        }
+    }
+
+    fn write_turtle_predicate(&self, iri: &str, out: &mut impl FmtWrite) {
+        if iri == RDF_TYPE {
+            let _ = out.write_char('a');
+        } else {
+            self.write_turtle_iri(iri, out);
+        }
+    }
+
+    fn write_turtle_iri(&self, iri: &str, out: &mut impl FmtWrite) {
+        let abbreviated = self
+            .prefixes
+            .iter()
+            .filter_map(|(prefix, namespace)| {
+                let local = iri.strip_prefix(namespace.as_str())?;
+                is_local_name(local).then_some((prefix, namespace.len(), local))
+            })
+            .max_by_key(|(_, len, _)| *len);
+        match abbreviated {
+            Some((prefix, _, local)) => {
+                let _ = write!(out, "{prefix}:{local}");
+            }
+            None => write_iri(iri, out),
+        }
+    }
+
+    /// Resolve every exportable trible in `set` into a sorted,
+    /// deduplicated statement list.
+    fn statements(&mut self, set: &TribleSet) -> Result<Vec<Statement>, ExportError> {
+        let mut uris: HashMap<Id, Value<Handle<Blake3, LongString>>> = HashMap::new();
+        for (entity, uri) in find!(
+            (entity: Id, uri: Value<Handle<Blake3, LongString>>),
+            pattern!(set, [{ ?entity @ rdf_uri: ?uri }])
+        ) {
+            uris.insert(entity, uri);
+        }
+
+        let mut predicates: HashMap<Id, (String, Id)> = self.predicates.clone();
+        let mut languages: HashMap<Id, String> = HashMap::new();
+        let mut described: HashSet<Id> = HashSet::new();
+        let mut names: HashMap<Id, Value<Handle<Blake3, LongString>>> = HashMap::new();
+        for (attr, name) in find!(
+            (attr: Id, name: Value<Handle<Blake3, LongString>>),
+            pattern!(set, [{ ?attr @ metadata::name: ?name }])
+        ) {
+            names.insert(attr, name);
+        }
+        for (attr, schema) in find!(
+            (attr: Id, schema: Id),
+            pattern!(set, [{ ?attr @ metadata::value_schema: ?schema }])
+        ) {
+            described.insert(attr);
+            let iri = match names.get(&attr) {
+                Some(name) => {
+                    let name = self.resolve(*name)?;
+                    match language_tagged(&name) {
+                        Some((iri, language)) => {
+                            languages.insert(attr, language.to_owned());
+                            iri.to_owned()
+                        }
+                        None => name.to_string(),
+                    }
+                }
+                None => match self.predicates.get(&attr) {
+                    Some((iri, _)) => iri.clone(),
+                    None => fallback_iri(attr),
+                },
+            };
+            predicates.insert(attr, (iri, schema));
+        }
+
+        let bookkeeping = [rdf_uri.id(), metadata::name.id(), metadata::value_schema.id()];
+        let mut statements = Vec::new();
+        for trible in set.iter() {
+            let (entity, attr) = (*trible.e(), *trible.a());
+            if attr == rdf_uri.id() || (described.contains(&entity) && bookkeeping.contains(&attr))
+            {
+                continue;
+            }
+            let Some((predicate, schema)) = predicates.get(&attr) else {
+                continue;
+            };
+            let Some(mut object) = self.term(*schema, trible.v::<UnknownValue>(), &uris)? else {
+                continue;
+            };
+            if let Term::Literal {
+                datatype: None,
+                language,
+                ..
+            } = &mut object
+            {
+                *language = languages.get(&attr).cloned();
+            }
+            let subject = self.node(entity, &uris)?;
+            statements.push(Statement {
+                subject,
+                predicate: predicate.clone(),
+                object,
+            });
+        }
+        statements.sort();
+        statements.dedup();
+        Ok(statements)
+    }
+
+    fn node(
+        &mut self,
+        entity: Id,
+        uris: &HashMap<Id, Value<Handle<Blake3, LongString>>>,
+    ) -> Result<Term, ExportError> {
+        match uris.get(&entity) {
+            Some(uri) => Ok(Term::Iri(self.resolve(*uri)?.to_string())),
+            None => Ok(Term::Blank(entity)),
+        }
+    }
+
+    fn term(
+        &mut self,
+        schema: Id,
+        value: &Value<UnknownValue>,
+        uris: &HashMap<Id, Value<Handle<Blake3, LongString>>>,
+    ) -> Result<Option<Term>, ExportError> {
+        let typed = |lexical: String, datatype: &'static str| Term::Literal {
+            lexical,
+            datatype: Some(datatype),
+            language: None,
+        };
+        let term = if schema == GenId::ID {
+            match value.transmute::<GenId>().try_from_value::<Id>() {
+                Ok(target) => Some(self.node(target, uris)?),
+                Err(_) => None,
+            }
+        } else if schema == Handle::<Blake3, LongString>::ID {
+            let text = self.resolve(value.transmute::<Handle<Blake3, LongString>>())?;
+            Some(Term::Literal {
+                lexical: text.to_string(),
+                datatype: None,
+                language: None,
+            })
+        } else if schema == ShortString::ID {
+            value
+                .transmute::<ShortString>()
+                .try_from_value::<String>()
+                .ok()
+                .map(|lexical| Term::Literal {
+                    lexical,
+                    datatype: None,
+                    language: None,
+                })
+        } else if schema == I256BE::ID {
+            let n: ethnum::I256 = value.transmute::<I256BE>().from_value();
+            Some(typed(n.to_string(), XSD_INTEGER))
+        } else if schema == U256BE::ID {
+            let n: ethnum::U256 = value.transmute::<U256BE>().from_value();
+            Some(typed(n.to_string(), XSD_NON_NEGATIVE_INTEGER))
+        } else if schema == R256BE::ID {
+            value
+                .transmute::<R256BE>()
+                .try_from_value::<Ratio<i128>>()
+                .ok()
+                .map(|ratio| match decimal_lexical(&ratio) {
+                    Some(lexical) => typed(lexical, XSD_DECIMAL),
+                    None => typed(format!("{}/{}", ratio.numer(), ratio.denom()), OWL_RATIONAL),
+                })
+        } else if schema == F64::ID {
+            let n: f64 = value.transmute::<F64>().from_value();
+            Some(typed(double_lexical(n), XSD_DOUBLE))
+        } else if schema == Boolean::ID {
+            value
+                .transmute::<Boolean>()
+                .try_from_value::<bool>()
+                .ok()
+                .map(|b| typed(b.to_string(), XSD_BOOLEAN))
+        } else {
+            super::display_text(schema, *value).map(|lexical| Term::Literal {
+                lexical,
+                datatype: None,
+                language: None,
+            })
+        };
+        Ok(term)
+    }
+
+    fn resolve(&mut self, handle: Value<Handle<Blake3, LongString>>) -> Result<View<str>, ExportError> {
+        if let Some(cached) = self.strings.get(&handle.raw) {
+            return Ok(cached.clone());
+        }
+
+        let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+        let text: View<str> = self
+            .store
+            .get::<View<str>, LongString>(handle)
+            .map_err(|err| ExportError::BlobStore {
+                hash: hex::encode(hash.raw),
+                source: err.to_string(),
+            })?;
+        self.strings.insert(handle.raw, text.clone());
+        Ok(text)
+    }
+}
+
+// ── Lexical forms ───────────────────────────────────────────────────
+
+/// The `xsd:decimal` form of `ratio`, or `None` if its denominator has
+/// prime factors other than 2 and 5 (or scaling it overflows).
+fn decimal_lexical(ratio: &Ratio<i128>) -> Option<String> {
+    let (numer, denom) = (*ratio.numer(), *ratio.denom());
+    let mut rest = denom;
+    let (mut twos, mut fives) = (0u32, 0u32);
+    while rest % 2 == 0 {
+        rest /= 2;
+        twos += 1;
+    }
+    while rest % 5 == 0 {
+        rest /= 5;
+        fives += 1;
+    }
+    if rest != 1 {
+        return None;
+    }
+    let scale = twos.max(fives);
+    let scaled = numer.checked_mul(10i128.checked_pow(scale)? / denom)?;
+    let sign = if scaled < 0 { "-" } else { "" };
+    let digits = scaled.unsigned_abs().to_string();
+    if scale == 0 {
+        return Some(format!("{sign}{digits}"));
+    }
+    let scale = scale as usize;
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    Some(format!("{sign}{int}.{frac}"))
+}
+
+fn double_lexical(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_owned()
+    } else if n.is_infinite() {
+        if n > 0.0 { "INF" } else { "-INF" }.to_owned()
+    } else {
+        Buffer::new().format_finite(n).to_owned()
+    }
+}
+
+/// Split an attribute name of the form `"<predicate> @<tag>"`, which the
+/// RDF importers give language-tagged literals, into the predicate IRI
+/// and the tag. The predicate must be free of whitespace, as an IRI is,
+/// and the tag a lowercase BCP 47 tag such as `en` or `pt-br`; any other
+/// name is a plain predicate.
+fn language_tagged(name: &str) -> Option<(&str, &str)> {
+    let (iri, tag) = name.rsplit_once(" @")?;
+    let subtag = |s: &str| (1..=8).contains(&s.len());
+    let mut subtags = tag.split('-');
+    let primary = subtags.next()?;
+    let valid = !iri.is_empty()
+        && !iri.contains(char::is_whitespace)
+        && subtag(primary)
+        && primary.bytes().all(|b| b.is_ascii_lowercase())
+        && subtags.all(|s| {
+            subtag(s) && s.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        });
+    valid.then_some((iri, tag))
+}
+
+/// Whether `local` can follow `prefix:` in Turtle without escaping.
+/// Conservative: ASCII alphanumerics, `_` and `-`, not starting with `-`.
+fn is_local_name(local: &str) -> bool {
+    !local.starts_with('-')
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn write_iri(iri: &str, out: &mut impl FmtWrite) {
+    let _ = out.write_char('<');
+    for c in iri.chars() {
+        match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' | '\0'..=' ' => {
+                let _ = write!(out, "\\u{:04X}", c as u32);
+            }
+            c => {
+                let _ = out.write_char(c);
+            }
+        }
+    }
+    let _ = out.write_char('>');
+}
+
+fn write_literal(text: &str, out: &mut impl FmtWrite) {
+    let _ = out.write_char('"');
+    for c in text.chars() {
+        match c {
+            '"' => {
+                let _ = out.write_str("\\\"");
+            }
+            '\\' => {
+                let _ = out.write_str("\\\\");
+            }
+            '\n' => {
+                let _ = out.write_str("\\n");
+            }
+            '\r' => {
+                let _ = out.write_str("\\r");
+            }
+            '\t' => {
+                let _ = out.write_str("\\t");
+            }
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04X}", c as u32);
+            }
+            c => {
+                let _ = out.write_char(c);
+            }
+        }
+    }
+    let _ = out.write_char('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(n: i128, d: i128) -> Option<String> {
+        decimal_lexical(&Ratio::new(n, d))
+    }
+
+    #[test]
+    fn decimals_expand_exactly() {
+        assert_eq!(decimal(157, 50).as_deref(), Some("3.14"));
+        assert_eq!(decimal(-1, 2).as_deref(), Some("-0.5"));
+        assert_eq!(decimal(1, 8).as_deref(), Some("0.125"));
+        assert_eq!(decimal(42, 1).as_deref(), Some("42"));
+        assert_eq!(decimal(1, 3), None);
+    }
+
+    #[test]
+    fn doubles_use_xsd_special_values() {
+        assert_eq!(double_lexical(2.5), "2.5");
+        assert_eq!(double_lexical(f64::NEG_INFINITY), "-INF");
+        assert_eq!(double_lexical(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn escapes_iris_and_literals() {
+        let mut out = String::new();
+        write_iri("http://ex/a b", &mut out);
+        write_literal("say \"hi\"\n\u{1}", &mut out);
+        assert_eq!(out, r#"<http://ex/a\u0020b>"say \"hi\"\n\u0001""#);
+    }
+
+    #[test]
+    fn language_tags_are_parsed_strictly() {
+        assert_eq!(
+            language_tagged("http://ex/label @en"),
+            Some(("http://ex/label", "en"))
+        );
+        assert_eq!(
+            language_tagged("http://ex/label @pt-br"),
+            Some(("http://ex/label", "pt-br"))
+        );
+        assert_eq!(language_tagged("http://ex/label"), None);
+        assert_eq!(language_tagged("meet @ noon"), None);
+        assert_eq!(language_tagged("a note @ home @en"), None);
+        assert_eq!(language_tagged("http://ex/label @EN"), None);
+        assert_eq!(language_tagged("http://ex/label @en-"), None);
+        assert_eq!(language_tagged(" @en"), None);
+    }
+
+    #[test]
+    fn local_names_are_conservative() {
+        assert!(is_local_name("frank"));
+        assert!(is_local_name(""));
+        assert!(!is_local_name("a/b"));
+        assert!(!is_local_name("-x"));
+    }
+}
@@ -0,0 +1,195 @@
+//! TribleSpace → Safetensors exporter.
+//!
+//! [`export_safetensors`] writes tensors described with the attributes from
+//! [`tensor`](crate::blob::schemas::array::tensor) as a
+//! [safetensors](https://github.com/huggingface/safetensors) file, keyed by
+//! their `metadata::name`. Strided tensors are written in row-major order.
+//! It reads back what
+//! [`import_safetensors`](crate::import::safetensors::import_safetensors)
+//! stores.
+//!
+//! ```rust,ignore
+//! let bytes = export_safetensors(&facts, tensors, &reader)?;
+//! std::fs::write("model.safetensors", bytes)?;
+//! ```
+
+use std::collections::BTreeMap;
+
+use anybytes::{Bytes, View};
+use serde_json::json;
+
+use crate::blob::schemas::array::elements::U64;
+use crate::blob::schemas::array::tensor::{element_count, row_major_strides};
+use crate::blob::schemas::array::Array;
+use crate::blob::schemas::longstring::LongString;
+use crate::blob::schemas::UnknownBlob;
+use crate::blob::BlobSchema;
+use crate::id::Id;
+use crate::import::safetensors::{swap_little_endian, DTYPES};
+use crate::metadata;
+use crate::prelude::{find, pattern};
+use crate::repo::BlobStoreGet;
+use crate::trible::TribleSet;
+use crate::value::schemas::hash::{Blake3, Handle, Hash};
+use crate::value::Value;
+
+use super::ExportError;
+
+/// Serialise the tensors in `tensors` (duplicates dropped) as a safetensors
+/// file. `store` must hold their names, shapes, strides and elements.
+///
+/// Fails with [`ExportError::Malformed`] when a tensor lacks a name or one
+/// of its tensor attributes, has an element type safetensors cannot
+/// represent, shares its name with another tensor, or has a layout that
+/// does not fit its elements. A layout that repeats elements (zero or
+/// overlapping strides) may expand them by at most 1 GiB.
+pub fn export_safetensors(
+    set: &TribleSet,
+    tensors: impl IntoIterator<Item = Id>,
+    store: &impl BlobStoreGet<Blake3>,
+) -> Result<Vec<u8>, ExportError> {
+    let mut named: BTreeMap<String, (&'static str, Vec<u64>, Vec<u8>)> = BTreeMap::new();
+    let mut seen = std::collections::HashSet::new();
+    for tensor in tensors {
+        if !seen.insert(tensor) {
+            continue;
+        }
+        let malformed = |reason| ExportError::Malformed {
+            entity: tensor,
+            reason,
+        };
+        let name = find!(
+            name: Value<Handle<Blake3, LongString>>,
+            pattern!(set, [{ tensor @ metadata::name: ?name }])
+        )
+        .next()
+        .ok_or_else(|| malformed("tensor has no metadata::name"))?;
+        let dtype = find!(dtype: Id, pattern!(set, [{ tensor @ metadata::tensor_dtype: ?dtype }]))
+            .next()
+            .ok_or_else(|| malformed("tensor has no metadata::tensor_dtype"))?;
+        let data = find!(
+            data: Value<Handle<Blake3, UnknownBlob>>,
+            pattern!(set, [{ tensor @ metadata::tensor_data: ?data }])
+        )
+        .next()
+        .ok_or_else(|| malformed("tensor has no metadata::tensor_data"))?;
+        let shape = find!(
+            shape: Value<Handle<Blake3, Array<U64>>>,
+            pattern!(set, [{ tensor @ metadata::tensor_shape: ?shape }])
+        )
+        .next()
+        .ok_or_else(|| malformed("tensor has no metadata::tensor_shape"))?;
+        let strides = find!(
+            strides: Value<Handle<Blake3, Array<U64>>>,
+            pattern!(set, [{ tensor @ metadata::tensor_strides: ?strides }])
+        )
+        .next();
+
+        let &(dtype, _, size) = DTYPES
+            .iter()
+            .find(|(_, id, _)| *id == dtype)
+            .ok_or_else(|| malformed("tensor dtype has no safetensors equivalent"))?;
+        let name: View<str> = load(store, name)?;
+        let elements: Bytes = load(store, data)?;
+        let shape: Vec<u64> = load::<View<[u64]>, _>(store, shape)?.to_vec();
+        let dims: Vec<usize> = shape
+            .iter()
+            .map(|&d| usize::try_from(d))
+            .collect::<Result<_, _>>()
+            .map_err(|_| malformed("tensor shape does not fit in memory"))?;
+        let strides: Vec<usize> = match strides {
+            Some(strides) => load::<View<[u64]>, _>(store, strides)?
+                .iter()
+                .map(|&s| usize::try_from(s))
+                .collect::<Result<_, _>>()
+                .map_err(|_| malformed("tensor strides do not fit in memory"))?,
+            None => row_major_strides(&dims),
+        };
+        let mut bytes = gather(&elements, &dims, &strides, size)
+            .ok_or_else(|| malformed("tensor layout does not fit its elements"))?;
+        swap_little_endian(&mut bytes, size);
+        if named.insert(name.to_string(), (dtype, shape, bytes)).is_some() {
+            return Err(malformed("another tensor has the same name"));
+        }
+    }
+
+    let mut header = serde_json::Map::new();
+    let mut offset = 0u64;
+    for (name, (dtype, shape, bytes)) in &named {
+        let end = offset + bytes.len() as u64;
+        header.insert(
+            name.clone(),
+            json!({ "dtype": dtype, "shape": shape, "data_offsets": [offset, end] }),
+        );
+        offset = end;
+    }
+    let mut header = serde_json::to_vec(&header).expect("header serialises");
+    // Pad with spaces so the tensor data starts 8-byte aligned.
+    header.resize(header.len().next_multiple_of(8), b' ');
+
+    let mut out = Vec::with_capacity(8 + header.len() + offset as usize);
+    out.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    out.extend_from_slice(&header);
+    for (_, _, bytes) in named.values() {
+        out.extend_from_slice(bytes);
+    }
+    Ok(out)
+}
+
+fn load<T, S>(store: &impl BlobStoreGet<Blake3>, handle: Value<Handle<Blake3, S>>) -> Result<T, ExportError>
+where
+    S: BlobSchema + 'static,
+    T: crate::blob::TryFromBlob<S>,
+    Handle<Blake3, S>: crate::value::ValueSchema,
+{
+    store.get::<T, S>(handle).map_err(|err| {
+        let hash: Value<Hash<Blake3>> = Handle::to_hash(handle);
+        ExportError::BlobStore {
+            hash: hex::encode(hash.raw),
+            source: err.to_string(),
+        }
+    })
+}
+
+/// How many bytes beyond the stored elements a strided layout may expand
+/// to when it addresses elements more than once (zero or overlapping
+/// strides).
+const MAX_REPEATED_BYTES: usize = 1 << 30;
+
+/// The elements of `data` addressed by `shape` and `strides`, in row-major
+/// order, or `None` if the layout reaches past `data` or would expand it
+/// by more than [`MAX_REPEATED_BYTES`].
+fn gather(data: &[u8], shape: &[usize], strides: &[usize], size: usize) -> Option<Vec<u8>> {
+    let count = element_count(shape)?;
+    if strides.len() != shape.len() {
+        return None;
+    }
+    let len = count.checked_mul(size)?;
+    if strides == row_major_strides(shape) {
+        return (len == data.len()).then(|| data.to_vec());
+    }
+    // A broadcast shape can claim any size over a single element; check it
+    // against the data before allocating.
+    if len > data.len().saturating_add(MAX_REPEATED_BYTES) {
+        return None;
+    }
+    let mut out = Vec::new();
+    out.try_reserve_exact(len).ok()?;
+    let mut index = vec![0usize; shape.len()];
+    for _ in 0..count {
+        let offset = index
+            .iter()
+            .zip(strides)
+            .try_fold(0usize, |sum, (&i, &s)| sum.checked_add(i.checked_mul(s)?))?;
+        let start = offset.checked_mul(size)?;
+        out.extend_from_slice(data.get(start..start.checked_add(size)?)?);
+        for dim in (0..shape.len()).rev() {
+            index[dim] += 1;
+            if index[dim] < shape[dim] {
+                break;
+            }
+            index[dim] = 0;
+        }
+    }
+    Some(out)
+}
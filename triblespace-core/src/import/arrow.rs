@@ -0,0 +1,782 @@
+//! Arrow → TribleSpace importer.
+//!
+//! [`ArrowImporter`] reads Arrow record batches (and, with the `parquet`
+//! feature, Parquet files) the way [`CsvImporter`](super::csv::CsvImporter)
+//! reads CSV: every row becomes one entity and every non-null value one
+//! trible.
+//!
+//! - Each column becomes an attribute, derived from the field name via
+//!   [`Attribute::from_name`] under the column's [`ColumnSchema`], unless it
+//!   is mapped with [`ArrowImporter::with_attribute`] or its field metadata
+//!   names one (see [`ATTRIBUTE_KEY`]).
+//! - The schema follows the Arrow type: integers and `Decimal128(_, 0)` are
+//!   `Integer`, floats `Float`, booleans `Boolean`, timestamps, dates and
+//!   `{start, end}` timestamp structs `Time`, `FixedSizeBinary(16)` `Id`, and
+//!   strings `ShortString` when every value fits, `LongString` otherwise.
+//!   [`ArrowImporter::with_column_schema`] overrides it; string columns then
+//!   parse like CSV cells, and integers may be read as floats.
+//! - Dictionary-encoded columns import their values, and list columns one
+//!   trible per element.
+//! - Row ids are derived from the row's attribute/value pairs, or from a key
+//!   column set with [`ArrowImporter::with_key_column`].
+//!
+//! ```rust,ignore
+//! let mut importer = ArrowImporter::<_, Blake3>::new(&mut blobs, None).with_key_column("isbn");
+//! let rows = importer.import_batch(&batch)?;
+//! let metadata = importer.metadata()?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Date32Type, Date64Type, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, OffsetSizeTrait, RecordBatch};
+use arrow_schema::{ArrowError, DataType, Field, TimeUnit};
+use hifitime::{Duration, Epoch};
+
+use crate::attribute::Attribute;
+use crate::id::{ExclusiveId, Id, RawId};
+use crate::import::json::EncodeError;
+use crate::repo::BlobStore;
+use crate::trible::{Fragment, Trible, TribleSet};
+use crate::value::schemas::boolean::Boolean;
+use crate::value::schemas::f64::F64;
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, HashProtocol};
+use crate::value::schemas::iu256::I256BE;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::schemas::UnknownValue;
+use crate::value::{RawValue, ToValue, TryToValue, Value, ValueSchema};
+
+use super::csv::{derive_row_id, describe_columns, CellError, ColumnSchema};
+
+/// Field metadata key holding the hex id of the attribute a column stores.
+/// Written by [`crate::export::arrow`]; the importer uses it, together with
+/// [`VALUE_SCHEMA_KEY`], for columns that aren't configured.
+pub const ATTRIBUTE_KEY: &str = "triblespace:attribute";
+
+/// Field metadata key holding the hex id of a column's value schema.
+pub const VALUE_SCHEMA_KEY: &str = "triblespace:value_schema";
+
+/// Error returned by [`ArrowImporter`].
+#[derive(Debug)]
+pub enum ArrowImportError {
+    /// A record batch could not be read.
+    Arrow(ArrowError),
+    /// The Parquet input could not be read.
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    /// A configured key or mapped column is not in the schema.
+    MissingColumn(String),
+    /// A column's Arrow type has no schema, or not the configured one.
+    UnsupportedColumn {
+        /// Name of the column.
+        column: String,
+        /// The Arrow type of its values.
+        data_type: DataType,
+        /// The configured schema, if any.
+        schema: Option<ColumnSchema>,
+    },
+    /// A row has no value in the key column.
+    MissingKey {
+        /// 0-based index of the row among all rows of the import.
+        row: usize,
+    },
+    /// A value does not convert to its column's schema.
+    InvalidCell {
+        /// 0-based index of the row among all rows of the import.
+        row: usize,
+        /// Name of the column.
+        column: String,
+        /// The schema the value had to match.
+        schema: ColumnSchema,
+        /// The value, as text.
+        value: String,
+    },
+    /// A value could not be written to the blob store.
+    Encode {
+        /// Name of the column.
+        column: String,
+        /// Underlying encoding error.
+        source: EncodeError,
+    },
+}
+
+impl fmt::Display for ArrowImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Arrow(err) => write!(f, "failed to read record batch: {err}"),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => write!(f, "failed to read Parquet: {err}"),
+            Self::MissingColumn(column) => write!(f, "schema has no column {column:?}"),
+            Self::UnsupportedColumn {
+                column,
+                data_type,
+                schema: Some(schema),
+            } => write!(
+                f,
+                "column {column:?} of type {data_type} cannot be imported as {schema}"
+            ),
+            Self::UnsupportedColumn {
+                column,
+                data_type,
+                schema: None,
+            } => write!(f, "column {column:?} of type {data_type} cannot be imported"),
+            Self::MissingKey { row } => write!(f, "row {row} has no key"),
+            Self::InvalidCell {
+                row,
+                column,
+                schema,
+                value,
+            } => write!(
+                f,
+                "value {value:?} in column {column:?} of row {row} is not a valid {schema}"
+            ),
+            Self::Encode { column, source } => {
+                write!(f, "failed to store column {column:?}: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArrowImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Arrow(err) => Some(err),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => Some(err),
+            Self::Encode { source, .. } => Some(source),
+            Self::MissingColumn(_)
+            | Self::UnsupportedColumn { .. }
+            | Self::MissingKey { .. }
+            | Self::InvalidCell { .. } => None,
+        }
+    }
+}
+
+impl From<ArrowError> for ArrowImportError {
+    fn from(err: ArrowError) -> Self {
+        Self::Arrow(err)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for ArrowImportError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(err)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ColumnConfig {
+    attribute: Option<Id>,
+    schema: Option<ColumnSchema>,
+    skip: bool,
+}
+
+/// The values of each slot of an array: none for a null, several for a
+/// list, or the text of a value the column's schema rejects.
+type Slots = Vec<Result<Vec<Value<UnknownValue>>, String>>;
+
+/// How one column of the current batch is imported.
+struct ColumnPlan {
+    name: String,
+    attribute: Id,
+    schema: ColumnSchema,
+    slots: Slots,
+}
+
+/// Arrow importer mapping columns to attributes and rows to entities.
+///
+/// Configure it with the `with_*` builders, then call
+/// [`import_batch`](Self::import_batch),
+/// [`import_batches`](Self::import_batches) or `import_parquet`.
+/// [`metadata`](Self::metadata) describes every attribute the importer
+/// derived from a field name.
+pub struct ArrowImporter<'a, Store, Hasher = Blake3>
+where
+    Store: BlobStore<Blake3>,
+    Hasher: HashProtocol,
+{
+    store: &'a mut Store,
+    id_salt: Option<[u8; 32]>,
+    key: Option<String>,
+    columns: HashMap<String, ColumnConfig>,
+    derived: HashMap<Id, (String, ColumnSchema)>,
+    _hasher: PhantomData<Hasher>,
+}
+
+impl<'a, Store, Hasher> ArrowImporter<'a, Store, Hasher>
+where
+    Store: BlobStore<Blake3>,
+    Hasher: HashProtocol,
+{
+    /// Creates a new importer backed by `store`. Pass an optional 32-byte
+    /// salt to namespace the derived row ids.
+    pub fn new(store: &'a mut Store, id_salt: Option<[u8; 32]>) -> Self {
+        Self {
+            store,
+            id_salt,
+            key: None,
+            columns: HashMap::new(),
+            derived: HashMap::new(),
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Derive row ids from the values in `column` alone instead of the
+    /// whole row. Rows sharing a key describe the same entity.
+    pub fn with_key_column(mut self, column: &str) -> Self {
+        self.key = Some(column.to_owned());
+        self
+    }
+
+    /// Import `column` under `schema` instead of the one its type implies.
+    pub fn with_column_schema(mut self, column: &str, schema: ColumnSchema) -> Self {
+        self.columns.entry(column.to_owned()).or_default().schema = Some(schema);
+        self
+    }
+
+    /// Import `column` as `attribute` instead of the attribute derived from
+    /// the field name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` is not one of the [`ColumnSchema`]s.
+    pub fn with_attribute<S: ValueSchema>(mut self, column: &str, attribute: &Attribute<S>) -> Self {
+        let schema = ColumnSchema::of::<S>().expect("Arrow columns support this value schema");
+        let config = self.columns.entry(column.to_owned()).or_default();
+        config.attribute = Some(attribute.id());
+        config.schema = Some(schema);
+        self
+    }
+
+    /// Leave `column` out of the import. A skipped key column still
+    /// derives row ids.
+    pub fn skip_column(mut self, column: &str) -> Self {
+        self.columns.entry(column.to_owned()).or_default().skip = true;
+        self
+    }
+
+    /// Imports one record batch, returning a [`Fragment`] that exports the
+    /// row entity ids.
+    pub fn import_batch(&mut self, batch: &RecordBatch) -> Result<Fragment, ArrowImportError> {
+        let mut rows = Vec::with_capacity(batch.num_rows());
+        let mut facts = TribleSet::new();
+        self.import_into(batch, 0, &mut rows, &mut facts)?;
+        Ok(Fragment::new(rows, facts))
+    }
+
+    /// Imports a stream of record batches, such as an IPC or Parquet
+    /// reader, into one [`Fragment`]. Row numbers in errors count across
+    /// batches.
+    pub fn import_batches(
+        &mut self,
+        batches: impl IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+    ) -> Result<Fragment, ArrowImportError> {
+        let mut rows = Vec::new();
+        let mut facts = TribleSet::new();
+        let mut offset = 0;
+        for batch in batches {
+            let batch = batch?;
+            self.import_into(&batch, offset, &mut rows, &mut facts)?;
+            offset += batch.num_rows();
+        }
+        Ok(Fragment::new(rows, facts))
+    }
+
+    /// Imports a Parquet file. Convenience wrapper around
+    /// [`import_batches`](Self::import_batches).
+    ///
+    /// Uncompressed, Snappy and Zstandard pages are supported, which
+    /// covers the defaults of pyarrow, pandas and Spark. Files using other
+    /// codecs fail with [`ArrowImportError::Parquet`].
+    #[cfg(feature = "parquet")]
+    pub fn import_parquet<R>(&mut self, reader: R) -> Result<Fragment, ArrowImportError>
+    where
+        R: parquet::file::reader::ChunkReader + 'static,
+    {
+        let batches =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(reader)?
+                .build()?;
+        self.import_batches(batches)
+    }
+
+    /// Returns a [`Fragment`] describing every attribute derived from a
+    /// field name so far, suitable for committing alongside the data.
+    /// Mapped attributes keep their own descriptions.
+    pub fn metadata(&mut self) -> Result<Fragment, Store::PutError> {
+        describe_columns(self.store, &self.derived)
+    }
+
+    fn import_into(
+        &mut self,
+        batch: &RecordBatch,
+        offset: usize,
+        rows: &mut Vec<Id>,
+        facts: &mut TribleSet,
+    ) -> Result<(), ArrowImportError> {
+        let fields = batch.schema_ref().fields();
+        for column in self.columns.keys().chain(self.key.iter()) {
+            if !fields.iter().any(|field| field.name() == column) {
+                return Err(ArrowImportError::MissingColumn(column.clone()));
+            }
+        }
+
+        let mut plans = Vec::new();
+        let mut key_plan = None;
+        for (field, array) in fields.iter().zip(batch.columns()) {
+            let name = field.name();
+            let config = self.columns.get(name).cloned().unwrap_or_default();
+            let is_key = self.key.as_deref() == Some(name);
+            if config.skip && !is_key {
+                continue;
+            }
+            let tagged = tagged_attribute(field);
+            let schema = match config.schema.or(tagged.map(|(_, schema)| schema)) {
+                Some(schema) => schema,
+                None => infer(array.as_ref()).ok_or_else(|| ArrowImportError::UnsupportedColumn {
+                    column: name.clone(),
+                    data_type: field.data_type().clone(),
+                    schema: None,
+                })?,
+            };
+            let attribute = match (config.attribute, tagged) {
+                (Some(attribute), _) => attribute,
+                (None, Some((attribute, tagged))) if tagged == schema => attribute,
+                (None, _) => {
+                    let id = schema.attribute(name);
+                    self.derived.insert(id, (name.clone(), schema));
+                    id
+                }
+            };
+            let slots = self.encode(array.as_ref(), schema, name)?;
+            let plan = ColumnPlan {
+                name: name.clone(),
+                attribute,
+                schema,
+                slots,
+            };
+            if is_key {
+                key_plan = Some((plan, config.skip));
+            } else {
+                plans.push(plan);
+            }
+        }
+
+        for index in 0..batch.num_rows() {
+            let row = offset + index;
+            let mut pairs: Vec<(RawId, RawValue)> = Vec::with_capacity(plans.len());
+            for plan in &plans {
+                pairs.extend(plan.pairs(index, row)?);
+            }
+            let id = match &key_plan {
+                Some((plan, skip)) => {
+                    let key = plan.pairs(index, row)?;
+                    if key.is_empty() {
+                        return Err(ArrowImportError::MissingKey { row });
+                    }
+                    let id = derive_row_id::<Hasher>(self.id_salt, &key);
+                    if !skip {
+                        pairs.extend(key);
+                    }
+                    id
+                }
+                None if pairs.is_empty() => continue,
+                None => derive_row_id::<Hasher>(self.id_salt, &pairs),
+            };
+            for (attr, value) in pairs {
+                let attr = Id::new(attr).expect("attribute ids are non-nil");
+                facts.insert(&Trible::new(
+                    ExclusiveId::force_ref(&id),
+                    &attr,
+                    &Value::<UnknownValue>::new(value),
+                ));
+            }
+            rows.push(id);
+        }
+        Ok(())
+    }
+
+    /// Encode every slot of `array` under `schema`. Dictionary values are
+    /// encoded once, however many rows use them.
+    fn encode(
+        &mut self,
+        array: &dyn Array,
+        schema: ColumnSchema,
+        column: &str,
+    ) -> Result<Slots, ArrowImportError> {
+        match array.data_type() {
+            DataType::Dictionary(_, _) => {
+                let dictionary = array.as_any_dictionary();
+                let values = self.encode(dictionary.values().as_ref(), schema, column)?;
+                if values.is_empty() {
+                    return Ok(vec![Ok(Vec::new()); array.len()]);
+                }
+                Ok(dictionary
+                    .normalized_keys()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, key)| {
+                        if array.is_null(i) {
+                            Ok(Vec::new())
+                        } else {
+                            values[key].clone()
+                        }
+                    })
+                    .collect())
+            }
+            DataType::List(_) => self.encode_list::<i32>(array, schema, column),
+            DataType::LargeList(_) => self.encode_list::<i64>(array, schema, column),
+            _ => self.encode_leaf(array, schema, column),
+        }
+    }
+
+    fn encode_list<O: OffsetSizeTrait>(
+        &mut self,
+        array: &dyn Array,
+        schema: ColumnSchema,
+        column: &str,
+    ) -> Result<Slots, ArrowImportError> {
+        let list = array.as_list::<O>();
+        let elements = self.encode(list.values().as_ref(), schema, column)?;
+        let offsets = list.value_offsets();
+        Ok((0..list.len())
+            .map(|i| {
+                let mut values = Vec::new();
+                if list.is_valid(i) {
+                    for element in &elements[offsets[i].as_usize()..offsets[i + 1].as_usize()] {
+                        values.extend(element.as_ref().map_err(Clone::clone)?.iter().copied());
+                    }
+                }
+                Ok(values)
+            })
+            .collect())
+    }
+
+    fn encode_leaf(
+        &mut self,
+        array: &dyn Array,
+        schema: ColumnSchema,
+        column: &str,
+    ) -> Result<Slots, ArrowImportError> {
+        let unsupported = || ArrowImportError::UnsupportedColumn {
+            column: column.to_owned(),
+            data_type: array.data_type().clone(),
+            schema: Some(schema),
+        };
+        let slots = match (array.data_type(), schema) {
+            (DataType::Utf8, _) => self.encode_text(array.as_string::<i32>().iter(), schema, column)?,
+            (DataType::LargeUtf8, _) => {
+                self.encode_text(array.as_string::<i64>().iter(), schema, column)?
+            }
+            (DataType::Utf8View, _) => {
+                self.encode_text(array.as_string_view().iter(), schema, column)?
+            }
+            (DataType::Int8, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<Int8Type>().iter(), schema)
+            }
+            (DataType::Int16, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<Int16Type>().iter(), schema)
+            }
+            (DataType::Int32, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<Int32Type>().iter(), schema)
+            }
+            (DataType::Int64, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<Int64Type>().iter(), schema)
+            }
+            (DataType::UInt8, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<UInt8Type>().iter(), schema)
+            }
+            (DataType::UInt16, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<UInt16Type>().iter(), schema)
+            }
+            (DataType::UInt32, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<UInt32Type>().iter(), schema)
+            }
+            (DataType::UInt64, ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<UInt64Type>().iter(), schema)
+            }
+            (DataType::Decimal128(_, 0), ColumnSchema::Integer | ColumnSchema::Float) => {
+                integers(array.as_primitive::<Decimal128Type>().iter(), schema)
+            }
+            (DataType::Float32, ColumnSchema::Float) => slots(
+                array.as_primitive::<Float32Type>().iter(),
+                |n| Ok(ToValue::<F64>::to_value(f64::from(n)).transmute()),
+            ),
+            (DataType::Float64, ColumnSchema::Float) => slots(
+                array.as_primitive::<Float64Type>().iter(),
+                |n| Ok(ToValue::<F64>::to_value(n).transmute()),
+            ),
+            (DataType::Boolean, ColumnSchema::Boolean) => slots(array.as_boolean().iter(), |b| {
+                Ok(ToValue::<Boolean>::to_value(b).transmute())
+            }),
+            (DataType::FixedSizeBinary(16), ColumnSchema::Id) => {
+                slots(array.as_fixed_size_binary().iter(), |bytes| {
+                    let raw: RawId = bytes.try_into().expect("16-byte values");
+                    match Id::new(raw) {
+                        Some(id) => Ok(GenId::value_from(id).transmute()),
+                        None => Err(hex::encode(raw)),
+                    }
+                })
+            }
+            (DataType::Struct(fields), ColumnSchema::Time) if fields.len() == 2 => {
+                let columns = array.as_struct().columns();
+                let (Some(lower), Some(upper)) = (unix_nanos(&columns[0]), unix_nanos(&columns[1]))
+                else {
+                    return Err(unsupported());
+                };
+                (0..array.len())
+                    .map(|i| {
+                        if array.is_null(i) {
+                            return Ok(Vec::new());
+                        }
+                        match (lower[i], upper[i]) {
+                            (Some(lower), Some(upper)) => {
+                                interval(lower, upper).map(|value| vec![value])
+                            }
+                            (lower, upper) => Err(format!("{lower:?}/{upper:?}")),
+                        }
+                    })
+                    .collect()
+            }
+            (_, ColumnSchema::Time) => {
+                let nanos = unix_nanos(array).ok_or_else(unsupported)?;
+                nanos
+                    .into_iter()
+                    .map(|ns| match ns {
+                        Some(ns) => interval(ns, ns).map(|value| vec![value]),
+                        None => Ok(Vec::new()),
+                    })
+                    .collect()
+            }
+            _ => return Err(unsupported()),
+        };
+        Ok(slots)
+    }
+
+    fn encode_text<'t>(
+        &mut self,
+        texts: impl Iterator<Item = Option<&'t str>>,
+        schema: ColumnSchema,
+        column: &str,
+    ) -> Result<Slots, ArrowImportError> {
+        texts
+            .map(|text| match text {
+                None => Ok(Ok(Vec::new())),
+                Some(text) => match schema.encode(text, self.store) {
+                    Ok(value) => Ok(Ok(vec![value])),
+                    Err(CellError::Invalid) => Ok(Err(text.to_owned())),
+                    Err(CellError::Encode(source)) => Err(ArrowImportError::Encode {
+                        column: column.to_owned(),
+                        source,
+                    }),
+                },
+            })
+            .collect()
+    }
+}
+
+impl ColumnPlan {
+    /// The attribute/value pairs of the slot `index`, `row` being its
+    /// number for errors.
+    fn pairs(&self, index: usize, row: usize) -> Result<Vec<(RawId, RawValue)>, ArrowImportError> {
+        match &self.slots[index] {
+            Ok(values) => Ok(values
+                .iter()
+                .map(|value| (self.attribute.raw(), value.raw))
+                .collect()),
+            Err(value) => Err(ArrowImportError::InvalidCell {
+                row,
+                column: self.name.clone(),
+                schema: self.schema,
+                value: value.clone(),
+            }),
+        }
+    }
+}
+
+/// The attribute and schema named by `field`'s metadata, if both are
+/// present and the schema is a [`ColumnSchema`].
+fn tagged_attribute(field: &Field) -> Option<(Id, ColumnSchema)> {
+    let metadata = field.metadata();
+    let attribute = Id::from_hex(metadata.get(ATTRIBUTE_KEY)?)?;
+    let schema = ColumnSchema::from_id(Id::from_hex(metadata.get(VALUE_SCHEMA_KEY)?)?)?;
+    Some((attribute, schema))
+}
+
+/// The schema a column of `array`'s type imports as by default, `None` if
+/// there is none.
+fn infer(array: &dyn Array) -> Option<ColumnSchema> {
+    let schema = match array.data_type() {
+        DataType::Dictionary(_, _) => return infer(array.as_any_dictionary().values().as_ref()),
+        DataType::List(_) => return infer(array.as_list::<i32>().values().as_ref()),
+        DataType::LargeList(_) => return infer(array.as_list::<i64>().values().as_ref()),
+        DataType::Utf8 => text_schema(array.as_string::<i32>().iter()),
+        DataType::LargeUtf8 => text_schema(array.as_string::<i64>().iter()),
+        DataType::Utf8View => text_schema(array.as_string_view().iter()),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Decimal128(_, 0) => ColumnSchema::Integer,
+        DataType::Float32 | DataType::Float64 => ColumnSchema::Float,
+        DataType::Boolean => ColumnSchema::Boolean,
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => ColumnSchema::Time,
+        DataType::Struct(fields)
+            if fields.len() == 2
+                && fields
+                    .iter()
+                    .all(|field| matches!(field.data_type(), DataType::Timestamp(_, _))) =>
+        {
+            ColumnSchema::Time
+        }
+        DataType::FixedSizeBinary(16) => ColumnSchema::Id,
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// `ShortString` if every text fits, `LongString` otherwise.
+fn text_schema<'t>(mut texts: impl Iterator<Item = Option<&'t str>>) -> ColumnSchema {
+    if texts.all(|text| text.is_none_or(|text| ColumnSchema::ShortString.accepts(text))) {
+        ColumnSchema::ShortString
+    } else {
+        ColumnSchema::LongString
+    }
+}
+
+/// One slot per item of `values`, encoded with `encode`.
+fn slots<T>(
+    values: impl Iterator<Item = Option<T>>,
+    mut encode: impl FnMut(T) -> Result<Value<UnknownValue>, String>,
+) -> Slots {
+    values
+        .map(|value| match value {
+            Some(value) => encode(value).map(|value| vec![value]),
+            None => Ok(Vec::new()),
+        })
+        .collect()
+}
+
+fn integers<T: Into<i128>>(
+    values: impl Iterator<Item = Option<T>>,
+    schema: ColumnSchema,
+) -> Slots {
+    slots(values, |n| {
+        let n: i128 = n.into();
+        Ok(match schema {
+            ColumnSchema::Float => ToValue::<F64>::to_value(n as f64).transmute(),
+            _ => ToValue::<I256BE>::to_value(n).transmute(),
+        })
+    })
+}
+
+/// Nanoseconds since the Unix epoch of each item of a timestamp or date
+/// array, `None` for other arrays.
+fn unix_nanos(array: &dyn Array) -> Option<Vec<Option<i128>>> {
+    fn scaled<I: Into<i128>>(values: impl Iterator<Item = Option<I>>, scale: i128) -> Vec<Option<i128>> {
+        values.map(|value| value.map(|v| v.into() * scale)).collect()
+    }
+    Some(match array.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            scaled(array.as_primitive::<TimestampSecondType>().iter(), 1_000_000_000)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            scaled(array.as_primitive::<TimestampMillisecondType>().iter(), 1_000_000)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            scaled(array.as_primitive::<TimestampMicrosecondType>().iter(), 1_000)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            scaled(array.as_primitive::<TimestampNanosecondType>().iter(), 1)
+        }
+        DataType::Date32 => scaled(array.as_primitive::<Date32Type>().iter(), 86_400_000_000_000),
+        DataType::Date64 => scaled(array.as_primitive::<Date64Type>().iter(), 1_000_000),
+        _ => return None,
+    })
+}
+
+/// The interval between two instants given in Unix nanoseconds.
+fn interval(lower: i128, upper: i128) -> Result<Value<UnknownValue>, String> {
+    let epoch = |ns| Epoch::from_unix_duration(Duration::from_total_nanoseconds(ns));
+    let value: Value<NsTAIInterval> = (epoch(lower), epoch(upper))
+        .try_to_value()
+        .map_err(|_| format!("{lower}/{upper}"))?;
+    Ok(value.transmute())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, DictionaryArray, Int32Array, ListArray, RecordBatch, StringArray};
+    use arrow_array::types::Int32Type;
+
+    use super::{infer, ArrowImportError, ArrowImporter, ColumnSchema};
+    use crate::blob::MemoryBlobStore;
+    use crate::value::schemas::hash::Blake3;
+
+    #[test]
+    fn dictionaries_and_lists_import_their_values() {
+        let tags: DictionaryArray<Int32Type> =
+            [Some("scifi"), None, Some("scifi")].into_iter().collect();
+        let pages: ListArray = ListArray::from_iter_primitive::<Int32Type, _, _>([
+            Some(vec![Some(1), Some(2)]),
+            Some(vec![]),
+            None,
+        ]);
+        let batch = RecordBatch::try_from_iter([
+            ("tag", Arc::new(tags) as ArrayRef),
+            ("page", Arc::new(pages) as ArrayRef),
+        ])
+        .unwrap();
+        assert_eq!(infer(batch.column(0).as_ref()), Some(ColumnSchema::ShortString));
+        assert_eq!(infer(batch.column(1).as_ref()), Some(ColumnSchema::Integer));
+
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let rows = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+            .import_batch(&batch)
+            .unwrap();
+        // The third row is empty, and the first row's two pages are two
+        // tribles.
+        assert_eq!(rows.exports().count(), 2);
+        assert_eq!(rows.facts().len(), 4);
+    }
+
+    #[test]
+    fn string_columns_parse_under_a_configured_schema() {
+        let batch = RecordBatch::try_from_iter([
+            ("n", Arc::new(StringArray::from(vec!["1", "two"])) as ArrayRef),
+            ("m", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+        ])
+        .unwrap();
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let err = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+            .with_column_schema("n", ColumnSchema::Integer)
+            .import_batch(&batch)
+            .unwrap_err();
+        assert!(
+            matches!(&err, ArrowImportError::InvalidCell { row: 1, value, .. } if value == "two"),
+            "{err}"
+        );
+        let err = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+            .with_column_schema("m", ColumnSchema::Boolean)
+            .import_batch(&batch)
+            .unwrap_err();
+        assert!(
+            matches!(&err, ArrowImportError::UnsupportedColumn { column, .. } if column == "m"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,633 @@
+//! CSV → TribleSpace importer.
+//!
+//! [`CsvImporter`] turns every data row into one entity and every non-empty
+//! cell into one trible. The header row names the columns:
+//!
+//! - Each column becomes an attribute, derived from the header via
+//!   [`Attribute::from_name`] under the column's schema, unless it is mapped
+//!   to an existing attribute with [`CsvImporter::with_attribute`].
+//! - Each column's [`ColumnSchema`] is given with
+//!   [`CsvImporter::with_column_schema`] or inferred from its cells: the
+//!   first of `Boolean`, `Integer`, `Float`, `Time`, `ShortString` that
+//!   parses every non-empty cell, falling back to `LongString`.
+//! - Row ids are derived from the row's attribute/value pairs, so identical
+//!   rows converge like [`JsonObjectImporter`](super::json::JsonObjectImporter)
+//!   objects do, or from a key column set with
+//!   [`CsvImporter::with_key_column`], so re-importing an updated file
+//!   addresses the same entities.
+//!
+//! Empty cells produce no trible. The whole input is read before the first
+//! trible is written, since inference needs every cell of a column.
+//!
+//! ```rust,ignore
+//! let mut importer = CsvImporter::<_, Blake3>::new(&mut blobs, None).with_key_column("isbn");
+//! let rows = importer.import_str("isbn,title,pages\n0441013597,Dune,412\n")?;
+//! let metadata = importer.metadata()?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use hifitime::Epoch;
+
+use crate::attribute::Attribute;
+use crate::blob::schemas::longstring::LongString;
+use crate::id::{ExclusiveId, Id, RawId, ID_LEN};
+use crate::import::json::EncodeError;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::repo::BlobStore;
+use crate::trible::{Fragment, Trible, TribleSet};
+use crate::value::schemas::boolean::Boolean;
+use crate::value::schemas::f64::F64;
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, Handle, HashProtocol};
+use crate::value::schemas::iu256::I256BE;
+use crate::value::schemas::shortstring::ShortString;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::schemas::UnknownValue;
+use crate::value::{RawValue, ToValue, TryToValue, Value, ValueSchema};
+
+/// Value schema of a CSV column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnSchema {
+    /// [`ShortString`]: text of at most 32 bytes without NUL.
+    ShortString,
+    /// `Handle<Blake3, LongString>`: text of any length, stored as a blob.
+    LongString,
+    /// [`I256BE`]: a decimal integer.
+    Integer,
+    /// [`F64`]: a floating point number.
+    Float,
+    /// [`Boolean`]: `true` or `false`, in any case.
+    Boolean,
+    /// [`NsTAIInterval`]: a timestamp (e.g. `2024-03-01T12:00:00Z`) stored
+    /// as a zero-width interval, or `start/end`.
+    Time,
+    /// [`GenId`]: a reference to another entity, written as 32 hex digits.
+    /// Never inferred.
+    Id,
+}
+
+impl ColumnSchema {
+    /// The order inference tries the schemas in.
+    const INFERENCE_ORDER: [ColumnSchema; 5] = [
+        ColumnSchema::Boolean,
+        ColumnSchema::Integer,
+        ColumnSchema::Float,
+        ColumnSchema::Time,
+        ColumnSchema::ShortString,
+    ];
+
+    /// The id of the value schema.
+    pub fn id(self) -> Id {
+        match self {
+            Self::ShortString => ShortString::ID,
+            Self::LongString => Handle::<Blake3, LongString>::ID,
+            Self::Integer => I256BE::ID,
+            Self::Float => F64::ID,
+            Self::Boolean => Boolean::ID,
+            Self::Time => NsTAIInterval::ID,
+            Self::Id => GenId::ID,
+        }
+    }
+
+    /// The attribute [`Attribute::from_name`] derives for `name` under this
+    /// schema.
+    pub fn attribute(self, name: &str) -> Id {
+        match self {
+            Self::ShortString => Attribute::<ShortString>::from_name(name).id(),
+            Self::LongString => Attribute::<Handle<Blake3, LongString>>::from_name(name).id(),
+            Self::Integer => Attribute::<I256BE>::from_name(name).id(),
+            Self::Float => Attribute::<F64>::from_name(name).id(),
+            Self::Boolean => Attribute::<Boolean>::from_name(name).id(),
+            Self::Time => Attribute::<NsTAIInterval>::from_name(name).id(),
+            Self::Id => Attribute::<GenId>::from_name(name).id(),
+        }
+    }
+
+    /// The column schema for the value schema `S`, if CSV supports it.
+    pub fn of<S: ValueSchema>() -> Option<Self> {
+        Self::from_id(S::ID)
+    }
+
+    /// The column schema whose value schema has the id `schema`.
+    pub fn from_id(schema: Id) -> Option<Self> {
+        [
+            Self::ShortString,
+            Self::LongString,
+            Self::Integer,
+            Self::Float,
+            Self::Boolean,
+            Self::Time,
+            Self::Id,
+        ]
+        .into_iter()
+        .find(|column| column.id() == schema)
+    }
+
+    /// Whether inference may pick this schema for `cell`. Numbers with a
+    /// leading zero don't count, so codes like `007` stay text.
+    pub(super) fn accepts(self, cell: &str) -> bool {
+        match self {
+            Self::ShortString => TryToValue::<ShortString>::try_to_value(cell).is_ok(),
+            Self::LongString => true,
+            Self::Integer => !leading_zero(cell) && cell.parse::<i128>().is_ok(),
+            Self::Float => !leading_zero(cell) && cell.parse::<f64>().is_ok(),
+            Self::Boolean => parse_bool(cell).is_some(),
+            Self::Time => parse_time(cell).is_some(),
+            Self::Id => Id::from_hex(cell).is_some(),
+        }
+    }
+
+    /// Encode the text `cell` under this schema, storing long strings in
+    /// `store`.
+    pub(super) fn encode<Store: BlobStore<Blake3>>(
+        self,
+        cell: &str,
+        store: &mut Store,
+    ) -> Result<Value<UnknownValue>, CellError> {
+        let value = match self {
+            Self::ShortString => TryToValue::<ShortString>::try_to_value(cell)
+                .map_err(|_| CellError::Invalid)?
+                .transmute(),
+            Self::LongString => {
+                let handle: Value<Handle<Blake3, LongString>> = store
+                    .put(cell.to_owned())
+                    .map_err(|err| CellError::Encode(EncodeError::from_error(err)))?;
+                handle.transmute()
+            }
+            Self::Integer => {
+                let n: i128 = cell.parse().map_err(|_| CellError::Invalid)?;
+                ToValue::<I256BE>::to_value(n).transmute()
+            }
+            Self::Float => {
+                let n: f64 = cell.parse().map_err(|_| CellError::Invalid)?;
+                ToValue::<F64>::to_value(n).transmute()
+            }
+            Self::Boolean => {
+                let b = parse_bool(cell).ok_or(CellError::Invalid)?;
+                ToValue::<Boolean>::to_value(b).transmute()
+            }
+            Self::Time => parse_time(cell).ok_or(CellError::Invalid)?.transmute(),
+            Self::Id => {
+                let id = Id::from_hex(cell).ok_or(CellError::Invalid)?;
+                GenId::value_from(id).transmute()
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Why [`ColumnSchema::encode`] rejected a cell.
+pub(super) enum CellError {
+    /// The cell does not parse under the schema.
+    Invalid,
+    /// The cell could not be written to the blob store.
+    Encode(EncodeError),
+}
+
+impl fmt::Display for ColumnSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::ShortString => "short string",
+            Self::LongString => "long string",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Time => "time",
+            Self::Id => "id",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Whether the digits of `cell` start with a zero that isn't the whole
+/// integer part, as in `007` or `-01.5`.
+fn leading_zero(cell: &str) -> bool {
+    let digits = cell.trim_start_matches(['+', '-']).as_bytes();
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    if cell.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if cell.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_time(cell: &str) -> Option<Value<NsTAIInterval>> {
+    let (lower, upper) = match cell.split_once('/') {
+        Some((lower, upper)) => (
+            Epoch::from_str(lower.trim()).ok()?,
+            Epoch::from_str(upper.trim()).ok()?,
+        ),
+        None => {
+            let epoch = Epoch::from_str(cell).ok()?;
+            (epoch, epoch)
+        }
+    };
+    (lower, upper).try_to_value().ok()
+}
+
+/// Error returned by [`CsvImporter`].
+#[derive(Debug)]
+pub enum CsvImportError {
+    /// The input could not be read or is not valid CSV.
+    Csv(::csv::Error),
+    /// A configured key or mapped column is not in the header.
+    MissingColumn(String),
+    /// A row has no value in the key column.
+    MissingKey {
+        /// 1-based line number of the row.
+        line: u64,
+    },
+    /// A cell does not parse under its column's schema.
+    InvalidCell {
+        /// 1-based line number of the row.
+        line: u64,
+        /// Header of the column.
+        column: String,
+        /// The schema the cell had to match.
+        schema: ColumnSchema,
+        /// The cell's text.
+        value: String,
+    },
+    /// A cell could not be written to the blob store.
+    Encode {
+        /// Header of the column.
+        column: String,
+        /// Underlying encoding error.
+        source: EncodeError,
+    },
+}
+
+impl fmt::Display for CsvImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv(err) => write!(f, "failed to read CSV: {err}"),
+            Self::MissingColumn(column) => write!(f, "CSV header has no column {column:?}"),
+            Self::MissingKey { line } => write!(f, "row on line {line} has an empty key"),
+            Self::InvalidCell {
+                line,
+                column,
+                schema,
+                value,
+            } => write!(
+                f,
+                "cell {value:?} in column {column:?} on line {line} is not a valid {schema}"
+            ),
+            Self::Encode { column, source } => {
+                write!(f, "failed to store column {column:?}: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Csv(err) => Some(err),
+            Self::Encode { source, .. } => Some(source),
+            Self::MissingColumn(_) | Self::MissingKey { .. } | Self::InvalidCell { .. } => None,
+        }
+    }
+}
+
+impl From<::csv::Error> for CsvImportError {
+    fn from(err: ::csv::Error) -> Self {
+        Self::Csv(err)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ColumnConfig {
+    attribute: Option<Id>,
+    schema: Option<ColumnSchema>,
+    skip: bool,
+}
+
+/// How one column of the current input is imported.
+struct ColumnPlan {
+    index: usize,
+    name: String,
+    attribute: Id,
+    schema: ColumnSchema,
+}
+
+/// CSV importer mapping columns to attributes and rows to entities.
+///
+/// Configure it with the `with_*` builders, then call
+/// [`import_str`](Self::import_str) or [`import_reader`](Self::import_reader)
+/// once per file. [`metadata`](Self::metadata) describes every attribute the
+/// importer derived from a header.
+pub struct CsvImporter<'a, Store, Hasher = Blake3>
+where
+    Store: BlobStore<Blake3>,
+    Hasher: HashProtocol,
+{
+    store: &'a mut Store,
+    id_salt: Option<[u8; 32]>,
+    delimiter: u8,
+    key: Option<String>,
+    columns: HashMap<String, ColumnConfig>,
+    derived: HashMap<Id, (String, ColumnSchema)>,
+    _hasher: PhantomData<Hasher>,
+}
+
+impl<'a, Store, Hasher> CsvImporter<'a, Store, Hasher>
+where
+    Store: BlobStore<Blake3>,
+    Hasher: HashProtocol,
+{
+    /// Creates a new importer backed by `store`. Pass an optional 32-byte
+    /// salt to namespace the derived row ids.
+    pub fn new(store: &'a mut Store, id_salt: Option<[u8; 32]>) -> Self {
+        Self {
+            store,
+            id_salt,
+            delimiter: b',',
+            key: None,
+            columns: HashMap::new(),
+            derived: HashMap::new(),
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Use `delimiter` instead of `,` between fields.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Derive row ids from the value in `column` alone instead of the whole
+    /// row. Rows sharing a key describe the same entity.
+    pub fn with_key_column(mut self, column: &str) -> Self {
+        self.key = Some(column.to_owned());
+        self
+    }
+
+    /// Import `column` under `schema` instead of inferring one.
+    pub fn with_column_schema(mut self, column: &str, schema: ColumnSchema) -> Self {
+        self.columns.entry(column.to_owned()).or_default().schema = Some(schema);
+        self
+    }
+
+    /// Import `column` as `attribute` instead of the attribute derived from
+    /// the header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` is not one of the [`ColumnSchema`]s.
+    pub fn with_attribute<S: ValueSchema>(mut self, column: &str, attribute: &Attribute<S>) -> Self {
+        let schema = ColumnSchema::of::<S>().expect("CSV columns support this value schema");
+        let config = self.columns.entry(column.to_owned()).or_default();
+        config.attribute = Some(attribute.id());
+        config.schema = Some(schema);
+        self
+    }
+
+    /// Leave `column` out of the import. A skipped key column still
+    /// derives row ids.
+    pub fn skip_column(mut self, column: &str) -> Self {
+        self.columns.entry(column.to_owned()).or_default().skip = true;
+        self
+    }
+
+    /// Imports CSV text. Convenience wrapper around
+    /// [`import_reader`](Self::import_reader).
+    pub fn import_str(&mut self, input: &str) -> Result<Fragment, CsvImportError> {
+        self.import_reader(input.as_bytes())
+    }
+
+    /// Imports CSV with a header row from `reader`, returning a [`Fragment`]
+    /// that exports the row entity ids.
+    pub fn import_reader(&mut self, reader: impl Read) -> Result<Fragment, CsvImportError> {
+        let mut csv = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .from_reader(reader);
+        let headers = csv.headers()?.clone();
+        let mut records = Vec::new();
+        for record in csv.records() {
+            records.push(record?);
+        }
+        let line = |record: &::csv::StringRecord| record.position().map_or(0, |pos| pos.line());
+
+        for column in self.columns.keys().chain(self.key.iter()) {
+            if !headers.iter().any(|header| header == column) {
+                return Err(CsvImportError::MissingColumn(column.clone()));
+            }
+        }
+
+        let mut plans = Vec::new();
+        let mut key_plan = None;
+        for (index, name) in headers.iter().enumerate() {
+            let config = self.columns.get(name).cloned().unwrap_or_default();
+            let is_key = self.key.as_deref() == Some(name);
+            if config.skip && !is_key {
+                continue;
+            }
+            let cells = records.iter().filter_map(|record| record.get(index));
+            let schema = match config.schema {
+                Some(schema) => schema,
+                None => match infer(cells) {
+                    Some(schema) => schema,
+                    // A column without values produces no tribles.
+                    None if is_key => ColumnSchema::LongString,
+                    None => continue,
+                },
+            };
+            let attribute = match config.attribute {
+                Some(attribute) => attribute,
+                None => self.derive_attribute(name, schema),
+            };
+            let plan = ColumnPlan {
+                index,
+                name: name.to_owned(),
+                attribute,
+                schema,
+            };
+            if is_key {
+                key_plan = Some((plan, config.skip));
+            } else {
+                plans.push(plan);
+            }
+        }
+
+        let mut facts = TribleSet::new();
+        let mut rows = Vec::with_capacity(records.len());
+        for record in &records {
+            let mut pairs: Vec<(RawId, RawValue)> = Vec::with_capacity(plans.len());
+            for plan in &plans {
+                if let Some(value) = self.cell(record, plan, line(record))? {
+                    pairs.push((plan.attribute.raw(), value.raw));
+                }
+            }
+            let row = match &key_plan {
+                Some((plan, skip)) => {
+                    let Some(key) = self.cell(record, plan, line(record))? else {
+                        return Err(CsvImportError::MissingKey {
+                            line: line(record),
+                        });
+                    };
+                    let key = (plan.attribute.raw(), key.raw);
+                    if !skip {
+                        pairs.push(key);
+                    }
+                    derive_row_id::<Hasher>(self.id_salt, &[key])
+                }
+                None if pairs.is_empty() => continue,
+                None => derive_row_id::<Hasher>(self.id_salt, &pairs),
+            };
+            for (attr, value) in pairs {
+                let attr = Id::new(attr).expect("attribute ids are non-nil");
+                facts.insert(&Trible::new(
+                    ExclusiveId::force_ref(&row),
+                    &attr,
+                    &Value::<UnknownValue>::new(value),
+                ));
+            }
+            rows.push(row);
+        }
+        Ok(Fragment::new(rows, facts))
+    }
+
+    /// Returns a [`Fragment`] describing every attribute derived from a
+    /// header so far, suitable for committing alongside the data. Mapped
+    /// attributes keep their own descriptions.
+    pub fn metadata(&mut self) -> Result<Fragment, Store::PutError> {
+        describe_columns(self.store, &self.derived)
+    }
+
+    /// The attribute derived for the column `name` under `schema`, recorded
+    /// for [`metadata`](Self::metadata).
+    fn derive_attribute(&mut self, name: &str, schema: ColumnSchema) -> Id {
+        let id = schema.attribute(name);
+        self.derived.insert(id, (name.to_owned(), schema));
+        id
+    }
+
+    /// Encode the cell of `record` in `plan`'s column, `None` if empty.
+    fn cell(
+        &mut self,
+        record: &::csv::StringRecord,
+        plan: &ColumnPlan,
+        line: u64,
+    ) -> Result<Option<Value<UnknownValue>>, CsvImportError> {
+        let cell = record.get(plan.index).unwrap_or("");
+        if cell.is_empty() {
+            return Ok(None);
+        }
+        plan.schema
+            .encode(cell, self.store)
+            .map(Some)
+            .map_err(|err| match err {
+                CellError::Invalid => CsvImportError::InvalidCell {
+                    line,
+                    column: plan.name.clone(),
+                    schema: plan.schema,
+                    value: cell.to_owned(),
+                },
+                CellError::Encode(source) => CsvImportError::Encode {
+                    column: plan.name.clone(),
+                    source,
+                },
+            })
+    }
+}
+
+/// The id of a row with the attribute/value `pairs`, independent of their
+/// order and namespaced by `salt`.
+pub(super) fn derive_row_id<Hasher: HashProtocol>(
+    salt: Option<[u8; 32]>,
+    pairs: &[(RawId, RawValue)],
+) -> Id {
+    let mut sorted = pairs.to_vec();
+    sorted.sort();
+
+    let mut hasher = Hasher::new();
+    if let Some(salt) = salt {
+        hasher.update(salt.as_ref());
+    }
+    for (attr, value) in &sorted {
+        hasher.update(attr);
+        hasher.update(value);
+    }
+    let digest: [u8; 32] = hasher.finalize();
+    let mut raw = [0u8; ID_LEN];
+    raw.copy_from_slice(&digest[digest.len() - ID_LEN..]);
+    if raw == [0; ID_LEN] {
+        raw[0] = 1;
+    }
+    Id::new(raw).expect("raw id is non-nil")
+}
+
+/// Describes the column schemas and the attributes in `derived`, keyed by
+/// id with their column name and schema.
+pub(super) fn describe_columns<Store: BlobStore<Blake3>>(
+    store: &mut Store,
+    derived: &HashMap<Id, (String, ColumnSchema)>,
+) -> Result<Fragment, Store::PutError> {
+    let mut meta = Fragment::default();
+    meta += <ShortString as ConstDescribe>::describe(store)?;
+    meta += <Handle<Blake3, LongString> as ConstDescribe>::describe(store)?;
+    meta += <I256BE as ConstDescribe>::describe(store)?;
+    meta += <F64 as ConstDescribe>::describe(store)?;
+    meta += <Boolean as ConstDescribe>::describe(store)?;
+    meta += <NsTAIInterval as ConstDescribe>::describe(store)?;
+    meta += <GenId as ConstDescribe>::describe(store)?;
+    for (attr, (name, schema)) in derived {
+        let handle = store.put(name.clone())?;
+        meta += entity! { ExclusiveId::force_ref(attr) @
+            metadata::name: handle,
+            metadata::value_schema: GenId::value_from(schema.id()),
+        };
+    }
+    Ok(meta)
+}
+
+/// The narrowest schema accepting every non-empty cell, `None` if all are
+/// empty.
+fn infer<'c>(cells: impl Iterator<Item = &'c str> + Clone) -> Option<ColumnSchema> {
+    let mut values = cells.filter(|cell| !cell.is_empty()).peekable();
+    values.peek()?;
+    Some(
+        ColumnSchema::INFERENCE_ORDER
+            .into_iter()
+            .find(|schema| values.clone().all(|cell| schema.accepts(cell)))
+            .unwrap_or(ColumnSchema::LongString),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer, ColumnSchema};
+
+    #[test]
+    fn inference_picks_the_narrowest_schema() {
+        let cases: [(&[&str], ColumnSchema); 8] = [
+            (&["true", "FALSE", ""], ColumnSchema::Boolean),
+            (&["1", "-42", ""], ColumnSchema::Integer),
+            (&["1", "4.2", "1e3"], ColumnSchema::Float),
+            (&["2024-03-01", "2024-03-01T12:00:00Z"], ColumnSchema::Time),
+            (&["1", "two"], ColumnSchema::ShortString),
+            (
+                &["a cell that does not fit into thirty-two bytes"],
+                ColumnSchema::LongString,
+            ),
+            (&["1", "true"], ColumnSchema::ShortString),
+            (&["0441013597", "12"], ColumnSchema::ShortString),
+        ];
+        for (cells, expected) in cases {
+            assert_eq!(infer(cells.iter().copied()), Some(expected), "{cells:?}");
+        }
+        assert_eq!(infer(["", ""].into_iter()), None);
+    }
+}
@@ -0,0 +1,442 @@
+//! Streaming JSON import for documents too large to hold in memory.
+//!
+//! [`JsonStreamImporter`] reads from any [`Read`] source and splits it into
+//! records without parsing the whole input: a document whose first token is
+//! `[` is one array whose elements are the records, anything else is a
+//! sequence of values separated by whitespace, which covers NDJSON, JSON
+//! Lines and concatenated JSON. Records are imported in chunks with
+//! [`JsonObjectImporter`] (or [`JsonTreeImporter`] when
+//! [`lossless`](JsonStreamImporter::lossless) is set), and every chunk
+//! becomes one commit in a [`Workspace`] together with the metadata
+//! describing its attributes.
+//!
+//! Only one record and one chunk of facts are held at a time, but commits
+//! and blobs stay in the workspace until it is pushed. Push after each
+//! chunk to keep memory bounded:
+//!
+//! ```rust,ignore
+//! let file = std::fs::File::open("events.ndjson")?;
+//! let mut stream = JsonStreamImporter::<_, Blake3>::new(file, None).with_commit_every(10_000);
+//! while let Some(progress) = stream.import_chunk(&mut ws)? {
+//!     repo.push(&mut ws)?;
+//!     eprintln!("{} records", progress.records);
+//! }
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+use std::marker::PhantomData;
+
+use crate::blob::schemas::UnknownBlob;
+use crate::blob::MemoryBlobStore;
+use crate::import::json::{JsonImportError, JsonObjectImporter};
+use crate::import::json_tree::JsonTreeImporter;
+use crate::repo::{BlobStore, Workspace};
+use crate::trible::{Fragment, TribleSet};
+use crate::value::schemas::hash::{Blake3, HashProtocol};
+
+/// Number of records per commit unless set with
+/// [`with_commit_every`](JsonStreamImporter::with_commit_every).
+pub const DEFAULT_COMMIT_EVERY: usize = 10_000;
+
+/// Error returned by [`JsonStreamImporter`].
+#[derive(Debug)]
+pub enum JsonStreamError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// A record could not be split from the input or imported.
+    Record {
+        /// Zero-based position of the record in the input.
+        index: usize,
+        /// Underlying import error.
+        source: JsonImportError,
+    },
+}
+
+impl fmt::Display for JsonStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read JSON input: {err}"),
+            Self::Record { index, source } => write!(f, "record {index}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonStreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Record { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for JsonStreamError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Running totals of a streaming import.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// Records imported so far.
+    pub records: usize,
+    /// Input bytes consumed so far.
+    pub bytes: u64,
+    /// Commits made so far, one per chunk.
+    pub commits: usize,
+    /// Tribles committed so far, counted per chunk, so facts repeated
+    /// across chunks are counted again.
+    pub tribles: usize,
+}
+
+/// Imports a JSON stream chunk by chunk into a [`Workspace`].
+pub struct JsonStreamImporter<R, Hasher = Blake3>
+where
+    Hasher: HashProtocol,
+{
+    records: Records<BufReader<R>>,
+    id_salt: Option<[u8; 32]>,
+    lossless: bool,
+    commit_every: usize,
+    message: String,
+    progress: ImportProgress,
+    _hasher: PhantomData<Hasher>,
+}
+
+impl<R, Hasher> JsonStreamImporter<R, Hasher>
+where
+    R: Read,
+    Hasher: HashProtocol,
+{
+    /// Creates an importer reading from `reader`. The optional salt
+    /// namespaces the derived entity ids as in [`JsonObjectImporter::new`].
+    pub fn new(reader: R, id_salt: Option<[u8; 32]>) -> Self {
+        Self {
+            records: Records::new(BufReader::new(reader)),
+            id_salt,
+            lossless: false,
+            commit_every: DEFAULT_COMMIT_EVERY,
+            message: "import JSON".to_owned(),
+            progress: ImportProgress::default(),
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Imports every record with [`JsonTreeImporter`], keeping field order
+    /// and accepting primitive records. Each record becomes its own tree;
+    /// a top-level array is not stored as an array node.
+    pub fn lossless(mut self) -> Self {
+        self.lossless = true;
+        self
+    }
+
+    /// Sets how many records go into each commit. Zero is treated as one.
+    pub fn with_commit_every(mut self, records: usize) -> Self {
+        self.commit_every = records.max(1);
+        self
+    }
+
+    /// Sets the message of the commits made by the importer.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Totals of the chunks imported so far.
+    pub fn progress(&self) -> ImportProgress {
+        self.progress
+    }
+
+    /// Reads and commits the next chunk of records, returning the updated
+    /// totals, or `None` once the input is exhausted.
+    ///
+    /// A failing chunk is not committed, but the chunks before it stay
+    /// committed.
+    pub fn import_chunk<Blobs>(
+        &mut self,
+        ws: &mut Workspace<Blobs>,
+    ) -> Result<Option<ImportProgress>, JsonStreamError>
+    where
+        Blobs: BlobStore<Blake3>,
+    {
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let id_salt = self.id_salt;
+        let (count, facts) = if self.lossless {
+            let mut importer = JsonTreeImporter::<_, Hasher>::new(&mut blobs, id_salt);
+            let (count, mut facts) = self.fill(|record| importer.import_str(record))?;
+            if count > 0 {
+                facts += importer
+                    .metadata()
+                    .expect("infallible blob put")
+                    .into_facts();
+            }
+            (count, facts)
+        } else {
+            let mut importer = JsonObjectImporter::<_, Hasher>::new(&mut blobs, id_salt);
+            let (count, mut facts) = self.fill(|record| importer.import_str(record))?;
+            if count > 0 {
+                facts += importer
+                    .metadata()
+                    .expect("infallible blob put")
+                    .into_facts();
+            }
+            (count, facts)
+        };
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let staged = blobs.reader().expect("infallible blob reader");
+        for (_, blob) in staged {
+            ws.put::<UnknownBlob, _>(blob);
+        }
+        self.progress.records += count;
+        self.progress.bytes = self.records.bytes;
+        self.progress.commits += 1;
+        self.progress.tribles += facts.len();
+        ws.commit(facts, &self.message);
+        Ok(Some(self.progress))
+    }
+
+    /// Imports the whole input, calling `on_chunk` with the totals after
+    /// every commit, and returns the final totals.
+    pub fn import_into<Blobs>(
+        &mut self,
+        ws: &mut Workspace<Blobs>,
+        mut on_chunk: impl FnMut(&ImportProgress),
+    ) -> Result<ImportProgress, JsonStreamError>
+    where
+        Blobs: BlobStore<Blake3>,
+    {
+        while let Some(progress) = self.import_chunk(ws)? {
+            on_chunk(&progress);
+        }
+        Ok(self.progress)
+    }
+
+    /// Imports up to a chunk of records with `import`, returning how many
+    /// were read and their facts.
+    fn fill(
+        &mut self,
+        mut import: impl FnMut(&str) -> Result<Fragment, JsonImportError>,
+    ) -> Result<(usize, TribleSet), JsonStreamError> {
+        let mut facts = TribleSet::new();
+        let mut count = 0;
+        while count < self.commit_every {
+            let index = self.progress.records + count;
+            let Some(record) = self.next_record(index)? else {
+                break;
+            };
+            facts += import(&record)
+                .map_err(|source| JsonStreamError::Record { index, source })?
+                .into_facts();
+            count += 1;
+        }
+        Ok((count, facts))
+    }
+
+    fn next_record(&mut self, index: usize) -> Result<Option<String>, JsonStreamError> {
+        match self.records.next_record() {
+            Ok(Some(bytes)) => String::from_utf8(bytes).map(Some).map_err(|_| {
+                JsonStreamError::Record {
+                    index,
+                    source: JsonImportError::Syntax("invalid utf-8".into()),
+                }
+            }),
+            Ok(None) => Ok(None),
+            Err(Framing::Io(err)) => Err(JsonStreamError::Io(err)),
+            Err(Framing::Syntax(msg)) => Err(JsonStreamError::Record {
+                index,
+                source: JsonImportError::Syntax(msg.into()),
+            }),
+        }
+    }
+}
+
+enum Framing {
+    Io(io::Error),
+    Syntax(&'static str),
+}
+
+impl From<io::Error> for Framing {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    Start,
+    Sequence,
+    Array { first: bool },
+    Done,
+}
+
+/// Splits a byte stream into the raw text of its records. Only brackets
+/// and string boundaries are tracked; the importers check the rest.
+struct Records<R> {
+    reader: R,
+    layout: Layout,
+    bytes: u64,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            layout: Layout::Start,
+            bytes: 0,
+        }
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+        self.bytes += 1;
+    }
+
+    fn skip_ws(&mut self) -> io::Result<()> {
+        while let Some(byte) = self.peek()? {
+            if !byte.is_ascii_whitespace() {
+                break;
+            }
+            self.bump();
+        }
+        Ok(())
+    }
+
+    fn next_record(&mut self) -> Result<Option<Vec<u8>>, Framing> {
+        if self.layout == Layout::Start {
+            self.skip_ws()?;
+            self.layout = match self.peek()? {
+                None => Layout::Done,
+                Some(b'[') => {
+                    self.bump();
+                    Layout::Array { first: true }
+                }
+                Some(_) => Layout::Sequence,
+            };
+        }
+        self.skip_ws()?;
+        match self.layout {
+            Layout::Start | Layout::Done => Ok(None),
+            Layout::Sequence => match self.peek()? {
+                None => {
+                    self.layout = Layout::Done;
+                    Ok(None)
+                }
+                Some(_) => self.value().map(Some),
+            },
+            Layout::Array { first } => {
+                match self.peek()? {
+                    Some(b']') => {
+                        self.bump();
+                        self.skip_ws()?;
+                        self.layout = Layout::Done;
+                        return match self.peek()? {
+                            None => Ok(None),
+                            Some(_) => Err(Framing::Syntax("trailing tokens")),
+                        };
+                    }
+                    Some(b',') if !first => {
+                        self.bump();
+                        self.skip_ws()?;
+                    }
+                    None => return Err(Framing::Syntax("unexpected end of input")),
+                    Some(_) if !first => return Err(Framing::Syntax("unexpected token")),
+                    Some(_) => {}
+                }
+                self.layout = Layout::Array { first: false };
+                self.value().map(Some)
+            }
+        }
+    }
+
+    /// Copies one value, starting at a non-whitespace byte.
+    fn value(&mut self) -> Result<Vec<u8>, Framing> {
+        let mut out = Vec::new();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let Some(byte) = self.peek()? else {
+                if depth == 0 && !in_string && !out.is_empty() {
+                    return Ok(out);
+                }
+                return Err(Framing::Syntax("unexpected end of input"));
+            };
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' if depth > 0 => depth -= 1,
+                    b'}' | b']' | b',' if depth == 0 && out.is_empty() => {
+                        return Err(Framing::Syntax("unexpected token"));
+                    }
+                    b'}' | b']' | b',' if depth == 0 => return Ok(out),
+                    byte if byte.is_ascii_whitespace() && depth == 0 => return Ok(out),
+                    _ => {}
+                }
+            }
+            out.push(byte);
+            self.bump();
+            if depth == 0 && !in_string && matches!(byte, b'}' | b']' | b'"') {
+                return Ok(out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Framing, Records};
+
+    fn split(input: &str) -> Result<Vec<String>, &'static str> {
+        let mut records = Records::new(input.as_bytes());
+        let mut out = Vec::new();
+        loop {
+            match records.next_record() {
+                Ok(Some(bytes)) => out.push(String::from_utf8(bytes).unwrap()),
+                Ok(None) => return Ok(out),
+                Err(Framing::Syntax(msg)) => return Err(msg),
+                Err(Framing::Io(err)) => panic!("{err}"),
+            }
+        }
+    }
+
+    #[test]
+    fn splits_sequences_and_arrays() {
+        let lines = "{\"a\": \"}\\\"\"}\n\n[1, {\"b\": 2}]\n\"x\" 3 true\n";
+        assert_eq!(
+            split(lines).unwrap(),
+            ["{\"a\": \"}\\\"\"}", "[1, {\"b\": 2}]", "\"x\"", "3", "true"]
+        );
+        assert_eq!(
+            split(" [ {\"a\": [1]} ,{},\n2 ] ").unwrap(),
+            ["{\"a\": [1]}", "{}", "2"]
+        );
+        assert!(split("[]").unwrap().is_empty());
+        assert!(split("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_broken_framing() {
+        assert_eq!(split("[{}, {}"), Err("unexpected end of input"));
+        assert_eq!(split("[{} {}]"), Err("unexpected token"));
+        assert_eq!(split("[{}] {}"), Err("trailing tokens"));
+        assert_eq!(split("{\"a\": 1"), Err("unexpected end of input"));
+    }
+}
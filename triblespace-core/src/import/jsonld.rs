@@ -0,0 +1,108 @@
+//! JSON-LD → TribleSpace importer.
+//!
+//! Streams a [JSON-LD](https://www.w3.org/TR/json-ld11/) document through
+//! the `oxjsonld` parser, which expands it against its `@context` and emits
+//! RDF. Triples then map exactly as in [`crate::import::ntriples`]: `@id`s
+//! become entity ids via [`crate::import::rdf_uri`], node objects without
+//! an `@id` become blank nodes with a fresh id per import, expanded
+//! property IRIs become described attributes, `@language` values land under
+//! `"<predicate> @<tag>"`, and `@type`d values (plus native JSON numbers and
+//! booleans) take the matching XSD schema.
+//!
+//! Contexts are expanded from the document itself. Remote contexts
+//! (`"@context": "https://…"`) are not fetched; supply their bodies by URL
+//! through [`ingest_jsonld_with_contexts`]. Named graphs (`@graph` under an
+//! `@id`) are flattened into the returned set.
+//!
+//! ```rust,ignore
+//! let data = br#"{
+//!     "@context": {"ex": "http://example.org/", "name": {"@id": "ex:name", "@language": "en"}},
+//!     "@id": "ex:frank",
+//!     "name": "Frank"
+//! }"#;
+//! let (facts, count) = ingest_jsonld(&mut workspace, &data[..], None)?;
+//! assert_eq!(count, 1);
+//! ```
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use oxjsonld::{JsonLdParseError, JsonLdParser, JsonLdRemoteDocument};
+
+use crate::import::rdf::RdfSink;
+use crate::import::RdfImportError;
+use crate::repo::{BlobStore, Workspace};
+use crate::trible::TribleSet;
+use crate::value::schemas::hash::Blake3;
+
+/// Read JSON-LD from `reader` and produce a [`TribleSet`] of facts plus the
+/// number of triples consumed. Relative IRIs resolve against `base_iri`
+/// (or an `@base` in the context). Literal blobs (strings, URIs) are
+/// written into `ws`'s local blob store.
+///
+/// Parsing stops at the first syntax or expansion error, including a
+/// reference to a remote context.
+pub fn ingest_jsonld<Blobs>(
+    ws: &mut Workspace<Blobs>,
+    reader: impl Read,
+    base_iri: Option<&str>,
+) -> Result<(TribleSet, usize), RdfImportError>
+where
+    Blobs: BlobStore<Blake3>,
+{
+    ingest_jsonld_with_contexts(ws, reader, base_iri, HashMap::new())
+}
+
+/// Like [`ingest_jsonld`], resolving remote contexts from `contexts`, a map
+/// from context URL to the JSON-LD document served there. A context URL
+/// missing from the map is an error.
+pub fn ingest_jsonld_with_contexts<Blobs>(
+    ws: &mut Workspace<Blobs>,
+    reader: impl Read,
+    base_iri: Option<&str>,
+    contexts: HashMap<String, Vec<u8>>,
+) -> Result<(TribleSet, usize), RdfImportError>
+where
+    Blobs: BlobStore<Blake3>,
+{
+    let mut parser = JsonLdParser::new();
+    if let Some(base_iri) = base_iri {
+        parser = parser
+            .with_base_iri(base_iri)
+            .map_err(|err| RdfImportError::InvalidBaseIri(Box::new(err)))?;
+    }
+    let quads = parser
+        .for_reader(reader)
+        .with_load_document_callback(move |url, _options| match contexts.get(url) {
+            Some(document) => Ok(JsonLdRemoteDocument {
+                document: document.clone(),
+                document_url: url.to_owned(),
+            }),
+            None => Err(format!("remote context {url} was not supplied").into()),
+        });
+
+    let mut sink = RdfSink::new(ws);
+    for quad in quads {
+        let quad = quad.map_err(|err| match err {
+            JsonLdParseError::Io(err) => RdfImportError::Io(err),
+            JsonLdParseError::Syntax(err) => RdfImportError::Syntax(Box::new(err)),
+        })?;
+        sink.ox_statement(&quad.subject, &quad.predicate, &quad.object);
+    }
+    Ok(sink.finish())
+}
+
+/// Convenience wrapper around [`ingest_jsonld`] that opens a file at `path`
+/// and streams it.
+pub fn ingest_jsonld_file<Blobs>(
+    ws: &mut Workspace<Blobs>,
+    path: &Path,
+    base_iri: Option<&str>,
+) -> Result<(TribleSet, usize), RdfImportError>
+where
+    Blobs: BlobStore<Blake3>,
+{
+    let file = std::fs::File::open(path).map_err(RdfImportError::Io)?;
+    ingest_jsonld(ws, std::io::BufReader::new(file), base_iri)
+}
@@ -4,12 +4,24 @@
 //! [`TribleSet`](crate::trible::TribleSet) changes ready to merge into a
 //! repository or workspace.
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "csv")]
+pub mod csv;
 mod import_attribute;
 pub mod json;
+pub mod json_stream;
 pub mod json_tree;
+#[cfg(feature = "rdf")]
+pub mod jsonld;
 pub mod ntriples;
+mod rdf;
+pub mod safetensors;
+#[cfg(feature = "rdf")]
+pub mod turtle;
 
 pub(crate) use import_attribute::ImportAttribute;
+pub use rdf::RdfImportError;
 
 use triblespace_core_macros::attributes;
 
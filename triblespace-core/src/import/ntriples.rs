@@ -4,6 +4,7 @@
 //! object URIs are derived deterministically into entity ids via
 //! [`crate::import::rdf_uri`] — the same URI always maps to the same
 //! triblespace `Id` across processes, so repeated imports converge.
+//! Blank nodes (`_:label`) get a fresh id per label and import.
 //!
 //! Predicate URIs become attribute ids via [`Attribute::from_name`], with
 //! the value schema chosen from the object's XSD datatype:
@@ -16,48 +17,46 @@
 //! - `xsd:float` / `xsd:double` → `F64`
 //! - `xsd:boolean` → `Boolean`
 //! - `xsd:string`, untyped, language-tagged strings → `Handle<Blake3, LongString>`
-//! - URI objects → `GenId`
+//!   (language-tagged ones under the attribute named `"<predicate> @<tag>"`)
+//! - URI and blank-node objects → `GenId`
+//!
+//! Each attribute is described (`metadata::name` holding the predicate URI,
+//! plus `metadata::value_schema`) alongside the facts, which is what lets
+//! [`crate::export::rdf`] write the data back out as RDF. The Turtle and
+//! JSON-LD importers share this mapping.
+//!
+//! [`Attribute::from_name`]: crate::attribute::Attribute::from_name
 
 use std::io::BufRead;
 use std::path::Path;
 
-use num_rational::Ratio;
-
-use crate::attribute::Attribute;
-use crate::blob::schemas::longstring::LongString;
-use crate::id::{ExclusiveId, Id};
-use crate::macros::entity;
-use crate::prelude::valueschemas;
+use crate::import::rdf::{typed_literal, RdfLiteral, RdfNode, RdfObject, RdfSink};
 use crate::repo::{BlobStore, Workspace};
-use crate::trible::{Trible, TribleSet};
-use crate::value::schemas::hash::{Blake3, Handle};
-use crate::value::{ToValue, Value};
-
-const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
-
-/// A parsed RDF literal mapped to native triblespace types.
-enum RdfLiteral {
-    Text(String),
-    SignedInt(i128),
-    UnsignedInt(u128),
-    Decimal(Ratio<i128>),
-    Float(f64),
-    Bool(bool),
-}
+use crate::trible::TribleSet;
+use crate::value::schemas::hash::Blake3;
+
+#[cfg(test)]
+use crate::import::rdf::parse_decimal;
 
 enum NtObject {
     Uri(String),
+    Blank(String),
     Literal(RdfLiteral),
 }
 
 // ── Parsing ─────────────────────────────────────────────────────────
 
+/// Parse one statement. Blank-node subjects come back with their `_:`
+/// prefix, which no IRI can start with.
 fn parse_line(line: &str) -> Option<(String, String, NtObject)> {
     let line = line.trim();
     if line.is_empty() || line.starts_with('#') {
         return None;
     }
-    let (subject, rest) = parse_uri(line)?;
+    let (subject, rest) = match parse_blank(line) {
+        Some((label, rest)) => (format!("_:{label}"), rest),
+        None => parse_uri(line)?,
+    };
     let rest = rest.trim_start();
     let (predicate, rest) = parse_uri(rest)?;
     let rest = rest.trim_start();
@@ -65,9 +64,17 @@ fn parse_line(line: &str) -> Option<(String, String, NtObject)> {
     let object = if rest.starts_with('<') {
         let (uri, _) = parse_uri(rest)?;
         NtObject::Uri(uri)
+    } else if let Some((label, _)) = parse_blank(rest) {
+        NtObject::Blank(label.to_owned())
     } else if rest.starts_with('"') {
-        let (text, datatype) = parse_literal_with_datatype(rest)?;
-        NtObject::Literal(typed_literal(text, datatype.as_deref()))
+        let (text, suffix) = parse_literal(rest)?;
+        match suffix {
+            LiteralSuffix::Datatype(dt) => NtObject::Literal(typed_literal(text, Some(&dt))),
+            LiteralSuffix::Language(language) => {
+                NtObject::Literal(RdfLiteral::Tagged { text, language })
+            }
+            LiteralSuffix::None => NtObject::Literal(RdfLiteral::Text(text)),
+        }
     } else {
         return None;
     };
@@ -75,119 +82,102 @@ fn parse_line(line: &str) -> Option<(String, String, NtObject)> {
     Some((subject, predicate, object))
 }
 
+fn parse_blank(input: &str) -> Option<(&str, &str)> {
+    let rest = input.strip_prefix("_:")?;
+    let end = rest
+        .find(|c: char| c.is_whitespace())
+        .unwrap_or(rest.len());
+    (end > 0).then(|| (&rest[..end], &rest[end..]))
+}
+
 fn parse_uri(input: &str) -> Option<(String, &str)> {
     if !input.starts_with('<') {
         return None;
     }
     let end = input[1..].find('>')?;
-    Some((input[1..=end].to_string(), &input[end + 2..]))
+    let raw = &input[1..=end];
+    let uri = if raw.contains('\\') {
+        let mut uri = String::new();
+        let mut chars = raw.char_indices();
+        while let Some((_, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next()? {
+                    (_, 'u') => uri.push(read_uchar(&mut chars, 4)?),
+                    (_, 'U') => uri.push(read_uchar(&mut chars, 8)?),
+                    _ => return None,
+                },
+                c => uri.push(c),
+            }
+        }
+        uri
+    } else {
+        raw.to_string()
+    };
+    Some((uri, &input[end + 2..]))
 }
 
-fn parse_literal_with_datatype(input: &str) -> Option<(String, Option<String>)> {
+/// Decode the hex digits of a `\uXXXX` / `\UXXXXXXXX` escape.
+fn read_uchar(chars: &mut impl Iterator<Item = (usize, char)>, len: usize) -> Option<char> {
+    let mut code = 0u32;
+    for _ in 0..len {
+        code = code * 16 + chars.next()?.1.to_digit(16)?;
+    }
+    char::from_u32(code)
+}
+
+enum LiteralSuffix {
+    None,
+    Datatype(String),
+    Language(String),
+}
+
+fn parse_literal(input: &str) -> Option<(String, LiteralSuffix)> {
     if !input.starts_with('"') {
         return None;
     }
-    let bytes = input.as_bytes();
-    let mut i = 1;
     let mut text = String::new();
-    while i < bytes.len() {
-        if bytes[i] == b'\\' && i + 1 < bytes.len() {
-            match bytes[i + 1] {
-                b'n' => text.push('\n'),
-                b't' => text.push('\t'),
-                b'r' => text.push('\r'),
-                b'"' => text.push('"'),
-                b'\\' => text.push('\\'),
-                _ => {
+    let mut chars = input.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                (_, 'n') => text.push('\n'),
+                (_, 't') => text.push('\t'),
+                (_, 'r') => text.push('\r'),
+                (_, '"') => text.push('"'),
+                (_, '\\') => text.push('\\'),
+                (_, 'u') => text.push(read_uchar(&mut chars, 4)?),
+                (_, 'U') => text.push(read_uchar(&mut chars, 8)?),
+                (_, other) => {
                     text.push('\\');
-                    text.push(bytes[i + 1] as char);
+                    text.push(other);
                 }
+            },
+            '"' => {
+                let rest = &input[i + 1..];
+                if let Some(rest) = rest.strip_prefix("^^") {
+                    let (dt, _) = parse_uri(rest)?;
+                    return Some((text, LiteralSuffix::Datatype(dt)));
+                }
+                if let Some(rest) = rest.strip_prefix('@') {
+                    let end = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                        .unwrap_or(rest.len());
+                    return Some((text, LiteralSuffix::Language(rest[..end].to_owned())));
+                }
+                return Some((text, LiteralSuffix::None));
             }
-            i += 2;
-        } else if bytes[i] == b'"' {
-            let rest = &input[i + 1..];
-            if let Some(rest) = rest.strip_prefix("^^") {
-                let (dt, _) = parse_uri(rest)?;
-                return Some((text, Some(dt)));
-            }
-            return Some((text, None));
-        } else {
-            text.push(bytes[i] as char);
-            i += 1;
+            c => text.push(c),
         }
     }
     None
 }
 
-/// Parse a decimal string into a `Ratio<i128>`.
-/// Handles `"3.14"` → `314/100`, `"42"` → `42/1`, `"-0.5"` → `-1/2`.
-fn parse_decimal(s: &str) -> Option<Ratio<i128>> {
-    if let Some(dot_pos) = s.find('.') {
-        let decimals = s.len() - dot_pos - 1;
-        let without_dot: String = s.chars().filter(|c| *c != '.').collect();
-        let numerator: i128 = without_dot.parse().ok()?;
-        let denominator: i128 = 10i128.checked_pow(decimals as u32)?;
-        Some(Ratio::new(numerator, denominator))
-    } else {
-        let n: i128 = s.parse().ok()?;
-        Some(Ratio::from_integer(n))
-    }
-}
-
-fn typed_literal(text: String, datatype: Option<&str>) -> RdfLiteral {
-    match datatype {
-        Some(dt) if dt.starts_with(XSD) => {
-            let local = &dt[XSD.len()..];
-            match local {
-                "integer" | "int" | "long" | "short" | "byte"
-                | "negativeInteger" | "nonPositiveInteger" => text
-                    .parse::<i128>()
-                    .map(RdfLiteral::SignedInt)
-                    .unwrap_or(RdfLiteral::Text(text)),
-                "nonNegativeInteger"
-                | "positiveInteger"
-                | "unsignedInt"
-                | "unsignedLong"
-                | "unsignedShort"
-                | "unsignedByte" => text
-                    .parse::<u128>()
-                    .map(RdfLiteral::UnsignedInt)
-                    .unwrap_or(RdfLiteral::Text(text)),
-                "decimal" => parse_decimal(&text)
-                    .map(RdfLiteral::Decimal)
-                    .unwrap_or(RdfLiteral::Text(text)),
-                "float" | "double" => text
-                    .parse::<f64>()
-                    .map(RdfLiteral::Float)
-                    .unwrap_or(RdfLiteral::Text(text)),
-                "boolean" => match text.as_str() {
-                    "true" | "1" => RdfLiteral::Bool(true),
-                    "false" | "0" => RdfLiteral::Bool(false),
-                    _ => RdfLiteral::Text(text),
-                },
-                _ => RdfLiteral::Text(text),
-            }
-        }
-        _ => RdfLiteral::Text(text),
-    }
-}
-
-// ── URI → Id ────────────────────────────────────────────────────────
-
-fn uri_to_id<Blobs>(ws: &mut Workspace<Blobs>, uri: &str) -> Id
-where
-    Blobs: BlobStore<Blake3>,
-{
-    let handle: Value<Handle<Blake3, LongString>> = ws.put(uri.to_owned());
-    let fragment = entity! { crate::import::rdf_uri: handle };
-    fragment.root().expect("intrinsic URI entity")
-}
-
 // ── Ingestion ───────────────────────────────────────────────────────
 
 /// Read N-Triples from `reader` and produce a [`TribleSet`] of facts plus
 /// the number of triples consumed. Literal blobs (strings, URIs) are
-/// written into `ws`'s local blob store.
+/// written into `ws`'s local blob store. Lines that fail to parse are
+/// skipped.
 ///
 /// Merge the returned [`TribleSet`] into a workspace via
 /// [`Workspace::commit`] or `+=` to materialize the import.
@@ -198,8 +188,7 @@ pub fn ingest_ntriples<Blobs>(
 where
     Blobs: BlobStore<Blake3>,
 {
-    let mut facts = TribleSet::new();
-    let mut count = 0;
+    let mut sink = RdfSink::new(ws);
 
     for line in reader.lines() {
         let Ok(line) = line else { continue };
@@ -207,55 +196,24 @@ where
             continue;
         };
 
-        let subject_id = uri_to_id(ws, &subject);
-
-        // Subject URI identity fragment — idempotent across repeated imports.
-        let sub_h: Value<Handle<Blake3, LongString>> = ws.put(subject.to_owned());
-        facts += entity! { crate::import::rdf_uri: sub_h };
-
-        let e = ExclusiveId::force_ref(&subject_id);
+        let subject = match subject.strip_prefix("_:") {
+            Some(label) => RdfNode::Blank(label),
+            None => RdfNode::Uri(&subject),
+        };
         match object {
-            NtObject::Uri(ref obj_uri) => {
-                let attr = Attribute::<valueschemas::GenId>::from_name(&predicate);
-                let obj_id = uri_to_id(ws, obj_uri);
-                let obj_h: Value<Handle<Blake3, LongString>> = ws.put(obj_uri.to_owned());
-                facts += entity! { crate::import::rdf_uri: obj_h };
-                facts.insert(&Trible::new(e, &attr.id(), &obj_id.to_value()));
-            }
-            NtObject::Literal(RdfLiteral::Text(ref text)) => {
-                let attr = Attribute::<Handle<Blake3, LongString>>::from_name(&predicate);
-                let handle: Value<Handle<Blake3, LongString>> = ws.put(text.to_owned());
-                facts.insert(&Trible::new(e, &attr.id(), &handle));
-            }
-            NtObject::Literal(RdfLiteral::SignedInt(val)) => {
-                let attr = Attribute::<valueschemas::I256BE>::from_name(&predicate);
-                let v: Value<valueschemas::I256BE> = val.to_value();
-                facts.insert(&Trible::new(e, &attr.id(), &v));
+            NtObject::Uri(uri) => {
+                sink.statement(subject, &predicate, RdfObject::Node(RdfNode::Uri(&uri)))
             }
-            NtObject::Literal(RdfLiteral::UnsignedInt(val)) => {
-                let attr = Attribute::<valueschemas::U256BE>::from_name(&predicate);
-                let v: Value<valueschemas::U256BE> = val.to_value();
-                facts.insert(&Trible::new(e, &attr.id(), &v));
+            NtObject::Blank(label) => {
+                sink.statement(subject, &predicate, RdfObject::Node(RdfNode::Blank(&label)))
             }
-            NtObject::Literal(RdfLiteral::Decimal(val)) => {
-                let attr = Attribute::<valueschemas::R256BE>::from_name(&predicate);
-                let v: Value<valueschemas::R256BE> = val.to_value();
-                facts.insert(&Trible::new(e, &attr.id(), &v));
-            }
-            NtObject::Literal(RdfLiteral::Float(val)) => {
-                let attr = Attribute::<valueschemas::F64>::from_name(&predicate);
-                facts.insert(&Trible::new(e, &attr.id(), &val.to_value()));
-            }
-            NtObject::Literal(RdfLiteral::Bool(val)) => {
-                let attr = Attribute::<valueschemas::Boolean>::from_name(&predicate);
-                facts.insert(&Trible::new(e, &attr.id(), &val.to_value()));
+            NtObject::Literal(literal) => {
+                sink.statement(subject, &predicate, RdfObject::Literal(literal))
             }
         }
-
-        count += 1;
     }
 
-    (facts, count)
+    sink.finish()
 }
 
 /// Convenience wrapper around [`ingest_ntriples`] that opens a file at
@@ -335,6 +293,14 @@ mod tests {
         assert!(matches!(o, NtObject::Literal(RdfLiteral::Bool(true))));
     }
 
+    #[test]
+    fn parse_unicode_and_escapes() {
+        let line = r#"<http://ex/caf\u00E9> <http://ex/p> "Caf\u00E9 \"é\"" ."#;
+        let (s, _, o) = parse_line(line).unwrap();
+        assert_eq!(s, "http://ex/café");
+        assert!(matches!(o, NtObject::Literal(RdfLiteral::Text(ref t)) if t == "Café \"é\""));
+    }
+
     #[test]
     fn decimal_parse_helper() {
         let r = parse_decimal("3.14").unwrap();
@@ -0,0 +1,284 @@
+//! Shared RDF → TribleSpace mapping used by the N-Triples, Turtle and
+//! JSON-LD importers.
+//!
+//! Every importer parses its syntax into [`RdfNode`]s and [`RdfObject`]s and
+//! hands the statements to an [`RdfSink`], so URIs, predicates and literals
+//! land identically whichever format they came from:
+//!
+//! - URIs become entity ids through an [`rdf_uri`] fragment, and the
+//!   `rdf_uri` edge is emitted so the URI can be recovered.
+//! - Blank nodes get a fresh id per label and import; they have no
+//!   identity outside the document they appear in.
+//! - Predicates become attributes via [`Attribute::from_name`] under the
+//!   schema of the object, described with `metadata::name` +
+//!   `metadata::value_schema` on first use.
+//! - Language-tagged strings use the attribute named `"<predicate> @<tag>"`
+//!   (tag lowercased). A space can't occur in an IRI, so these never
+//!   collide with a plain predicate, and `"chat"@fr` stays distinct from
+//!   `"chat"@en` and from an untagged `"chat"`.
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use num_rational::Ratio;
+
+use crate::attribute::Attribute;
+use crate::blob::schemas::longstring::LongString;
+use crate::id::{rngid, ExclusiveId, Id};
+use crate::import::rdf_uri;
+use crate::macros::entity;
+use crate::metadata;
+use crate::prelude::valueschemas;
+use crate::repo::{BlobStore, Workspace};
+use crate::trible::{Trible, TribleSet};
+use crate::value::schemas::genid::GenId;
+use crate::value::schemas::hash::{Blake3, Handle};
+use crate::value::{ToValue, Value, ValueSchema};
+
+pub(crate) const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Error returned by the streaming RDF importers.
+#[derive(Debug)]
+pub enum RdfImportError {
+    /// Reading the input failed.
+    Io(std::io::Error),
+    /// The input is not valid for its format.
+    Syntax(Box<dyn Error + Send + Sync>),
+    /// The base IRI passed to the importer does not parse.
+    InvalidBaseIri(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for RdfImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read RDF input: {err}"),
+            Self::Syntax(err) => write!(f, "invalid RDF input: {err}"),
+            Self::InvalidBaseIri(err) => write!(f, "invalid base IRI: {err}"),
+        }
+    }
+}
+
+impl Error for RdfImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Syntax(err) | Self::InvalidBaseIri(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// A parsed RDF literal mapped to native triblespace types.
+pub(crate) enum RdfLiteral {
+    Text(String),
+    Tagged { text: String, language: String },
+    SignedInt(i128),
+    UnsignedInt(u128),
+    Decimal(Ratio<i128>),
+    Float(f64),
+    Bool(bool),
+}
+
+/// A subject, or a resource in object position.
+pub(crate) enum RdfNode<'a> {
+    Uri(&'a str),
+    Blank(&'a str),
+}
+
+pub(crate) enum RdfObject<'a> {
+    Node(RdfNode<'a>),
+    Literal(RdfLiteral),
+}
+
+/// Parse a decimal string into a `Ratio<i128>`.
+/// Handles `"3.14"` → `314/100`, `"42"` → `42/1`, `"-0.5"` → `-1/2`.
+pub(crate) fn parse_decimal(s: &str) -> Option<Ratio<i128>> {
+    if let Some(dot_pos) = s.find('.') {
+        let decimals = s.len() - dot_pos - 1;
+        let without_dot: String = s.chars().filter(|c| *c != '.').collect();
+        let numerator: i128 = without_dot.parse().ok()?;
+        let denominator: i128 = 10i128.checked_pow(decimals as u32)?;
+        Some(Ratio::new(numerator, denominator))
+    } else {
+        let n: i128 = s.parse().ok()?;
+        Some(Ratio::from_integer(n))
+    }
+}
+
+/// Map a literal's lexical form and datatype IRI onto a native type,
+/// falling back to text for unknown datatypes and unparsable values.
+pub(crate) fn typed_literal(text: String, datatype: Option<&str>) -> RdfLiteral {
+    match datatype {
+        Some(dt) if dt.starts_with(XSD) => {
+            let local = &dt[XSD.len()..];
+            match local {
+                "integer" | "int" | "long" | "short" | "byte"
+                | "negativeInteger" | "nonPositiveInteger" => text
+                    .parse::<i128>()
+                    .map(RdfLiteral::SignedInt)
+                    .unwrap_or(RdfLiteral::Text(text)),
+                "nonNegativeInteger"
+                | "positiveInteger"
+                | "unsignedInt"
+                | "unsignedLong"
+                | "unsignedShort"
+                | "unsignedByte" => text
+                    .parse::<u128>()
+                    .map(RdfLiteral::UnsignedInt)
+                    .unwrap_or(RdfLiteral::Text(text)),
+                "decimal" => parse_decimal(&text)
+                    .map(RdfLiteral::Decimal)
+                    .unwrap_or(RdfLiteral::Text(text)),
+                "float" | "double" => text
+                    .parse::<f64>()
+                    .map(RdfLiteral::Float)
+                    .unwrap_or(RdfLiteral::Text(text)),
+                "boolean" => match text.as_str() {
+                    "true" | "1" => RdfLiteral::Bool(true),
+                    "false" | "0" => RdfLiteral::Bool(false),
+                    _ => RdfLiteral::Text(text),
+                },
+                _ => RdfLiteral::Text(text),
+            }
+        }
+        _ => RdfLiteral::Text(text),
+    }
+}
+
+/// Accumulates the facts for one import.
+pub(crate) struct RdfSink<'w, Blobs: BlobStore<Blake3>> {
+    ws: &'w mut Workspace<Blobs>,
+    facts: TribleSet,
+    described: HashSet<Id>,
+    blanks: HashMap<String, Id>,
+    count: usize,
+}
+
+impl<'w, Blobs: BlobStore<Blake3>> RdfSink<'w, Blobs> {
+    pub(crate) fn new(ws: &'w mut Workspace<Blobs>) -> Self {
+        Self {
+            ws,
+            facts: TribleSet::new(),
+            described: HashSet::new(),
+            blanks: HashMap::new(),
+            count: 0,
+        }
+    }
+
+    /// The facts and the number of statements consumed.
+    pub(crate) fn finish(self) -> (TribleSet, usize) {
+        (self.facts, self.count)
+    }
+
+    fn node(&mut self, node: RdfNode<'_>) -> Id {
+        match node {
+            RdfNode::Uri(uri) => {
+                // URI identity fragment — idempotent across repeated imports.
+                let handle: Value<Handle<Blake3, LongString>> = self.ws.put(uri.to_owned());
+                let fragment = entity! { rdf_uri: handle };
+                let id = fragment.root().expect("intrinsic URI entity");
+                self.facts += fragment;
+                id
+            }
+            RdfNode::Blank(label) => *self
+                .blanks
+                .entry(label.to_owned())
+                .or_insert_with(|| *rngid()),
+        }
+    }
+
+    /// Attribute for `name` under `S`. The first use in an import also
+    /// emits its description so the predicate can be recovered from the
+    /// facts, e.g. by [`crate::export::rdf`].
+    fn attribute<S: ValueSchema>(&mut self, name: &str) -> Id {
+        let id = Attribute::<S>::from_name(name).id();
+        if self.described.insert(id) {
+            let handle: Value<Handle<Blake3, LongString>> = self.ws.put(name.to_owned());
+            self.facts += entity! { ExclusiveId::force_ref(&id) @
+                metadata::name: handle,
+                metadata::value_schema: GenId::value_from(S::ID),
+            };
+        }
+        id
+    }
+
+    fn insert<S: ValueSchema>(&mut self, subject: Id, predicate: &str, value: Value<S>) {
+        let attr = self.attribute::<S>(predicate);
+        self.facts
+            .insert(&Trible::new(ExclusiveId::force_ref(&subject), &attr, &value));
+    }
+
+    /// Record one statement.
+    pub(crate) fn statement(&mut self, subject: RdfNode<'_>, predicate: &str, object: RdfObject<'_>) {
+        let subject = self.node(subject);
+        match object {
+            RdfObject::Node(node) => {
+                let object = self.node(node);
+                self.insert::<valueschemas::GenId>(subject, predicate, object.to_value());
+            }
+            RdfObject::Literal(RdfLiteral::Text(text)) => {
+                let handle: Value<Handle<Blake3, LongString>> = self.ws.put(text);
+                self.insert(subject, predicate, handle);
+            }
+            RdfObject::Literal(RdfLiteral::Tagged { text, language }) => {
+                let name = format!("{predicate} @{}", language.to_ascii_lowercase());
+                let handle: Value<Handle<Blake3, LongString>> = self.ws.put(text);
+                self.insert(subject, &name, handle);
+            }
+            RdfObject::Literal(RdfLiteral::SignedInt(val)) => {
+                let v: Value<valueschemas::I256BE> = val.to_value();
+                self.insert(subject, predicate, v);
+            }
+            RdfObject::Literal(RdfLiteral::UnsignedInt(val)) => {
+                let v: Value<valueschemas::U256BE> = val.to_value();
+                self.insert(subject, predicate, v);
+            }
+            RdfObject::Literal(RdfLiteral::Decimal(val)) => {
+                let v: Value<valueschemas::R256BE> = val.to_value();
+                self.insert(subject, predicate, v);
+            }
+            RdfObject::Literal(RdfLiteral::Float(val)) => {
+                let v: Value<valueschemas::F64> = val.to_value();
+                self.insert(subject, predicate, v);
+            }
+            RdfObject::Literal(RdfLiteral::Bool(val)) => {
+                let v: Value<valueschemas::Boolean> = val.to_value();
+                self.insert(subject, predicate, v);
+            }
+        }
+        self.count += 1;
+    }
+}
+
+#[cfg(feature = "rdf")]
+impl<Blobs: BlobStore<Blake3>> RdfSink<'_, Blobs> {
+    /// Record a triple from one of the `oxttl` / `oxjsonld` parsers.
+    /// RDF-star quoted triples have no trible counterpart and are skipped.
+    pub(crate) fn ox_statement(
+        &mut self,
+        subject: &oxrdf::Subject,
+        predicate: &oxrdf::NamedNode,
+        object: &oxrdf::Term,
+    ) {
+        #[allow(unreachable_patterns)]
+        let subject = match subject {
+            oxrdf::Subject::NamedNode(node) => RdfNode::Uri(node.as_str()),
+            oxrdf::Subject::BlankNode(node) => RdfNode::Blank(node.as_str()),
+            _ => return,
+        };
+        #[allow(unreachable_patterns)]
+        let object = match object {
+            oxrdf::Term::NamedNode(node) => RdfObject::Node(RdfNode::Uri(node.as_str())),
+            oxrdf::Term::BlankNode(node) => RdfObject::Node(RdfNode::Blank(node.as_str())),
+            oxrdf::Term::Literal(literal) => RdfObject::Literal(match literal.language() {
+                Some(language) => RdfLiteral::Tagged {
+                    text: literal.value().to_owned(),
+                    language: language.to_owned(),
+                },
+                None => typed_literal(literal.value().to_owned(), Some(literal.datatype().as_str())),
+            }),
+            _ => return,
+        };
+        self.statement(subject, predicate.as_str(), object);
+    }
+}
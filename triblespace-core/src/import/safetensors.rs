@@ -0,0 +1,255 @@
+//! Safetensors → TribleSpace importer.
+//!
+//! [`import_safetensors`] stores every tensor of a
+//! [safetensors](https://github.com/huggingface/safetensors) file as an
+//! [`Array`] blob and describes it with the tensor attributes from
+//! [`tensor`](crate::blob::schemas::array::tensor), plus its name under
+//! `metadata::name`. Tensor ids are derived from these attributes, so
+//! importing the same file twice yields the same entities. The optional
+//! `__metadata__` string map of the file is not imported.
+//!
+//! ```rust,ignore
+//! let file = std::fs::read("model.safetensors")?;
+//! let tensors = import_safetensors(&mut blobs, &file)?;
+//! for tensor in tensors.exports() {
+//!     let weights = Tensor::<BF16>::load(&tensors, tensor, &reader)?;
+//! }
+//! ```
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+use zerocopy::FromBytes;
+
+use crate::blob::schemas::array::elements::{
+    BF16, F16, F32, F64, I16, I32, I64, I8, U16, U32, U64, U8,
+};
+use crate::blob::schemas::array::tensor::element_count;
+use crate::blob::schemas::array::{Array, ArrayElement};
+use crate::blob::schemas::UnknownBlob;
+use crate::id::Id;
+use crate::import::json::EncodeError;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::ConstId;
+use crate::repo::BlobStorePut;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::{Blake3, Handle};
+use crate::value::Value;
+
+/// Safetensors dtype names with the element type and byte size they map to.
+pub(crate) const DTYPES: [(&str, Id, usize); 12] = [
+    ("F16", F16::ID, 2),
+    ("BF16", BF16::ID, 2),
+    ("F32", F32::ID, 4),
+    ("F64", F64::ID, 8),
+    ("I8", I8::ID, 1),
+    ("I16", I16::ID, 2),
+    ("I32", I32::ID, 4),
+    ("I64", I64::ID, 8),
+    ("U8", U8::ID, 1),
+    ("U16", U16::ID, 2),
+    ("U32", U32::ID, 4),
+    ("U64", U64::ID, 8),
+];
+
+/// Error returned by [`import_safetensors`].
+#[derive(Debug)]
+pub enum SafetensorsImportError {
+    /// The file ends inside its header or tensor data.
+    Truncated,
+    /// The header is not a JSON object of tensor entries.
+    InvalidHeader(String),
+    /// A tensor uses an element type without an [`ArrayElement`].
+    UnsupportedDtype {
+        /// Name of the tensor.
+        tensor: String,
+        /// The safetensors dtype.
+        dtype: String,
+    },
+    /// A tensor's byte range does not fit its shape and dtype.
+    InvalidTensor {
+        /// Name of the tensor.
+        tensor: String,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
+    /// A tensor could not be written to the blob store.
+    Encode {
+        /// Name of the tensor.
+        tensor: String,
+        /// Underlying encoding error.
+        source: EncodeError,
+    },
+}
+
+impl fmt::Display for SafetensorsImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "safetensors file is truncated"),
+            Self::InvalidHeader(reason) => write!(f, "invalid safetensors header: {reason}"),
+            Self::UnsupportedDtype { tensor, dtype } => {
+                write!(f, "tensor {tensor:?} has unsupported dtype {dtype}")
+            }
+            Self::InvalidTensor { tensor, reason } => {
+                write!(f, "invalid tensor {tensor:?}: {reason}")
+            }
+            Self::Encode { tensor, source } => {
+                write!(f, "failed to store tensor {tensor:?}: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SafetensorsImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Encode { source, .. } => Some(source),
+            Self::Truncated
+            | Self::InvalidHeader(_)
+            | Self::UnsupportedDtype { .. }
+            | Self::InvalidTensor { .. } => None,
+        }
+    }
+}
+
+/// Import the tensors of the safetensors file `bytes`, storing their blobs
+/// in `blobs`. The returned fragment exports one entity per tensor.
+pub fn import_safetensors<B>(blobs: &mut B, bytes: &[u8]) -> Result<Fragment, SafetensorsImportError>
+where
+    B: BlobStorePut<Blake3>,
+{
+    let header_len: [u8; 8] = bytes
+        .get(..8)
+        .ok_or(SafetensorsImportError::Truncated)?
+        .try_into()
+        .expect("slice has 8 bytes");
+    let header_end = usize::try_from(u64::from_le_bytes(header_len))
+        .ok()
+        .and_then(|len| len.checked_add(8))
+        .ok_or(SafetensorsImportError::Truncated)?;
+    let header = bytes
+        .get(8..header_end)
+        .ok_or(SafetensorsImportError::Truncated)?;
+    let data = &bytes[header_end..];
+    let header: JsonValue = serde_json::from_slice(header)
+        .map_err(|err| SafetensorsImportError::InvalidHeader(err.to_string()))?;
+    let JsonValue::Object(entries) = header else {
+        return Err(SafetensorsImportError::InvalidHeader(
+            "header is not an object".to_owned(),
+        ));
+    };
+
+    let mut tensors = Vec::new();
+    let mut facts = Fragment::empty();
+    for (name, entry) in &entries {
+        if name == "__metadata__" {
+            continue;
+        }
+        let invalid = |reason| SafetensorsImportError::InvalidTensor {
+            tensor: name.clone(),
+            reason,
+        };
+        let encode = |err| SafetensorsImportError::Encode {
+            tensor: name.clone(),
+            source: EncodeError::from_error(err),
+        };
+
+        let dtype = entry["dtype"]
+            .as_str()
+            .ok_or_else(|| invalid("missing dtype"))?;
+        let &(_, dtype_id, size) = DTYPES
+            .iter()
+            .find(|(known, _, _)| *known == dtype)
+            .ok_or_else(|| SafetensorsImportError::UnsupportedDtype {
+                tensor: name.clone(),
+                dtype: dtype.to_owned(),
+            })?;
+        let shape = entry["shape"]
+            .as_array()
+            .and_then(|dims| dims.iter().map(JsonValue::as_u64).collect::<Option<Vec<u64>>>())
+            .ok_or_else(|| invalid("shape is not a list of sizes"))?;
+        let (begin, end) = match entry["data_offsets"].as_array().map(Vec::as_slice) {
+            Some([begin, end]) => begin.as_u64().zip(end.as_u64()),
+            _ => None,
+        }
+        .and_then(|(begin, end)| Some((usize::try_from(begin).ok()?, usize::try_from(end).ok()?)))
+        .ok_or_else(|| invalid("data_offsets is not a pair of offsets"))?;
+        let elements = data
+            .get(begin..end)
+            .ok_or_else(|| invalid("data_offsets lie outside the data"))?;
+        let dims: Option<Vec<usize>> = shape.iter().map(|&d| usize::try_from(d).ok()).collect();
+        let count = dims
+            .as_deref()
+            .and_then(element_count)
+            .and_then(|count| count.checked_mul(size));
+        if count != Some(elements.len()) {
+            return Err(invalid("data size does not match shape and dtype"));
+        }
+
+        let data = put_as(blobs, dtype, elements).map_err(encode)?;
+        let fragment = entity! {
+            metadata::name: blobs.put(name.clone()).map_err(encode)?,
+            metadata::tensor_data: data,
+            metadata::tensor_dtype: dtype_id,
+            metadata::tensor_shape: blobs.put::<Array<U64>, _>(shape).map_err(encode)?,
+        };
+        tensors.push(fragment.root().expect("intrinsic tensor entity"));
+        facts += fragment;
+    }
+    Ok(Fragment::new(tensors, facts.into_facts()))
+}
+
+/// Stores the little-endian `bytes` as an `Array` of the element type named
+/// by `dtype`, which must be listed in [`DTYPES`].
+fn put_as<B: BlobStorePut<Blake3>>(
+    blobs: &mut B,
+    dtype: &str,
+    bytes: &[u8],
+) -> Result<Value<Handle<Blake3, UnknownBlob>>, B::PutError> {
+    match dtype {
+        "F16" => put_elements::<F16, B>(blobs, bytes),
+        "BF16" => put_elements::<BF16, B>(blobs, bytes),
+        "F32" => put_elements::<F32, B>(blobs, bytes),
+        "F64" => put_elements::<F64, B>(blobs, bytes),
+        "I8" => put_elements::<I8, B>(blobs, bytes),
+        "I16" => put_elements::<I16, B>(blobs, bytes),
+        "I32" => put_elements::<I32, B>(blobs, bytes),
+        "I64" => put_elements::<I64, B>(blobs, bytes),
+        "U8" => put_elements::<U8, B>(blobs, bytes),
+        "U16" => put_elements::<U16, B>(blobs, bytes),
+        "U32" => put_elements::<U32, B>(blobs, bytes),
+        "U64" => put_elements::<U64, B>(blobs, bytes),
+        _ => unreachable!("{dtype} is not listed in DTYPES"),
+    }
+}
+
+fn put_elements<T, B>(
+    blobs: &mut B,
+    bytes: &[u8],
+) -> Result<Value<Handle<Blake3, UnknownBlob>>, B::PutError>
+where
+    T: ArrayElement,
+    T::Native: FromBytes,
+    B: BlobStorePut<Blake3>,
+{
+    // Copy into a typed vector: the blob must be aligned for `T`, and
+    // `Array` stores native byte order.
+    let mut bytes = bytes.to_vec();
+    swap_little_endian(&mut bytes, std::mem::size_of::<T::Native>());
+    let elements: Vec<T::Native> = bytes
+        .chunks_exact(std::mem::size_of::<T::Native>())
+        .map(|chunk| T::Native::read_from_bytes(chunk).expect("chunk has the element size"))
+        .collect();
+    Ok(blobs.put::<Array<T>, _>(elements)?.transmute())
+}
+
+/// Converts elements of `size` bytes between little-endian and native byte
+/// order, in place.
+pub(crate) fn swap_little_endian(bytes: &mut [u8], size: usize) {
+    if cfg!(target_endian = "big") {
+        for element in bytes.chunks_exact_mut(size) {
+            element.reverse();
+        }
+    }
+}
@@ -3,8 +3,11 @@
 //! This namespace is used to bootstrap the meaning of other namespaces.
 //! It defines meta attributes that are used to describe other attributes.
 
+use crate::blob::schemas::array::elements::U64;
+use crate::blob::schemas::array::Array;
 use crate::blob::schemas::longstring::LongString;
 use crate::blob::schemas::wasmcode::WasmCode;
+use crate::blob::schemas::UnknownBlob;
 use crate::id::Id;
 use crate::id_hex;
 use crate::prelude::valueschemas;
@@ -127,4 +130,13 @@ attributes! {
     "9B06AA4060EF9928A923FC7E6A6B6438" as finished_at: valueschemas::NsTAIInterval;
     /// When an entity expires or becomes invalid.
     "89FEC3B560336BA88B10759DECD3155F" as expires_at: valueschemas::NsTAIInterval;
+    /// The `Array<T>` blob holding a tensor's elements. `tensor_dtype` names `T`.
+    "F931EA40F51BA2609088C2EEE2828BA8" as tensor_data: valueschemas::Handle<hash::Blake3, UnknownBlob>;
+    /// The id of a tensor's element type (an `ArrayElement` such as `F32`).
+    "3EA158A863AF2C001B4003D1EC760EB2" as tensor_dtype: valueschemas::GenId;
+    /// The extent of each tensor dimension, outermost first.
+    "F84142B017BDFF9C9D2028155DA94A66" as tensor_shape: valueschemas::Handle<hash::Blake3, Array<U64>>;
+    /// Optional element strides per dimension; tensors without them are
+    /// contiguous in row-major order.
+    "A9A693ECEC8DE091ED418C4FD476C51C" as tensor_strides: valueschemas::Handle<hash::Blake3, Array<U64>>;
 }
@@ -55,6 +55,7 @@ pub use crate::query::exists;
 pub use crate::query::find;
 pub use crate::query::intersectionconstraint::and;
 pub use crate::query::intersectionconstraint::IntersectionConstraint;
+pub use crate::query::intervalconstraint::{interval_relation, AllenRelation, IntervalRelation};
 pub use crate::query::rangeconstraint::{value_range, ValueRange};
 pub use crate::query::sortedsliceconstraint::SortedSlice;
 pub use crate::query::temp;
@@ -3,7 +3,7 @@
 /// Re-export of built-in element types (`F32`, `U8`, etc.).
 pub use crate::blob::schemas::array::elements;
 /// Re-export of [`Array`] and [`ArrayElement`].
-pub use crate::blob::schemas::array::{Array, ArrayElement};
+pub use crate::blob::schemas::array::{Array, ArrayElement, Tensor};
 /// Re-export of [`FileBytes`].
 pub use crate::blob::schemas::filebytes::FileBytes;
 /// Re-export of [`LongString`].
@@ -2,6 +2,10 @@
 
 /// Re-export of [`Boolean`].
 pub use crate::value::schemas::boolean::Boolean;
+/// Re-export of [`LocalDate`].
+pub use crate::value::schemas::calendar::LocalDate;
+/// Re-export of [`NsDuration`].
+pub use crate::value::schemas::duration::NsDuration;
 /// Re-export of [`ED25519PublicKey`].
 pub use crate::value::schemas::ed25519::ED25519PublicKey;
 /// Re-export of [`ED25519RComponent`].
@@ -18,6 +22,8 @@ pub use crate::value::schemas::f256::F256LE;
 pub use crate::value::schemas::f64::F64;
 /// Re-export of [`GenId`].
 pub use crate::value::schemas::genid::GenId;
+/// Re-export of [`GeoPoint`].
+pub use crate::value::schemas::geopoint::GeoPoint;
 /// Re-export of [`Blake3`].
 pub use crate::value::schemas::hash::Blake3;
 /// Re-export of [`Handle`].
@@ -40,6 +46,14 @@ pub use crate::value::schemas::iu256::U256BE;
 pub use crate::value::schemas::iu256::U256LE;
 /// Re-export of [`LineLocation`].
 pub use crate::value::schemas::linelocation::LineLocation;
+/// Re-export of [`Ipv4Addr`].
+pub use crate::value::schemas::net::Ipv4Addr;
+/// Re-export of [`Ipv6Addr`].
+pub use crate::value::schemas::net::Ipv6Addr;
+/// Re-export of [`MacAddr`].
+pub use crate::value::schemas::net::MacAddr;
+/// Re-export of [`SocketAddr`].
+pub use crate::value::schemas::net::SocketAddr;
 /// Re-export of [`R256`].
 pub use crate::value::schemas::r256::R256;
 /// Re-export of [`R256BE`].
@@ -52,3 +66,5 @@ pub use crate::value::schemas::range::{RangeInclusiveU128, RangeU128};
 pub use crate::value::schemas::shortstring::ShortString;
 /// Re-export of [`NsTAIInterval`].
 pub use crate::value::schemas::time::NsTAIInterval;
+/// Re-export of [`Uuid`].
+pub use crate::value::schemas::uuid::Uuid;
@@ -27,6 +27,8 @@ pub mod hashsetconstraint;
 pub mod ignore;
 /// [`IntersectionConstraint`](intersectionconstraint::IntersectionConstraint) — logical AND.
 pub mod intersectionconstraint;
+/// [`IntervalRelation`](intervalconstraint::IntervalRelation) — relates two time intervals by Allen's interval algebra.
+pub mod intervalconstraint;
 /// [`PatchValueConstraint`](patchconstraint::PatchValueConstraint) and [`PatchIdConstraint`](patchconstraint::PatchIdConstraint) — constrains variables to PATCH entries.
 pub mod patchconstraint;
 /// [`ValueRange`](rangeconstraint::ValueRange) — restricts a variable to a byte-lexicographic range.
@@ -0,0 +1,276 @@
+use super::*;
+
+use crate::value::schemas::time::NsTAIInterval;
+
+/// One of Allen's thirteen relations between two time intervals.
+///
+/// [`NsTAIInterval`] bounds are inclusive nanoseconds, so an interval
+/// `[lower, upper]` is read as the half-open span `[lower, upper + 1)`:
+/// `a` *meets* `b` when `b` starts on the nanosecond after `a` ends. Every
+/// ordered pair of intervals stands in exactly one relation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AllenRelation {
+    /// `a` ends at least one nanosecond before `b` starts.
+    Before,
+    /// `b` starts on the nanosecond after `a` ends.
+    Meets,
+    /// `a` starts first and ends inside `b`.
+    Overlaps,
+    /// Both start together and `a` ends first.
+    Starts,
+    /// `a` lies strictly inside `b`.
+    During,
+    /// Both end together and `a` starts last.
+    Finishes,
+    /// Both have the same bounds.
+    Equals,
+    /// Both end together and `a` starts first.
+    FinishedBy,
+    /// `b` lies strictly inside `a`.
+    Contains,
+    /// Both start together and `b` ends first.
+    StartedBy,
+    /// `b` starts first and ends inside `a`.
+    OverlappedBy,
+    /// `a` starts on the nanosecond after `b` ends.
+    MetBy,
+    /// `a` starts at least one nanosecond after `b` ends.
+    After,
+}
+
+impl AllenRelation {
+    /// All thirteen relations.
+    pub const ALL: [AllenRelation; 13] = [
+        AllenRelation::Before,
+        AllenRelation::Meets,
+        AllenRelation::Overlaps,
+        AllenRelation::Starts,
+        AllenRelation::During,
+        AllenRelation::Finishes,
+        AllenRelation::Equals,
+        AllenRelation::FinishedBy,
+        AllenRelation::Contains,
+        AllenRelation::StartedBy,
+        AllenRelation::OverlappedBy,
+        AllenRelation::MetBy,
+        AllenRelation::After,
+    ];
+
+    /// The relations in which two intervals share at least one nanosecond.
+    pub const INTERSECTING: [AllenRelation; 9] = [
+        AllenRelation::Overlaps,
+        AllenRelation::Starts,
+        AllenRelation::During,
+        AllenRelation::Finishes,
+        AllenRelation::Equals,
+        AllenRelation::FinishedBy,
+        AllenRelation::Contains,
+        AllenRelation::StartedBy,
+        AllenRelation::OverlappedBy,
+    ];
+
+    /// The relation of `a` to `b`, both given as inclusive
+    /// `(lower, upper)` nanosecond bounds.
+    pub fn between(a: (i128, i128), b: (i128, i128)) -> AllenRelation {
+        use std::cmp::Ordering::*;
+
+        let (a_start, a_end) = (a.0, a.1.saturating_add(1));
+        let (b_start, b_end) = (b.0, b.1.saturating_add(1));
+        if a_end < b_start {
+            return AllenRelation::Before;
+        }
+        if a_end == b_start {
+            return AllenRelation::Meets;
+        }
+        if b_end < a_start {
+            return AllenRelation::After;
+        }
+        if b_end == a_start {
+            return AllenRelation::MetBy;
+        }
+        match (a_start.cmp(&b_start), a_end.cmp(&b_end)) {
+            (Less, Less) => AllenRelation::Overlaps,
+            (Less, Equal) => AllenRelation::FinishedBy,
+            (Less, Greater) => AllenRelation::Contains,
+            (Equal, Less) => AllenRelation::Starts,
+            (Equal, Equal) => AllenRelation::Equals,
+            (Equal, Greater) => AllenRelation::StartedBy,
+            (Greater, Less) => AllenRelation::During,
+            (Greater, Equal) => AllenRelation::Finishes,
+            (Greater, Greater) => AllenRelation::OverlappedBy,
+        }
+    }
+
+    /// The relation of `b` to `a` when `a` stands in `self` to `b`.
+    pub fn inverse(self) -> AllenRelation {
+        AllenRelation::ALL[12 - self as usize]
+    }
+}
+
+/// Requires the intervals bound to two variables to stand in one of a set of
+/// [`AllenRelation`]s.
+///
+/// Like [`ValueRange`](super::rangeconstraint::ValueRange) this constraint
+/// only **confirms**: pair it with patterns that propose both intervals.
+///
+/// ```rust,ignore
+/// // Meetings that overlap a booking in any way.
+/// find!((meeting: Id, booking: Id),
+///     and!(
+///         pattern!(&data, [{ ?meeting @ calendar::slot: ?m }]),
+///         pattern!(&data, [{ ?booking @ rooms::slot: ?b }]),
+///         interval_relation(m, b, AllenRelation::INTERSECTING),
+///     )
+/// )
+/// ```
+pub struct IntervalRelation {
+    a: VariableId,
+    b: VariableId,
+    // One bit per `AllenRelation` discriminant.
+    relations: u16,
+}
+
+impl IntervalRelation {
+    /// Create a constraint requiring `a` to stand in one of `relations` to `b`.
+    pub fn new(
+        a: Variable<NsTAIInterval>,
+        b: Variable<NsTAIInterval>,
+        relations: impl IntoIterator<Item = AllenRelation>,
+    ) -> Self {
+        IntervalRelation {
+            a: a.index,
+            b: b.index,
+            relations: relations
+                .into_iter()
+                .fold(0, |bits, relation| bits | (1 << relation as u16)),
+        }
+    }
+
+    fn holds(&self, a: &RawValue, b: &RawValue) -> bool {
+        let bounds = |raw: &RawValue| {
+            Value::<NsTAIInterval>::new(*raw)
+                .try_from_value::<(i128, i128)>()
+                .ok()
+        };
+        match (bounds(a), bounds(b)) {
+            (Some(a), Some(b)) => self.relations & (1 << AllenRelation::between(a, b) as u16) != 0,
+            _ => false,
+        }
+    }
+}
+
+/// Convenience function to create an [`IntervalRelation`] constraint.
+pub fn interval_relation(
+    a: Variable<NsTAIInterval>,
+    b: Variable<NsTAIInterval>,
+    relations: impl IntoIterator<Item = AllenRelation>,
+) -> IntervalRelation {
+    IntervalRelation::new(a, b, relations)
+}
+
+impl<'a> Constraint<'a> for IntervalRelation {
+    fn variables(&self) -> VariableSet {
+        let mut vs = VariableSet::new_empty();
+        vs.set(self.a);
+        vs.set(self.b);
+        vs
+    }
+
+    /// Returns `usize::MAX` so the intersection never chooses this
+    /// constraint as the proposer — it only confirms.
+    fn estimate(&self, variable: VariableId, _binding: &Binding) -> Option<usize> {
+        if variable == self.a || variable == self.b {
+            Some(usize::MAX)
+        } else {
+            None
+        }
+    }
+
+    /// Does not propose — the paired patterns handle proposals.
+    fn propose(&self, _variable: VariableId, _binding: &Binding, _proposals: &mut Vec<RawValue>) {
+        // Intentionally empty: this constraint only confirms.
+    }
+
+    /// Once the peer variable is bound, retains only proposals standing in
+    /// one of the relations to it.
+    fn confirm(&self, variable: VariableId, binding: &Binding, proposals: &mut Vec<RawValue>) {
+        if variable == self.a {
+            if let Some(b) = binding.get(self.b) {
+                proposals.retain(|a| self.holds(a, b));
+            }
+        } else if variable == self.b {
+            if let Some(a) = binding.get(self.a) {
+                proposals.retain(|b| self.holds(a, b));
+            }
+        }
+    }
+
+    /// Returns `false` when both intervals are bound and unrelated.
+    fn satisfied(&self, binding: &Binding) -> bool {
+        match (binding.get(self.a), binding.get(self.b)) {
+            (Some(a), Some(b)) => self.holds(a, b),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn every_pair_has_exactly_one_relation() {
+        let spans = [(0, 0), (0, 4), (2, 6), (5, 9), (0, 9), (3, 3), (10, 12)];
+        for a in spans {
+            for b in spans {
+                let relation = AllenRelation::between(a, b);
+                assert_eq!(AllenRelation::between(b, a), relation.inverse(), "{a:?} {b:?}");
+            }
+        }
+        assert_eq!(AllenRelation::between((0, 4), (5, 9)), AllenRelation::Meets);
+        assert_eq!(AllenRelation::between((0, 3), (5, 9)), AllenRelation::Before);
+        assert_eq!(AllenRelation::between((0, 5), (5, 9)), AllenRelation::Overlaps);
+        assert_eq!(AllenRelation::between((3, 3), (0, 9)), AllenRelation::During);
+        assert_eq!(AllenRelation::between((0, 9), (0, 9)), AllenRelation::Equals);
+    }
+
+    #[test]
+    fn filters_interval_pairs_in_queries() {
+        let slot = |lower: i128, upper: i128| -> Value<NsTAIInterval> {
+            let mut raw = [0u8; 32];
+            raw[..16].copy_from_slice(&crate::value::schemas::time::i128_to_ordered_be(lower));
+            raw[16..].copy_from_slice(&crate::value::schemas::time::i128_to_ordered_be(upper));
+            Value::new(raw)
+        };
+        let meetings = [slot(0, 59), slot(60, 119), slot(200, 299)];
+        let booking = slot(30, 89);
+        let meeting_set: std::collections::HashSet<Value<NsTAIInterval>> =
+            meetings.iter().copied().collect();
+
+        let mut overlapping: Vec<Value<NsTAIInterval>> = find!(
+            (m: Value<NsTAIInterval>, b: Value<NsTAIInterval>),
+            and!(
+                (&meeting_set).has(m),
+                b.is(booking),
+                interval_relation(m, b, AllenRelation::INTERSECTING),
+            )
+        )
+        .map(|(m, _)| m)
+        .collect();
+        overlapping.sort();
+        assert_eq!(overlapping, [meetings[0], meetings[1]]);
+
+        let after: Vec<Value<NsTAIInterval>> = find!(
+            (m: Value<NsTAIInterval>, b: Value<NsTAIInterval>),
+            and!(
+                (&meeting_set).has(m),
+                b.is(booking),
+                interval_relation(m, b, [AllenRelation::After]),
+            )
+        )
+        .map(|(m, _)| m)
+        .collect();
+        assert_eq!(after, [meetings[2]]);
+    }
+}
@@ -111,6 +111,8 @@
 //!
 /// Branch metadata construction and signature verification.
 pub mod branch;
+/// Portable bundle files for carrying branches between disconnected stores.
+pub mod bundle;
 /// Capability-based authorization for triblespace networks.
 pub mod capability;
 /// Commit metadata construction and signature verification.
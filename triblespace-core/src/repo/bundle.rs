@@ -0,0 +1,908 @@
+//! Portable bundle files for moving branches between disconnected piles.
+//!
+//! A bundle is a single file holding a small manifest (the branch metadata
+//! handles it carries, plus the base commits it was cut against) followed by
+//! every blob reachable from those branches that the base does not already
+//! cover. It is the offline counterpart to syncing over the network: write a
+//! bundle on one machine, carry it across an air gap, and import it into
+//! another pile.
+//!
+//! The on-disk layout mirrors the pile's record format so the same 64-byte
+//! alignment and blob headers apply:
+//!
+//! ```text
+//! [BundleHeader: magic | version | blob count | manifest handle]
+//! [BlobHeader | manifest bytes | padding]
+//! [BlobHeader | blob bytes | padding]  x (blob count - 1)
+//! ```
+//!
+//! Like `git bundle`, a bundle cut against a base is *thin*: commits that
+//! are reachable from the base are omitted and become prerequisites the
+//! importing store must already have. [`Bundle::verify`] checks every
+//! record's hash, every branch and commit signature it can see, and reports
+//! those prerequisites. [`import_bundle`] refuses to run unless they are
+//! present, then creates or fast-forwards local branches. Branches that have
+//! diverged locally are left untouched and reported, so callers can
+//! materialise them elsewhere (e.g. as tracking branches) and merge.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anybytes::Bytes;
+use anybytes::View;
+use ed25519_dalek::VerifyingKey;
+use hex_literal::hex;
+use hifitime::Epoch;
+use itertools::Itertools;
+use zerocopy::Immutable;
+use zerocopy::IntoBytes;
+use zerocopy::KnownLayout;
+use zerocopy::TryFromBytes;
+
+use super::pile::padding_for_blob;
+use super::pile::BlobHeader;
+use super::pile::MAGIC_MARKER_BLOB;
+use super::BlobChildren;
+use super::BlobStore;
+use super::BlobStoreGet;
+use super::BlobStorePut;
+use super::BranchStore;
+use super::CommitHandle;
+use super::PushResult;
+use crate::blob::schemas::longstring::LongString;
+use crate::blob::schemas::simplearchive::SimpleArchive;
+use crate::blob::schemas::simplearchive::UnarchiveError;
+use crate::blob::schemas::UnknownBlob;
+use crate::blob::Blob;
+use crate::blob::MemoryBlobStore;
+use crate::blob::ToBlob;
+use crate::id::Id;
+use crate::id::RawId;
+use crate::macros::entity;
+use crate::macros::find;
+use crate::macros::pattern;
+use crate::metadata;
+use crate::trible::TribleSet;
+use crate::value::schemas::ed25519 as ed;
+use crate::value::schemas::hash::Blake3;
+use crate::value::schemas::hash::Handle;
+use crate::value::schemas::hash::Hash;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::RawValue;
+use crate::value::TryFromValue;
+use crate::value::TryToValue;
+use crate::value::Value;
+
+const MAGIC_MARKER_BUNDLE: RawId = hex!("0E41ECCEED029D3088EF55B28332C34A");
+
+/// Bundle format version written by [`create_bundle`].
+pub const BUNDLE_VERSION: u64 = 1;
+
+const BUNDLE_HEADER_LEN: usize = std::mem::size_of::<BundleHeader>();
+
+triblespace_core_macros::attributes! {
+    /// Branch metadata blob carried by a bundle. Repeated once per branch.
+    "186761892488AA4F98869769B3397F4E" as pub bundle_branch: Handle<Blake3, SimpleArchive>;
+    /// Commit the bundle was cut against. Commits reachable from a base are
+    /// left out of the bundle and must already exist in the importing store.
+    "A858F88E41E9CABFC753157E8F17A972" as pub bundle_base: Handle<Blake3, SimpleArchive>;
+}
+
+#[derive(TryFromBytes, IntoBytes, Immutable, KnownLayout, Copy, Clone)]
+#[repr(C)]
+struct BundleHeader {
+    magic_marker: RawId,
+    version: u64,
+    blob_count: u64,
+    manifest: RawValue,
+}
+
+/// Error produced while reading or verifying a bundle file.
+#[derive(Debug)]
+pub enum BundleError {
+    /// The bundle file could not be read.
+    Io(std::io::Error),
+    /// The file ended in the middle of a record.
+    Truncated,
+    /// The file does not start with the bundle magic marker.
+    BadMagic,
+    /// The bundle was written by an incompatible format version.
+    UnsupportedVersion(u64),
+    /// A blob record at the given byte offset is malformed.
+    CorruptRecord(usize),
+    /// A blob record's bytes do not hash to the handle in its header.
+    HashMismatch(usize),
+    /// The manifest blob named by the header is not in the bundle.
+    MissingManifest,
+    /// The manifest blob could not be decoded.
+    BadManifest,
+    /// A blob that must be part of the bundle is absent.
+    MissingBlob(Value<Handle<Blake3, UnknownBlob>>),
+    /// Branch metadata or a commit in the bundle is malformed.
+    BadBranchMetadata(Value<Handle<Blake3, SimpleArchive>>),
+    /// A branch or commit signature failed to verify.
+    BadSignature(Value<Handle<Blake3, SimpleArchive>>),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(e) => write!(f, "failed to read bundle: {e}"),
+            BundleError::Truncated => write!(f, "bundle is truncated"),
+            BundleError::BadMagic => write!(f, "not a bundle file"),
+            BundleError::UnsupportedVersion(v) => write!(f, "unsupported bundle version {v}"),
+            BundleError::CorruptRecord(offset) => {
+                write!(f, "corrupt blob record at offset {offset}")
+            }
+            BundleError::HashMismatch(offset) => {
+                write!(f, "blob record at offset {offset} does not match its hash")
+            }
+            BundleError::MissingManifest => write!(f, "bundle manifest is missing"),
+            BundleError::BadManifest => write!(f, "bundle manifest is malformed"),
+            BundleError::MissingBlob(h) => {
+                write!(f, "bundle is missing blob {}", hex::encode(h.raw))
+            }
+            BundleError::BadBranchMetadata(h) => {
+                write!(
+                    f,
+                    "malformed branch or commit metadata {}",
+                    hex::encode(h.raw)
+                )
+            }
+            BundleError::BadSignature(h) => {
+                write!(f, "signature on {} does not verify", hex::encode(h.raw))
+            }
+        }
+    }
+}
+
+impl std::error::Error for BundleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BundleError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BundleError {
+    fn from(err: std::io::Error) -> Self {
+        BundleError::Io(err)
+    }
+}
+
+/// The roots recorded in a bundle's manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleManifest {
+    /// Branch metadata blobs carried by the bundle, sorted.
+    pub branches: Vec<Value<Handle<Blake3, SimpleArchive>>>,
+    /// Commits the bundle was cut against, sorted.
+    pub bases: Vec<CommitHandle>,
+}
+
+impl BundleManifest {
+    fn to_tribles(&self) -> TribleSet {
+        let now =
+            Epoch::now().unwrap_or_else(|_| Epoch::from_gregorian_utc(1970, 1, 1, 0, 0, 0, 0));
+        let created_at: Value<NsTAIInterval> = (now, now)
+            .try_to_value()
+            .expect("same epoch is a valid point interval");
+        entity! {
+            bundle_branch*: self.branches.iter().copied(),
+            bundle_base*: self.bases.iter().copied(),
+            metadata::created_at: created_at,
+        }
+        .into()
+    }
+
+    fn from_tribles(set: &TribleSet) -> Self {
+        let branches = find!(
+            h: Value<Handle<Blake3, SimpleArchive>>,
+            pattern!(set, [{ _?e @ bundle_branch: ?h }])
+        )
+        .sorted()
+        .dedup()
+        .collect();
+        let bases = find!(
+            h: Value<Handle<Blake3, SimpleArchive>>,
+            pattern!(set, [{ _?e @ bundle_base: ?h }])
+        )
+        .sorted()
+        .dedup()
+        .collect();
+        BundleManifest { branches, bases }
+    }
+}
+
+/// A branch carried by a bundle, as reported by [`Bundle::verify`].
+#[derive(Debug, Clone)]
+pub struct BundledBranch {
+    /// The branch id.
+    pub id: Id,
+    /// The branch name, if its name blob travelled with the bundle.
+    pub name: Option<String>,
+    /// The branch metadata blob.
+    pub metadata: Value<Handle<Blake3, SimpleArchive>>,
+    /// The commit the branch points at, if any.
+    pub head: Option<CommitHandle>,
+    /// The key that signed the branch head, if the metadata is signed.
+    pub signed_by: Option<VerifyingKey>,
+    /// Number of this branch's commits contained in the bundle.
+    pub commits: usize,
+}
+
+/// Result of a successful [`Bundle::verify`].
+#[derive(Debug, Clone)]
+pub struct BundleVerification {
+    /// The branches in the bundle, in manifest order.
+    pub branches: Vec<BundledBranch>,
+    /// Blobs referenced by bundled commits but left out of the bundle. The
+    /// importing store must already hold them.
+    pub prerequisites: Vec<Value<Handle<Blake3, UnknownBlob>>>,
+}
+
+/// A bundle file loaded into memory (or mapped from disk).
+///
+/// Every record's hash is checked while loading, so the blobs exposed by
+/// [`Bundle::reader`] are exactly what their handles claim.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    manifest: BundleManifest,
+    blobs: <MemoryBlobStore<Blake3> as BlobStore<Blake3>>::Reader,
+}
+
+impl Bundle {
+    /// Memory-maps and parses the bundle at `path`.
+    pub fn open(path: &Path) -> Result<Self, BundleError> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Err(BundleError::Truncated);
+        }
+        // SAFETY: bundles are written once and never modified in place;
+        // the mapping is only read while parsing and verifying.
+        let bytes = unsafe { Bytes::map_file(&file)? };
+        Self::from_bytes(bytes)
+    }
+
+    /// Parses a bundle from its serialized bytes.
+    pub fn from_bytes(mut bytes: Bytes) -> Result<Self, BundleError> {
+        let total = bytes.len();
+        let (header, _) = BundleHeader::try_read_from_prefix(bytes.as_ref())
+            .map_err(|_| BundleError::Truncated)?;
+        if header.magic_marker != MAGIC_MARKER_BUNDLE {
+            return Err(BundleError::BadMagic);
+        }
+        if header.version != BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(header.version));
+        }
+        bytes
+            .take_prefix(BUNDLE_HEADER_LEN)
+            .ok_or(BundleError::Truncated)?;
+
+        let mut store = MemoryBlobStore::<Blake3>::new();
+        for _ in 0..header.blob_count {
+            let offset = total - bytes.len();
+            let (record, _) = BlobHeader::try_read_from_prefix(bytes.as_ref())
+                .map_err(|_| BundleError::Truncated)?;
+            if record.magic_marker != MAGIC_MARKER_BLOB {
+                return Err(BundleError::CorruptRecord(offset));
+            }
+            let len =
+                usize::try_from(record.length).map_err(|_| BundleError::CorruptRecord(offset))?;
+            bytes
+                .take_prefix(std::mem::size_of::<BlobHeader>())
+                .ok_or(BundleError::Truncated)?;
+            let data = bytes.take_prefix(len).ok_or(BundleError::Truncated)?;
+            bytes
+                .take_prefix(padding_for_blob(len))
+                .ok_or(BundleError::Truncated)?;
+            if Hash::<Blake3>::digest(&data).raw != record.hash {
+                return Err(BundleError::HashMismatch(offset));
+            }
+            store.insert(Blob::<UnknownBlob>::new(data));
+        }
+        if !bytes.is_empty() {
+            return Err(BundleError::CorruptRecord(total - bytes.len()));
+        }
+
+        let blobs = store.reader().expect("memory reader is infallible");
+        let manifest_handle = Value::<Handle<Blake3, SimpleArchive>>::new(header.manifest);
+        if blobs
+            .get::<Bytes, UnknownBlob>(manifest_handle.transmute())
+            .is_err()
+        {
+            return Err(BundleError::MissingManifest);
+        }
+        let manifest: TribleSet = blobs
+            .get(manifest_handle)
+            .map_err(|_| BundleError::BadManifest)?;
+        Ok(Bundle {
+            manifest: BundleManifest::from_tribles(&manifest),
+            blobs,
+        })
+    }
+
+    /// The roots recorded in the bundle's manifest.
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Read access to the blobs carried by the bundle.
+    pub fn reader(&self) -> &<MemoryBlobStore<Blake3> as BlobStore<Blake3>>::Reader {
+        &self.blobs
+    }
+
+    /// Checks the branch and commit signatures in the bundle and lists the
+    /// blobs it depends on but does not contain.
+    ///
+    /// Branch metadata must name exactly one branch. Signed branch metadata
+    /// must verify against its head commit, and every bundled commit with
+    /// content must carry a valid signature over that content.
+    pub fn verify(&self) -> Result<BundleVerification, BundleError> {
+        let mut prerequisites = HashSet::new();
+        let mut branches = Vec::with_capacity(self.manifest.branches.len());
+        for &meta_handle in &self.manifest.branches {
+            let meta: TribleSet = self
+                .blobs
+                .get(meta_handle)
+                .map_err(|_| BundleError::MissingBlob(meta_handle.transmute()))?;
+
+            let id = find!(
+                id: Id,
+                pattern!(&meta, [{ _?e @ super::branch: ?id }])
+            )
+            .exactly_one()
+            .map_err(|_| BundleError::BadBranchMetadata(meta_handle))?;
+            let head = find!(
+                h: CommitHandle,
+                pattern!(&meta, [{ _?e @ super::head: ?h }])
+            )
+            .at_most_one()
+            .map_err(|_| BundleError::BadBranchMetadata(meta_handle))?;
+            let name = find!(
+                h: Value<Handle<Blake3, LongString>>,
+                pattern!(&meta, [{ _?e @ metadata::name: ?h }])
+            )
+            .next()
+            .and_then(|h| self.blobs.get::<View<str>, LongString>(h).ok())
+            .map(|name| name.as_ref().to_string());
+            let signer = find!(
+                key: Value<ed::ED25519PublicKey>,
+                pattern!(&meta, [{ _?e @ super::signed_by: ?key }])
+            )
+            .next();
+
+            let mut signed_by = None;
+            let mut commits = 0;
+            if let Some(head) = head {
+                let head_blob: Blob<SimpleArchive> = self
+                    .blobs
+                    .get(head)
+                    .map_err(|_| BundleError::MissingBlob(head.transmute()))?;
+                if let Some(key) = signer {
+                    super::branch::verify(head_blob, meta.clone())
+                        .map_err(|_| BundleError::BadSignature(meta_handle))?;
+                    signed_by = Some(
+                        VerifyingKey::try_from_value(&key)
+                            .map_err(|_| BundleError::BadSignature(meta_handle))?,
+                    );
+                }
+                commits = self.verify_commits(head, &mut prerequisites)?;
+            }
+
+            branches.push(BundledBranch {
+                id,
+                name,
+                metadata: meta_handle,
+                head,
+                signed_by,
+                commits,
+            });
+        }
+
+        Ok(BundleVerification {
+            branches,
+            prerequisites: prerequisites.into_iter().sorted().collect(),
+        })
+    }
+
+    /// Walks the commits reachable from `head` inside the bundle, checking
+    /// signatures and collecting references that point outside of it.
+    fn verify_commits(
+        &self,
+        head: CommitHandle,
+        prerequisites: &mut HashSet<Value<Handle<Blake3, UnknownBlob>>>,
+    ) -> Result<usize, BundleError> {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([head]);
+        let mut commits = 0;
+        while let Some(handle) = queue.pop_front() {
+            if !visited.insert(handle) {
+                continue;
+            }
+            let blob: Blob<SimpleArchive> = match self.blobs.get(handle) {
+                Ok(blob) => blob,
+                Err(_) => {
+                    prerequisites.insert(handle.transmute());
+                    continue;
+                }
+            };
+            let commit: TribleSet = blob
+                .try_from_blob()
+                .map_err(|_| BundleError::BadBranchMetadata(handle))?;
+            commits += 1;
+
+            if let Some(content) = find!(
+                c: CommitHandle,
+                pattern!(&commit, [{ _?e @ super::content: ?c }])
+            )
+            .next()
+            {
+                match self
+                    .blobs
+                    .get::<Blob<SimpleArchive>, SimpleArchive>(content)
+                {
+                    Ok(content_blob) => super::commit::verify(content_blob, commit.clone())
+                        .map_err(|_| BundleError::BadSignature(handle))?,
+                    Err(_) => {
+                        prerequisites.insert(content.transmute());
+                    }
+                }
+            }
+            for parent in find!(
+                p: CommitHandle,
+                pattern!(&commit, [{ _?e @ super::parent: ?p }])
+            ) {
+                queue.push_back(parent);
+            }
+        }
+        Ok(commits)
+    }
+}
+
+/// Error returned by [`create_bundle`].
+#[derive(Debug)]
+pub enum CreateBundleError<Storage: BranchStore<Blake3> + BlobStore<Blake3>> {
+    /// A requested branch does not exist in the store.
+    UnknownBranch(Id),
+    /// An error occurred while looking up a branch head.
+    BranchHead(Storage::HeadError),
+    /// An error occurred while creating a blob reader.
+    StorageReader(<Storage as BlobStore<Blake3>>::ReaderError),
+    /// An error occurred while reading branch metadata.
+    StorageGet(
+        <<Storage as BlobStore<Blake3>>::Reader as BlobStoreGet<Blake3>>::GetError<UnarchiveError>,
+    ),
+    /// An error occurred while loading a blob to write into the bundle.
+    StorageLoad(
+        <<Storage as BlobStore<Blake3>>::Reader as BlobStoreGet<Blake3>>::GetError<Infallible>,
+    ),
+    /// Writing the bundle failed.
+    Io(std::io::Error),
+}
+
+/// Writes a bundle carrying `branches` to `out`.
+///
+/// Every blob reachable from the branches' metadata is included, except
+/// blobs that are also reachable from one of the `bases`. Head commits are
+/// always included so branch signatures stay verifiable, even when a branch
+/// has not moved past its base. Returns the manifest that was written.
+pub fn create_bundle<Storage>(
+    store: &mut Storage,
+    branches: &[Id],
+    bases: &[CommitHandle],
+    mut out: impl Write,
+) -> Result<BundleManifest, CreateBundleError<Storage>>
+where
+    Storage: BranchStore<Blake3> + BlobStore<Blake3>,
+    <Storage as BlobStore<Blake3>>::Reader: BlobChildren<Blake3>,
+{
+    let mut manifest = BundleManifest::default();
+    for &branch in branches {
+        let meta = store
+            .head(branch)
+            .map_err(CreateBundleError::BranchHead)?
+            .ok_or(CreateBundleError::UnknownBranch(branch))?;
+        manifest.branches.push(meta);
+    }
+    manifest.branches.sort();
+    manifest.branches.dedup();
+    manifest.bases = bases.iter().copied().sorted().dedup().collect();
+
+    let reader = store.reader().map_err(CreateBundleError::StorageReader)?;
+    let mut heads = HashSet::new();
+    for &meta_handle in &manifest.branches {
+        let meta: TribleSet = reader
+            .get(meta_handle)
+            .map_err(CreateBundleError::StorageGet)?;
+        heads.extend(
+            find!(
+                h: CommitHandle,
+                pattern!(&meta, [{ _?e @ super::head: ?h }])
+            )
+            .map(|h| h.raw),
+        );
+    }
+
+    let excluded: HashSet<RawValue> = super::reachable(
+        &reader,
+        manifest
+            .bases
+            .iter()
+            .map(|h| h.transmute::<Handle<Blake3, UnknownBlob>>()),
+    )
+    .map(|h| h.raw)
+    .collect();
+
+    // Breadth-first walk that stops at the base: excluded blobs are neither
+    // emitted nor expanded, except for head commits which are emitted bare.
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<Value<Handle<Blake3, UnknownBlob>>> =
+        manifest.branches.iter().map(|h| h.transmute()).collect();
+    let mut included = Vec::new();
+    while let Some(handle) = queue.pop_front() {
+        if !visited.insert(handle.raw) {
+            continue;
+        }
+        if excluded.contains(&handle.raw) {
+            if heads.contains(&handle.raw) {
+                included.push(handle);
+            }
+            continue;
+        }
+        included.push(handle);
+        queue.extend(
+            reader
+                .children(handle)
+                .into_iter()
+                .filter(|child| !visited.contains(&child.raw)),
+        );
+    }
+
+    let manifest_blob: Blob<SimpleArchive> = manifest.to_tribles().to_blob();
+    let header = BundleHeader {
+        magic_marker: MAGIC_MARKER_BUNDLE,
+        version: BUNDLE_VERSION,
+        blob_count: included.len() as u64 + 1,
+        manifest: manifest_blob.get_handle::<Blake3>().raw,
+    };
+    out.write_all(header.as_bytes())
+        .map_err(CreateBundleError::Io)?;
+    write_record(&mut out, &manifest_blob.bytes).map_err(CreateBundleError::Io)?;
+    for handle in included {
+        let blob: Blob<UnknownBlob> = reader.get(handle).map_err(CreateBundleError::StorageLoad)?;
+        write_record(&mut out, &blob.bytes).map_err(CreateBundleError::Io)?;
+    }
+    out.flush().map_err(CreateBundleError::Io)?;
+    Ok(manifest)
+}
+
+fn write_record(out: &mut impl Write, bytes: &Bytes) -> std::io::Result<()> {
+    let now_in_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let header = BlobHeader::new(now_in_ms, bytes.len() as u64, Hash::<Blake3>::digest(bytes));
+    out.write_all(header.as_bytes())?;
+    out.write_all(bytes.as_ref())?;
+    out.write_all(&[0u8; 64][..padding_for_blob(bytes.len())])
+}
+
+/// What [`import_bundle`] did with one bundled branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    /// The branch did not exist locally and was created.
+    Created,
+    /// The local branch already contains the bundled head.
+    UpToDate,
+    /// The local branch was fast-forwarded from `from` to the bundled head.
+    FastForwarded {
+        /// The local head before the import.
+        from: Option<CommitHandle>,
+    },
+    /// Local and bundled history have diverged; the branch was left alone.
+    Diverged {
+        /// The local head, which does not contain the bundled head.
+        local: CommitHandle,
+    },
+}
+
+/// Per-branch result of [`import_bundle`].
+#[derive(Debug, Clone)]
+pub struct ImportedBranch {
+    /// The bundled branch, as reported by [`Bundle::verify`].
+    pub branch: BundledBranch,
+    /// What happened to the local branch.
+    pub outcome: ImportOutcome,
+}
+
+/// Error returned by [`import_bundle`].
+#[derive(Debug)]
+pub enum ImportBundleError<Storage: BranchStore<Blake3> + BlobStore<Blake3>> {
+    /// The bundle failed verification.
+    Bundle(BundleError),
+    /// The store lacks a blob the bundle was cut against.
+    MissingPrerequisite(Value<Handle<Blake3, UnknownBlob>>),
+    /// An error occurred while creating a blob reader.
+    StorageReader(<Storage as BlobStore<Blake3>>::ReaderError),
+    /// An error occurred while reading branch or commit metadata.
+    StorageGet(
+        <<Storage as BlobStore<Blake3>>::Reader as BlobStoreGet<Blake3>>::GetError<UnarchiveError>,
+    ),
+    /// An error occurred while copying bundle blobs into the store.
+    StoragePut(<Storage as BlobStorePut<Blake3>>::PutError),
+    /// An error occurred while looking up a branch head.
+    BranchHead(Storage::HeadError),
+    /// An error occurred while updating a branch.
+    BranchUpdate(Storage::UpdateError),
+    /// The branch moved while it was being fast-forwarded.
+    BranchAdvanced(Id),
+}
+
+/// Imports a verified bundle into `store`.
+///
+/// The bundle is verified first and its prerequisites must already be in
+/// the store. All bundled blobs are then copied over, and each branch is
+/// created if missing or fast-forwarded if its local head is an ancestor of
+/// the bundled one. Fast-forwards adopt the bundled metadata verbatim so
+/// the branch signature travels with it. Diverged branches are reported as
+/// [`ImportOutcome::Diverged`] and left for the caller to reconcile.
+pub fn import_bundle<Storage>(
+    store: &mut Storage,
+    bundle: &Bundle,
+) -> Result<Vec<ImportedBranch>, ImportBundleError<Storage>>
+where
+    Storage: BranchStore<Blake3> + BlobStore<Blake3>,
+{
+    let verification = bundle.verify().map_err(ImportBundleError::Bundle)?;
+
+    let reader = store.reader().map_err(ImportBundleError::StorageReader)?;
+    for &handle in &verification.prerequisites {
+        if reader.get::<Bytes, UnknownBlob>(handle).is_err() {
+            return Err(ImportBundleError::MissingPrerequisite(handle));
+        }
+    }
+
+    for (_, blob) in bundle.blobs.iter() {
+        store.put(blob).map_err(ImportBundleError::StoragePut)?;
+    }
+
+    let reader = store.reader().map_err(ImportBundleError::StorageReader)?;
+    let mut imported = Vec::with_capacity(verification.branches.len());
+    for branch in verification.branches {
+        let outcome = match store
+            .head(branch.id)
+            .map_err(ImportBundleError::BranchHead)?
+        {
+            None => {
+                update_branch(store, branch.id, None, branch.metadata)?;
+                ImportOutcome::Created
+            }
+            Some(local_meta) if local_meta == branch.metadata => ImportOutcome::UpToDate,
+            Some(local_meta) => {
+                let meta: TribleSet = reader
+                    .get(local_meta)
+                    .map_err(ImportBundleError::StorageGet)?;
+                let local_head = find!(
+                    h: CommitHandle,
+                    pattern!(&meta, [{ _?e @ super::head: ?h }])
+                )
+                .next();
+                match (local_head, branch.head) {
+                    (_, None) => ImportOutcome::UpToDate,
+                    (Some(local), Some(remote)) if is_ancestor(&reader, remote, local)? => {
+                        ImportOutcome::UpToDate
+                    }
+                    (Some(local), Some(remote)) if !is_ancestor(&reader, local, remote)? => {
+                        ImportOutcome::Diverged { local }
+                    }
+                    (from, Some(_)) => {
+                        update_branch(store, branch.id, Some(local_meta), branch.metadata)?;
+                        ImportOutcome::FastForwarded { from }
+                    }
+                }
+            }
+        };
+        imported.push(ImportedBranch { branch, outcome });
+    }
+    Ok(imported)
+}
+
+fn update_branch<Storage>(
+    store: &mut Storage,
+    id: Id,
+    old: Option<Value<Handle<Blake3, SimpleArchive>>>,
+    new: Value<Handle<Blake3, SimpleArchive>>,
+) -> Result<(), ImportBundleError<Storage>>
+where
+    Storage: BranchStore<Blake3> + BlobStore<Blake3>,
+{
+    match store
+        .update(id, old, Some(new))
+        .map_err(ImportBundleError::BranchUpdate)?
+    {
+        PushResult::Success() => Ok(()),
+        PushResult::Conflict(_) => Err(ImportBundleError::BranchAdvanced(id)),
+    }
+}
+
+/// Returns true if `ancestor` is `descendant` or one of its transitive
+/// parents.
+fn is_ancestor<Storage>(
+    reader: &<Storage as BlobStore<Blake3>>::Reader,
+    ancestor: CommitHandle,
+    descendant: CommitHandle,
+) -> Result<bool, ImportBundleError<Storage>>
+where
+    Storage: BranchStore<Blake3> + BlobStore<Blake3>,
+{
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([descendant]);
+    while let Some(handle) = queue.pop_front() {
+        if handle == ancestor {
+            return Ok(true);
+        }
+        if !visited.insert(handle) {
+            continue;
+        }
+        let commit: TribleSet = reader.get(handle).map_err(ImportBundleError::StorageGet)?;
+        queue.extend(find!(
+            p: CommitHandle,
+            pattern!(&commit, [{ _?e @ super::parent: ?p }])
+        ));
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::literature;
+    use crate::id::rngid;
+    use crate::repo::memoryrepo::MemoryRepo;
+    use crate::repo::Repository;
+    use ed25519_dalek::SigningKey;
+    use rand::rngs::OsRng;
+
+    fn repo() -> Repository<MemoryRepo> {
+        Repository::new(
+            MemoryRepo::default(),
+            SigningKey::generate(&mut OsRng),
+            TribleSet::new(),
+        )
+        .unwrap()
+    }
+
+    fn commit(repo: &mut Repository<MemoryRepo>, branch: Id, label: &str) -> CommitHandle {
+        let mut ws = repo.pull(branch).expect("pull");
+        let fragment = entity! { &rngid() @ literature::firstname: label };
+        ws.commit(fragment, label);
+        repo.push(&mut ws).expect("push");
+        repo.pull(branch).expect("pull").head().expect("head")
+    }
+
+    fn bundle_of(
+        repo: &mut Repository<MemoryRepo>,
+        branches: &[Id],
+        bases: &[CommitHandle],
+    ) -> Bundle {
+        let mut out = Vec::new();
+        create_bundle(repo.storage_mut(), branches, bases, &mut out).expect("create");
+        Bundle::from_bytes(Bytes::from_source(out)).expect("parse")
+    }
+
+    #[test]
+    fn full_bundle_roundtrips_into_an_empty_store() {
+        let mut source = repo();
+        let branch = *source.create_branch("main", None).expect("branch");
+        commit(&mut source, branch, "a");
+        let head = commit(&mut source, branch, "b");
+
+        let bundle = bundle_of(&mut source, &[branch], &[]);
+        let verified = bundle.verify().expect("verify");
+        assert!(verified.prerequisites.is_empty());
+        assert_eq!(verified.branches.len(), 1);
+        assert_eq!(verified.branches[0].name.as_deref(), Some("main"));
+        assert_eq!(verified.branches[0].head, Some(head));
+        assert_eq!(verified.branches[0].commits, 2);
+
+        let mut target = repo();
+        let imported = import_bundle(target.storage_mut(), &bundle).expect("import");
+        assert_eq!(imported[0].outcome, ImportOutcome::Created);
+        let mut ws = target.pull(branch).expect("pull");
+        assert_eq!(ws.head(), Some(head));
+        assert_eq!(ws.checkout(..).expect("checkout").facts().len(), 2);
+    }
+
+    #[test]
+    fn thin_bundle_requires_and_fast_forwards_from_its_base() {
+        let mut source = repo();
+        let branch = *source.create_branch("main", None).expect("branch");
+        let base = commit(&mut source, branch, "a");
+
+        let mut target = repo();
+        let full = bundle_of(&mut source, &[branch], &[]);
+        import_bundle(target.storage_mut(), &full).expect("import");
+
+        let head = commit(&mut source, branch, "b");
+        let thin = bundle_of(&mut source, &[branch], &[base]);
+        let verified = thin.verify().expect("verify");
+        assert_eq!(verified.branches[0].commits, 1);
+        assert_eq!(verified.prerequisites, vec![base.transmute()]);
+        assert!(thin
+            .reader()
+            .get::<Bytes, UnknownBlob>(base.transmute())
+            .is_err());
+
+        let mut empty = repo();
+        assert!(matches!(
+            import_bundle(empty.storage_mut(), &thin),
+            Err(ImportBundleError::MissingPrerequisite(h)) if h == base.transmute()
+        ));
+
+        let imported = import_bundle(target.storage_mut(), &thin).expect("import");
+        assert_eq!(
+            imported[0].outcome,
+            ImportOutcome::FastForwarded { from: Some(base) }
+        );
+        assert_eq!(target.pull(branch).expect("pull").head(), Some(head));
+
+        let again = import_bundle(target.storage_mut(), &thin).expect("import");
+        assert_eq!(again[0].outcome, ImportOutcome::UpToDate);
+    }
+
+    #[test]
+    fn diverged_branches_are_left_alone() {
+        let mut source = repo();
+        let branch = *source.create_branch("main", None).expect("branch");
+        commit(&mut source, branch, "a");
+
+        let mut target = repo();
+        import_bundle(
+            target.storage_mut(),
+            &bundle_of(&mut source, &[branch], &[]),
+        )
+        .expect("import");
+        let local = commit(&mut target, branch, "local");
+        commit(&mut source, branch, "remote");
+
+        let imported = import_bundle(
+            target.storage_mut(),
+            &bundle_of(&mut source, &[branch], &[]),
+        )
+        .expect("import");
+        assert_eq!(imported[0].outcome, ImportOutcome::Diverged { local });
+        assert_eq!(target.pull(branch).expect("pull").head(), Some(local));
+    }
+
+    #[test]
+    fn corrupted_records_are_rejected() {
+        let mut source = repo();
+        let branch = *source.create_branch("main", None).expect("branch");
+        commit(&mut source, branch, "a");
+
+        let mut out = Vec::new();
+        create_bundle(source.storage_mut(), &[branch], &[], &mut out).expect("create");
+        let last = out.len() - 1;
+        let mut truncated = out.clone();
+        truncated.truncate(last - 64);
+        assert!(matches!(
+            Bundle::from_bytes(Bytes::from_source(truncated)),
+            Err(BundleError::Truncated)
+        ));
+
+        // Flip a byte inside the manifest record's payload.
+        out[BUNDLE_HEADER_LEN + std::mem::size_of::<BlobHeader>()] ^= 0xff;
+        assert!(matches!(
+            Bundle::from_bytes(Bytes::from_source(out)),
+            Err(BundleError::HashMismatch(offset)) if offset == BUNDLE_HEADER_LEN
+        ));
+    }
+}
@@ -141,6 +141,11 @@ pub enum BuildError {
     /// The provided parent signature blob did not contain exactly one
     /// signature entity (i.e. exactly one entity carrying [`sig_signs`]).
     ParentSigShape,
+    /// The capability handed to [`renew_capability`] could not be parsed
+    /// or is missing required attributes.
+    MalformedCap,
+    /// The key renewing a capability is not the key that issued it.
+    IssuerMismatch,
 }
 
 /// Build a capability link.
@@ -310,6 +315,114 @@ fn issuer_subject_value(key: VerifyingKey) -> Value<ed::ED25519PublicKey> {
     key.to_value()
 }
 
+// ── Validity windows ─────────────────────────────────────────────────
+
+/// How far the verifier's clock may disagree with the issuer's.
+///
+/// A cap is accepted from `not_before - CLOCK_SKEW_TOLERANCE` until
+/// `not_after + CLOCK_SKEW_TOLERANCE`, so a freshly issued cap isn't
+/// refused by a peer whose clock runs a little behind, and short-lived
+/// caps don't flap at their boundary.
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_seconds(300.0);
+
+/// Build the `metadata::expires_at` interval for a cap valid from now
+/// for `lifetime`. The lower bound is the cap's `not_before`, the upper
+/// bound its `not_after`.
+pub fn valid_for(lifetime: Duration) -> Value<NsTAIInterval> {
+    let now = Epoch::now().expect("system time");
+    (now, now + lifetime)
+        .try_to_value()
+        .expect("non-negative lifetime yields a valid interval")
+}
+
+/// Decode a validity interval into `(not_before, not_after)`.
+fn validity_bounds(expiry: &Value<NsTAIInterval>) -> Option<(Epoch, Epoch)> {
+    <(Epoch, Epoch)>::try_from_value(expiry).ok()
+}
+
+/// Re-issue a capability with a new validity window.
+///
+/// The renewed cap keeps the subject, issuer, scope root and scope facts
+/// of `cap_blob`; only the expiry (and, for delegated caps, the parent
+/// link) changes. `issuer` must be the key that signed the original cap,
+/// and `parent` is the issuer's *current* cap pair (`None` for caps
+/// issued by the team root) — so an issuer who renewed their own cap
+/// first passes the renewed one and the new link chains off it.
+///
+/// The original cap stays valid until it expires; renewing is additive.
+pub fn renew_capability(
+    issuer: &SigningKey,
+    cap_blob: &Blob<SimpleArchive>,
+    parent: Option<(Blob<SimpleArchive>, Blob<SimpleArchive>)>,
+    expiry: Value<NsTAIInterval>,
+) -> Result<(Blob<SimpleArchive>, Blob<SimpleArchive>), BuildError> {
+    let cap_set: TribleSet =
+        TryFromBlob::try_from_blob(cap_blob.clone()).map_err(|_| BuildError::MalformedCap)?;
+    let fields = extract_cap_fields(&cap_set).map_err(|_| BuildError::MalformedCap)?;
+    if fields.issuer != issuer.verifying_key() {
+        return Err(BuildError::IssuerMismatch);
+    }
+    let mut scope_facts = TribleSet::new();
+    for trible in cap_set.iter() {
+        if *trible.e() == fields.scope_root {
+            scope_facts.insert(trible);
+        }
+    }
+    build_capability(
+        issuer,
+        fields.subject,
+        parent,
+        fields.scope_root,
+        scope_facts,
+        expiry,
+    )
+}
+
+/// The claims of a single cap blob, decoded without checking any
+/// signatures. See [`inspect_capability`].
+#[derive(Debug, Clone)]
+pub struct CapabilityInfo {
+    /// The pubkey the cap authorizes.
+    pub subject: VerifyingKey,
+    /// The pubkey that signed the cap.
+    pub issuer: VerifyingKey,
+    /// The scope root entity id within the cap blob.
+    pub scope_root: crate::id::Id,
+    /// Permission tags on the scope root.
+    pub permissions: HashSet<crate::id::Id>,
+    /// Branch restriction, or `None` for an unrestricted scope.
+    pub branches: Option<HashSet<crate::id::Id>>,
+    /// Start of the validity window.
+    pub not_before: Epoch,
+    /// End of the validity window.
+    pub not_after: Epoch,
+    /// Handle of the parent cap blob; `None` for team-root-issued caps.
+    pub parent: Option<Value<Handle<Blake3, SimpleArchive>>>,
+}
+
+/// Decode a cap blob's claims for display (`trible team inspect`) or
+/// for deciding whether it needs renewing.
+///
+/// This is a structural read only: nothing is verified. Use
+/// [`verify_chain`] to find out whether the cap is actually honoured.
+pub fn inspect_capability(cap_blob: &Blob<SimpleArchive>) -> Result<CapabilityInfo, VerifyError> {
+    let cap_set: TribleSet = TryFromBlob::try_from_blob(cap_blob.clone())?;
+    let fields = extract_cap_fields(&cap_set)?;
+    let (not_before, not_after) =
+        validity_bounds(&fields.expiry).ok_or(VerifyError::MalformedCap)?;
+    let (permissions, branches) = collect_scope_facts(&cap_set, fields.scope_root);
+    Ok(CapabilityInfo {
+        subject: fields.subject,
+        issuer: fields.issuer,
+        scope_root: fields.scope_root,
+        permissions,
+        branches: if branches.is_empty() { None } else { Some(branches) },
+        not_before,
+        not_after,
+        parent: fields.parent_handle,
+    })
+}
+
 // ── Scope subsumption ────────────────────────────────────────────────
 
 /// Collect the permission tag ids and branch restrictions from a scope
@@ -459,19 +572,52 @@ pub fn build_revocation(
     let rev_blob: Blob<SimpleArchive> = TribleSet::from(rev_fragment).to_blob();
 
     let signature: Signature = revoker.sign(&rev_blob.bytes);
-    let revoker_pubkey = revoker.verifying_key();
-    let rev_handle: Value<Handle<Blake3, SimpleArchive>> =
-        (&rev_blob).get_handle();
+    let sig_blob =
+        revocation_sig_blob(revoker.verifying_key(), &rev_blob, signature);
+
+    (rev_blob, sig_blob)
+}
 
+/// Rebuild the sig blob of a revocation pair from its parts.
+///
+/// Sig blobs are content-addressed and carry nothing beyond the
+/// revoker, the signature and the rev blob's handle, so a revocation
+/// can travel as `(rev_blob, signature)` over size-constrained
+/// channels (gossip, DHT records) and be reassembled into the exact
+/// pair [`build_revocation`] produced. Whether the result verifies is
+/// still up to [`verify_revocation`].
+pub fn revocation_sig_blob(
+    revoker: VerifyingKey,
+    rev_blob: &Blob<SimpleArchive>,
+    signature: Signature,
+) -> Blob<SimpleArchive> {
+    let rev_handle: Value<Handle<Blake3, SimpleArchive>> = rev_blob.get_handle();
     let sig_fragment = entity! {
         sig_signs: rev_handle,
-        crate::repo::signed_by: revoker_pubkey,
+        crate::repo::signed_by: revoker,
         crate::repo::signature_r: signature,
         crate::repo::signature_s: signature,
     };
-    let sig_blob: Blob<SimpleArchive> = TribleSet::from(sig_fragment).to_blob();
+    TribleSet::from(sig_fragment).to_blob()
+}
 
-    (rev_blob, sig_blob)
+/// The signature carried by a revocation (or capability) sig blob, as
+/// needed to hand the pair to [`revocation_sig_blob`] on the far side.
+/// Returns `None` if the blob does not hold exactly one signature.
+pub fn revocation_signature(sig_blob: Blob<SimpleArchive>) -> Option<Signature> {
+    let sig_set: TribleSet = TryFromBlob::try_from_blob(sig_blob).ok()?;
+    let mut iter = find!(
+        (sig: crate::id::Id, r, s),
+        pattern!(&sig_set, [{
+            ?sig @
+            crate::repo::signature_r: ?r,
+            crate::repo::signature_s: ?s,
+        }])
+    );
+    match (iter.next(), iter.next()) {
+        (Some((_sig, r, s)), None) => Some(Signature::from_components(r, s)),
+        _ => None,
+    }
 }
 
 /// Verify a revocation blob pair: the sig blob attests to the rev
@@ -690,7 +836,7 @@ use ed25519_dalek::Verifier;
 use std::collections::HashSet;
 use crate::value::TryFromValue;
 use crate::value::TryToValue;
-use hifitime::Epoch;
+use hifitime::{Duration, Epoch};
 
 /// Errors returned by [`verify_chain`].
 #[derive(Debug)]
@@ -710,8 +856,12 @@ pub enum VerifyError {
     /// A cap's `cap_issuer` did not match the accompanying sig's
     /// `signed_by`.
     IssuerMismatch,
-    /// A cap or its embedded parent sig has expired.
+    /// A cap in the chain has expired (its `not_after` lies further in
+    /// the past than [`CLOCK_SKEW_TOLERANCE`]).
     Expired,
+    /// A cap in the chain is not valid yet (its `not_before` lies further
+    /// in the future than [`CLOCK_SKEW_TOLERANCE`]).
+    NotYetValid,
     /// A pubkey appearing in the chain is in the revocation list.
     Revoked,
     /// A child cap's scope was not a subset of its parent's scope.
@@ -736,6 +886,31 @@ pub enum VerifyError {
     ChainTooDeep,
 }
 
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::ParseBlob(e) => write!(f, "unparseable capability blob: {e}"),
+            VerifyError::Fetch => write!(f, "capability blob could not be fetched"),
+            VerifyError::BadSignature => write!(f, "bad capability signature"),
+            VerifyError::SubjectMismatch => write!(f, "capability issued to another key"),
+            VerifyError::IssuerMismatch => write!(f, "capability not signed by its issuer"),
+            VerifyError::Expired => write!(f, "capability expired"),
+            VerifyError::NotYetValid => write!(f, "capability not valid yet"),
+            VerifyError::Revoked => write!(f, "key in the chain is revoked"),
+            VerifyError::ScopeNotSubset => write!(f, "capability exceeds its parent's scope"),
+            VerifyError::MalformedCap => write!(f, "malformed capability"),
+            VerifyError::MalformedSig => write!(f, "malformed capability signature"),
+            VerifyError::LeafCapMissing => write!(f, "leaf capability missing"),
+            VerifyError::NonRootMissingParent => {
+                write!(f, "non-root capability has no parent")
+            }
+            VerifyError::ChainTooDeep => write!(f, "capability chain too deep"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
 impl From<UnarchiveError> for VerifyError {
     fn from(e: UnarchiveError) -> Self {
         VerifyError::ParseBlob(e)
@@ -788,10 +963,13 @@ impl From<UnarchiveError> for VerifyError {
 ///     scope_branch: *allowed_branch,
 /// });
 ///
+/// let now = hifitime::Epoch::now().unwrap();
 /// let verified = VerifiedCapability {
 ///     subject: SigningKey::generate(&mut OsRng).verifying_key(),
 ///     scope_root: *scope_root,
 ///     cap_set,
+///     not_before: now,
+///     not_after: now + hifitime::Duration::from_seconds(3600.0),
 /// };
 ///
 /// // permissions() exposes the raw tag set.
@@ -820,9 +998,30 @@ pub struct VerifiedCapability {
     /// The leaf cap's full TribleSet (caller can extract its scope by
     /// querying tribles anchored at `scope_root`).
     pub cap_set: TribleSet,
+    /// Start of the chain's effective validity window: the latest
+    /// `not_before` of any link.
+    pub not_before: Epoch,
+    /// End of the chain's effective validity window: the earliest
+    /// `not_after` of any link. A delegated cap is only honoured as long
+    /// as every cap above it is.
+    pub not_after: Epoch,
 }
 
 impl VerifiedCapability {
+    /// Returns `true` if `now` falls inside the chain's validity window,
+    /// widened by `tolerance` on both ends. Long-lived holders of a
+    /// verified cap (e.g. an authenticated connection) use this to notice
+    /// that the chain has since expired.
+    pub fn is_valid_at(&self, now: Epoch, tolerance: Duration) -> bool {
+        self.not_before - tolerance <= now && now <= self.not_after + tolerance
+    }
+
+    /// Time left until the chain expires, or `None` if it already has
+    /// (ignoring clock-skew tolerance).
+    pub fn remaining(&self, now: Epoch) -> Option<Duration> {
+        (now <= self.not_after).then(|| self.not_after - now)
+    }
+
     /// Returns the set of permissions tagged on this cap's scope root
     /// (a subset of `{`[`PERM_READ`]`,`[`PERM_WRITE`]`,`[`PERM_ADMIN`]`}`).
     pub fn permissions(&self) -> HashSet<crate::id::Id> {
@@ -861,6 +1060,61 @@ impl VerifiedCapability {
             Some(set) => set.contains(branch),
         }
     }
+
+    /// Returns `true` if the cap grants write-equivalent permission
+    /// (write or admin — admin implies write, matching the subsumption
+    /// rules in [`scope_subsumes`]).
+    pub fn grants_write(&self) -> bool {
+        let perms = self.permissions();
+        perms.contains(&PERM_WRITE) || perms.contains(&PERM_ADMIN)
+    }
+
+    /// Returns `true` if the cap grants write-equivalent permission on
+    /// the given branch — i.e. the cap [`grants_write`](Self::grants_write)
+    /// AND either is unrestricted or its restriction set contains
+    /// `branch`. Used to decide whether a branch head published by the
+    /// cap's subject may be accepted.
+    pub fn grants_write_on(&self, branch: &crate::id::Id) -> bool {
+        if !self.grants_write() {
+            return false;
+        }
+        match self.granted_branches() {
+            None => true,
+            Some(set) => set.contains(branch),
+        }
+    }
+}
+
+/// Return the next handle up a capability chain from one of its blobs.
+///
+/// For a signature blob this is the cap blob it attests to
+/// ([`sig_signs`]); for a cap blob it is the parent cap
+/// ([`cap_parent`]), or `None` once the founder's cap is reached. Blobs
+/// that are neither yield `None`.
+///
+/// Verification itself needs the whole chain to be fetchable through a
+/// synchronous callback. Callers that retrieve blobs asynchronously
+/// (e.g. from a remote peer) use this to collect the chain blob by blob
+/// before handing it to [`verify_chain`].
+pub fn chain_successor(
+    blob: &Blob<SimpleArchive>,
+) -> Option<Value<Handle<Blake3, SimpleArchive>>> {
+    let set: TribleSet = TryFromBlob::try_from_blob(blob.clone()).ok()?;
+    let mut signs = find!(
+        (sig: crate::id::Id, h: Value<Handle<Blake3, SimpleArchive>>),
+        pattern!(&set, [{ ?sig @ sig_signs: ?h }])
+    );
+    if let (Some((_, h)), None) = (signs.next(), signs.next()) {
+        return Some(h);
+    }
+    let mut parents = find!(
+        (cap: crate::id::Id, h: Value<Handle<Blake3, SimpleArchive>>),
+        pattern!(&set, [{ ?cap @ cap_parent: ?h }])
+    );
+    match (parents.next(), parents.next()) {
+        (Some((_, h)), None) => Some(h),
+        _ => None,
+    }
 }
 
 /// Maximum chain depth the verifier will walk before giving up. Real
@@ -1062,22 +1316,53 @@ pub fn verify_chain<F>(
     leaf_sig_handle: Value<Handle<Blake3, SimpleArchive>>,
     expected_subject: VerifyingKey,
     revoked: &HashSet<VerifyingKey>,
+    fetch_blob: F,
+) -> Result<VerifiedCapability, VerifyError>
+where
+    F: FnMut(Value<Handle<Blake3, SimpleArchive>>) -> Option<Blob<SimpleArchive>>,
+{
+    verify_chain_at(
+        team_root,
+        leaf_sig_handle,
+        expected_subject,
+        revoked,
+        Epoch::now().expect("system time"),
+        CLOCK_SKEW_TOLERANCE,
+        fetch_blob,
+    )
+}
+
+/// [`verify_chain`] against an explicit clock.
+///
+/// Every link's validity window must contain `now`, widened by
+/// `tolerance` on both ends; otherwise verification fails with
+/// [`VerifyError::NotYetValid`] or [`VerifyError::Expired`]. The
+/// returned [`VerifiedCapability`] carries the intersection of all the
+/// windows so callers can tell when to verify again.
+pub fn verify_chain_at<F>(
+    team_root: VerifyingKey,
+    leaf_sig_handle: Value<Handle<Blake3, SimpleArchive>>,
+    expected_subject: VerifyingKey,
+    revoked: &HashSet<VerifyingKey>,
+    now: Epoch,
+    tolerance: Duration,
     mut fetch_blob: F,
 ) -> Result<VerifiedCapability, VerifyError>
 where
     F: FnMut(Value<Handle<Blake3, SimpleArchive>>) -> Option<Blob<SimpleArchive>>,
 {
-    let now: Epoch = hifitime::Epoch::now().expect("system time");
-
-    // Helper: a cap is valid until the *upper bound* of its expiry
-    // interval. We compare that upper bound against `now`.
-    let is_expired = |expiry: &Value<NsTAIInterval>| -> bool {
-        match <(Epoch, Epoch)>::try_from_value(expiry) {
-            Ok((_lower, upper)) => upper < now,
-            // A malformed/inverted interval is treated as expired so
-            // adversarial caps can't fall through.
-            Err(_) => true,
+    // A link is honoured from `not_before` until `not_after`, each
+    // widened by the skew tolerance. A malformed/inverted interval is
+    // treated as expired so adversarial caps can't fall through.
+    let check_window = |expiry: &Value<NsTAIInterval>| -> Result<(Epoch, Epoch), VerifyError> {
+        let (not_before, not_after) = validity_bounds(expiry).ok_or(VerifyError::Expired)?;
+        if now < not_before - tolerance {
+            return Err(VerifyError::NotYetValid);
         }
+        if not_after + tolerance < now {
+            return Err(VerifyError::Expired);
+        }
+        Ok((not_before, not_after))
     };
 
     // ── Leaf step ────────────────────────────────────────────────────
@@ -1116,9 +1401,7 @@ where
     if leaf_signer != leaf_fields.issuer {
         return Err(VerifyError::IssuerMismatch);
     }
-    if is_expired(&leaf_fields.expiry) {
-        return Err(VerifyError::Expired);
-    }
+    let (mut not_before, mut not_after) = check_window(&leaf_fields.expiry)?;
     if revoked.contains(&leaf_fields.issuer)
         || revoked.contains(&leaf_fields.subject)
     {
@@ -1137,6 +1420,8 @@ where
                 subject: leaf_fields.subject,
                 scope_root: leaf_fields.scope_root,
                 cap_set: leaf_cap_set,
+                not_before,
+                not_after,
             });
         }
 
@@ -1184,9 +1469,9 @@ where
         if parent_signer != parent_fields.issuer {
             return Err(VerifyError::IssuerMismatch);
         }
-        if is_expired(&parent_fields.expiry) {
-            return Err(VerifyError::Expired);
-        }
+        let (parent_not_before, parent_not_after) = check_window(&parent_fields.expiry)?;
+        not_before = not_before.max(parent_not_before);
+        not_after = not_after.min(parent_not_after);
         if revoked.contains(&parent_fields.issuer)
             || revoked.contains(&parent_fields.subject)
         {
@@ -1429,6 +1714,166 @@ mod tests {
         assert_eq!(verified.scope_root, member_scope_root);
     }
 
+    /// `not_before` and `not_after` are both enforced, each widened by
+    /// the clock-skew tolerance.
+    #[test]
+    fn verify_chain_at_enforces_window_with_skew() {
+        let team_root = signing_key();
+        let founder = signing_key();
+        let (scope_root, scope_facts) = empty_scope();
+        let hour = Duration::from_seconds(3600.0);
+        let minute = Duration::from_seconds(60.0);
+        let now = Epoch::now().expect("system time");
+        let window: Value<NsTAIInterval> =
+            (now + hour, now + 2 * hour).try_to_value().unwrap();
+
+        let (cap_blob, sig_blob) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            scope_root,
+            scope_facts,
+            window,
+        )
+        .expect("cap builds");
+        let leaf: Value<Handle<Blake3, SimpleArchive>> = sig_blob.get_handle();
+        let verify_at = |at: Epoch| {
+            verify_chain_at(
+                team_root.verifying_key(),
+                leaf,
+                founder.verifying_key(),
+                &HashSet::new(),
+                at,
+                CLOCK_SKEW_TOLERANCE,
+                store_for(&[&cap_blob, &sig_blob]),
+            )
+        };
+
+        assert!(matches!(verify_at(now), Err(VerifyError::NotYetValid)));
+        // Inside the tolerance on either end.
+        assert!(verify_at(now + hour - minute).is_ok());
+        assert!(verify_at(now + 2 * hour + minute).is_ok());
+        assert!(matches!(
+            verify_at(now + 2 * hour + 10 * minute),
+            Err(VerifyError::Expired)
+        ));
+    }
+
+    /// A delegated cap is only good while its parents are: the verified
+    /// window is the intersection of every link's window.
+    #[test]
+    fn verified_window_is_bounded_by_the_parent() {
+        let team_root = signing_key();
+        let founder = signing_key();
+        let member = signing_key();
+        let hour = Duration::from_seconds(3600.0);
+
+        let (founder_scope_root, founder_scope_facts) = empty_scope();
+        let (founder_cap, founder_sig) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            founder_scope_root,
+            founder_scope_facts,
+            valid_for(hour),
+        )
+        .expect("founder cap builds");
+        let founder_not_after = inspect_capability(&founder_cap).unwrap().not_after;
+
+        let (member_scope_root, member_scope_facts) = empty_scope();
+        let (member_cap, member_sig) = build_capability(
+            &founder,
+            member.verifying_key(),
+            Some((founder_cap.clone(), founder_sig.clone())),
+            member_scope_root,
+            member_scope_facts,
+            now_plus_24h(),
+        )
+        .expect("member cap builds");
+
+        let verified = verify_chain(
+            team_root.verifying_key(),
+            member_sig.get_handle(),
+            member.verifying_key(),
+            &HashSet::new(),
+            store_for(&[&founder_cap, &founder_sig, &member_cap, &member_sig]),
+        )
+        .expect("chain verifies");
+        assert_eq!(verified.not_after, founder_not_after);
+
+        let now = Epoch::now().expect("system time");
+        let left = verified.remaining(now).expect("not expired yet");
+        assert!(left <= hour);
+        assert!(verified.is_valid_at(now, Duration::ZERO));
+        assert!(!verified.is_valid_at(now + 2 * hour, CLOCK_SKEW_TOLERANCE));
+        assert!(verified.remaining(now + 2 * hour).is_none());
+    }
+
+    /// Renewal keeps subject and scope, moves the window, and has to be
+    /// signed by the original issuer.
+    #[test]
+    fn renew_capability_extends_window_and_keeps_scope() {
+        let team_root = signing_key();
+        let founder = signing_key();
+        let member = signing_key();
+        let branch = *crate::id::ufoid();
+
+        let (founder_scope_root, founder_scope_facts) = scope_with(&[PERM_ADMIN], &[]);
+        let (founder_cap, founder_sig) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            founder_scope_root,
+            founder_scope_facts,
+            now_plus_24h(),
+        )
+        .expect("founder cap builds");
+        let (member_scope_root, member_scope_facts) = scope_with(&[PERM_WRITE], &[branch]);
+        let (member_cap, _member_sig) = build_capability(
+            &founder,
+            member.verifying_key(),
+            Some((founder_cap.clone(), founder_sig.clone())),
+            member_scope_root,
+            member_scope_facts,
+            valid_for(Duration::from_seconds(60.0)),
+        )
+        .expect("member cap builds");
+
+        let (renewed_cap, renewed_sig) = renew_capability(
+            &founder,
+            &member_cap,
+            Some((founder_cap.clone(), founder_sig.clone())),
+            valid_for(Duration::from_seconds(7.0 * 86400.0)),
+        )
+        .expect("issuer can renew");
+
+        let before = inspect_capability(&member_cap).unwrap();
+        let after = inspect_capability(&renewed_cap).unwrap();
+        assert_eq!(after.subject, member.verifying_key());
+        assert_eq!(after.issuer, founder.verifying_key());
+        assert_eq!(after.scope_root, before.scope_root);
+        assert_eq!(after.permissions, before.permissions);
+        assert_eq!(after.branches, Some(HashSet::from([branch])));
+        assert_eq!(after.parent, Some(founder_cap.get_handle()));
+        assert!(after.not_after > before.not_after);
+
+        let verified = verify_chain(
+            team_root.verifying_key(),
+            renewed_sig.get_handle(),
+            member.verifying_key(),
+            &HashSet::new(),
+            store_for(&[&founder_cap, &founder_sig, &renewed_cap, &renewed_sig]),
+        )
+        .expect("renewed chain verifies");
+        assert!(verified.grants_write_on(&branch));
+
+        // Someone else can't re-sign the member's cap.
+        assert!(matches!(
+            renew_capability(&member, &member_cap, None, now_plus_24h()),
+            Err(BuildError::IssuerMismatch)
+        ));
+    }
+
     /// Subject mismatch: presenting a cap whose subject doesn't match
     /// the connecting peer's pubkey. Should fail with SubjectMismatch.
     #[test]
@@ -1665,6 +2110,22 @@ mod tests {
         assert_eq!(out_target, target.verifying_key());
     }
 
+    #[test]
+    fn revocation_sig_blob_reassembles_the_pair() {
+        let revoker = signing_key();
+        let target = signing_key();
+        let (rev_blob, sig_blob) =
+            build_revocation(&revoker, target.verifying_key());
+
+        let signature = revocation_signature(sig_blob.clone()).expect("one signature");
+        let rebuilt = revocation_sig_blob(revoker.verifying_key(), &rev_blob, signature);
+        assert_eq!(rebuilt.bytes, sig_blob.bytes);
+
+        // The wrong revoker yields a pair that no longer verifies.
+        let forged = revocation_sig_blob(target.verifying_key(), &rev_blob, signature);
+        assert!(verify_revocation(rev_blob, forged).is_err());
+    }
+
     #[test]
     fn revocation_set_filters_unauthorised_revokers() {
         let team_root = signing_key();
@@ -2067,13 +2528,83 @@ mod tests {
         let scope_root = crate::id::ufoid();
         // Empty cap_set with no tags hung off scope_root.
         let cap_set = TribleSet::new();
+        let now = Epoch::now().expect("system time");
         let verified = VerifiedCapability {
             subject: signing_key().verifying_key(),
             scope_root: *scope_root,
             cap_set,
+            not_before: now,
+            not_after: now,
         };
         assert!(!verified.grants_read());
         let any_branch = crate::id::ufoid();
         assert!(!verified.grants_read_on(&any_branch));
     }
+
+    #[test]
+    fn verified_capability_write_gates_by_permission_and_branch() {
+        let allowed = *crate::id::ufoid();
+        let other = *crate::id::ufoid();
+        let cap = |perms: &[Id], branches: &[Id]| {
+            let (scope_root, cap_set) = scope_with(perms, branches);
+            let now = Epoch::now().expect("system time");
+            VerifiedCapability {
+                subject: signing_key().verifying_key(),
+                scope_root,
+                cap_set,
+                not_before: now,
+                not_after: now,
+            }
+        };
+
+        let read_only = cap(&[PERM_READ], &[]);
+        assert!(!read_only.grants_write());
+        assert!(!read_only.grants_write_on(&allowed));
+
+        let writer = cap(&[PERM_WRITE], &[allowed]);
+        assert!(writer.grants_write_on(&allowed));
+        assert!(!writer.grants_write_on(&other));
+
+        let admin = cap(&[PERM_ADMIN], &[]);
+        assert!(admin.grants_write_on(&allowed));
+        assert!(admin.grants_write_on(&other));
+    }
+
+    /// `chain_successor` walks sig → cap → parent cap → … and stops at
+    /// the founder's cap.
+    #[test]
+    fn chain_successor_walks_to_the_founder_cap() {
+        let team_root = signing_key();
+        let founder = signing_key();
+        let member = signing_key();
+
+        let (founder_scope_root, founder_scope_facts) = empty_scope();
+        let (founder_cap, founder_sig) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            founder_scope_root,
+            founder_scope_facts,
+            now_plus_24h(),
+        )
+        .expect("founder cap builds");
+        let (member_scope_root, member_scope_facts) = empty_scope();
+        let (member_cap, member_sig) = build_capability(
+            &founder,
+            member.verifying_key(),
+            Some((founder_cap.clone(), founder_sig)),
+            member_scope_root,
+            member_scope_facts,
+            now_plus_24h(),
+        )
+        .expect("member cap builds");
+
+        let member_cap_handle: Value<Handle<Blake3, SimpleArchive>> =
+            member_cap.get_handle();
+        let founder_cap_handle: Value<Handle<Blake3, SimpleArchive>> =
+            founder_cap.get_handle();
+        assert_eq!(chain_successor(&member_sig), Some(member_cap_handle));
+        assert_eq!(chain_successor(&member_cap), Some(founder_cap_handle));
+        assert_eq!(chain_successor(&founder_cap), None);
+    }
 }
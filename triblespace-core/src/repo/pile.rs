@@ -58,11 +58,11 @@ use crate::value::RawValue;
 use crate::value::Value;
 use crate::value::ValueSchema;
 
-const MAGIC_MARKER_BLOB: RawId = hex!("1E08B022FF2F47B6EBACF1D68EB35D96");
+pub(super) const MAGIC_MARKER_BLOB: RawId = hex!("1E08B022FF2F47B6EBACF1D68EB35D96");
 const MAGIC_MARKER_BRANCH: RawId = hex!("2BC991A7F5D5D2A3A468C53B0AA03504");
 const MAGIC_MARKER_BRANCH_TOMBSTONE: RawId = hex!("E888CC787202D2AE4C654BFE9699C430");
 
-const BLOB_HEADER_LEN: usize = std::mem::size_of::<BlobHeader>();
+pub(super) const BLOB_HEADER_LEN: usize = std::mem::size_of::<BlobHeader>();
 const BLOB_ALIGNMENT: usize = BLOB_HEADER_LEN;
 
 /// Largest single blob record we'll write with the concurrent `write_vectored`
@@ -143,15 +143,15 @@ impl BranchTombstoneHeader {
 
 #[derive(TryFromBytes, IntoBytes, Immutable, KnownLayout, Copy, Clone)]
 #[repr(C)]
-struct BlobHeader {
-    magic_marker: RawId,
-    timestamp: u64,
-    length: u64,
-    hash: RawValue,
+pub(super) struct BlobHeader {
+    pub(super) magic_marker: RawId,
+    pub(super) timestamp: u64,
+    pub(super) length: u64,
+    pub(super) hash: RawValue,
 }
 
 impl BlobHeader {
-    fn new<H: HashProtocol>(timestamp: u64, length: u64, hash: Value<Hash<H>>) -> Self {
+    pub(super) fn new<H: HashProtocol>(timestamp: u64, length: u64, hash: Value<Hash<H>>) -> Self {
         Self {
             magic_marker: MAGIC_MARKER_BLOB,
             timestamp,
@@ -189,7 +189,7 @@ pub struct Pile<H: HashProtocol = Blake3> {
     applied_length: usize,
 }
 
-fn padding_for_blob(blob_size: usize) -> usize {
+pub(super) fn padding_for_blob(blob_size: usize) -> usize {
     (BLOB_ALIGNMENT - ((BLOB_HEADER_LEN + blob_size) % BLOB_ALIGNMENT)) % BLOB_ALIGNMENT
 }
 
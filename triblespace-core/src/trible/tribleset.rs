@@ -1,4 +1,5 @@
 mod triblesetconstraint;
+pub mod triblesetgeoconstraint;
 pub mod triblesetidrangeconstraint;
 pub mod triblesetrangeconstraint;
 
@@ -20,6 +21,7 @@ use crate::trible::VAEOrder;
 use crate::trible::VEAOrder;
 use crate::trible::TRIBLE_LEN;
 use crate::value::schemas::genid::GenId;
+use crate::value::schemas::geopoint::{GeoPoint, GeoRegion};
 use crate::value::ValueSchema;
 
 use std::iter::FromIterator;
@@ -192,6 +194,26 @@ impl TribleSet {
         triblesetrangeconstraint::TribleSetRangeConstraint::new(variable, min, max, self.clone())
     }
 
+    /// Creates a constraint that proposes only [`GeoPoint`] values lying in
+    /// `region`, scanning the Z-order value ranges covering it on the VEA
+    /// index.
+    ///
+    /// ```rust,ignore
+    /// find!(at: Value<GeoPoint>,
+    ///     and!(
+    ///         pattern!(&data, [{ ?id @ attr: ?at }]),
+    ///         data.geo_within(at, GeoRegion::Radius { lat, lon, meters: 5_000.0 }),
+    ///     )
+    /// )
+    /// ```
+    pub fn geo_within(
+        &self,
+        variable: Variable<GeoPoint>,
+        region: GeoRegion,
+    ) -> triblesetgeoconstraint::GeoRegionConstraint {
+        triblesetgeoconstraint::GeoRegionConstraint::new(variable, region, self.clone())
+    }
+
     /// Creates a constraint that proposes only entity IDs in the byte range
     /// `[min, max]` (inclusive) using the EAV index with `infixes_range`.
     ///
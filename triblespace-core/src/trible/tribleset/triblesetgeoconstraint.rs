@@ -0,0 +1,180 @@
+use crate::query::Binding;
+use crate::query::Constraint;
+use crate::query::Variable;
+use crate::query::VariableId;
+use crate::query::VariableSet;
+use crate::trible::TribleSet;
+use crate::value::schemas::geopoint::GeoPoint;
+use crate::value::schemas::geopoint::GeoRegion;
+use crate::value::RawValue;
+use crate::value::Value;
+use crate::value::VALUE_LEN;
+
+/// A constraint that proposes the [`GeoPoint`] values of a TribleSet lying
+/// in a [`GeoRegion`].
+///
+/// The region is decomposed into Z-order value ranges
+/// ([`GeoRegion::ranges`]); proposing scans each range on the VEA index like
+/// [`TribleSetRangeConstraint`](super::triblesetrangeconstraint::TribleSetRangeConstraint)
+/// does, then drops the points the ranges over-approximate.
+///
+/// Create via [`TribleSet::geo_within`]:
+///
+/// ```rust,ignore
+/// let berlin = GeoRegion::Radius { lat: 52.52, lon: 13.405, meters: 25_000.0 };
+/// find!((id: Id, at: Value<GeoPoint>),
+///     and!(
+///         pattern!(&data, [{ ?id @ location: ?at }]),
+///         data.geo_within(at, berlin),
+///     )
+/// )
+/// ```
+pub struct GeoRegionConstraint {
+    variable_v: VariableId,
+    region: GeoRegion,
+    ranges: Vec<(RawValue, RawValue)>,
+    set: TribleSet,
+    // As for range constraints, the ranges and the set are fixed for the
+    // lifetime of the query, so the estimate is computed once.
+    cached_estimate: usize,
+}
+
+impl GeoRegionConstraint {
+    pub fn new(variable_v: Variable<GeoPoint>, region: GeoRegion, set: TribleSet) -> Self {
+        let ranges: Vec<(RawValue, RawValue)> = region
+            .ranges()
+            .into_iter()
+            .map(|(min, max)| (min.raw, max.raw))
+            .collect();
+        let cached_estimate = ranges
+            .iter()
+            .map(|(min, max)| set.vea.count_range::<0, VALUE_LEN>(&[0u8; 0], min, max))
+            .sum::<u64>()
+            .min(usize::MAX as u64) as usize;
+        GeoRegionConstraint {
+            variable_v: variable_v.index,
+            region,
+            ranges,
+            set,
+            cached_estimate,
+        }
+    }
+
+    fn matches(&self, raw: &RawValue) -> bool {
+        Value::<GeoPoint>::new(*raw)
+            .try_from_value::<(f64, f64)>()
+            .is_ok_and(|point| self.region.contains(point))
+    }
+}
+
+impl<'a> Constraint<'a> for GeoRegionConstraint {
+    fn variables(&self) -> VariableSet {
+        VariableSet::new_singleton(self.variable_v)
+    }
+
+    fn estimate(&self, variable: VariableId, _binding: &Binding) -> Option<usize> {
+        if variable != self.variable_v {
+            return None;
+        }
+        Some(self.cached_estimate)
+    }
+
+    fn propose(&self, variable: VariableId, _binding: &Binding, proposals: &mut Vec<RawValue>) {
+        if variable != self.variable_v {
+            return;
+        }
+        for (min, max) in &self.ranges {
+            self.set
+                .vea
+                .infixes_range::<0, VALUE_LEN, _>(&[0u8; 0], min, max, |v| {
+                    if self.matches(v) {
+                        proposals.push(*v);
+                    }
+                });
+        }
+    }
+
+    fn confirm(&self, variable: VariableId, _binding: &Binding, proposals: &mut Vec<RawValue>) {
+        if variable == self.variable_v {
+            proposals.retain(|v| self.matches(v));
+        }
+    }
+
+    fn satisfied(&self, binding: &Binding) -> bool {
+        match binding.get(self.variable_v) {
+            Some(v) => self.matches(v),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::valueschemas::GeoPoint;
+    use crate::prelude::*;
+    use crate::value::schemas::geopoint::GeoRegion;
+
+    attributes! {
+        "DE911CB72A33DD10C51F4E9FB5E5EEFB" as geo_test_location: GeoPoint;
+    }
+
+    #[test]
+    fn geo_within_matches_exact_region() {
+        let places = [
+            ("berlin", (52.5200, 13.4050)),
+            ("potsdam", (52.3906, 13.0645)),
+            ("hamburg", (53.5511, 9.9937)),
+            ("fiji", (-17.7134, 178.0650)),
+            ("samoa", (-13.7590, -172.1046)),
+        ];
+        let mut data = TribleSet::new();
+        let mut names = std::collections::HashMap::new();
+        for (name, at) in places {
+            let e = ufoid();
+            names.insert(*e, name);
+            data += entity! { &e @ geo_test_location: at };
+        }
+        let search = |region: GeoRegion| {
+            let mut found: Vec<&str> = find!(
+                (e: Id, at: Value<GeoPoint>),
+                and!(
+                    pattern!(&data, [{ ?e @ geo_test_location: ?at }]),
+                    data.geo_within(at, region),
+                )
+            )
+            .map(|(e, _)| names[&e])
+            .collect();
+            found.sort();
+            found
+        };
+
+        let near_berlin = GeoRegion::Radius {
+            lat: 52.52,
+            lon: 13.405,
+            meters: 30_000.0,
+        };
+        assert_eq!(search(near_berlin), ["berlin", "potsdam"]);
+        let tight = GeoRegion::Radius {
+            lat: 52.52,
+            lon: 13.405,
+            meters: 20_000.0,
+        };
+        assert_eq!(search(tight), ["berlin"]);
+
+        let germany = GeoRegion::BoundingBox {
+            south: 47.0,
+            west: 5.0,
+            north: 55.0,
+            east: 15.0,
+        };
+        assert_eq!(search(germany), ["berlin", "hamburg", "potsdam"]);
+
+        let across_antimeridian = GeoRegion::BoundingBox {
+            south: -20.0,
+            west: 170.0,
+            north: -10.0,
+            east: -170.0,
+        };
+        assert_eq!(search(across_antimeridian), ["fiji", "samoa"]);
+    }
+}
@@ -2,6 +2,10 @@
 
 /// Boolean value schema (all-zero / all-one encoding).
 pub mod boolean;
+/// Civil calendar date schema and UTC offset conversions.
+pub mod calendar;
+/// Signed nanosecond duration schema.
+pub mod duration;
 /// Ed25519 signature component and public key schemas.
 pub mod ed25519;
 /// 256-bit IEEE-like floating point schemas (little-endian and big-endian).
@@ -10,12 +14,16 @@ pub mod f256;
 pub mod f64;
 /// Opaque 128-bit identifier schema.
 pub mod genid;
+/// Latitude/longitude schema ordered along a Z-order curve.
+pub mod geopoint;
 /// Cryptographic hash and typed blob handle schemas.
 pub mod hash;
 /// 256-bit signed and unsigned integer schemas (little-endian and big-endian).
 pub mod iu256;
 /// Line/column source location schema.
 pub mod linelocation;
+/// IP address, socket address and MAC address schemas.
+pub mod net;
 /// 256-bit rational number schemas (little-endian and big-endian).
 pub mod r256;
 /// Range schemas for pairs of `u128` values.
@@ -24,6 +32,8 @@ pub mod range;
 pub mod shortstring;
 /// TAI nanosecond interval schema.
 pub mod time;
+/// RFC 4122 UUID schema.
+pub mod uuid;
 
 use crate::id::ExclusiveId;
 use crate::id::Id;
@@ -0,0 +1,402 @@
+use crate::id::ExclusiveId;
+use crate::id::Id;
+use crate::id_hex;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::repo::BlobStore;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::Blake3;
+use crate::value::schemas::time::NsTAIInterval;
+use crate::value::ToValue;
+use crate::value::TryFromValue;
+use crate::value::TryToValue;
+use crate::value::Value;
+use crate::value::ValueSchema;
+
+use std::convert::TryInto;
+use std::str::FromStr;
+
+use hifitime::Epoch;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const SIGN_BIT: u64 = 1 << 63;
+
+/// Error raised for dates that do not exist in the proleptic Gregorian
+/// calendar, for times of day out of range, and for values whose layout does
+/// not match [`LocalDate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidDate;
+
+impl std::fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not a valid calendar date or time of day")
+    }
+}
+
+impl std::error::Error for InvalidDate {}
+
+/// A civil date as `(year, month, day)`.
+pub type CivilDate = (i32, u8, u8);
+
+/// A wall-clock time as `(hour, minute, second, nanosecond)`.
+pub type WallTime = (u8, u8, u8, u32);
+
+/// A value schema for a civil calendar date without a time zone.
+///
+/// The date is stored as the number of days since 1970-01-01 in the
+/// proleptic Gregorian calendar, as an order-preserving big-endian `i64` in
+/// the last 8 bytes; the rest is zero. Dates sort chronologically. Convert
+/// from and to `(year, month, day)` tuples, and use
+/// [`UtcOffset::day_interval`] to find the TAI interval a date covers in a
+/// particular time zone.
+pub struct LocalDate;
+
+impl ConstId for LocalDate {
+    const ID: Id = id_hex!("DA647130CC5D69AFA8A5CAFFE9DA5CE7");
+}
+
+impl ConstDescribe for LocalDate {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "Civil calendar date without a time zone: days since 1970-01-01 in the proleptic Gregorian calendar, stored as an i64 XOR'd with i64::MIN, big-endian, in the last 8 bytes. The other 24 bytes are zero and dates sort chronologically.\n\nUse for birthdays, due dates, holidays and other days that do not name an instant. Use NsTAIInterval when the exact span of time matters; a date covers a different interval in every time zone.\n\nYears follow ISO 8601: year 0 is 1 BC and earlier years are negative.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("local_date")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::LOCAL_DATE_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm_formatter {
+    use core::fmt::Write;
+
+    use triblespace_core_macros::value_formatter;
+
+    #[value_formatter]
+    pub(crate) fn local_date(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..24].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&raw[24..]);
+        let days = (u64::from_be_bytes(bytes) ^ (1 << 63)) as i64;
+
+        // Howard Hinnant's civil_from_days, see `super::civil_from_days`.
+        let z = days as i128 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i128::from(month <= 2);
+
+        if (0..=9999).contains(&year) {
+            write!(out, "{year:04}-{month:02}-{day:02}")
+        } else {
+            write!(out, "{year:+05}-{month:02}-{day:02}")
+        }
+        .map_err(|_| 1u32)?;
+        Ok(())
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+/// (Howard Hinnant's `days_from_civil`).
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The proleptic Gregorian date `days` after 1970-01-01, if its year fits
+/// an `i32` (Howard Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> Option<(i32, u8, u8)> {
+    let z = i128::from(days) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let year = yoe + era * 400 + i128::from(month <= 2);
+    Some((year.try_into().ok()?, month, day))
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn check_date(year: i32, month: u8, day: u8) -> Result<(), InvalidDate> {
+    if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+        Ok(())
+    } else {
+        Err(InvalidDate)
+    }
+}
+
+impl ValueSchema for LocalDate {
+    type ValidationError = InvalidDate;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        <(i32, u8, u8)>::try_from_value(&value)?;
+        Ok(value)
+    }
+}
+
+/// `(year, month, day)`, with months and days counted from 1.
+impl TryToValue<LocalDate> for (i32, u8, u8) {
+    type Error = InvalidDate;
+
+    fn try_to_value(self) -> Result<Value<LocalDate>, InvalidDate> {
+        let (year, month, day) = self;
+        check_date(year, month, day)?;
+        let days = days_from_civil(year, month, day);
+        let mut raw = [0u8; 32];
+        raw[24..].copy_from_slice(&((days as u64) ^ SIGN_BIT).to_be_bytes());
+        Ok(Value::new(raw))
+    }
+}
+
+/// Panics for dates that do not exist; use [`TryToValue`] to handle them.
+impl ToValue<LocalDate> for (i32, u8, u8) {
+    fn to_value(self) -> Value<LocalDate> {
+        self.try_to_value().expect("invalid calendar date")
+    }
+}
+
+impl TryFromValue<'_, LocalDate> for (i32, u8, u8) {
+    type Error = InvalidDate;
+
+    fn try_from_value(v: &Value<LocalDate>) -> Result<Self, InvalidDate> {
+        if v.raw[..24].iter().any(|&b| b != 0) {
+            return Err(InvalidDate);
+        }
+        let days = (u64::from_be_bytes(v.raw[24..].try_into().unwrap()) ^ SIGN_BIT) as i64;
+        civil_from_days(days).ok_or(InvalidDate)
+    }
+}
+
+/// Error returned when parsing a [`UtcOffset`] or building one out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidOffset;
+
+impl std::fmt::Display for InvalidOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expected a UTC offset like Z, +02:00 or -0530 within ±24 hours")
+    }
+}
+
+impl std::error::Error for InvalidOffset {}
+
+/// A fixed offset of local wall-clock time from UTC.
+///
+/// Converts between civil date and time in a zone and TAI [`Epoch`]s,
+/// applying the TAI−UTC leap-second offset through [`hifitime`]. The
+/// reading `23:59:60` during an inserted leap second is not representable:
+/// [`to_epoch`](Self::to_epoch) rejects second 60 and
+/// [`from_epoch`](Self::from_epoch) never yields it. Offsets are fixed: to
+/// follow daylight-saving rules, look up the offset a named zone has at the
+/// instant in question (for example from the IANA database) and use that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UtcOffset {
+    seconds: i32,
+}
+
+impl UtcOffset {
+    /// Coordinated Universal Time.
+    pub const UTC: UtcOffset = UtcOffset { seconds: 0 };
+
+    /// An offset of `seconds` east of UTC, if it is shorter than a day.
+    pub fn from_seconds(seconds: i32) -> Result<Self, InvalidOffset> {
+        if seconds.unsigned_abs() < SECONDS_PER_DAY as u32 {
+            Ok(UtcOffset { seconds })
+        } else {
+            Err(InvalidOffset)
+        }
+    }
+
+    /// Seconds east of UTC.
+    pub fn seconds(self) -> i32 {
+        self.seconds
+    }
+
+    /// The instant at which the wall clock in this zone shows `date` and
+    /// `time`.
+    pub fn to_epoch(self, date: CivilDate, time: WallTime) -> Result<Epoch, InvalidDate> {
+        let (year, month, day) = date;
+        let (hour, minute, second, nanos) = time;
+        check_date(year, month, day)?;
+        if hour > 23 || minute > 59 || second > 59 || nanos > 999_999_999 {
+            return Err(InvalidDate);
+        }
+        let local = days_from_civil(year, month, day) * SECONDS_PER_DAY
+            + i64::from(hour) * 3_600
+            + i64::from(minute) * 60
+            + i64::from(second);
+        // Shift the wall clock to UTC before handing it to hifitime so that
+        // leap seconds between the two readings are accounted for.
+        let utc = local - i64::from(self.seconds);
+        let (year, month, day) = civil_from_days(utc.div_euclid(SECONDS_PER_DAY)).ok_or(InvalidDate)?;
+        let secs = utc.rem_euclid(SECONDS_PER_DAY);
+        Epoch::maybe_from_gregorian_utc(
+            year,
+            month,
+            day,
+            (secs / 3_600) as u8,
+            (secs % 3_600 / 60) as u8,
+            (secs % 60) as u8,
+            nanos,
+        )
+        .map_err(|_| InvalidDate)
+    }
+
+    /// The wall-clock date and time this zone shows at `epoch`.
+    pub fn from_epoch(self, epoch: Epoch) -> (CivilDate, WallTime) {
+        let (year, month, day, hour, minute, second, nanos) = epoch.to_gregorian_utc();
+        let utc = days_from_civil(year, month, day) * SECONDS_PER_DAY
+            + i64::from(hour) * 3_600
+            + i64::from(minute) * 60
+            + i64::from(second);
+        let local = utc + i64::from(self.seconds);
+        let date = civil_from_days(local.div_euclid(SECONDS_PER_DAY))
+            .expect("hifitime epochs stay within i32 years");
+        let secs = local.rem_euclid(SECONDS_PER_DAY);
+        (
+            date,
+            (
+                (secs / 3_600) as u8,
+                (secs % 3_600 / 60) as u8,
+                (secs % 60) as u8,
+                nanos,
+            ),
+        )
+    }
+
+    /// The inclusive TAI interval `date` spans in this zone, from midnight
+    /// to the last nanosecond before the next midnight.
+    pub fn day_interval(self, date: CivilDate) -> Result<Value<NsTAIInterval>, InvalidDate> {
+        let start = self.to_epoch(date, (0, 0, 0, 0))?;
+        let next = civil_from_days(days_from_civil(date.0, date.1, date.2) + 1).ok_or(InvalidDate)?;
+        let end = self.to_epoch(next, (0, 0, 0, 0))? - hifitime::Duration::from_total_nanoseconds(1);
+        (start, end).try_to_value().map_err(|_| InvalidDate)
+    }
+}
+
+impl std::fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.seconds < 0 { '-' } else { '+' };
+        let abs = self.seconds.unsigned_abs();
+        write!(f, "{sign}{:02}:{:02}", abs / 3_600, abs % 3_600 / 60)?;
+        if !abs.is_multiple_of(60) {
+            write!(f, ":{:02}", abs % 60)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `Z`, `+HH`, `+HH:MM`, `+HHMM` and `+HH:MM:SS` (or `-`).
+impl FromStr for UtcOffset {
+    type Err = InvalidOffset;
+
+    fn from_str(s: &str) -> Result<Self, InvalidOffset> {
+        if s.eq_ignore_ascii_case("z") {
+            return Ok(UtcOffset::UTC);
+        }
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'+') => (1, &s[1..]),
+            Some(b'-') => (-1, &s[1..]),
+            _ => return Err(InvalidOffset),
+        };
+        let digits: String = rest.chars().filter(|&c| c != ':').collect();
+        if !digits.bytes().all(|b| b.is_ascii_digit()) || ![2, 4, 6].contains(&digits.len()) {
+            return Err(InvalidOffset);
+        }
+        let part = |i: usize| -> i32 { digits.get(i..i + 2).map_or(0, |p| p.parse().unwrap()) };
+        let (hours, minutes, seconds) = (part(0), part(2), part(4));
+        if minutes > 59 || seconds > 59 {
+            return Err(InvalidOffset);
+        }
+        UtcOffset::from_seconds(sign * (hours * 3_600 + minutes * 60 + seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::schemas::time::Lower;
+
+    #[test]
+    fn dates_round_trip_and_sort() {
+        let dates = [(-1, 12, 31), (0, 2, 29), (1969, 12, 31), (1970, 1, 1), (2024, 2, 29), (9999, 12, 31)];
+        for pair in dates.windows(2) {
+            let (a, b): (Value<LocalDate>, Value<LocalDate>) = (pair[0].to_value(), pair[1].to_value());
+            assert!(a.raw < b.raw, "{:?} < {:?}", pair[0], pair[1]);
+        }
+        for date in dates {
+            let value: Value<LocalDate> = date.to_value();
+            assert_eq!(value.try_from_value::<(i32, u8, u8)>(), Ok(date));
+        }
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(TryToValue::<LocalDate>::try_to_value((2023, 2, 29)), Err(InvalidDate));
+        assert_eq!(TryToValue::<LocalDate>::try_to_value((2024, 13, 1)), Err(InvalidDate));
+    }
+
+    #[test]
+    fn offsets_convert_wall_clock_to_tai() {
+        let berlin_summer: UtcOffset = "+02:00".parse().unwrap();
+        let new_york: UtcOffset = "-0500".parse().unwrap();
+        assert_eq!(berlin_summer.to_string(), "+02:00");
+        assert_eq!("Z".parse::<UtcOffset>(), Ok(UtcOffset::UTC));
+        assert!("+2:00".parse::<UtcOffset>().is_err());
+        assert!("+24:00".parse::<UtcOffset>().is_err());
+
+        let meeting = berlin_summer.to_epoch((2024, 6, 1), (9, 30, 0, 0)).unwrap();
+        assert_eq!(meeting, Epoch::from_gregorian_utc_hms(2024, 6, 1, 7, 30, 0));
+        assert_eq!(new_york.from_epoch(meeting), ((2024, 6, 1), (2, 30, 0, 0)));
+        let late = new_york.to_epoch((2024, 5, 31), (22, 0, 0, 0)).unwrap();
+        assert_eq!(late, Epoch::from_gregorian_utc_hms(2024, 6, 1, 3, 0, 0));
+
+        // A local day runs from local midnight to the next one.
+        let interval = berlin_summer.day_interval((2024, 6, 1)).unwrap();
+        let (lower, upper): (i128, i128) = interval.try_from_value().unwrap();
+        assert_eq!(upper - lower + 1, 86_400 * 1_000_000_000);
+        let start: Lower = interval.from_value();
+        assert_eq!(
+            start.0,
+            Epoch::from_gregorian_utc_hms(2024, 5, 31, 22, 0, 0)
+                .to_tai_duration()
+                .total_nanoseconds()
+        );
+    }
+}
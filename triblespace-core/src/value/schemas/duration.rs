@@ -0,0 +1,227 @@
+use crate::id::ExclusiveId;
+use crate::id::Id;
+use crate::id_hex;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::repo::BlobStore;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::Blake3;
+use crate::value::schemas::time::{i128_from_ordered_be, i128_to_ordered_be};
+use crate::value::ToValue;
+use crate::value::TryFromValue;
+use crate::value::Value;
+use crate::value::ValueSchema;
+
+use std::convert::Infallible;
+use std::convert::TryInto;
+
+/// Error raised when a value does not match the [`NsDuration`] layout, or
+/// when a duration does not fit the requested Rust type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The upper 16 bytes are not zero.
+    InvalidLayout,
+    /// The duration is negative or too long for [`std::time::Duration`].
+    OutOfRange(i128),
+}
+
+impl std::fmt::Display for DurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurationError::InvalidLayout => f.write_str("duration value has non-zero upper bytes"),
+            DurationError::OutOfRange(nanos) => {
+                write!(f, "duration of {nanos} ns does not fit std::time::Duration")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+/// A value schema for a signed, relative time span in nanoseconds.
+///
+/// The span is an `i128` nanosecond count stored in the lower 16 bytes with
+/// the same order-preserving big-endian encoding as
+/// [`NsTAIInterval`](super::time::NsTAIInterval) bounds; the upper 16 bytes
+/// are zero. Byte order matches numeric order, so durations support range
+/// queries.
+pub struct NsDuration;
+
+impl ConstId for NsDuration {
+    const ID: Id = id_hex!("C1DB7BE3219879290B8C11A93A5E4123");
+}
+
+impl ConstDescribe for NsDuration {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "Signed time span in nanoseconds. The i128 count is XOR'd with i128::MIN and stored big-endian in the lower 16 bytes; the upper 16 bytes are zero. Byte-lexicographic order matches numeric order, so durations can be range-queried.\n\nUse for relative quantities such as timeouts, run times, offsets and recurrence periods. Use NsTAIInterval for absolute spans anchored on the TAI timeline.\n\nA duration is a fixed number of nanoseconds; calendar units such as months or days across daylight-saving changes are not durations.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("ns_duration")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::NS_DURATION_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm_formatter {
+    use core::fmt::Write;
+
+    use triblespace_core_macros::value_formatter;
+
+    #[value_formatter]
+    pub(crate) fn ns_duration(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..16].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&raw[16..]);
+        let nanos = (u128::from_be_bytes(bytes) ^ (1u128 << 127)) as i128;
+        if nanos < 0 {
+            out.write_char('-').map_err(|_| 1u32)?;
+        }
+        let nanos = nanos.unsigned_abs();
+        let (secs, frac) = (nanos / 1_000_000_000, nanos % 1_000_000_000);
+        let (days, hours) = (secs / 86_400, secs % 86_400 / 3_600);
+        let (minutes, seconds) = (secs % 3_600 / 60, secs % 60);
+        let mut written = false;
+        for (amount, unit) in [(days, 'd'), (hours, 'h'), (minutes, 'm')] {
+            if amount > 0 {
+                if written {
+                    out.write_char(' ').map_err(|_| 1u32)?;
+                }
+                write!(out, "{amount}{unit}").map_err(|_| 1u32)?;
+                written = true;
+            }
+        }
+        if seconds > 0 || frac > 0 || !written {
+            if written {
+                out.write_char(' ').map_err(|_| 1u32)?;
+            }
+            write!(out, "{seconds}").map_err(|_| 1u32)?;
+            if frac > 0 {
+                let mut digits = [0u8; 9];
+                let mut rest = frac;
+                for digit in digits.iter_mut().rev() {
+                    *digit = b'0' + (rest % 10) as u8;
+                    rest /= 10;
+                }
+                let len = 9 - digits.iter().rev().take_while(|&&d| d == b'0').count();
+                out.write_char('.').map_err(|_| 1u32)?;
+                for &digit in &digits[..len] {
+                    out.write_char(digit as char).map_err(|_| 1u32)?;
+                }
+            }
+            out.write_char('s').map_err(|_| 1u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl ValueSchema for NsDuration {
+    type ValidationError = DurationError;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        if value.raw[..16] == [0; 16] {
+            Ok(value)
+        } else {
+            Err(DurationError::InvalidLayout)
+        }
+    }
+}
+
+impl ToValue<NsDuration> for i128 {
+    fn to_value(self) -> Value<NsDuration> {
+        let mut raw = [0; 32];
+        raw[16..].copy_from_slice(&i128_to_ordered_be(self));
+        Value::new(raw)
+    }
+}
+
+impl ToValue<NsDuration> for hifitime::Duration {
+    fn to_value(self) -> Value<NsDuration> {
+        self.total_nanoseconds().to_value()
+    }
+}
+
+impl ToValue<NsDuration> for std::time::Duration {
+    fn to_value(self) -> Value<NsDuration> {
+        // `as_nanos` is below 2^94 and always fits an i128.
+        (self.as_nanos() as i128).to_value()
+    }
+}
+
+impl TryFromValue<'_, NsDuration> for i128 {
+    type Error = Infallible;
+
+    fn try_from_value(v: &Value<NsDuration>) -> Result<Self, Infallible> {
+        Ok(i128_from_ordered_be(v.raw[16..].try_into().unwrap()))
+    }
+}
+
+impl TryFromValue<'_, NsDuration> for hifitime::Duration {
+    type Error = Infallible;
+
+    fn try_from_value(v: &Value<NsDuration>) -> Result<Self, Infallible> {
+        let nanos: i128 = v.from_value();
+        Ok(hifitime::Duration::from_total_nanoseconds(nanos))
+    }
+}
+
+impl TryFromValue<'_, NsDuration> for std::time::Duration {
+    type Error = DurationError;
+
+    fn try_from_value(v: &Value<NsDuration>) -> Result<Self, DurationError> {
+        let nanos: i128 = v.from_value();
+        let secs = u64::try_from(nanos.div_euclid(1_000_000_000))
+            .map_err(|_| DurationError::OutOfRange(nanos))?;
+        Ok(std::time::Duration::new(
+            secs,
+            nanos.rem_euclid(1_000_000_000) as u32,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_and_orders_numerically() {
+        let spans: [i128; 5] = [-90_000_000_000, -1, 0, 1_500_000_000, 86_400_000_000_000];
+        for pair in spans.windows(2) {
+            let (a, b): (Value<NsDuration>, Value<NsDuration>) =
+                (pair[0].to_value(), pair[1].to_value());
+            assert!(a.raw < b.raw);
+        }
+
+        let std = std::time::Duration::from_millis(1_500);
+        let value: Value<NsDuration> = std.to_value();
+        assert_eq!(value.try_from_value::<std::time::Duration>(), Ok(std));
+        let hifi: hifitime::Duration = value.from_value();
+        assert_eq!(hifi, hifitime::Duration::from_milliseconds(1_500.0));
+
+        let negative: Value<NsDuration> = (-1i128).to_value();
+        assert_eq!(
+            negative.try_from_value::<std::time::Duration>(),
+            Err(DurationError::OutOfRange(-1))
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use crate::id::ExclusiveId;
+use crate::id::Id;
+use crate::id_hex;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::repo::BlobStore;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::Blake3;
+use crate::value::ToValue;
+use crate::value::TryFromValue;
+use crate::value::TryToValue;
+use crate::value::Value;
+use crate::value::ValueSchema;
+
+use std::convert::TryInto;
+
+/// Mean earth radius in meters, used for great-circle distances.
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// Upper bound on the cells one rectangle is decomposed into before the
+/// remaining partially covered cells are kept whole.
+const MAX_CELLS: usize = 64;
+
+/// Error raised for coordinates outside latitude `[-90, 90]` and longitude
+/// `[-180, 180]` degrees, and for values whose layout does not match
+/// [`GeoPoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidGeoPoint;
+
+impl std::fmt::Display for InvalidGeoPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("latitude/longitude out of range or not a geo point value")
+    }
+}
+
+impl std::error::Error for InvalidGeoPoint {}
+
+/// A value schema for a point on the earth as `(latitude, longitude)` in
+/// degrees.
+///
+/// Layout: eight zero bytes, a 64-bit Z-order (Morton) code interleaving the
+/// latitude and longitude quantized to 32 bits each, then the exact latitude
+/// and longitude as big-endian `f64` bits. Values therefore sort along the
+/// Z-order curve, so nearby points mostly share prefixes and a
+/// [`GeoRegion`] decomposes into a handful of contiguous value ranges.
+pub struct GeoPoint;
+
+impl ConstId for GeoPoint {
+    const ID: Id = id_hex!("482A7E0A431A92EDC271297452740917");
+}
+
+impl ConstDescribe for GeoPoint {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "Latitude and longitude in degrees. Bytes 0..8 are zero, bytes 8..16 hold a big-endian Z-order code interleaving both coordinates quantized to 32 bits, and bytes 16..32 hold the exact latitude and longitude as big-endian f64 bits.\n\nUse for locations that should be searched by bounding box or radius: values sort along the Z-order curve, so a region becomes a few contiguous ranges of the value index. Keep two F64 attributes only if you never search by area.\n\nLatitude must lie in [-90, 90] and longitude in [-180, 180]; the Z-order code must match the coordinates. Negative zero is stored as zero.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("geo_point")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::GEO_POINT_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm_formatter {
+    use core::fmt::Write;
+
+    use triblespace_core_macros::value_formatter;
+
+    #[value_formatter]
+    pub(crate) fn geo_point(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..8].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let mut lat = [0u8; 8];
+        lat.copy_from_slice(&raw[16..24]);
+        let mut lon = [0u8; 8];
+        lon.copy_from_slice(&raw[24..32]);
+        let lat = f64::from_be_bytes(lat);
+        let lon = f64::from_be_bytes(lon);
+        write!(out, "{lat},{lon}").map_err(|_| 1u32)?;
+        Ok(())
+    }
+}
+
+impl ValueSchema for GeoPoint {
+    type ValidationError = InvalidGeoPoint;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        let (lat, lon) = <(f64, f64)>::try_from_value(&value)?;
+        if morton(lat, lon).to_be_bytes() != value.raw[8..16] {
+            return Err(InvalidGeoPoint);
+        }
+        Ok(value)
+    }
+}
+
+fn in_range(lat: f64, lon: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
+}
+
+/// `(latitude, longitude)` in degrees.
+impl TryToValue<GeoPoint> for (f64, f64) {
+    type Error = InvalidGeoPoint;
+
+    fn try_to_value(self) -> Result<Value<GeoPoint>, Self::Error> {
+        let (lat, lon) = self;
+        if !in_range(lat, lon) {
+            return Err(InvalidGeoPoint);
+        }
+        // Adding zero turns -0.0 into 0.0 so equal points have equal values.
+        let (lat, lon) = (lat + 0.0, lon + 0.0);
+        let mut raw = [0u8; 32];
+        raw[8..16].copy_from_slice(&morton(lat, lon).to_be_bytes());
+        raw[16..24].copy_from_slice(&lat.to_be_bytes());
+        raw[24..32].copy_from_slice(&lon.to_be_bytes());
+        Ok(Value::new(raw))
+    }
+}
+
+/// Panics for coordinates out of range; use [`TryToValue`] to handle them.
+impl ToValue<GeoPoint> for (f64, f64) {
+    fn to_value(self) -> Value<GeoPoint> {
+        self.try_to_value().expect("latitude/longitude out of range")
+    }
+}
+
+impl TryFromValue<'_, GeoPoint> for (f64, f64) {
+    type Error = InvalidGeoPoint;
+
+    fn try_from_value(v: &Value<GeoPoint>) -> Result<Self, Self::Error> {
+        if v.raw[..8] != [0; 8] {
+            return Err(InvalidGeoPoint);
+        }
+        let lat = f64::from_be_bytes(v.raw[16..24].try_into().unwrap());
+        let lon = f64::from_be_bytes(v.raw[24..32].try_into().unwrap());
+        if !in_range(lat, lon) {
+            return Err(InvalidGeoPoint);
+        }
+        Ok((lat, lon))
+    }
+}
+
+/// Great-circle (haversine) distance in meters between two
+/// `(latitude, longitude)` points in degrees.
+pub fn great_circle_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat_a, lat_b) = (a.0.to_radians(), b.0.to_radians());
+    let dlat = lat_b - lat_a;
+    let dlon = (b.1 - a.1).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * h.sqrt().min(1.0).asin()
+}
+
+/// An area searched by [`TribleSet::geo_within`](crate::trible::TribleSet::geo_within).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoRegion {
+    /// Points with `south <= lat <= north` and longitude between `west` and
+    /// `east`. A box with `west > east` crosses the antimeridian.
+    BoundingBox {
+        /// Southern latitude bound in degrees.
+        south: f64,
+        /// Western longitude bound in degrees.
+        west: f64,
+        /// Northern latitude bound in degrees.
+        north: f64,
+        /// Eastern longitude bound in degrees.
+        east: f64,
+    },
+    /// Points at most `meters` away from `(lat, lon)` along the great circle.
+    Radius {
+        /// Latitude of the center in degrees.
+        lat: f64,
+        /// Longitude of the center in degrees.
+        lon: f64,
+        /// Search radius in meters.
+        meters: f64,
+    },
+}
+
+impl GeoRegion {
+    /// Whether the `(latitude, longitude)` point lies in the region.
+    pub fn contains(&self, point: (f64, f64)) -> bool {
+        let (lat, lon) = point;
+        match *self {
+            GeoRegion::BoundingBox {
+                south,
+                west,
+                north,
+                east,
+            } => {
+                let lon_in = if west <= east {
+                    west <= lon && lon <= east
+                } else {
+                    west <= lon || lon <= east
+                };
+                south <= lat && lat <= north && lon_in
+            }
+            GeoRegion::Radius {
+                lat: center_lat,
+                lon: center_lon,
+                meters,
+            } => great_circle_distance((center_lat, center_lon), point) <= meters,
+        }
+    }
+
+    /// Sorted, disjoint value ranges whose union covers every point of the
+    /// region. The ranges over-approximate at the edges, so matches still
+    /// have to be checked with [`GeoRegion::contains`].
+    pub fn ranges(&self) -> Vec<(Value<GeoPoint>, Value<GeoPoint>)> {
+        let mut cells = Vec::new();
+        for (south, west, north, east) in self.rectangles() {
+            cover(
+                (quantize_lat(south), quantize_lat(north)),
+                (quantize_lon(west), quantize_lon(east)),
+                &mut cells,
+            );
+        }
+        cells.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(cells.len());
+        for (lo, hi) in cells {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1.saturating_add(1) => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        merged
+            .into_iter()
+            .map(|(lo, hi)| {
+                let mut min = [0u8; 32];
+                min[8..16].copy_from_slice(&lo.to_be_bytes());
+                let mut max = [u8::MAX; 32];
+                max[..8].fill(0);
+                max[8..16].copy_from_slice(&hi.to_be_bytes());
+                (Value::new(min), Value::new(max))
+            })
+            .collect()
+    }
+
+    /// `(south, west, north, east)` rectangles with `west <= east` covering
+    /// the region.
+    fn rectangles(&self) -> Vec<(f64, f64, f64, f64)> {
+        let (south, west, north, east) = match *self {
+            GeoRegion::BoundingBox {
+                south,
+                west,
+                north,
+                east,
+            } => (south, west, north, east),
+            GeoRegion::Radius { lat, lon, meters } => {
+                let angle = meters / EARTH_RADIUS_METERS;
+                let dlat = angle.to_degrees();
+                let (south, north) = (lat - dlat, lat + dlat);
+                if south <= -90.0 || north >= 90.0 {
+                    // The circle contains a pole and so every longitude.
+                    (south.max(-90.0), -180.0, north.min(90.0), 180.0)
+                } else {
+                    let ratio = angle.sin() / lat.to_radians().cos();
+                    if ratio >= 1.0 {
+                        (south, -180.0, north, 180.0)
+                    } else {
+                        let dlon = ratio.asin().to_degrees();
+                        let wrap = |lon: f64| {
+                            if lon < -180.0 {
+                                lon + 360.0
+                            } else if lon > 180.0 {
+                                lon - 360.0
+                            } else {
+                                lon
+                            }
+                        };
+                        (south, wrap(lon - dlon), north, wrap(lon + dlon))
+                    }
+                }
+            }
+        };
+        if west <= east {
+            vec![(south, west, north, east)]
+        } else {
+            vec![(south, west, north, 180.0), (south, -180.0, north, east)]
+        }
+    }
+}
+
+fn quantize(degrees: f64, span: f64) -> u32 {
+    let scaled = (degrees / span + 0.5) * 4_294_967_296.0;
+    // Truncation floors the non-negative, clamped value.
+    scaled.clamp(0.0, u32::MAX as f64) as u32
+}
+
+fn quantize_lat(lat: f64) -> u32 {
+    quantize(lat, 180.0)
+}
+
+fn quantize_lon(lon: f64) -> u32 {
+    quantize(lon, 360.0)
+}
+
+/// Spreads the bits of `v` to the even bit positions.
+fn spread(v: u32) -> u64 {
+    let mut x = v as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+    x
+}
+
+/// Gathers the even bits of `x`; the inverse of [`spread`].
+fn compact(x: u64) -> u32 {
+    let mut x = x & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+    x as u32
+}
+
+/// Z-order code with the latitude bit above the longitude bit of each pair.
+fn morton(lat: f64, lon: f64) -> u64 {
+    (spread(quantize_lat(lat)) << 1) | spread(quantize_lon(lon))
+}
+
+/// The codes covered by the cell with `level`-pair prefix `code`.
+fn cell_codes(code: u64, level: u32) -> (u64, u64) {
+    let shift = 64 - 2 * level;
+    let lo = u128::from(code) << shift;
+    let hi = lo + (1u128 << shift) - 1;
+    (lo as u64, hi as u64)
+}
+
+/// Appends the code ranges of quadtree cells covering the quantized
+/// rectangle. Cells are refined until they lie fully inside the rectangle
+/// or the [`MAX_CELLS`] budget is spent.
+fn cover(lat: (u32, u32), lon: (u32, u32), out: &mut Vec<(u64, u64)>) {
+    let start = out.len();
+    let mut partial = vec![0u64];
+    let mut level = 0u32;
+    while level < 32 && !partial.is_empty() && out.len() - start + partial.len() * 4 <= MAX_CELLS
+    {
+        level += 1;
+        let size = 1u64 << (32 - level);
+        let mut next = Vec::new();
+        for code in partial {
+            for child in 0..4 {
+                let code = (code << 2) | child;
+                let lat_lo = u64::from(compact(code >> 1)) * size;
+                let lon_lo = u64::from(compact(code)) * size;
+                let (lat_hi, lon_hi) = (lat_lo + size - 1, lon_lo + size - 1);
+                let (lat_min, lat_max) = (u64::from(lat.0), u64::from(lat.1));
+                let (lon_min, lon_max) = (u64::from(lon.0), u64::from(lon.1));
+                if lat_hi < lat_min || lat_lo > lat_max || lon_hi < lon_min || lon_lo > lon_max {
+                    continue;
+                }
+                if lat_min <= lat_lo && lat_hi <= lat_max && lon_min <= lon_lo && lon_hi <= lon_max
+                {
+                    out.push(cell_codes(code, level));
+                } else {
+                    next.push(code);
+                }
+            }
+        }
+        partial = next;
+    }
+    out.extend(partial.into_iter().map(|code| cell_codes(code, level)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, lon: f64) -> Value<GeoPoint> {
+        (lat, lon).to_value()
+    }
+
+    #[test]
+    fn round_trips_and_validates() {
+        for (lat, lon) in [(52.52, 13.405), (-33.8688, 151.2093), (90.0, 180.0), (-90.0, -180.0)] {
+            let value = point(lat, lon);
+            assert_eq!(GeoPoint::validate(value), Ok(value));
+            assert_eq!(<(f64, f64)>::try_from_value(&value), Ok((lat, lon)));
+        }
+        assert_eq!(point(-0.0, -0.0), point(0.0, 0.0));
+        assert_eq!(
+            TryToValue::<GeoPoint>::try_to_value((91.0, 0.0)),
+            Err(InvalidGeoPoint)
+        );
+        assert_eq!(
+            TryToValue::<GeoPoint>::try_to_value((f64::NAN, 0.0)),
+            Err(InvalidGeoPoint)
+        );
+
+        let mut tampered = point(1.0, 2.0);
+        tampered.raw[15] ^= 1;
+        assert_eq!(GeoPoint::validate(tampered), Err(InvalidGeoPoint));
+    }
+
+    #[test]
+    fn morton_interleaves_latitude_above_longitude() {
+        assert_eq!(compact(spread(0xDEAD_BEEF)), 0xDEAD_BEEF);
+        assert_eq!(morton(-90.0, -180.0), 0);
+        assert_eq!(morton(90.0, 180.0), u64::MAX);
+        // The northern hemisphere sorts after the southern one.
+        assert!(morton(0.0, -180.0) > morton(-0.1, 180.0));
+    }
+
+    #[test]
+    fn ranges_cover_points_in_the_region() {
+        let regions = [
+            GeoRegion::BoundingBox {
+                south: 52.3,
+                west: 13.0,
+                north: 52.7,
+                east: 13.8,
+            },
+            GeoRegion::BoundingBox {
+                south: -20.0,
+                west: 170.0,
+                north: -10.0,
+                east: -170.0,
+            },
+            GeoRegion::Radius {
+                lat: 52.52,
+                lon: 13.405,
+                meters: 25_000.0,
+            },
+            GeoRegion::Radius {
+                lat: 89.9,
+                lon: 0.0,
+                meters: 50_000.0,
+            },
+        ];
+        for region in regions {
+            let ranges = region.ranges();
+            assert!(!ranges.is_empty() && ranges.len() <= 2 * MAX_CELLS);
+            assert!(ranges.windows(2).all(|w| w[0].1.raw < w[1].0.raw));
+            let mut inside = 0;
+            // Sample a grid a little larger than each covering rectangle.
+            for (south, west, north, east) in region.rectangles() {
+                let (south, north) = ((south - 1.0).max(-90.0), (north + 1.0).min(90.0));
+                let (west, east) = ((west - 1.0).max(-180.0), (east + 1.0).min(180.0));
+                for i in 0..=100 {
+                    for j in 0..=100 {
+                        let lat = south + (north - south) * f64::from(i) / 100.0;
+                        let lon = west + (east - west) * f64::from(j) / 100.0;
+                        if !region.contains((lat, lon)) {
+                            continue;
+                        }
+                        inside += 1;
+                        let raw = point(lat, lon).raw;
+                        assert!(
+                            ranges.iter().any(|(min, max)| min.raw <= raw && raw <= max.raw),
+                            "{region:?} misses ({lat}, {lon})"
+                        );
+                    }
+                }
+            }
+            assert!(inside > 0, "{region:?} has no sample points");
+        }
+    }
+
+    #[test]
+    fn great_circle_distance_matches_known_values() {
+        let berlin = (52.5200, 13.4050);
+        let paris = (48.8566, 2.3522);
+        let distance = great_circle_distance(berlin, paris);
+        assert!((distance - 877_500.0).abs() < 2_000.0, "{distance}");
+        assert_eq!(great_circle_distance(berlin, berlin), 0.0);
+    }
+}
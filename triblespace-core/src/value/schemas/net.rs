@@ -0,0 +1,644 @@
+use crate::id::ExclusiveId;
+use crate::id::Id;
+use crate::id_hex;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::query::rangeconstraint::ValueRange;
+use crate::query::Variable;
+use crate::repo::BlobStore;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::Blake3;
+use crate::value::ToValue;
+use crate::value::TryFromValue;
+use crate::value::TryToValue;
+use crate::value::Value;
+use crate::value::ValueSchema;
+
+use std::convert::TryInto;
+use std::net::IpAddr;
+
+/// Error raised when the padding bytes in front of an address are not zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidAddress;
+
+impl std::fmt::Display for InvalidAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("address value has non-zero padding bytes")
+    }
+}
+
+impl std::error::Error for InvalidAddress {}
+
+/// Error returned when a CIDR prefix is longer than the address it applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefixTooLong {
+    /// The requested prefix length in bits.
+    pub prefix: u8,
+    /// The number of bits in the address.
+    pub max: u8,
+}
+
+impl std::fmt::Display for PrefixTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "prefix /{} is longer than the {}-bit address",
+            self.prefix, self.max
+        )
+    }
+}
+
+impl std::error::Error for PrefixTooLong {}
+
+/// Error returned when parsing a MAC address from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacAddrParseError;
+
+impl std::fmt::Display for MacAddrParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expected six hex octets separated by ':' or '-'")
+    }
+}
+
+impl std::error::Error for MacAddrParseError {}
+
+/// A value schema for IPv4 addresses.
+///
+/// The four octets are stored big-endian in the last four bytes and the
+/// rest is zero, so byte order matches numeric address order and a CIDR
+/// block is a contiguous range (see [`Ipv4Addr::cidr`]).
+pub struct Ipv4Addr;
+
+impl ConstId for Ipv4Addr {
+    const ID: Id = id_hex!("2F0BBD953C7A8F829C8336E4399F6320");
+}
+
+/// A value schema for IPv6 addresses.
+///
+/// The sixteen octets are stored big-endian in the lower 16 bytes and the
+/// upper 16 bytes are zero. IPv4 addresses converted from
+/// [`std::net::IpAddr`] are stored IPv4-mapped (`::ffff:a.b.c.d`), so one
+/// attribute can hold both families and still be range-queried by prefix.
+pub struct Ipv6Addr;
+
+impl ConstId for Ipv6Addr {
+    const ID: Id = id_hex!("0910CB4B17D4CE4640D0AD2C9E560A3E");
+}
+
+/// A value schema for a socket address: an IP address plus port.
+///
+/// Layout (big-endian): six zero bytes, the IPv6 (or IPv4-mapped) address
+/// in bytes 6..22, the port in 22..24, the IPv6 flow info in 24..28 and the
+/// scope id in 28..32. Values sort by address, then port.
+pub struct SocketAddr;
+
+impl ConstId for SocketAddr {
+    const ID: Id = id_hex!("87839C5161C53677FA3E37762A9D4A63");
+}
+
+/// A value schema for 48-bit (EUI-48) MAC addresses.
+///
+/// The six octets are stored in the last six bytes and the rest is zero,
+/// so addresses sharing a vendor prefix (OUI) form a contiguous range.
+pub struct MacAddr;
+
+impl ConstId for MacAddr {
+    const ID: Id = id_hex!("19E5268AB6F52873B8073A402B6976CB");
+}
+
+impl ConstDescribe for Ipv4Addr {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "IPv4 address stored big-endian in the last 4 bytes; the other 28 bytes are zero. Bytewise order matches numeric address order, so a CIDR block is one contiguous range.\n\nUse for hosts, peers and log fields that are always IPv4. Use Ipv6Addr when an attribute may hold both families; IPv4 addresses are then stored IPv4-mapped.\n\nNon-zero padding is invalid. Store networks as two addresses or a separate prefix length if you need them as values.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("ipv4_addr")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::IPV4_ADDR_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+impl ConstDescribe for Ipv6Addr {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "IPv6 address stored big-endian in the lower 16 bytes; the upper 16 bytes are zero. Bytewise order matches numeric address order, so a CIDR block is one contiguous range.\n\nUse for addresses of either family: IPv4 addresses are stored IPv4-mapped (::ffff:a.b.c.d) and read back as IPv4. Use Ipv4Addr when only IPv4 occurs.\n\nScope ids and flow labels are not part of the address; use SocketAddr to keep them.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("ipv6_addr")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::IPV6_ADDR_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+impl ConstDescribe for SocketAddr {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "Socket address: six zero bytes, then an IPv6 or IPv4-mapped address (16 bytes), the port (2 bytes), the IPv6 flow info (4 bytes) and the scope id (4 bytes), all big-endian. Values sort by address, then port.\n\nUse for endpoints such as listeners, peers and connection logs. Use Ipv4Addr or Ipv6Addr when the port does not matter.\n\nAn IPv4-mapped address with zero flow info and scope id reads back as an IPv4 socket address.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("socket_addr")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::SOCKET_ADDR_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+impl ConstDescribe for MacAddr {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "48-bit MAC (EUI-48) address stored in the last 6 bytes; the other 26 bytes are zero. Addresses from one vendor prefix (OUI) form a contiguous range.\n\nUse for network interfaces and hardware inventories. Text like aa:bb:cc:dd:ee:ff or AA-BB-CC-DD-EE-FF converts directly.\n\nEUI-64 identifiers do not fit this schema; store them as U256BE or ShortString.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("mac_addr")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::MAC_ADDR_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm_formatter {
+    use core::fmt::Write;
+
+    use triblespace_core_macros::value_formatter;
+
+    #[value_formatter]
+    pub(crate) fn ipv4_addr(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..28].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let addr = core::net::Ipv4Addr::new(raw[28], raw[29], raw[30], raw[31]);
+        write!(out, "{addr}").map_err(|_| 1u32)?;
+        Ok(())
+    }
+
+    #[value_formatter]
+    pub(crate) fn ipv6_addr(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..16].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&raw[16..]);
+        let addr = core::net::Ipv6Addr::from(octets);
+        match addr.to_ipv4_mapped() {
+            Some(v4) => write!(out, "{v4}"),
+            None => write!(out, "{addr}"),
+        }
+        .map_err(|_| 1u32)?;
+        Ok(())
+    }
+
+    #[value_formatter]
+    pub(crate) fn socket_addr(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..6].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        let mut octets = [0u8; 16];
+        octets.copy_from_slice(&raw[6..22]);
+        let ip = core::net::Ipv6Addr::from(octets);
+        let port = u16::from_be_bytes([raw[22], raw[23]]);
+        let flowinfo = u32::from_be_bytes([raw[24], raw[25], raw[26], raw[27]]);
+        let scope_id = u32::from_be_bytes([raw[28], raw[29], raw[30], raw[31]]);
+        match ip.to_ipv4_mapped() {
+            Some(v4) if flowinfo == 0 && scope_id == 0 => write!(out, "{v4}:{port}"),
+            _ => write!(
+                out,
+                "{}",
+                core::net::SocketAddrV6::new(ip, port, flowinfo, scope_id)
+            ),
+        }
+        .map_err(|_| 1u32)?;
+        Ok(())
+    }
+
+    #[value_formatter]
+    pub(crate) fn mac_addr(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        if raw[..26].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        for (i, byte) in raw[26..].iter().enumerate() {
+            if i > 0 {
+                out.write_char(':').map_err(|_| 1u32)?;
+            }
+            write!(out, "{byte:02x}").map_err(|_| 1u32)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inclusive bounds of the addresses sharing the first `prefix` bits of
+/// `network`.
+fn prefix_bounds(network: u128, prefix: u8, bits: u8) -> Result<(u128, u128), PrefixTooLong> {
+    if prefix > bits {
+        return Err(PrefixTooLong { prefix, max: bits });
+    }
+    let host_bits = u32::from(bits - prefix);
+    let hosts = u128::MAX.checked_shr(128 - host_bits).unwrap_or(0);
+    Ok((network & !hosts, network | hosts))
+}
+
+impl Ipv4Addr {
+    /// The first and last address of the CIDR block `network/prefix`, for
+    /// [`TribleSet::value_in_range`](crate::trible::TribleSet::value_in_range)
+    /// or [`value_range`](crate::query::rangeconstraint::value_range).
+    pub fn cidr_bounds(
+        network: std::net::Ipv4Addr,
+        prefix: u8,
+    ) -> Result<(Value<Self>, Value<Self>), PrefixTooLong> {
+        let (min, max) = prefix_bounds(u32::from(network).into(), prefix, 32)?;
+        Ok((
+            std::net::Ipv4Addr::from(min as u32).to_value(),
+            std::net::Ipv4Addr::from(max as u32).to_value(),
+        ))
+    }
+
+    /// Restricts `variable` to the CIDR block `network/prefix`.
+    ///
+    /// ```rust,ignore
+    /// find!((host: Id),
+    ///     and!(
+    ///         pattern!(&data, [{ ?host @ addr: ?ip }]),
+    ///         Ipv4Addr::cidr(ip, Ipv4Addr::new(10, 0, 0, 0), 8)?,
+    ///     )
+    /// )
+    /// ```
+    pub fn cidr(
+        variable: Variable<Self>,
+        network: std::net::Ipv4Addr,
+        prefix: u8,
+    ) -> Result<ValueRange, PrefixTooLong> {
+        let (min, max) = Self::cidr_bounds(network, prefix)?;
+        Ok(ValueRange::new(variable, min, max))
+    }
+}
+
+impl Ipv6Addr {
+    /// The first and last address of the CIDR block `network/prefix`. An
+    /// IPv4 network selects its IPv4-mapped block, with `prefix` counted in
+    /// IPv4 bits.
+    pub fn cidr_bounds(
+        network: impl Into<IpAddr>,
+        prefix: u8,
+    ) -> Result<(Value<Self>, Value<Self>), PrefixTooLong> {
+        let (network, prefix) = match network.into() {
+            IpAddr::V4(_) if prefix > 32 => return Err(PrefixTooLong { prefix, max: 32 }),
+            IpAddr::V4(v4) => (v4.to_ipv6_mapped(), prefix + 96),
+            IpAddr::V6(v6) => (v6, prefix),
+        };
+        let (min, max) = prefix_bounds(network.into(), prefix, 128)?;
+        Ok((
+            std::net::Ipv6Addr::from(min).to_value(),
+            std::net::Ipv6Addr::from(max).to_value(),
+        ))
+    }
+
+    /// Restricts `variable` to the CIDR block `network/prefix`.
+    pub fn cidr(
+        variable: Variable<Self>,
+        network: impl Into<IpAddr>,
+        prefix: u8,
+    ) -> Result<ValueRange, PrefixTooLong> {
+        let (min, max) = Self::cidr_bounds(network, prefix)?;
+        Ok(ValueRange::new(variable, min, max))
+    }
+}
+
+impl ValueSchema for Ipv4Addr {
+    type ValidationError = InvalidAddress;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        std::net::Ipv4Addr::try_from_value(&value)?;
+        Ok(value)
+    }
+}
+
+impl ValueSchema for Ipv6Addr {
+    type ValidationError = InvalidAddress;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        std::net::Ipv6Addr::try_from_value(&value)?;
+        Ok(value)
+    }
+}
+
+impl ValueSchema for SocketAddr {
+    type ValidationError = InvalidAddress;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        std::net::SocketAddr::try_from_value(&value)?;
+        Ok(value)
+    }
+}
+
+impl ValueSchema for MacAddr {
+    type ValidationError = InvalidAddress;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        <[u8; 6]>::try_from_value(&value)?;
+        Ok(value)
+    }
+}
+
+impl ToValue<Ipv4Addr> for std::net::Ipv4Addr {
+    fn to_value(self) -> Value<Ipv4Addr> {
+        let mut raw = [0; 32];
+        raw[28..].copy_from_slice(&self.octets());
+        Value::new(raw)
+    }
+}
+
+impl ToValue<Ipv4Addr> for &std::net::Ipv4Addr {
+    fn to_value(self) -> Value<Ipv4Addr> {
+        (*self).to_value()
+    }
+}
+
+impl TryFromValue<'_, Ipv4Addr> for std::net::Ipv4Addr {
+    type Error = InvalidAddress;
+
+    fn try_from_value(v: &Value<Ipv4Addr>) -> Result<Self, InvalidAddress> {
+        if v.raw[..28].iter().any(|&b| b != 0) {
+            return Err(InvalidAddress);
+        }
+        let octets: [u8; 4] = v.raw[28..].try_into().unwrap();
+        Ok(octets.into())
+    }
+}
+
+impl ToValue<Ipv6Addr> for std::net::Ipv6Addr {
+    fn to_value(self) -> Value<Ipv6Addr> {
+        let mut raw = [0; 32];
+        raw[16..].copy_from_slice(&self.octets());
+        Value::new(raw)
+    }
+}
+
+impl ToValue<Ipv6Addr> for &std::net::Ipv6Addr {
+    fn to_value(self) -> Value<Ipv6Addr> {
+        (*self).to_value()
+    }
+}
+
+/// IPv4 addresses are stored IPv4-mapped.
+impl ToValue<Ipv6Addr> for IpAddr {
+    fn to_value(self) -> Value<Ipv6Addr> {
+        match self {
+            IpAddr::V4(v4) => v4.to_ipv6_mapped().to_value(),
+            IpAddr::V6(v6) => v6.to_value(),
+        }
+    }
+}
+
+impl ToValue<Ipv6Addr> for &IpAddr {
+    fn to_value(self) -> Value<Ipv6Addr> {
+        (*self).to_value()
+    }
+}
+
+impl TryFromValue<'_, Ipv6Addr> for std::net::Ipv6Addr {
+    type Error = InvalidAddress;
+
+    fn try_from_value(v: &Value<Ipv6Addr>) -> Result<Self, InvalidAddress> {
+        if v.raw[..16] != [0; 16] {
+            return Err(InvalidAddress);
+        }
+        let octets: [u8; 16] = v.raw[16..].try_into().unwrap();
+        Ok(octets.into())
+    }
+}
+
+/// IPv4-mapped addresses come back as IPv4.
+impl TryFromValue<'_, Ipv6Addr> for IpAddr {
+    type Error = InvalidAddress;
+
+    fn try_from_value(v: &Value<Ipv6Addr>) -> Result<Self, InvalidAddress> {
+        let v6 = std::net::Ipv6Addr::try_from_value(v)?;
+        Ok(IpAddr::V6(v6).to_canonical())
+    }
+}
+
+impl ToValue<SocketAddr> for std::net::SocketAddr {
+    fn to_value(self) -> Value<SocketAddr> {
+        let (ip, flowinfo, scope_id) = match self {
+            std::net::SocketAddr::V4(v4) => (v4.ip().to_ipv6_mapped(), 0, 0),
+            std::net::SocketAddr::V6(v6) => (*v6.ip(), v6.flowinfo(), v6.scope_id()),
+        };
+        let mut raw = [0; 32];
+        raw[6..22].copy_from_slice(&ip.octets());
+        raw[22..24].copy_from_slice(&self.port().to_be_bytes());
+        raw[24..28].copy_from_slice(&flowinfo.to_be_bytes());
+        raw[28..].copy_from_slice(&scope_id.to_be_bytes());
+        Value::new(raw)
+    }
+}
+
+impl ToValue<SocketAddr> for &std::net::SocketAddr {
+    fn to_value(self) -> Value<SocketAddr> {
+        (*self).to_value()
+    }
+}
+
+/// IPv4-mapped addresses without flow info or scope id come back as IPv4.
+impl TryFromValue<'_, SocketAddr> for std::net::SocketAddr {
+    type Error = InvalidAddress;
+
+    fn try_from_value(v: &Value<SocketAddr>) -> Result<Self, InvalidAddress> {
+        if v.raw[..6] != [0; 6] {
+            return Err(InvalidAddress);
+        }
+        let octets: [u8; 16] = v.raw[6..22].try_into().unwrap();
+        let ip = std::net::Ipv6Addr::from(octets);
+        let port = u16::from_be_bytes(v.raw[22..24].try_into().unwrap());
+        let flowinfo = u32::from_be_bytes(v.raw[24..28].try_into().unwrap());
+        let scope_id = u32::from_be_bytes(v.raw[28..].try_into().unwrap());
+        Ok(match ip.to_ipv4_mapped() {
+            Some(v4) if flowinfo == 0 && scope_id == 0 => (v4, port).into(),
+            _ => std::net::SocketAddrV6::new(ip, port, flowinfo, scope_id).into(),
+        })
+    }
+}
+
+impl ToValue<MacAddr> for [u8; 6] {
+    fn to_value(self) -> Value<MacAddr> {
+        let mut raw = [0; 32];
+        raw[26..].copy_from_slice(&self);
+        Value::new(raw)
+    }
+}
+
+impl ToValue<MacAddr> for &[u8; 6] {
+    fn to_value(self) -> Value<MacAddr> {
+        (*self).to_value()
+    }
+}
+
+impl TryFromValue<'_, MacAddr> for [u8; 6] {
+    type Error = InvalidAddress;
+
+    fn try_from_value(v: &Value<MacAddr>) -> Result<Self, InvalidAddress> {
+        if v.raw[..26].iter().any(|&b| b != 0) {
+            return Err(InvalidAddress);
+        }
+        Ok(v.raw[26..].try_into().unwrap())
+    }
+}
+
+/// Parses `aa:bb:cc:dd:ee:ff` or `aa-bb-cc-dd-ee-ff`, in either case.
+impl TryToValue<MacAddr> for &str {
+    type Error = MacAddrParseError;
+
+    fn try_to_value(self) -> Result<Value<MacAddr>, Self::Error> {
+        let separator = if self.contains('-') { '-' } else { ':' };
+        let mut octets = [0u8; 6];
+        let mut parts = self.split(separator);
+        for octet in &mut octets {
+            let part = parts.next().ok_or(MacAddrParseError)?;
+            if part.len() != 2 {
+                return Err(MacAddrParseError);
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| MacAddrParseError)?;
+        }
+        if parts.next().is_some() {
+            return Err(MacAddrParseError);
+        }
+        Ok(octets.to_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_round_trip_and_sort_numerically() {
+        let a: Value<Ipv4Addr> = std::net::Ipv4Addr::new(9, 255, 255, 255).to_value();
+        let b: Value<Ipv4Addr> = std::net::Ipv4Addr::new(10, 0, 0, 1).to_value();
+        assert!(a.raw < b.raw);
+        assert_eq!(
+            std::net::Ipv4Addr::try_from_value(&b),
+            Ok(std::net::Ipv4Addr::new(10, 0, 0, 1))
+        );
+
+        let v4 = IpAddr::from([192, 168, 1, 7]);
+        let v6 = IpAddr::from(std::net::Ipv6Addr::LOCALHOST);
+        let mapped: Value<Ipv6Addr> = v4.to_value();
+        assert_eq!(IpAddr::try_from_value(&mapped), Ok(v4));
+        assert_eq!(IpAddr::try_from_value(&v6.to_value()), Ok(v6));
+    }
+
+    #[test]
+    fn socket_addrs_keep_family_and_scope() {
+        let v4: std::net::SocketAddr = "10.1.2.3:8080".parse().unwrap();
+        let v6: std::net::SocketAddr = "[fe80::1%3]:443".parse().unwrap();
+        for addr in [v4, v6] {
+            let value: Value<SocketAddr> = addr.to_value();
+            assert_eq!(std::net::SocketAddr::try_from_value(&value), Ok(addr));
+        }
+        let low: Value<SocketAddr> = "10.1.2.3:80".parse::<std::net::SocketAddr>().unwrap().to_value();
+        assert!(low.raw < v4.to_value().raw);
+    }
+
+    #[test]
+    fn cidr_bounds_cover_the_block() {
+        let (min, max) = Ipv4Addr::cidr_bounds(std::net::Ipv4Addr::new(10, 1, 2, 3), 16).unwrap();
+        assert_eq!(
+            std::net::Ipv4Addr::try_from_value(&min),
+            Ok(std::net::Ipv4Addr::new(10, 1, 0, 0))
+        );
+        assert_eq!(
+            std::net::Ipv4Addr::try_from_value(&max),
+            Ok(std::net::Ipv4Addr::new(10, 1, 255, 255))
+        );
+        let (min, max) = Ipv4Addr::cidr_bounds(std::net::Ipv4Addr::new(1, 2, 3, 4), 0).unwrap();
+        assert_eq!(std::net::Ipv4Addr::try_from_value(&min), Ok(std::net::Ipv4Addr::UNSPECIFIED));
+        assert_eq!(std::net::Ipv4Addr::try_from_value(&max), Ok(std::net::Ipv4Addr::BROADCAST));
+        assert_eq!(
+            Ipv4Addr::cidr_bounds(std::net::Ipv4Addr::LOCALHOST, 33).unwrap_err(),
+            PrefixTooLong { prefix: 33, max: 32 }
+        );
+
+        let (min, max) = Ipv6Addr::cidr_bounds(std::net::Ipv4Addr::new(192, 168, 0, 0), 24).unwrap();
+        assert_eq!(IpAddr::try_from_value(&min), Ok(IpAddr::from([192, 168, 0, 0])));
+        assert_eq!(IpAddr::try_from_value(&max), Ok(IpAddr::from([192, 168, 0, 255])));
+        let host: std::net::Ipv6Addr = "2001:db8::42".parse().unwrap();
+        let (min, max) = Ipv6Addr::cidr_bounds(host, 128).unwrap();
+        assert_eq!(min, max);
+    }
+
+    #[test]
+    fn mac_addrs_parse_both_separators() {
+        let colon: Value<MacAddr> = "aa:BB:cc:00:11:22".try_to_value().unwrap();
+        let dash: Value<MacAddr> = "AA-bb-CC-00-11-22".try_to_value().unwrap();
+        assert_eq!(colon, dash);
+        assert_eq!(
+            <[u8; 6]>::try_from_value(&colon),
+            Ok([0xaa, 0xbb, 0xcc, 0x00, 0x11, 0x22])
+        );
+        assert!(TryToValue::<MacAddr>::try_to_value("aa:bb:cc:00:11").is_err());
+        assert!(TryToValue::<MacAddr>::try_to_value("aa:bb:cc:00:11:22:33").is_err());
+        assert!(TryToValue::<MacAddr>::try_to_value("aabb:cc:00:11:22").is_err());
+    }
+}
@@ -55,12 +55,12 @@ const SIGN_BIT: u128 = 1u128 << 127;
 
 /// Encode i128 as order-preserving big-endian: flip sign bit, then BE.
 /// Maps i128::MIN→0, 0→2^127, i128::MAX→u128::MAX.
-fn i128_to_ordered_be(v: i128) -> [u8; 16] {
+pub(crate) fn i128_to_ordered_be(v: i128) -> [u8; 16] {
     ((v as u128) ^ SIGN_BIT).to_be_bytes()
 }
 
 /// Decode order-preserving big-endian back to i128.
-fn i128_from_ordered_be(bytes: [u8; 16]) -> i128 {
+pub(crate) fn i128_from_ordered_be(bytes: [u8; 16]) -> i128 {
     (u128::from_be_bytes(bytes) ^ SIGN_BIT) as i128
 }
 
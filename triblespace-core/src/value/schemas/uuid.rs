@@ -0,0 +1,157 @@
+use crate::id::ExclusiveId;
+use crate::id::Id;
+use crate::id_hex;
+use crate::macros::entity;
+use crate::metadata;
+use crate::metadata::{ConstDescribe, ConstId};
+use crate::repo::BlobStore;
+use crate::trible::Fragment;
+use crate::value::schemas::hash::Blake3;
+use crate::value::ToValue;
+use crate::value::TryFromValue;
+use crate::value::Value;
+use crate::value::ValueSchema;
+
+use std::convert::TryInto;
+
+/// Error raised when a value does not match the [`Uuid`] layout: the upper
+/// 16 bytes must be zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidUuid;
+
+impl std::fmt::Display for InvalidUuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("uuid value has non-zero upper bytes")
+    }
+}
+
+impl std::error::Error for InvalidUuid {}
+
+/// A value schema for RFC 4122 UUIDs.
+///
+/// The 16 UUID bytes are stored in the lower 16 bytes in their standard
+/// (big-endian) order and the upper 16 bytes are zero, so byte order matches
+/// the order of [`uuid::Uuid`]. Unlike [`GenId`](super::genid::GenId), the
+/// nil UUID is a valid value and the bytes are not treated as an entity id.
+pub struct Uuid;
+
+impl ConstId for Uuid {
+    const ID: Id = id_hex!("C20676285B2C5F9AC578113D82196192");
+}
+
+impl ConstDescribe for Uuid {
+    fn describe<B>(blobs: &mut B) -> Result<Fragment, B::PutError>
+    where
+        B: BlobStore<Blake3>,
+    {
+        let id = Self::ID;
+        let description = blobs.put(
+            "RFC 4122 UUID stored in the lower 16 bytes in standard byte order; the upper 16 bytes are zero. Bytewise order matches the usual UUID ordering, so time-ordered versions such as v7 sort chronologically.\n\nUse for identifiers minted by other systems (database keys, request ids, device ids) that should keep their UUID identity. Use GenId for references between entities in the same space.\n\nThe nil UUID is a valid value. Version and variant bits are not checked.",
+        )?;
+        let tribles = entity! {
+            ExclusiveId::force_ref(&id) @
+                metadata::name: blobs.put("uuid")?,
+                metadata::description: description,
+                metadata::tag: metadata::KIND_VALUE_SCHEMA,
+        };
+
+        #[cfg(feature = "wasm")]
+        let tribles = {
+            let mut tribles = tribles;
+            tribles += entity! { ExclusiveId::force_ref(&id) @
+                metadata::value_formatter: blobs.put(wasm_formatter::UUID_WASM)?,
+            };
+            tribles
+        };
+        Ok(tribles)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm_formatter {
+    use core::fmt::Write;
+
+    use triblespace_core_macros::value_formatter;
+
+    #[value_formatter]
+    pub(crate) fn uuid(raw: &[u8; 32], out: &mut impl Write) -> Result<(), u32> {
+        const TABLE: &[u8; 16] = b"0123456789abcdef";
+
+        if raw[..16].iter().any(|&b| b != 0) {
+            return Err(2);
+        }
+        for (i, &byte) in raw[16..].iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                out.write_char('-').map_err(|_| 1u32)?;
+            }
+            out.write_char(TABLE[(byte >> 4) as usize] as char)
+                .map_err(|_| 1u32)?;
+            out.write_char(TABLE[(byte & 0x0F) as usize] as char)
+                .map_err(|_| 1u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl ValueSchema for Uuid {
+    type ValidationError = InvalidUuid;
+
+    fn validate(value: Value<Self>) -> Result<Value<Self>, Self::ValidationError> {
+        if value.raw[..16] == [0; 16] {
+            Ok(value)
+        } else {
+            Err(InvalidUuid)
+        }
+    }
+}
+
+impl ToValue<Uuid> for uuid::Uuid {
+    fn to_value(self) -> Value<Uuid> {
+        let mut raw = [0; 32];
+        raw[16..].copy_from_slice(self.as_bytes());
+        Value::new(raw)
+    }
+}
+
+impl ToValue<Uuid> for &uuid::Uuid {
+    fn to_value(self) -> Value<Uuid> {
+        (*self).to_value()
+    }
+}
+
+impl TryFromValue<'_, Uuid> for uuid::Uuid {
+    type Error = InvalidUuid;
+
+    fn try_from_value(v: &Value<Uuid>) -> Result<Self, Self::Error> {
+        if v.raw[..16] != [0; 16] {
+            return Err(InvalidUuid);
+        }
+        Ok(uuid::Uuid::from_bytes(v.raw[16..].try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvalidUuid, Uuid};
+    use crate::value::{TryFromValue, Value, ValueSchema};
+
+    #[test]
+    fn round_trips_and_orders_like_uuid() {
+        let low = uuid::Uuid::from_u128(0x0190_0000_0000_7000_8000_0000_0000_0001);
+        let high = uuid::Uuid::from_u128(0x0191_0000_0000_7000_8000_0000_0000_0000);
+        let (a, b) = (Uuid::value_from(low), Uuid::value_from(high));
+        assert!(a.raw < b.raw);
+        assert_eq!(uuid::Uuid::try_from_value(&a), Ok(low));
+        assert_eq!(
+            uuid::Uuid::try_from_value(&Uuid::value_from(uuid::Uuid::nil())),
+            Ok(uuid::Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn rejects_upper_bytes() {
+        let mut raw = [0u8; 32];
+        raw[0] = 1;
+        assert_eq!(Uuid::validate(Value::new(raw)), Err(InvalidUuid));
+    }
+}
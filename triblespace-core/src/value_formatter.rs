@@ -327,6 +327,8 @@ mod tests {
     fn builtins_emit_and_run() {
         use crate::blob::schemas::longstring::LongString;
         use crate::value::schemas::boolean::Boolean;
+        use crate::value::schemas::calendar::LocalDate;
+        use crate::value::schemas::duration::NsDuration;
         use crate::value::schemas::ed25519::ED25519PublicKey;
         use crate::value::schemas::ed25519::ED25519RComponent;
         use crate::value::schemas::ed25519::ED25519SComponent;
@@ -334,6 +336,7 @@ mod tests {
         use crate::value::schemas::f256::F256LE;
         use crate::value::schemas::f64::F64;
         use crate::value::schemas::genid::GenId;
+        use crate::value::schemas::geopoint::GeoPoint;
         use crate::value::schemas::hash::Blake3;
         use crate::value::schemas::hash::Handle;
         use crate::value::schemas::hash::Hash;
@@ -342,11 +345,13 @@ mod tests {
         use crate::value::schemas::iu256::U256BE;
         use crate::value::schemas::iu256::U256LE;
         use crate::value::schemas::linelocation::LineLocation;
+        use crate::value::schemas::net::{Ipv4Addr, Ipv6Addr, MacAddr, SocketAddr};
         use crate::value::schemas::r256::R256BE;
         use crate::value::schemas::r256::R256LE;
         use crate::value::schemas::range::RangeInclusiveU128;
         use crate::value::schemas::range::RangeU128;
         use crate::value::schemas::shortstring::ShortString;
+        use crate::value::schemas::uuid::Uuid;
         use crate::value::schemas::UnknownValue;
         use crate::value::Value;
         use crate::value::ValueSchema;
@@ -368,6 +373,14 @@ mod tests {
         space += RangeU128::describe(&mut store).expect("rangeu128 metadata");
         space += RangeInclusiveU128::describe(&mut store).expect("rangeu128 inclusive metadata");
         space += LineLocation::describe(&mut store).expect("linelocation metadata");
+        space += Uuid::describe(&mut store).expect("uuid metadata");
+        space += Ipv4Addr::describe(&mut store).expect("ipv4 metadata");
+        space += Ipv6Addr::describe(&mut store).expect("ipv6 metadata");
+        space += SocketAddr::describe(&mut store).expect("socket addr metadata");
+        space += MacAddr::describe(&mut store).expect("mac addr metadata");
+        space += GeoPoint::describe(&mut store).expect("geo point metadata");
+        space += NsDuration::describe(&mut store).expect("duration metadata");
+        space += LocalDate::describe(&mut store).expect("local date metadata");
 
         space += ED25519RComponent::describe(&mut store).expect("ed25519 r metadata");
         space += ED25519SComponent::describe(&mut store).expect("ed25519 s metadata");
@@ -500,6 +513,70 @@ mod tests {
             "1:2..3:4"
         );
 
+        let uuid = formatter_for(Uuid::ID);
+        let raw = Uuid::value_from(uuid::Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef)).raw;
+        assert_eq!(
+            uuid.format_value_with_limits(&raw, limits).unwrap(),
+            "01234567-89ab-cdef-0123-456789abcdef"
+        );
+
+        let ipv4 = formatter_for(Ipv4Addr::ID);
+        let raw = Ipv4Addr::value_from(std::net::Ipv4Addr::new(10, 0, 0, 1)).raw;
+        assert_eq!(ipv4.format_value_with_limits(&raw, limits).unwrap(), "10.0.0.1");
+
+        let ipv6 = formatter_for(Ipv6Addr::ID);
+        let raw = Ipv6Addr::value_from(std::net::Ipv6Addr::LOCALHOST).raw;
+        assert_eq!(ipv6.format_value_with_limits(&raw, limits).unwrap(), "::1");
+        let raw = Ipv6Addr::value_from(std::net::IpAddr::from([192, 168, 0, 1])).raw;
+        assert_eq!(
+            ipv6.format_value_with_limits(&raw, limits).unwrap(),
+            "192.168.0.1"
+        );
+
+        let socket_addr = formatter_for(SocketAddr::ID);
+        for text in ["10.0.0.1:8080", "[2001:db8::1]:443"] {
+            let addr: std::net::SocketAddr = text.parse().unwrap();
+            let raw = SocketAddr::value_from(addr).raw;
+            assert_eq!(
+                socket_addr.format_value_with_limits(&raw, limits).unwrap(),
+                text
+            );
+        }
+
+        let mac_addr = formatter_for(MacAddr::ID);
+        let raw = MacAddr::value_from([0xAA, 0xBB, 0xCC, 0x00, 0x11, 0x22]).raw;
+        assert_eq!(
+            mac_addr.format_value_with_limits(&raw, limits).unwrap(),
+            "aa:bb:cc:00:11:22"
+        );
+
+        let geo_point = formatter_for(GeoPoint::ID);
+        let raw = GeoPoint::value_from((52.52f64, -13.5f64)).raw;
+        assert_eq!(
+            geo_point.format_value_with_limits(&raw, limits).unwrap(),
+            "52.52,-13.5"
+        );
+
+        let ns_duration = formatter_for(NsDuration::ID);
+        for (nanos, text) in [
+            (90_061_500_000_000i128, "1d 1h 1m 1.5s"),
+            (-2_000_000_000, "-2s"),
+            (0, "0s"),
+        ] {
+            let raw = NsDuration::value_from(nanos).raw;
+            assert_eq!(
+                ns_duration.format_value_with_limits(&raw, limits).unwrap(),
+                text
+            );
+        }
+
+        let local_date = formatter_for(LocalDate::ID);
+        let raw = LocalDate::value_from((2024i32, 2u8, 29u8)).raw;
+        assert_eq!(
+            local_date.format_value_with_limits(&raw, limits).unwrap(),
+            "2024-02-29"
+        );
+
         let f256le = formatter_for(F256LE::ID);
         let raw = F256LE::value_from(f256::f256::from(1u8)).raw;
         assert_eq!(
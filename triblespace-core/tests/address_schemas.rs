@@ -0,0 +1,109 @@
+//! UUID and network address schemas: CIDR blocks as range constraints and
+//! text rendering in the exporters.
+
+use std::net::{IpAddr, Ipv4Addr as StdIpv4Addr};
+
+use triblespace_core::attribute::Attribute;
+use triblespace_core::prelude::valueschemas::{Ipv4Addr, Ipv6Addr, ShortString};
+use triblespace_core::prelude::*;
+
+fn hosts() -> TribleSet {
+    let name = Attribute::<ShortString>::from_name("name");
+    let v4 = Attribute::<Ipv4Addr>::from_name("v4");
+    let ip = Attribute::<Ipv6Addr>::from_name("ip");
+    let mut set = TribleSet::new();
+    for (host, addr) in [
+        ("gateway", [10, 0, 0, 1]),
+        ("build", [10, 0, 7, 20]),
+        ("laptop", [192, 168, 1, 5]),
+        ("edge", [10, 1, 0, 1]),
+    ] {
+        let id = ufoid();
+        set += entity! { &id @
+            name: host,
+            v4: StdIpv4Addr::from(addr),
+            ip: IpAddr::from(addr),
+        };
+    }
+    set
+}
+
+#[test]
+fn cidr_blocks_select_hosts() {
+    let set = hosts();
+    let name = Attribute::<ShortString>::from_name("name");
+    let v4 = Attribute::<Ipv4Addr>::from_name("v4");
+    let ip = Attribute::<Ipv6Addr>::from_name("ip");
+
+    let mut in_v4_block: Vec<String> = find!(
+        (host: String, addr: Value<Ipv4Addr>),
+        and!(
+            pattern!(&set, [{ name: ?host, v4: ?addr }]),
+            Ipv4Addr::cidr(addr, StdIpv4Addr::new(10, 0, 0, 0), 16).unwrap(),
+        )
+    )
+    .map(|(host, _)| host)
+    .collect();
+    in_v4_block.sort();
+    assert_eq!(in_v4_block, ["build", "gateway"]);
+
+    // IPv4 networks match the IPv4-mapped addresses of an `Ipv6Addr` attribute.
+    let (min, max) = Ipv6Addr::cidr_bounds(StdIpv4Addr::new(10, 0, 0, 0), 8).unwrap();
+    let mut in_mapped_block: Vec<String> = find!(
+        (host: String, addr: Value<Ipv6Addr>),
+        and!(
+            pattern!(&set, [{ name: ?host, ip: ?addr }]),
+            set.value_in_range(addr, min, max),
+        )
+    )
+    .map(|(host, _)| host)
+    .collect();
+    in_mapped_block.sort();
+    assert_eq!(in_mapped_block, ["build", "edge", "gateway"]);
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn exporters_render_addresses_as_text() {
+    use std::net::SocketAddr as StdSocketAddr;
+    use triblespace_core::blob::schemas::longstring::LongString;
+    use triblespace_core::blob::MemoryBlobStore;
+    use triblespace_core::export::csv::CsvWriter;
+    use triblespace_core::prelude::valueschemas::{Blake3, MacAddr, SocketAddr, Uuid};
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let device = Attribute::<Uuid>::from_name("device");
+    let ip = Attribute::<Ipv6Addr>::from_name("ip");
+    let endpoint = Attribute::<SocketAddr>::from_name("endpoint");
+    let mac = Attribute::<MacAddr>::from_name("mac");
+
+    let device_id = uuid::Uuid::from_u128(0x0191_2e6a_0c4b_7d2e_8f00_0000_0000_0001);
+    let endpoint_addr: StdSocketAddr = "[2001:db8::7]:8443".parse().unwrap();
+    let id = ufoid();
+    let mut set = TribleSet::new();
+    set += entity! { &id @
+        device: device_id,
+        ip: IpAddr::from([192, 168, 1, 5]),
+        endpoint: endpoint_addr,
+        mac: [0x02, 0x42, 0xac, 0x11, 0x00, 0x02],
+    };
+    set += device.describe(&mut blobs).unwrap().into_facts();
+    set += ip.describe(&mut blobs).unwrap().into_facts();
+    set += endpoint.describe(&mut blobs).unwrap().into_facts();
+    set += mac.describe(&mut blobs).unwrap().into_facts();
+    // `from_name` only hashes the names; the exporter reads them back.
+    for name in ["device", "ip", "endpoint", "mac"] {
+        blobs.put::<LongString, _>(name.to_string()).unwrap();
+    }
+
+    let mut out = String::new();
+    CsvWriter::new()
+        .write_entities(&set, [*id], &blobs.reader().unwrap(), &mut out)
+        .expect("export");
+    let expected = format!(
+        "id,device,endpoint,ip,mac\n\
+         {:x},01912e6a-0c4b-7d2e-8f00-000000000001,[2001:db8::7]:8443,192.168.1.5,02:42:ac:11:00:02\n",
+        *id
+    );
+    assert_eq!(out, expected);
+}
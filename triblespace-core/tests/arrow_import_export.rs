@@ -0,0 +1,316 @@
+//! Arrow coverage: entities and `find!` projections export to typed record
+//! batches, which import back into the same facts, directly or through
+//! Parquet.
+#![cfg(feature = "arrow")]
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, TimeUnit};
+use triblespace_core::attribute::Attribute;
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::blob::MemoryBlobStore;
+use triblespace_core::export::arrow::{entities_to_record_batch, rows_to_record_batch};
+use triblespace_core::import::arrow::{ArrowImportError, ArrowImporter, ATTRIBUTE_KEY};
+use triblespace_core::import::csv::CsvImporter;
+use triblespace_core::metadata::Describe;
+use triblespace_core::prelude::valueschemas::{Blake3, GenId, Handle, ShortString, I256BE};
+use triblespace_core::prelude::*;
+
+const BOOKS: &str = "\
+isbn,title,pages,price,in_print,published
+0441013597,Dune,412,9.95,true,1965-08-01
+0441172717,Dune Messiah,256,,false,1969-10-15
+";
+
+/// The books as facts plus the metadata describing their attributes.
+fn books(blobs: &mut MemoryBlobStore<Blake3>) -> (TribleSet, TribleSet) {
+    let mut importer = CsvImporter::<_, Blake3>::new(blobs, None).with_key_column("isbn");
+    let rows = importer.import_str(BOOKS).expect("import");
+    let metadata = importer.metadata().expect("metadata").into_facts();
+    (rows.into_facts(), metadata)
+}
+
+/// Every entity of `facts`; the exporter drops the repeats.
+fn entities(facts: &TribleSet) -> impl Iterator<Item = Id> + '_ {
+    facts.iter().map(|trible| *trible.e())
+}
+
+#[test]
+fn entities_export_typed_columns_and_reimport() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let (facts, metadata) = books(&mut blobs);
+    let mut set = facts.clone();
+    set += metadata;
+    let batch = entities_to_record_batch(&set, entities(&facts), &blobs.reader().unwrap())
+        .expect("export");
+    assert_eq!(batch.num_rows(), 2);
+
+    let schema = batch.schema();
+    let types: Vec<(&str, &DataType)> = schema
+        .fields()
+        .iter()
+        .map(|field| (field.name().as_str(), field.data_type()))
+        .collect();
+    let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()));
+    assert_eq!(types[0].0, "id");
+    assert_eq!(
+        types[0].1,
+        &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::FixedSizeBinary(16)))
+    );
+    assert_eq!(&types[1..4], &[
+        ("in_print", &DataType::Boolean),
+        ("isbn", &DataType::Utf8),
+        ("pages", &DataType::Int64),
+    ]);
+    assert_eq!(types[4], ("price", &DataType::Float64));
+    assert!(
+        matches!(types[5], ("published", DataType::Struct(fields))
+            if fields.iter().all(|field| field.data_type() == &timestamp)),
+        "{types:?}"
+    );
+    assert_eq!(types[6], ("title", &DataType::Utf8));
+    let pages = Attribute::<I256BE>::from_name("pages");
+    assert_eq!(
+        schema.field(3).metadata().get(ATTRIBUTE_KEY),
+        Some(&format!("{:X}", pages.id()))
+    );
+
+    // The tagged columns land on the original attributes, and the key
+    // column addresses the original entities.
+    let mut importer = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("isbn")
+        .skip_column("id");
+    let again = importer.import_batch(&batch).expect("import");
+    assert_eq!(again.facts(), &facts);
+}
+
+#[test]
+fn repeated_values_become_lists() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let tag = Attribute::<ShortString>::from_name("tag");
+    let mut set = TribleSet::new();
+    let book = ufoid();
+    let paper = ufoid();
+    set += entity! { &book @ tag: "scifi" };
+    set += entity! { &book @ tag: "classic" };
+    set += entity! { &paper @ tag: "draft" };
+    let book = book.release();
+    let paper = paper.release();
+    let facts = set.clone();
+    set += tag.describe(&mut blobs).expect("describe").into_facts();
+    let _: Value<Handle<Blake3, LongString>> = blobs.put("tag".to_owned()).expect("name");
+
+    let batch = entities_to_record_batch(&set, [book, paper], &blobs.reader().unwrap())
+        .expect("export");
+    assert!(matches!(batch.schema().field(1).data_type(), DataType::List(_)));
+
+    let mut importer = ArrowImporter::<_, Blake3>::new(&mut blobs, None);
+    let again = importer.import_batch(&batch).expect("import");
+    // Without a key the rows get new ids, and the id column references the
+    // old ones.
+    let ids = GenId::value_from(book);
+    let imported = again.facts();
+    assert_eq!(
+        find!((t: String), pattern!(imported, [{ tag: ?t }])).count(),
+        3
+    );
+    let id = Attribute::<GenId>::from_name("id");
+    assert_eq!(
+        find!((e: Id), pattern!(imported, [{ ?e @ id: ids }])).count(),
+        1
+    );
+    assert_eq!(facts.len() + 2, imported.len());
+}
+
+#[test]
+fn malformed_list_values_export_as_null_elements() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::Array;
+    use triblespace_core::prelude::valueschemas::NsTAIInterval;
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let period = Attribute::<NsTAIInterval>::from_name("period");
+    let start = hifitime::Epoch::from_unix_seconds(0.0);
+    let valid: Value<NsTAIInterval> = (start, start + hifitime::Duration::from_seconds(1.0))
+        .try_to_value()
+        .expect("interval");
+    let mut raw = valid.raw;
+    raw.rotate_left(16);
+    let inverted = Value::<NsTAIInterval>::new(raw);
+
+    let mut set = TribleSet::new();
+    let event = ufoid();
+    set += entity! { &event @ period: valid };
+    set += entity! { &event @ period: inverted };
+    let event = event.release();
+    set += period.describe(&mut blobs).expect("describe").into_facts();
+    let _: Value<Handle<Blake3, LongString>> = blobs.put("period".to_owned()).expect("name");
+
+    let batch = entities_to_record_batch(&set, [event], &blobs.reader().unwrap())
+        .expect("export");
+    let periods = batch.column(1).as_list::<i32>().value(0);
+    assert_eq!(periods.len(), 2);
+    assert_eq!(periods.null_count(), 1);
+}
+
+#[test]
+fn untagged_schemas_reimport_under_name_derived_attributes() {
+    use triblespace_core::import::arrow::VALUE_SCHEMA_KEY;
+    use triblespace_core::prelude::valueschemas::{Uuid, U256BE};
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let title = Attribute::<ShortString>::from_name("title");
+    let copies = Attribute::<U256BE>::from_name("copies");
+    let serial = Attribute::<Uuid>::from_name("serial");
+    let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+
+    let mut set = TribleSet::new();
+    let book = ufoid();
+    set += entity! { &book @ title: "Dune", copies: 7u64, serial: uuid };
+    let book = book.release();
+    let facts = set.clone();
+    for name in ["title", "copies", "serial"] {
+        let _: Value<Handle<Blake3, LongString>> = blobs.put(name.to_owned()).expect("name");
+    }
+    set += title.describe(&mut blobs).expect("describe").into_facts();
+    set += copies.describe(&mut blobs).expect("describe").into_facts();
+    set += serial.describe(&mut blobs).expect("describe").into_facts();
+
+    let batch = entities_to_record_batch(&set, [book], &blobs.reader().unwrap())
+        .expect("export");
+    let schema = batch.schema();
+    let tagged = |name: &str| {
+        let metadata = schema.field_with_name(name).unwrap().metadata();
+        metadata.contains_key(ATTRIBUTE_KEY) && metadata.contains_key(VALUE_SCHEMA_KEY)
+    };
+    assert!(tagged("title"));
+    assert!(!tagged("copies"));
+    assert!(!tagged("serial"));
+
+    let mut importer = ArrowImporter::<_, Blake3>::new(&mut blobs, None).skip_column("id");
+    let again = importer.import_batch(&batch).expect("import");
+    let imported = again.facts();
+    // The tagged column lands on its attribute, the others on attributes
+    // derived from their names under the inferred schemas (the UUID text
+    // is too long for a short string).
+    assert_eq!(find!((t: String), pattern!(imported, [{ title: ?t }])).count(), 1);
+    assert_eq!(find!((c: Value<U256BE>), pattern!(imported, [{ copies: ?c }])).count(), 0);
+    assert_eq!(find!((s: Value<Uuid>), pattern!(imported, [{ serial: ?s }])).count(), 0);
+    let copies_again = Attribute::<I256BE>::from_name("copies");
+    let serial_again = Attribute::<Handle<Blake3, LongString>>::from_name("serial");
+    let copies_value: Value<I256BE> = 7i64.to_value();
+    assert_eq!(
+        find!((), pattern!(imported, [{ copies_again: copies_value }])).count(),
+        1
+    );
+    let serial_text: Value<Handle<Blake3, LongString>> =
+        blobs.put(uuid.hyphenated().to_string()).expect("text");
+    assert_eq!(
+        find!((), pattern!(imported, [{ serial_again: serial_text }])).count(),
+        1
+    );
+    assert_eq!(imported.len(), facts.len());
+}
+
+#[test]
+fn projections_export_as_rows() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let (facts, _) = books(&mut blobs);
+    let title = Attribute::<ShortString>::from_name("title");
+    let pages = Attribute::<I256BE>::from_name("pages");
+    let mut found: Vec<(Id, String, i128)> = find!(
+        (e: Id, t: String, p: i128),
+        pattern!(&facts, [{ ?e @ title: ?t, pages: ?p }])
+    )
+    .collect();
+    found.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let batch = rows_to_record_batch(&["id", "title", "pages"], found.clone()).expect("batch");
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.column(2).data_type(), &DataType::Decimal128(38, 0));
+    let titles = batch.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(titles.value(0), "Dune");
+    assert_eq!(titles.value(1), "Dune Messiah");
+
+    let err = rows_to_record_batch(&["title"], found).unwrap_err();
+    assert!(err.to_string().contains("1 headers for 3 columns"), "{err}");
+}
+
+#[test]
+fn mapped_columns_and_errors() {
+    let title = Attribute::<Handle<Blake3, LongString>>::from_name("book/title");
+    let batch = RecordBatch::try_from_iter([
+        ("id", Arc::new(Int64Array::from(vec![Some(1), None])) as ArrayRef),
+        ("title", Arc::new(StringArray::from(vec!["Dune", "Emma"])) as ArrayRef),
+    ])
+    .unwrap();
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let rows = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_attribute("title", &title)
+        .import_batch(&batch)
+        .expect("import");
+    let facts = rows.facts();
+    assert_eq!(
+        find!((t: Value<Handle<Blake3, LongString>>), pattern!(facts, [{ title: ?t }])).count(),
+        2
+    );
+
+    let err = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("id")
+        .import_batch(&batch)
+        .unwrap_err();
+    assert!(matches!(err, ArrowImportError::MissingKey { row: 1 }), "{err}");
+    let err = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .skip_column("isbn")
+        .import_batch(&batch)
+        .unwrap_err();
+    assert!(matches!(&err, ArrowImportError::MissingColumn(column) if column == "isbn"));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_roundtrip() {
+    use triblespace_core::export::arrow::write_parquet;
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let (facts, metadata) = books(&mut blobs);
+    let mut set = facts.clone();
+    set += metadata;
+    let batch = entities_to_record_batch(&set, entities(&facts), &blobs.reader().unwrap())
+        .expect("export");
+
+    let mut file = Vec::new();
+    write_parquet(&batch, &mut file).expect("write parquet");
+    let again = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("isbn")
+        .skip_column("id")
+        .import_parquet(bytes::Bytes::from(file))
+        .expect("read parquet");
+    assert_eq!(again.facts(), &facts);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn compressed_parquet_imports() {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::{Compression, ZstdLevel};
+    use parquet::file::properties::WriterProperties;
+
+    let titles: ArrayRef = Arc::new(StringArray::from(vec!["Dune", "Dune Messiah"]));
+    let pages: ArrayRef = Arc::new(Int64Array::from(vec![412, 256]));
+    let batch = RecordBatch::try_from_iter([("title", titles), ("pages", pages)]).unwrap();
+    for compression in [Compression::SNAPPY, Compression::ZSTD(ZstdLevel::default())] {
+        let props = WriterProperties::builder().set_compression(compression).build();
+        let mut file = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut file, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut blobs = MemoryBlobStore::<Blake3>::new();
+        let rows = ArrowImporter::<_, Blake3>::new(&mut blobs, None)
+            .import_parquet(bytes::Bytes::from(file))
+            .unwrap_or_else(|err| panic!("read {compression:?} parquet: {err}"));
+        assert_eq!(rows.facts().len(), 4);
+    }
+}
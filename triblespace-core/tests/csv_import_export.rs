@@ -0,0 +1,200 @@
+//! CSV coverage: inferred and explicit column schemas, key-column and
+//! content-derived row ids, mapped attributes, and export of entities and
+//! `find!` projections back to CSV.
+#![cfg(feature = "csv")]
+
+use triblespace_core::attribute::Attribute;
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::blob::MemoryBlobStore;
+use triblespace_core::export::csv::CsvWriter;
+use triblespace_core::import::csv::{ColumnSchema, CsvImportError, CsvImporter};
+use triblespace_core::metadata;
+use triblespace_core::prelude::valueschemas::{Blake3, Handle, ShortString, F64, I256BE};
+use triblespace_core::prelude::*;
+use triblespace_core::value::schemas::time::NsTAIInterval;
+
+const BOOKS: &str = "\
+isbn,title,pages,price,in_print,published
+0441013597,Dune,412,9.95,true,1965-08-01
+0441172717,Dune Messiah,256,,false,1969-10-15
+";
+
+#[test]
+fn inferred_columns_follow_attribute_from_name() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let mut importer = CsvImporter::<_, Blake3>::new(&mut blobs, None).with_key_column("isbn");
+    let rows = importer.import_str(BOOKS).expect("import");
+    let exports: Vec<Id> = rows.exports().collect();
+    assert_eq!(exports.len(), 2);
+    // One trible per non-empty cell.
+    assert_eq!(rows.facts().len(), 11);
+
+    let isbn = Attribute::<ShortString>::from_name("isbn");
+    let pages = Attribute::<I256BE>::from_name("pages");
+    let price = Attribute::<F64>::from_name("price");
+    let in_print = Attribute::<valueschemas::Boolean>::from_name("in_print");
+    let published = Attribute::<NsTAIInterval>::from_name("published");
+    let facts = rows.facts();
+    let mut found: Vec<(String, i128, bool)> = find!(
+        (i: String, p: i128, b: bool),
+        pattern!(facts, [{ isbn: ?i, pages: ?p, in_print: ?b }])
+    )
+    .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("0441013597".to_owned(), 412, true),
+            ("0441172717".to_owned(), 256, false),
+        ]
+    );
+    let prices: Vec<f64> = find!((p: f64), pattern!(facts, [{ price: ?p }]))
+        .map(|(p,)| p)
+        .collect();
+    assert_eq!(prices, vec![9.95]);
+    assert_eq!(
+        find!((t: Value<NsTAIInterval>), pattern!(facts, [{ published: ?t }])).count(),
+        2
+    );
+
+    // Re-importing an edited file addresses the same entities.
+    let edited = BOOKS.replace("412", "413");
+    let again = importer.import_str(&edited).expect("import");
+    assert_eq!(again.exports().collect::<Vec<_>>(), exports);
+
+    let described = importer.metadata().expect("metadata");
+    let described = described.facts();
+    for attr in [isbn.id(), pages.id(), price.id(), in_print.id(), published.id()] {
+        let names = find!(
+            (name: Value<Handle<Blake3, LongString>>),
+            pattern!(described, [{ attr @ metadata::name: ?name }])
+        );
+        assert_eq!(names.count(), 1);
+    }
+}
+
+#[test]
+fn content_ids_converge_and_respect_the_salt() {
+    let data = "name,age\nalice,30\nbob,41\nalice,30\n";
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let rows = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .import_str(data)
+        .expect("import");
+    // The repeated row converges on one entity.
+    let ids: Vec<Id> = rows.exports().collect();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(rows.facts().len(), 4);
+
+    let salted = CsvImporter::<_, Blake3>::new(&mut blobs, Some([7; 32]))
+        .import_str(data)
+        .expect("import");
+    assert_eq!(salted.facts().len(), 4);
+    assert!(salted.exports().all(|id| !ids.contains(&id)));
+}
+
+#[test]
+fn explicit_schemas_mappings_and_errors() {
+    let title = Attribute::<Handle<Blake3, LongString>>::from_name("book/title");
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let data = "id;title;code;note\n1;Dune;007;x\n2;Emma;12;y\n";
+    let rows = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_delimiter(b';')
+        .with_key_column("id")
+        .skip_column("id")
+        .skip_column("note")
+        .with_attribute("title", &title)
+        .with_column_schema("code", ColumnSchema::Integer)
+        .import_str(data)
+        .expect("import");
+    let facts = rows.facts();
+    assert_eq!(facts.len(), 4);
+    let code = Attribute::<I256BE>::from_name("code");
+    let mut codes: Vec<i128> = find!((c: i128), pattern!(facts, [{ code: ?c }]))
+        .map(|(c,)| c)
+        .collect();
+    codes.sort();
+    assert_eq!(codes, vec![7, 12]);
+    assert_eq!(
+        find!((t: Value<Handle<Blake3, LongString>>), pattern!(facts, [{ title: ?t }])).count(),
+        2
+    );
+
+    let err = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_column_schema("age", ColumnSchema::Integer)
+        .import_str("name,age\nalice,30\nbob,old\n")
+        .unwrap_err();
+    assert!(
+        matches!(&err, CsvImportError::InvalidCell { line: 3, column, value, .. } if column == "age" && value == "old"),
+        "{err}"
+    );
+    let err = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("id")
+        .import_str("name\nalice\n")
+        .unwrap_err();
+    assert!(matches!(&err, CsvImportError::MissingColumn(column) if column == "id"));
+    let err = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("id")
+        .import_str("id,name\n1,alice\n,bob\n")
+        .unwrap_err();
+    assert!(matches!(err, CsvImportError::MissingKey { line: 3 }));
+}
+
+#[test]
+fn entities_export_and_reimport() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let mut importer = CsvImporter::<_, Blake3>::new(&mut blobs, None).with_key_column("isbn");
+    let rows = importer.import_str(BOOKS).expect("import");
+    let mut set = rows.facts().clone();
+    set += importer.metadata().expect("metadata").into_facts();
+    let isbn = Attribute::<ShortString>::from_name("isbn");
+    let facts = rows.facts();
+    let id_of = |key: &str| {
+        find!((e: Id), pattern!(facts, [{ ?e @ isbn: key }]))
+            .map(|(e,)| e)
+            .next()
+            .expect("imported row")
+    };
+    let (dune, messiah) = (id_of("0441013597"), id_of("0441172717"));
+
+    let mut out = String::new();
+    let written = CsvWriter::new()
+        .write_entities(&set, [messiah, dune, messiah], &blobs.reader().unwrap(), &mut out)
+        .expect("export");
+    assert_eq!(written, 2);
+    let expected = format!(
+        "id,in_print,isbn,pages,price,published,title\n\
+         {messiah:x},false,0441172717,256,,1969-10-15T00:00:00 UTC,Dune Messiah\n\
+         {dune:x},true,0441013597,412,9.95,1965-08-01T00:00:00 UTC,Dune\n"
+    );
+    assert_eq!(out, expected);
+
+    // The export reads back into the same facts, keyed by the same column.
+    let again = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .with_key_column("isbn")
+        .skip_column("id")
+        .import_str(&out)
+        .expect("import");
+    assert_eq!(again.facts(), rows.facts());
+}
+
+#[test]
+fn projections_export_as_rows() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let rows = CsvImporter::<_, Blake3>::new(&mut blobs, None)
+        .import_str(BOOKS)
+        .expect("import");
+    let facts = rows.facts();
+    let title = Attribute::<ShortString>::from_name("title");
+    let pages = Attribute::<I256BE>::from_name("pages");
+    let mut found: Vec<(String, i128)> = find!(
+        (t: String, p: i128),
+        pattern!(facts, [{ title: ?t, pages: ?p }])
+    )
+    .collect();
+    found.sort();
+
+    let mut out = String::new();
+    let written = CsvWriter::new().write_rows(&["title", "pages"], found, &mut out);
+    assert_eq!(written, 2);
+    assert_eq!(out, "title,pages\nDune,412\nDune Messiah,256\n");
+}
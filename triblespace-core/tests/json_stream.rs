@@ -0,0 +1,125 @@
+//! Streaming JSON import: NDJSON and top-level arrays are split into
+//! records and committed chunk by chunk, landing on the same facts as the
+//! in-memory importers.
+
+use anybytes::View;
+use ed25519_dalek::SigningKey;
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::blob::MemoryBlobStore;
+use triblespace_core::import::json::{JsonImportError, JsonObjectImporter};
+use triblespace_core::import::json_stream::{JsonStreamError, JsonStreamImporter};
+use triblespace_core::import::json_tree::{self, kind_string};
+use triblespace_core::macros::{find, pattern};
+use triblespace_core::prelude::valueschemas::{Blake3, Handle};
+use triblespace_core::repo::memoryrepo::MemoryRepo;
+use triblespace_core::repo::{Repository, Workspace};
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::Value;
+
+const EVENTS: &str = r#"{"user": "ada", "action": "login", "tags": ["web"]}
+{"user": "bob", "action": "login"}
+
+{"user": "ada", "action": "upload", "size": 512}
+{"user": "cy", "action": "logout", "ok": true}
+{"user": "bob", "action": "logout", "session": {"length": 30}}
+"#;
+
+fn new_repo() -> Repository<MemoryRepo> {
+    let signing_key = SigningKey::from_bytes(&[0x22; 32]);
+    Repository::new(MemoryRepo::default(), signing_key, TribleSet::new()).expect("fresh repo")
+}
+
+fn commits(ws: &mut Workspace<MemoryRepo>) -> usize {
+    ws.checkout(..).expect("checkout").commits().into_iter().count()
+}
+
+#[test]
+fn ndjson_chunks_match_whole_import() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let mut seen = Vec::new();
+    let progress = JsonStreamImporter::<_, Blake3>::new(EVENTS.as_bytes(), None)
+        .with_commit_every(2)
+        .import_into(&mut ws, |progress| seen.push(progress.records))
+        .expect("stream import");
+    assert_eq!(seen, [2, 4, 5]);
+    assert_eq!(progress.commits, 3);
+    assert_eq!(progress.bytes, EVENTS.len() as u64);
+    assert_eq!(commits(&mut ws), 3);
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let mut importer = JsonObjectImporter::<_, Blake3>::new(&mut blobs, None);
+    let mut expected = TribleSet::new();
+    for line in EVENTS.lines().filter(|line| !line.is_empty()) {
+        expected += importer.import_str(line).expect("import").into_facts();
+    }
+    expected += importer.metadata().expect("metadata").into_facts();
+    let checkout = ws.checkout(..).expect("checkout");
+    assert_eq!(checkout.facts(), &expected);
+
+    repo.push(&mut ws).expect("push");
+}
+
+#[test]
+fn lossless_array_elements_become_trees() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let input = r#"[ "first", {"nested": ["second"]}, 3 ]"#;
+    let progress = JsonStreamImporter::<_, Blake3>::new(input.as_bytes(), None)
+        .lossless()
+        .import_into(&mut ws, |_| {})
+        .expect("stream import");
+    assert_eq!(progress.records, 3);
+    assert_eq!(progress.commits, 1);
+
+    let checkout = ws.checkout(..).expect("checkout");
+    let handles: Vec<Value<Handle<Blake3, LongString>>> = find!(
+        (text: Value<Handle<Blake3, LongString>>),
+        pattern!(checkout.facts(), [{ _?node @
+            json_tree::kind: kind_string,
+            json_tree::string: ?text,
+        }])
+    )
+    .map(|(text,)| text)
+    .collect();
+    let mut texts: Vec<String> = handles
+        .into_iter()
+        .map(|handle| ws.get::<View<str>, _>(handle).expect("string blob").to_string())
+        .collect();
+    texts.sort();
+    assert_eq!(texts, ["first", "second"]);
+}
+
+#[test]
+fn failing_record_keeps_earlier_chunks() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let input = "{\"a\": 1}\n{\"a\": 2}\n\"primitive\"\n";
+    let mut stream = JsonStreamImporter::<_, Blake3>::new(input.as_bytes(), None)
+        .with_commit_every(2);
+    let progress = stream.import_chunk(&mut ws).expect("first chunk");
+    assert_eq!(progress.map(|progress| progress.records), Some(2));
+    let err = stream.import_chunk(&mut ws).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            JsonStreamError::Record {
+                index: 2,
+                source: JsonImportError::PrimitiveRoot
+            }
+        ),
+        "{err}"
+    );
+    assert_eq!(commits(&mut ws), 1);
+
+    let err = JsonStreamImporter::<_, Blake3>::new("[{}, {} {}]".as_bytes(), None)
+        .import_into(&mut ws, |_| {})
+        .unwrap_err();
+    assert!(matches!(err, JsonStreamError::Record { index: 2, .. }), "{err}");
+}
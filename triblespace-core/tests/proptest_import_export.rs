@@ -1,8 +1,89 @@
 use proptest::prelude::*;
 use triblespace_core::blob::MemoryBlobStore;
+use triblespace_core::export::json_tree::export_json_tree;
 use triblespace_core::import::json::JsonObjectImporter;
+use triblespace_core::import::json_tree::JsonTreeImporter;
+use triblespace_core::repo::BlobStore;
 use triblespace_core::value::schemas::hash::Blake3;
 
+/// A JSON document as the lossless importer sees it: raw number text,
+/// ordered (possibly duplicate) object keys.
+#[derive(Debug, Clone)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+fn json_strategy() -> impl Strategy<Value = Json> {
+    let leaf = prop_oneof![
+        Just(Json::Null),
+        any::<bool>().prop_map(Json::Bool),
+        "-?(0|[1-9][0-9]{0,24})(\\.[0-9]{1,6})?([eE][+-]?[0-9]{1,3})?".prop_map(Json::Number),
+        any::<String>().prop_map(Json::String),
+    ];
+    leaf.prop_recursive(4, 48, 6, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..6).prop_map(Json::Array),
+            // A small key alphabet makes duplicate keys likely.
+            proptest::collection::vec(("[a-c\"\\\n]{0,2}", inner), 0..6).prop_map(Json::Object),
+        ]
+    })
+}
+
+/// Render `json`, putting `ws` around every token.
+fn render(json: &Json, ws: &str, out: &mut String) {
+    let quoted = |text: &str| serde_json::to_string(text).expect("strings serialise");
+    match json {
+        Json::Null => out.push_str("null"),
+        Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Json::Number(n) => out.push_str(n),
+        Json::String(s) => out.push_str(&quoted(s)),
+        Json::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(ws);
+                render(item, ws, out);
+                out.push_str(ws);
+            }
+            out.push(']');
+        }
+        Json::Object(fields) => {
+            out.push('{');
+            for (i, (key, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(ws);
+                out.push_str(&quoted(key));
+                out.push_str(ws);
+                out.push(':');
+                out.push_str(ws);
+                render(value, ws, out);
+                out.push_str(ws);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn tree_roundtrip(input: &str) -> String {
+    let mut store: MemoryBlobStore<Blake3> = MemoryBlobStore::default();
+    let frag = JsonTreeImporter::<_, Blake3>::new(&mut store, None)
+        .import_str(input)
+        .expect("valid JSON");
+    let root = frag.root().expect("rooted fragment");
+    let mut out = String::new();
+    export_json_tree(frag.facts(), root, &store.reader().unwrap(), &mut out).expect("export");
+    out
+}
+
 proptest! {
     // ── JSON import round-trip ─────────────────────────────────────────
 
@@ -114,4 +195,22 @@ proptest! {
         let result = importer.import_str(&json);
         prop_assert!(result.is_err(), "primitive root should be rejected");
     }
+
+    // ── Lossless JSON tree round-trip ──────────────────────────────────
+
+    #[test]
+    fn json_tree_export_reproduces_compact_text(json in json_strategy()) {
+        let mut compact = String::new();
+        render(&json, "", &mut compact);
+        prop_assert_eq!(tree_roundtrip(&compact), compact);
+    }
+
+    #[test]
+    fn json_tree_export_ignores_whitespace(json in json_strategy()) {
+        let mut compact = String::new();
+        render(&json, "", &mut compact);
+        let mut spaced = String::new();
+        render(&json, " \n\t", &mut spaced);
+        prop_assert_eq!(tree_roundtrip(&spaced), compact);
+    }
 }
@@ -0,0 +1,174 @@
+//! RDF exporter coverage: N-Triples imported through `ingest_ntriples`
+//! exports back to the same statements (and re-imports to the same facts),
+//! Turtle abbreviates through declared prefixes, and N-Quads names one graph
+//! per commit.
+
+use std::collections::BTreeSet;
+use std::io::Cursor;
+
+use ed25519_dalek::SigningKey;
+use triblespace_core::attribute::Attribute;
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::export::rdf::{branch_graph, commit_graph, RdfWriter};
+use triblespace_core::import::ntriples::ingest_ntriples;
+use triblespace_core::import::rdf_uri;
+use triblespace_core::macros::entity;
+use triblespace_core::prelude::valueschemas::{self, Blake3, Handle};
+use triblespace_core::prelude::BlobStore;
+use triblespace_core::repo::memoryrepo::MemoryRepo;
+use triblespace_core::repo::Repository;
+use triblespace_core::trible::{Trible, TribleSet};
+use triblespace_core::value::{ToValue, Value};
+
+fn new_repo() -> Repository<MemoryRepo> {
+    let signing_key = SigningKey::from_bytes(&[0x22; 32]);
+    Repository::new(MemoryRepo::default(), signing_key, TribleSet::new()).expect("fresh repo")
+}
+
+const NT_SAMPLE: &str = r#"<http://example.org/dune> <http://example.org/price> "9.95"^^<http://www.w3.org/2001/XMLSchema#decimal> .
+<http://example.org/dune> <http://example.org/title> "Dune" .
+<http://example.org/frank> <http://example.org/birthyear> "1920"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<http://example.org/frank> <http://example.org/firstname> "Frank" .
+<http://example.org/frank> <http://example.org/height> "1.8"^^<http://www.w3.org/2001/XMLSchema#double> .
+<http://example.org/frank> <http://example.org/living> "false"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+<http://example.org/frank> <http://example.org/motto> "Fear is the \"mind-killer\".\nCafé" .
+<http://example.org/frank> <http://example.org/novels> "23"^^<http://www.w3.org/2001/XMLSchema#nonNegativeInteger> .
+<http://example.org/frank> <http://example.org/wrote> <http://example.org/dune> .
+"#;
+
+fn lines(text: &str) -> BTreeSet<String> {
+    text.lines().map(str::to_owned).collect()
+}
+
+#[test]
+fn ntriples_roundtrip_through_import() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let (facts, count) = ingest_ntriples(&mut ws, Cursor::new(NT_SAMPLE));
+    assert_eq!(count, 9);
+    ws.commit(facts.clone(), "ntriples import");
+    repo.push(&mut ws).expect("push");
+
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut out = String::new();
+    let written = RdfWriter::new(&reader)
+        .write_ntriples(&facts, &mut out)
+        .expect("export");
+    assert_eq!(written, 9);
+    assert_eq!(lines(&out), lines(NT_SAMPLE));
+
+    // Importing the export reproduces the original facts exactly.
+    let mut ws = repo.pull(branch_id).expect("workspace");
+    let (again, _) = ingest_ntriples(&mut ws, Cursor::new(out));
+    assert_eq!(again, facts);
+}
+
+#[test]
+fn turtle_groups_subjects_and_uses_prefixes() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let data = r#"
+<http://example.org/frank> <http://example.org/wrote> <http://example.org/dune> .
+<http://example.org/frank> <http://example.org/wrote> <http://example.org/children-of-dune> .
+<http://example.org/frank> <http://example.org/birthyear> "1920"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<http://example.org/frank> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/Author> .
+"#;
+    let (facts, _) = ingest_ntriples(&mut ws, Cursor::new(data));
+    ws.commit(facts.clone(), "ntriples import");
+    repo.push(&mut ws).expect("push");
+
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut out = String::new();
+    RdfWriter::new(&reader)
+        .with_prefix("ex", "http://example.org/")
+        .write_turtle(&facts, &mut out)
+        .expect("export");
+    assert_eq!(
+        out,
+        "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+         @prefix ex: <http://example.org/> .\n\
+         \n\
+         ex:frank ex:birthyear \"1920\"^^xsd:integer ;\n    \
+         ex:wrote ex:children-of-dune, ex:dune ;\n    \
+         a ex:Author .\n"
+    );
+}
+
+#[test]
+fn nquads_name_a_graph_per_commit() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let (first, _) = ingest_ntriples(
+        &mut ws,
+        Cursor::new(r#"<http://ex/a> <http://ex/n> "1"^^<http://www.w3.org/2001/XMLSchema#integer> ."#),
+    );
+    ws.commit(first, "first");
+    let first_commit = ws.head().expect("head");
+    let (second, _) = ingest_ntriples(
+        &mut ws,
+        Cursor::new(r#"<http://ex/b> <http://ex/n> "2"^^<http://www.w3.org/2001/XMLSchema#integer> ."#),
+    );
+    ws.commit(second, "second");
+    let second_commit = ws.head().expect("head");
+    repo.push(&mut ws).expect("push");
+
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut writer = RdfWriter::new(&reader);
+    let mut out = String::new();
+    for raw in ws.checkout(..).expect("checkout").commits().iter() {
+        let commit = Value::new(*raw);
+        let facts = ws.checkout(commit).expect("checkout commit");
+        writer
+            .write_nquads(&facts, Some(&commit_graph(commit)), &mut out)
+            .expect("export");
+    }
+    let int = "\"^^<http://www.w3.org/2001/XMLSchema#integer>";
+    let expected = [
+        format!("<http://ex/a> <http://ex/n> \"1{int} <{}> .", commit_graph(first_commit)),
+        format!("<http://ex/b> <http://ex/n> \"2{int} <{}> .", commit_graph(second_commit)),
+    ];
+    assert_eq!(lines(&out), expected.into_iter().collect());
+
+    let mut branch = String::new();
+    let facts = ws.checkout(..).expect("checkout");
+    let graph = branch_graph(branch_id);
+    assert_eq!(writer.write_nquads(&facts, Some(&graph), &mut branch).unwrap(), 2);
+    assert!(branch.lines().all(|line| line.ends_with(&format!("<{graph}> ."))));
+}
+
+#[test]
+fn registered_predicates_cover_undescribed_attributes() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    // Facts written by hand: the subject carries an `rdf_uri`, but the
+    // attribute has no description in the set.
+    let uri: Value<Handle<Blake3, LongString>> = ws.put("http://ex/a".to_owned());
+    let subject = entity! { rdf_uri: uri };
+    let id = subject.root().expect("rooted");
+    let mut facts = subject.into_facts();
+    let ratio = Attribute::<valueschemas::R256BE>::from_name("http://ex/third");
+    let third: Value<valueschemas::R256BE> = num_rational::Ratio::new(1i128, 3).to_value();
+    facts.insert(&Trible::force(&id, &ratio.id(), &third));
+    ws.commit(facts.clone(), "hand-written");
+    repo.push(&mut ws).expect("push");
+
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut out = String::new();
+    assert_eq!(RdfWriter::new(&reader).write_ntriples(&facts, &mut out).unwrap(), 0);
+    RdfWriter::new(&reader)
+        .with_predicate("http://ex/third")
+        .write_ntriples(&facts, &mut out)
+        .unwrap();
+    assert_eq!(
+        out,
+        "<http://ex/a> <http://ex/third> \"1/3\"^^<http://www.w3.org/2002/07/owl#rational> .\n"
+    );
+}
@@ -0,0 +1,185 @@
+//! Streaming Turtle and JSON-LD importers: both land on the same facts as
+//! `ingest_ntriples` for the same graph, expand prefixes, shorthands and
+//! contexts, keep language tags apart, and give blank nodes fresh ids.
+#![cfg(feature = "rdf")]
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Cursor;
+
+use ed25519_dalek::SigningKey;
+use triblespace_core::export::rdf::RdfWriter;
+use triblespace_core::import::jsonld::{ingest_jsonld, ingest_jsonld_with_contexts};
+use triblespace_core::import::ntriples::ingest_ntriples;
+use triblespace_core::import::turtle::ingest_turtle;
+use triblespace_core::import::RdfImportError;
+use triblespace_core::prelude::BlobStore;
+use triblespace_core::repo::memoryrepo::MemoryRepo;
+use triblespace_core::repo::Repository;
+use triblespace_core::trible::TribleSet;
+
+fn new_repo() -> Repository<MemoryRepo> {
+    let signing_key = SigningKey::from_bytes(&[0x33; 32]);
+    Repository::new(MemoryRepo::default(), signing_key, TribleSet::new()).expect("fresh repo")
+}
+
+fn lines(text: &str) -> BTreeSet<String> {
+    text.lines().map(str::to_owned).collect()
+}
+
+const NT_SAMPLE: &str = r#"<http://example.org/frank> <http://example.org/birthyear> "1920"^^<http://www.w3.org/2001/XMLSchema#integer> .
+<http://example.org/frank> <http://example.org/height> "1.8"^^<http://www.w3.org/2001/XMLSchema#decimal> .
+<http://example.org/frank> <http://example.org/living> "false"^^<http://www.w3.org/2001/XMLSchema#boolean> .
+<http://example.org/frank> <http://example.org/name> "Frank" .
+<http://example.org/frank> <http://example.org/wrote> <http://example.org/dune> .
+"#;
+
+#[test]
+fn turtle_matches_ntriples() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let turtle = r#"
+@prefix ex: <http://example.org/> .
+@base <http://example.org/> .
+ex:frank ex:birthyear 1920 ;
+    ex:height 1.8 ;
+    ex:living false ;
+    ex:name "Frank" ;
+    ex:wrote <dune> .
+"#;
+    let (facts, count) = ingest_turtle(&mut ws, turtle.as_bytes(), None).expect("turtle");
+    assert_eq!(count, 5);
+    let (expected, _) = ingest_ntriples(&mut ws, Cursor::new(NT_SAMPLE));
+    assert_eq!(facts, expected);
+}
+
+#[test]
+fn jsonld_matches_ntriples() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let context = br#"{"@context": {
+        "ex": "http://example.org/",
+        "xsd": "http://www.w3.org/2001/XMLSchema#",
+        "birthyear": {"@id": "ex:birthyear", "@type": "xsd:integer"},
+        "height": {"@id": "ex:height", "@type": "xsd:decimal"},
+        "living": "ex:living",
+        "name": "ex:name",
+        "wrote": {"@id": "ex:wrote", "@type": "@id"}
+    }}"#;
+    let document = br#"{
+        "@context": "http://example.org/context.jsonld",
+        "@id": "http://example.org/frank",
+        "birthyear": "1920",
+        "height": "1.8",
+        "living": false,
+        "name": "Frank",
+        "wrote": "http://example.org/dune"
+    }"#;
+
+    // Remote contexts are never fetched.
+    let err = ingest_jsonld(&mut ws, &document[..], None).unwrap_err();
+    assert!(matches!(err, RdfImportError::Syntax(_)), "{err}");
+
+    let contexts = HashMap::from([(
+        "http://example.org/context.jsonld".to_owned(),
+        context.to_vec(),
+    )]);
+    let (facts, count) =
+        ingest_jsonld_with_contexts(&mut ws, &document[..], None, contexts).expect("json-ld");
+    assert_eq!(count, 5);
+    let (expected, _) = ingest_ntriples(&mut ws, Cursor::new(NT_SAMPLE));
+    assert_eq!(facts, expected);
+}
+
+#[test]
+fn language_tags_roundtrip_through_export() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let turtle = r#"
+@prefix ex: <http://example.org/> .
+ex:cat ex:label "chat"@fr, "cat"@EN, "cat" .
+"#;
+    let (facts, count) = ingest_turtle(&mut ws, turtle.as_bytes(), None).expect("turtle");
+    assert_eq!(count, 3);
+
+    let document = br#"{
+        "@context": {"label": {"@id": "http://example.org/label", "@language": "fr"}},
+        "@id": "http://example.org/cat",
+        "label": "chat"
+    }"#;
+    let (tagged, _) = ingest_jsonld(&mut ws, &document[..], None).expect("json-ld");
+    assert!(tagged.difference(&facts).is_empty());
+
+    ws.commit(facts.clone(), "turtle import");
+    repo.push(&mut ws).expect("push");
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut out = String::new();
+    RdfWriter::new(&reader)
+        .write_ntriples(&facts, &mut out)
+        .expect("export");
+    assert_eq!(
+        lines(&out),
+        lines(
+            r#"<http://example.org/cat> <http://example.org/label> "cat" .
+<http://example.org/cat> <http://example.org/label> "cat"@en .
+<http://example.org/cat> <http://example.org/label> "chat"@fr .
+"#
+        )
+    );
+}
+
+#[test]
+fn blank_nodes_get_fresh_ids_per_import() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let turtle = r#"
+@prefix ex: <http://example.org/> .
+ex:frank ex:address [ ex:city "Tacoma" ] ;
+    ex:knows _:b, _:b .
+_:b ex:name "Bev" .
+"#;
+    let (first, count) = ingest_turtle(&mut ws, turtle.as_bytes(), None).expect("turtle");
+    assert_eq!(count, 5);
+    let (second, _) = ingest_turtle(&mut ws, turtle.as_bytes(), None).expect("turtle");
+    assert_ne!(first, second);
+
+    ws.commit(first.clone(), "turtle import");
+    repo.push(&mut ws).expect("push");
+    let reader = repo.storage_mut().reader().expect("reader");
+    let mut out = String::new();
+    let written = RdfWriter::new(&reader)
+        .write_ntriples(&first, &mut out)
+        .expect("export");
+    // `_:b` is used twice but is one node, and both blank nodes are written
+    // with labels that N-Triples reads back as blank nodes.
+    assert_eq!(written, 4);
+    let blanks: BTreeSet<&str> = out
+        .split_whitespace()
+        .filter(|token| token.starts_with("_:"))
+        .collect();
+    assert_eq!(blanks.len(), 2);
+
+    let mut ws = repo.pull(branch_id).expect("workspace");
+    let (again, count) = ingest_ntriples(&mut ws, Cursor::new(out.clone()));
+    assert_eq!(count, 4);
+    assert_eq!(again.len(), first.len());
+}
+
+#[test]
+fn syntax_errors_are_reported() {
+    let mut repo = new_repo();
+    let branch_id = repo.ensure_branch("main", None).expect("branch");
+    let mut ws = repo.pull(branch_id).expect("workspace");
+
+    let err = ingest_turtle(&mut ws, &b"ex:frank ex:name \"Frank\" ."[..], None).unwrap_err();
+    assert!(matches!(err, RdfImportError::Syntax(_)), "{err}");
+    let err = ingest_turtle(&mut ws, &b""[..], Some("not an iri")).unwrap_err();
+    assert!(matches!(err, RdfImportError::InvalidBaseIri(_)), "{err}");
+}
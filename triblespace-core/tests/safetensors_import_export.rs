@@ -0,0 +1,163 @@
+//! Safetensors coverage: files import into tensor entities that load as
+//! typed views, and export back into the same file.
+
+use half::bf16;
+use triblespace_core::blob::schemas::array::elements::{BF16, F32};
+use triblespace_core::blob::schemas::array::{Array, Tensor};
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::blob::MemoryBlobStore;
+use triblespace_core::export::safetensors::export_safetensors;
+use triblespace_core::export::ExportError;
+use triblespace_core::import::safetensors::{import_safetensors, SafetensorsImportError};
+use triblespace_core::metadata;
+use triblespace_core::prelude::valueschemas::{Blake3, Handle};
+use triblespace_core::prelude::*;
+
+/// A safetensors file with `header` and the tensor `data`.
+fn file(header: &str, data: &[u8]) -> Vec<u8> {
+    let mut out = (header.len() as u64).to_le_bytes().to_vec();
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn named(facts: &TribleSet, reader: &impl BlobStoreGet<Blake3>, name: &str) -> Id {
+    find!(
+        (tensor: Id, handle: Value<Handle<Blake3, LongString>>),
+        pattern!(facts, [{ ?tensor @ metadata::name: ?handle }])
+    )
+    .find(|(_, handle)| {
+        let text: View<str> = reader.get(*handle).expect("name");
+        &*text == name
+    })
+    .map(|(tensor, _)| tensor)
+    .expect("tensor")
+}
+
+#[test]
+fn tensors_import_load_and_export_again() {
+    let mut data = Vec::new();
+    for x in [0.5f32, -1.0, 2.0] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    for x in [1.0f32, 2.0, 3.0, 4.0] {
+        data.extend_from_slice(&bf16::from_f32(x).to_le_bytes());
+    }
+    let header = r#"{"__metadata__":{"format":"pt"},"bias":{"dtype":"F32","shape":[3],"data_offsets":[0,12]},"embed":{"dtype":"BF16","shape":[2,2],"data_offsets":[12,20]}}"#;
+    let bytes = file(header, &data);
+
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let imported = import_safetensors(&mut blobs, &bytes).expect("import");
+    let tensors: Vec<Id> = imported.exports().collect();
+    assert_eq!(tensors.len(), 2);
+    let facts = imported.into_facts();
+    let reader = blobs.reader().unwrap();
+
+    let bias = Tensor::<F32>::load(&facts, named(&facts, &reader, "bias"), &reader).expect("bias");
+    assert_eq!(bias.as_slice(), Some(&[0.5, -1.0, 2.0][..]));
+    let embed = Tensor::<BF16>::load(&facts, named(&facts, &reader, "embed"), &reader).expect("embed");
+    assert_eq!(embed.shape(), [2, 2]);
+    assert_eq!(embed.get(&[1, 0]), Some(&bf16::from_f32(3.0)));
+
+    let exported = export_safetensors(&facts, tensors, &reader).expect("export");
+    let header_len = u64::from_le_bytes(exported[..8].try_into().unwrap());
+    assert_eq!(header_len % 8, 0);
+    assert_eq!(&exported[8 + header_len as usize..], &data[..]);
+
+    // The exported file drops `__metadata__` but otherwise round-trips.
+    let mut again = MemoryBlobStore::<Blake3>::new();
+    let reimported = import_safetensors(&mut again, &exported).expect("reimport");
+    assert_eq!(reimported.facts(), &facts);
+    let reader = again.reader().unwrap();
+    let tensors: Vec<Id> = reimported.exports().collect();
+    assert_eq!(
+        export_safetensors(reimported.facts(), tensors, &reader).expect("export again"),
+        exported
+    );
+}
+
+#[test]
+fn strided_tensors_export_row_major() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let tensor = ufoid();
+    let matrix: Value<Handle<Blake3, Array<F32>>> =
+        blobs.put(vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    // The 2x3 matrix read as its 3x2 transpose.
+    let facts = entity! { &tensor @
+        metadata::name: blobs.put("transposed").unwrap(),
+        metadata::tensor_data: matrix.transmute(),
+        metadata::tensor_dtype: F32::ID,
+        metadata::tensor_shape: blobs.put(vec![3u64, 2]).unwrap(),
+        metadata::tensor_strides: blobs.put(vec![1u64, 3]).unwrap(),
+    }
+    .into_facts();
+    let reader = blobs.reader().unwrap();
+
+    let view = Tensor::<F32>::load(&facts, *tensor, &reader).expect("load");
+    assert_eq!(view.get(&[2, 1]), Some(&6.0));
+
+    let exported = export_safetensors(&facts, [*tensor], &reader).expect("export");
+    let mut again = MemoryBlobStore::<Blake3>::new();
+    let reimported = import_safetensors(&mut again, &exported).expect("reimport");
+    let reader = again.reader().unwrap();
+    let root = reimported.exports().next().expect("tensor");
+    let contiguous = Tensor::<F32>::load(reimported.facts(), root, &reader).expect("load");
+    assert_eq!(contiguous.shape(), [3, 2]);
+    assert_eq!(contiguous.as_slice(), Some(&[1.0, 4.0, 2.0, 5.0, 3.0, 6.0][..]));
+}
+
+#[test]
+fn broadcast_layouts_are_bounded_by_their_elements() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    let scalar: Value<Handle<Blake3, Array<F32>>> = blobs.put(vec![1.0f32]).unwrap();
+    let broadcast = |blobs: &mut MemoryBlobStore<Blake3>, name: &str, side: u64| {
+        let tensor = ufoid();
+        let facts = entity! { &tensor @
+            metadata::name: blobs.put(name.to_string()).unwrap(),
+            metadata::tensor_data: scalar.transmute(),
+            metadata::tensor_dtype: F32::ID,
+            metadata::tensor_shape: blobs.put(vec![side, side]).unwrap(),
+            metadata::tensor_strides: blobs.put(vec![0u64, 0]).unwrap(),
+        }
+        .into_facts();
+        (*tensor, facts)
+    };
+
+    // A small broadcast is materialised.
+    let (tensor, facts) = broadcast(&mut blobs, "ones", 3);
+    let reader = blobs.reader().unwrap();
+    let exported = export_safetensors(&facts, [tensor], &reader).expect("export");
+    let mut again = MemoryBlobStore::<Blake3>::new();
+    let reimported = import_safetensors(&mut again, &exported).expect("reimport");
+    let reader = again.reader().unwrap();
+    let root = reimported.exports().next().expect("tensor");
+    let ones = Tensor::<F32>::load(reimported.facts(), root, &reader).expect("load");
+    assert_eq!(ones.as_slice(), Some(&[1.0; 9][..]));
+
+    // A huge one is rejected before anything is allocated.
+    let (tensor, facts) = broadcast(&mut blobs, "everything", 1 << 30);
+    let reader = blobs.reader().unwrap();
+    assert!(matches!(
+        export_safetensors(&facts, [tensor], &reader),
+        Err(ExportError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let mut blobs = MemoryBlobStore::<Blake3>::new();
+    assert!(matches!(
+        import_safetensors(&mut blobs, &[1, 0]),
+        Err(SafetensorsImportError::Truncated)
+    ));
+    let header = r#"{"mask":{"dtype":"BOOL","shape":[2],"data_offsets":[0,2]}}"#;
+    assert!(matches!(
+        import_safetensors(&mut blobs, &file(header, &[0, 1])),
+        Err(SafetensorsImportError::UnsupportedDtype { dtype, .. }) if dtype == "BOOL"
+    ));
+    let header = r#"{"bias":{"dtype":"F32","shape":[3],"data_offsets":[0,8]}}"#;
+    assert!(matches!(
+        import_safetensors(&mut blobs, &file(header, &[0; 8])),
+        Err(SafetensorsImportError::InvalidTensor { .. })
+    ));
+}
@@ -26,6 +26,14 @@ pub enum NetCommand {
     /// reachable from its head and materialize a tracking branch
     /// (fire-and-forget — results arrive via `NetEvent`s).
     Track { peer: iroh_base::EndpointId, branch: RawBranchId },
+    /// Distribute a team-root revocation (fire-and-forget): broadcast
+    /// it on the gossip topic, store it in the DHT record keyed by the
+    /// team root, and drop inbound connections it invalidates. Carries
+    /// the rev blob bytes and the team root's signature over them —
+    /// the sig blob is rebuilt by the receiver. The DHT record is
+    /// re-stored on every revocation refresh for as long as the node
+    /// runs.
+    PublishRevocation { rev: Vec<u8>, signature: [u8; 64] },
 
     /// RPC: list a remote peer's branches. One protocol round trip.
     /// Replies with the (branch_id, branch_metadata_blob_hash) pairs.
@@ -51,6 +59,21 @@ pub enum NetCommand {
     },
 }
 
+/// How a remote branch HEAD reached this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadOrigin {
+    /// Requested explicitly via [`NetCommand::Track`]. Carries the handle
+    /// of the head signer's capability sig blob when the serving peer's
+    /// `OP_HEAD` reply included one; the chain is fetched ahead of the
+    /// head, as for gossip.
+    Pulled { cap: Option<RawHash> },
+    /// Broadcast over the gossip topic. Carries the handle of the
+    /// publisher's capability sig blob when the message included one;
+    /// the chain blobs are fetched ahead of the head so the receiver
+    /// can verify the publisher's write permission locally.
+    Gossip { cap: Option<RawHash> },
+}
+
 /// Events received from the network thread.
 #[derive(Debug)]
 pub enum NetEvent {
@@ -58,5 +81,10 @@ pub enum NetEvent {
     Blob(Vec<u8>),
     /// A remote branch HEAD was learned (via gossip or fetch).
     /// Includes the publisher's public key for provenance.
-    Head { branch: RawBranchId, head: RawHash, publisher: PublisherKey },
+    Head {
+        branch: RawBranchId,
+        head: RawHash,
+        publisher: PublisherKey,
+        origin: HeadOrigin,
+    },
 }
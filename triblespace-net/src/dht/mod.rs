@@ -148,6 +148,18 @@ pub mod rpc {
         pub data: Vec<u8>,
     }
 
+    impl ED25519SignedMessage {
+        /// Check the signature over `data` against the record key, read
+        /// as the signer's public key.
+        pub fn verify(&self, key: &Id) -> bool {
+            let Ok(signer) = ed25519_dalek::VerifyingKey::from_bytes(key) else {
+                return false;
+            };
+            let signature = ed25519_dalek::Signature::from_bytes(&self.signature);
+            signer.verify_strict(&self.data, &signature).is_ok()
+        }
+    }
+
     /// DHT value type.
     ///
     /// The order of the enum is important for serialization/deserialization
@@ -393,7 +405,7 @@ pub mod api {
 
     use crate::dht::{
         now,
-        rpc::{Blake3Immutable, Id, Kind, Value},
+        rpc::{Blake3Immutable, ED25519SignedMessage, Id, Kind, Value},
     };
 
     #[rpc_requests(message = ApiMessage)]
@@ -548,6 +560,82 @@ pub mod api {
             Ok((hash, res))
         }
 
+        /// Store a message signed by `key` under that key. `data` must
+        /// be at most 1024 bytes and `signature` an ed25519 signature
+        /// over it; storing nodes reject records that don't verify.
+        pub async fn put_signed(
+            &self,
+            key: [u8; 32],
+            signature: [u8; 64],
+            data: Vec<u8>,
+        ) -> irpc::Result<Vec<EndpointId>> {
+            let mut rx = self
+                .0
+                .server_streaming(
+                    NetworkPut {
+                        id: Id::from(key),
+                        value: Value::ED25519SignedMessage(ED25519SignedMessage {
+                            timestamp: now(),
+                            signature,
+                            data,
+                        }),
+                    },
+                    32,
+                )
+                .await?;
+            let mut stored_on = Vec::new();
+            loop {
+                match rx.recv().await {
+                    Ok(Some(id)) => stored_on.push(id),
+                    Ok(None) => break,
+                    Err(_) => break,
+                }
+            }
+            Ok(stored_on)
+        }
+
+        /// Collect the signed messages stored under `key`. Values whose
+        /// signature doesn't verify against the key are skipped, as are
+        /// duplicates returned by several nodes.
+        pub async fn get_signed(
+            &self,
+            key: [u8; 32],
+        ) -> irpc::Result<Vec<ED25519SignedMessage>> {
+            let id = Id::from(key);
+            let mut rx = self
+                .0
+                .server_streaming(
+                    NetworkGet {
+                        id,
+                        kind: Kind::ED25519SignedMessage,
+                        seed: None,
+                        n: None,
+                    },
+                    32,
+                )
+                .await?;
+            let mut messages: Vec<ED25519SignedMessage> = Vec::new();
+            loop {
+                match rx.recv().await {
+                    Ok(Some((_from, value))) => {
+                        let Value::ED25519SignedMessage(message) = value else {
+                            continue;
+                        };
+                        if message.verify(&id)
+                            && !messages.iter().any(|m| {
+                                m.signature == message.signature && m.data == message.data
+                            })
+                        {
+                            messages.push(message);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(_) => break,
+                }
+            }
+            Ok(messages)
+        }
+
         /// Announce that this node provides the blob with the given blake3 hash.
         pub async fn announce_provider(
             &self,
@@ -1707,9 +1795,17 @@ where
     async fn handle_rpc(&mut self, message: RpcMessage) {
         match message {
             RpcMessage::Set(msg) => {
+                // Signed messages must verify against their key; anything
+                // else is only as trustworthy as its sender.
+                if let Value::ED25519SignedMessage(signed) = &msg.value
+                    && (signed.data.len() > 1024 || !signed.verify(&msg.key))
+                {
+                    msg.tx.send(SetResponse::ErrInvalid).await.ok();
+                    return;
+                }
                 // just set the value in the local storage
                 //
-                // TODO: check if the data is expired or invalid and return the
+                // TODO: check if the data is expired and return the
                 // appropriate error response.
                 //
                 // Sanity check that this node is a good node to store
@@ -7,14 +7,15 @@
 //!
 //! Async is jailed inside the spawned thread.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 
 use iroh_base::EndpointId;
 use ed25519_dalek::SigningKey;
 
-use crate::channel::{NetCommand, NetEvent};
+use crate::channel::{HeadOrigin, NetCommand, NetEvent};
 use crate::identity::iroh_secret;
 use crate::protocol::*;
 
@@ -140,6 +141,74 @@ where
     }
 }
 
+// ── Gossip messages ──────────────────────────────────────────────────
+
+/// Gossip HEAD message: tag + branch(16) + head(32) + publisher(32) +
+/// publisher cap sig handle(32) = 113 bytes.
+const GOSSIP_HEAD: u8 = 0x02;
+const GOSSIP_HEAD_LEN: usize = 113;
+/// Pre-capability HEAD message without the trailing cap handle (81
+/// bytes). Still parsed so such heads surface as rejections instead of
+/// vanishing silently.
+const GOSSIP_HEAD_V1: u8 = 0x01;
+const GOSSIP_HEAD_V1_LEN: usize = 81;
+
+/// A decoded gossip HEAD announcement.
+#[derive(Debug, PartialEq, Eq)]
+struct GossipHead {
+    branch: RawBranchId,
+    head: RawHash,
+    publisher: crate::channel::PublisherKey,
+    cap: Option<RawHash>,
+}
+
+fn parse_gossip_head(content: &[u8]) -> Option<GossipHead> {
+    let cap = match (content.first()?, content.len()) {
+        (&GOSSIP_HEAD, GOSSIP_HEAD_LEN) => {
+            let mut cap = [0u8; 32];
+            cap.copy_from_slice(&content[81..113]);
+            Some(cap)
+        }
+        (&GOSSIP_HEAD_V1, GOSSIP_HEAD_V1_LEN) => None,
+        _ => return None,
+    };
+    let mut branch = [0u8; 16];
+    branch.copy_from_slice(&content[1..17]);
+    let mut head = [0u8; 32];
+    head.copy_from_slice(&content[17..49]);
+    let mut publisher = [0u8; 32];
+    publisher.copy_from_slice(&content[49..81]);
+    Some(GossipHead { branch, head, publisher, cap })
+}
+
+/// Gossip revocation message: tag + team root signature(64) + rev blob
+/// bytes. The sig blob is rebuilt on arrival with the team root as
+/// revoker, so only revocations the team root signed survive.
+const GOSSIP_REVOCATION: u8 = 0x03;
+/// Upper bound on the rev blob carried by a revocation message — the
+/// same limit the DHT puts on signed records.
+const MAX_REVOCATION_LEN: usize = 1024;
+
+fn encode_gossip_revocation(signature: &[u8; 64], rev: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(1 + 64 + rev.len());
+    msg.push(GOSSIP_REVOCATION);
+    msg.extend_from_slice(signature);
+    msg.extend_from_slice(rev);
+    msg
+}
+
+fn parse_gossip_revocation(content: &[u8]) -> Option<([u8; 64], &[u8])> {
+    if content.first()? != &GOSSIP_REVOCATION
+        || content.len() <= 65
+        || content.len() > 65 + MAX_REVOCATION_LEN
+    {
+        return None;
+    }
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&content[1..65]);
+    Some((signature, &content[65..]))
+}
+
 // ── Outgoing half ────────────────────────────────────────────────────
 
 /// Send commands to the host thread + update the serving snapshot.
@@ -154,13 +223,25 @@ pub struct NetSender {
     cmd_tx: mpsc::Sender<NetCommand>,
     snapshot: Arc<Mutex<Option<Box<dyn AnySnapshot>>>>,
     revoked: Arc<std::sync::RwLock<HashSet<ed25519_dalek::VerifyingKey>>>,
+    reachability: Arc<Mutex<ReachabilityIndex>>,
     team_root: ed25519_dalek::VerifyingKey,
     id: EndpointId,
+    /// Set once the first snapshot has been scanned for revocations.
+    scanned: Arc<AtomicBool>,
 }
 
 impl NetSender {
     pub fn id(&self) -> EndpointId { self.id }
 
+    /// The team root every capability chain must lead back to.
+    pub fn team_root(&self) -> ed25519_dalek::VerifyingKey { self.team_root }
+
+    /// A copy of the live revoked set (boot-time revocations plus any
+    /// picked up by `update_snapshot` or from the network since).
+    pub fn revoked(&self) -> HashSet<ed25519_dalek::VerifyingKey> {
+        self.revoked.read().unwrap().clone()
+    }
+
     pub fn announce(&self, hash: RawHash) {
         let _ = self.cmd_tx.send(NetCommand::Announce(hash));
     }
@@ -219,32 +300,46 @@ impl NetSender {
         // move the same box into the snapshot Arc afterwards.
         let boxed: Box<dyn AnySnapshot> = Box::new(snapshot);
 
-        // Rescan for revocations gossiped into the pile since the last
+        // Rescan for revocations written into the pile since the last
         // snapshot. Authorisation policy: only revocations signed by
         // the configured team root take effect.
-        let mut authorised: HashSet<ed25519_dalek::VerifyingKey> =
-            HashSet::new();
-        authorised.insert(self.team_root);
         let pairs = triblespace_core::repo::capability::extract_revocation_pairs(
             boxed.all_simple_archive_blobs(),
         );
-        let scanned: HashSet<ed25519_dalek::VerifyingKey> =
-            triblespace_core::repo::capability::build_revocation_set(
-                &authorised, pairs,
-            );
 
         // Union into the live set — the relay's revoked set is
         // monotonically growing. Boot-time revocations stay in even if
         // the corresponding blob is later GC'd from the pile, and a
-        // newly-gossiped revocation lands here without a restart.
-        if !scanned.is_empty() {
-            let mut guard = self.revoked.write().unwrap();
-            for k in scanned {
-                guard.insert(k);
+        // newly-written revocation lands here without a restart. The
+        // first snapshot publishes every team-root revocation it holds —
+        // the live set is seeded from `PeerConfig::revoked`, so those
+        // would otherwise never reach the team. After that only targets
+        // new to the set are published; ones already known (including
+        // those that arrived over the network) are not sent again.
+        let first = !self.scanned.swap(true, Ordering::SeqCst);
+        for (rev_blob, sig_blob) in pairs {
+            use triblespace_core::repo::capability::{revocation_signature, verify_revocation};
+            let Some(signature) = revocation_signature(sig_blob.clone()) else { continue };
+            let rev = rev_blob.bytes.to_vec();
+            let Ok((revoker, target)) = verify_revocation(rev_blob, sig_blob) else { continue };
+            if revoker != self.team_root {
+                continue;
+            }
+            if self.revoked.write().unwrap().insert(target) || first {
+                let _ = self.cmd_tx.send(NetCommand::PublishRevocation {
+                    rev,
+                    signature: signature.to_bytes(),
+                });
             }
         }
 
-        *self.snapshot.lock().unwrap() = Some(boxed);
+        // Swap the snapshot and drop the cached reachability sets under
+        // the snapshot lock. The handler takes the snapshot lock before
+        // the index lock, so it can never pair the new snapshot with
+        // closures computed against the old one.
+        let mut guard = self.snapshot.lock().unwrap();
+        *guard = Some(boxed);
+        self.reachability.lock().unwrap().clear();
     }
 }
 
@@ -276,9 +371,12 @@ pub fn spawn(key: SigningKey, config: PeerConfig) -> (NetSender, NetReceiver) {
         Arc::new(Mutex::new(None));
     let revoked: Arc<std::sync::RwLock<HashSet<ed25519_dalek::VerifyingKey>>> =
         Arc::new(std::sync::RwLock::new(config.revoked.clone()));
+    let reachability: Arc<Mutex<ReachabilityIndex>> =
+        Arc::new(Mutex::new(ReachabilityIndex::default()));
     let team_root = config.team_root;
     let thread_snapshot = snapshot.clone();
     let thread_revoked = revoked.clone();
+    let thread_reachability = reachability.clone();
 
     let _thread = thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().expect("tokio runtime");
@@ -289,6 +387,7 @@ pub fn spawn(key: SigningKey, config: PeerConfig) -> (NetSender, NetReceiver) {
             evt_tx,
             thread_snapshot,
             thread_revoked,
+            thread_reachability,
         ));
     });
 
@@ -296,8 +395,10 @@ pub fn spawn(key: SigningKey, config: PeerConfig) -> (NetSender, NetReceiver) {
         cmd_tx,
         snapshot,
         revoked,
+        reachability,
         team_root,
         id,
+        scanned: Arc::new(AtomicBool::new(false)),
     };
     let receiver = NetReceiver { evt_rx };
     (sender, receiver)
@@ -329,6 +430,7 @@ async fn host_loop(
     events: mpsc::Sender<NetEvent>,
     snapshot: Arc<Mutex<Option<Box<dyn AnySnapshot>>>>,
     revoked: Arc<std::sync::RwLock<HashSet<ed25519_dalek::VerifyingKey>>>,
+    reachability: Arc<Mutex<ReachabilityIndex>>,
 ) {
     use iroh::endpoint::presets;
     use iroh::protocol::Router;
@@ -351,10 +453,23 @@ async fn host_loop(
     // so `update_snapshot` can extend it from sync code (revocations
     // gossiped into the pile) and the handler reads the latest value
     // on every OP_AUTH.
+    let connections: AuthedConnections = Arc::new(Mutex::new(HashMap::new()));
     let handler = SnapshotHandler {
         snapshot: snapshot.clone(),
         team_root: config.team_root,
         revoked: revoked.clone(),
+        reachability,
+        self_cap,
+        connections: connections.clone(),
+    };
+    // Revocations learned from gossip or the DHT take effect through
+    // the same shared set and connection registry.
+    let revocations = RevocationSink {
+        team_root: config.team_root,
+        revoked: revoked.clone(),
+        snapshot: snapshot.clone(),
+        connections,
+        events: events.clone(),
     };
     router_builder = router_builder.accept(PILE_SYNC_ALPN, handler);
 
@@ -398,20 +513,17 @@ async fn host_loop(
             let events_tx = events.clone();
             let ep2 = ep.clone();
             let dht_api2 = dht_api.clone();
+            let revocations2 = revocations.clone();
             tokio::spawn(async move {
                 let mut receiver = receiver;
                 while let Ok(Some(event)) = receiver.try_next().await {
                     match &event {
                         iroh_gossip::api::Event::Received(msg) => {
-                            // Gossip HEAD message: 0x01 + branch(16) + head(32) + publisher(32) = 81 bytes
-                            if msg.content.len() == 81 && msg.content[0] == 0x01 {
-                                let mut branch = [0u8; 16];
-                                branch.copy_from_slice(&msg.content[1..17]);
-                                let mut head = [0u8; 32];
-                                head.copy_from_slice(&msg.content[17..49]);
-                                let mut publisher = [0u8; 32];
-                                publisher.copy_from_slice(&msg.content[49..81]);
-
+                            if let Some((signature, rev)) = parse_gossip_revocation(&msg.content) {
+                                revocations2.receive(&signature, rev);
+                            } else if let Some(GossipHead { branch, head, publisher, cap }) =
+                                parse_gossip_head(&msg.content)
+                            {
                                 let ep2 = ep2.clone();
                                 let events_tx2 = events_tx.clone();
                                 let dht2 = dht_api2.clone();
@@ -423,8 +535,15 @@ async fn host_loop(
                                     msg.delivered_from.into()
                                 };
                                 tokio::spawn(async move {
+                                    // Pull the publisher's credential chain first so
+                                    // it is in the store by the time the Peer sees
+                                    // the head and checks write permission.
+                                    if let Some(cap) = cap {
+                                        fetch_capability_chain(&ep2, fetch_peer, cap, &dht2, &events_tx2, &self_cap2).await;
+                                    }
                                     eprintln!("[net] fetching HEAD {} from publisher {}", hex::encode(&head[..4]), hex::encode(&publisher[..4]));
-                                    track_known_head(&ep2, fetch_peer, branch, head, publisher, &dht2, &events_tx2, &self_cap2).await;
+                                    let origin = HeadOrigin::Gossip { cap };
+                                    track_known_head(&ep2, fetch_peer, branch, head, publisher, origin, &dht2, &events_tx2, &self_cap2).await;
                                 });
                             }
                         }
@@ -444,7 +563,40 @@ async fn host_loop(
     let _router = router_builder.spawn();
 
     // Command loop.
+    let mut last_revocation_refresh: Option<std::time::Instant> = None;
+    // Revocations handed to us via `PublishRevocation`, keyed by the
+    // team root's signature — republished to the DHT on every refresh.
+    let mut published: HashMap<[u8; 64], Vec<u8>> = HashMap::new();
     loop {
+        // Pick up revocations from the team root's DHT record: at
+        // startup, then every REVOCATION_REFRESH. The same tick re-sweeps
+        // the authenticated connections, which drops those whose cap
+        // has expired since they authenticated, and re-stores the
+        // revocations this node published so the record does not
+        // expire out of the DHT.
+        if last_revocation_refresh.is_none_or(|t| t.elapsed() >= REVOCATION_REFRESH) {
+            last_revocation_refresh = Some(std::time::Instant::now());
+            revocations.sweep();
+            if let Some(api) = &dht_api {
+                let key = config.team_root.to_bytes();
+                for (signature, rev) in &published {
+                    let api = api.clone();
+                    let (signature, rev) = (*signature, rev.clone());
+                    tokio::spawn(async move {
+                        let _ = api.put_signed(key, signature, rev).await;
+                    });
+                }
+                let api = api.clone();
+                let revocations = revocations.clone();
+                tokio::spawn(async move {
+                    if let Ok(messages) = api.get_signed(revocations.team_root.to_bytes()).await {
+                        for message in messages {
+                            revocations.receive(&message.signature, &message.data);
+                        }
+                    }
+                });
+            }
+        }
         while let Ok(cmd) = commands.try_recv() {
             match cmd {
                 NetCommand::Announce(hash) => {
@@ -458,17 +610,36 @@ async fn host_loop(
                 }
                 NetCommand::Gossip { branch, head } => {
                     if let Some(sender) = &gossip_sender {
-                        let mut msg = Vec::with_capacity(81);
-                        msg.push(0x01);
+                        let mut msg = Vec::with_capacity(GOSSIP_HEAD_LEN);
+                        msg.push(GOSSIP_HEAD);
                         msg.extend_from_slice(&branch);
                         msg.extend_from_slice(&head);
                         msg.extend_from_slice(my_id.as_bytes());
+                        msg.extend_from_slice(&self_cap);
                         let sender = sender.clone();
                         tokio::spawn(async move {
                             let _ = sender.broadcast(msg.into()).await;
                         });
                     }
                 }
+                NetCommand::PublishRevocation { rev, signature } => {
+                    revocations.sweep();
+                    published.insert(signature, rev.clone());
+                    if let Some(sender) = &gossip_sender {
+                        let msg = encode_gossip_revocation(&signature, &rev);
+                        let sender = sender.clone();
+                        tokio::spawn(async move {
+                            let _ = sender.broadcast(msg.into()).await;
+                        });
+                    }
+                    if let Some(api) = &dht_api {
+                        let api = api.clone();
+                        let key = config.team_root.to_bytes();
+                        tokio::spawn(async move {
+                            let _ = api.put_signed(key, signature, rev).await;
+                        });
+                    }
+                }
                 NetCommand::Track { peer, branch } => {
                     let ep = ep.clone();
                     let events_tx = events.clone();
@@ -481,17 +652,23 @@ async fn host_loop(
                             Ok(c) => c,
                             Err(e) => { eprintln!("[net] connect: {e}"); return; }
                         };
-                        let head = match op_head(&conn, &branch).await {
+                        let (head, cap) = match op_head(&conn, &branch).await {
                             Ok(Some(h)) => h,
                             Ok(None) => { eprintln!("[net] no head"); return; }
                             Err(e) => { eprintln!("[net] head: {e}"); return; }
                         };
                         conn.close(0u32.into(), b"ok");
                         // For explicit track, the publisher is the peer
-                        // we asked (they vouched for this head).
+                        // we asked. It only serves the head; `cap` is the
+                        // signer's credential, and the signer is the one
+                        // who has to be allowed to write the branch.
                         let mut publisher = [0u8; 32];
                         publisher.copy_from_slice(peer.as_bytes());
-                        track_known_head(&ep, peer, branch, head, publisher, &dht, &events_tx, &self_cap).await;
+                        if let Some(cap) = cap {
+                            fetch_capability_chain(&ep, peer, cap, &dht, &events_tx, &self_cap).await;
+                        }
+                        let origin = HeadOrigin::Pulled { cap };
+                        track_known_head(&ep, peer, branch, head, publisher, origin, &dht, &events_tx, &self_cap).await;
                     });
                 }
                 NetCommand::ListBranches { peer, reply } => {
@@ -521,7 +698,7 @@ async fn host_loop(
                             let conn = connect_authed(&ep, peer, &self_cap).await?;
                             let head = op_head(&conn, &branch).await?;
                             conn.close(0u32.into(), b"ok");
-                            Ok(head)
+                            Ok(head.map(|(head, _cap)| head))
                         }.await;
                         let _ = reply.send(result);
                     });
@@ -636,13 +813,18 @@ async fn fetch_reachable(
 /// Shared tail of the gossip-arrival handler and the `Track` command:
 /// both know (fetch_peer, branch, head, publisher) by the time they
 /// get here. Gossip gets the head directly from the broadcast message;
-/// `Track` asks the peer via `op_head` first.
+/// `Track` asks the peer via `op_head` first. Whether the head is
+/// accepted is decided by the Peer (see
+/// [`authorize_head`](crate::tracking::authorize_head)), using the
+/// cap carried by `origin`.
+#[allow(clippy::too_many_arguments)]
 async fn track_known_head(
     ep: &iroh::Endpoint,
     fetch_peer: EndpointId,
     branch: RawBranchId,
     head: RawHash,
     publisher: crate::channel::PublisherKey,
+    origin: HeadOrigin,
     dht: &Option<crate::dht::api::ApiClient>,
     events: &mpsc::Sender<NetEvent>,
     self_cap: &RawHash,
@@ -650,7 +832,179 @@ async fn track_known_head(
     if let Err(e) = fetch_reachable(ep, fetch_peer, &head, dht, events, self_cap).await {
         eprintln!("[net] fetch error: {e}");
     } else {
-        let _ = events.send(NetEvent::Head { branch, head, publisher });
+        let _ = events.send(NetEvent::Head { branch, head, publisher, origin });
+    }
+}
+
+/// Fetch every blob of the capability chain whose leaf sig blob is
+/// `cap`, emitting each as a [`NetEvent::Blob`] so it lands in the
+/// local store. Stops quietly at the first blob that can't be fetched
+/// or at [`MAX_CHAIN_DEPTH`](triblespace_core::repo::capability::MAX_CHAIN_DEPTH);
+/// an incomplete chain simply fails verification later.
+async fn fetch_capability_chain(
+    ep: &iroh::Endpoint,
+    peer: EndpointId,
+    cap: RawHash,
+    dht: &Option<crate::dht::api::ApiClient>,
+    events: &mpsc::Sender<NetEvent>,
+    self_cap: &RawHash,
+) {
+    use triblespace_core::blob::Blob;
+    use triblespace_core::repo::capability::{MAX_CHAIN_DEPTH, chain_successor};
+
+    let mut next = Some(cap);
+    // Leaf sig blob plus up to MAX_CHAIN_DEPTH + 1 cap blobs.
+    for _ in 0..MAX_CHAIN_DEPTH + 2 {
+        let Some(hash) = next else { return };
+        let data = match fetch_blob(ep, &hash, dht, peer, self_cap).await {
+            Ok(Some(data)) => data,
+            Ok(None) => return,
+            Err(e) => { eprintln!("[net] cap chain fetch: {e}"); return; }
+        };
+        let blob = Blob::new(anybytes::Bytes::from_source(data.clone()));
+        next = chain_successor(&blob).map(|h| h.raw);
+        let _ = events.send(NetEvent::Blob(data));
+    }
+}
+
+// ── Revocations ──────────────────────────────────────────────────────
+
+/// How often the DHT record of team-root revocations is re-read, so a
+/// node that missed the gossip broadcast (offline, not yet subscribed)
+/// still converges on the team's revoked set. The revocations this node
+/// published are re-stored on the same tick, and authenticated
+/// connections are re-verified at the same pace.
+const REVOCATION_REFRESH: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// QUIC close code used when a connection's capability gets revoked.
+const CLOSE_REVOKED: u32 = 1;
+
+/// QUIC close code used when a connection's capability stops verifying
+/// for any other reason.
+const CLOSE_UNAUTHORIZED: u32 = 2;
+
+/// QUIC close code used when a connection's capability has expired. The
+/// peer has to reconnect and present a renewed cap.
+const CLOSE_EXPIRED: u32 = 3;
+
+/// An inbound connection whose capability verified at `OP_AUTH` time.
+struct AuthedConnection {
+    peer: ed25519_dalek::VerifyingKey,
+    cap: RawHash,
+    conn: iroh::endpoint::Connection,
+}
+
+/// Authenticated inbound connections, keyed by `Connection::stable_id`.
+/// Entries are added on a successful `OP_AUTH` and removed when the
+/// accept loop of the connection ends.
+type AuthedConnections = Arc<Mutex<HashMap<usize, AuthedConnection>>>;
+
+/// Re-verify every authenticated connection against `revoked` and
+/// close those whose chain no longer verifies: it hits a revoked key
+/// ([`CLOSE_REVOKED`]), has an expired link ([`CLOSE_EXPIRED`]), or
+/// fails for another reason ([`CLOSE_UNAUTHORIZED`]). Chains are re-walked
+/// rather than matched on the leaf subject, so revoking an issuer also
+/// drops everyone it delegated to. Returns how many connections were
+/// closed.
+fn sweep_revoked_connections(
+    connections: &AuthedConnections,
+    snapshot: &Arc<Mutex<Option<Box<dyn AnySnapshot>>>>,
+    team_root: ed25519_dalek::VerifyingKey,
+    revoked: &HashSet<ed25519_dalek::VerifyingKey>,
+) -> usize {
+    use triblespace_core::blob::Blob;
+    use triblespace_core::blob::schemas::simplearchive::SimpleArchive;
+    use triblespace_core::repo::capability::{VerifyError, verify_chain};
+    use triblespace_core::value::Value;
+    use triblespace_core::value::schemas::hash::{Blake3, Handle};
+
+    let mut closed = 0;
+    connections.lock().unwrap().retain(|_, entry| {
+        let result = verify_chain(
+            team_root,
+            Value::<Handle<Blake3, SimpleArchive>>::new(entry.cap),
+            entry.peer,
+            revoked,
+            |h: Value<Handle<Blake3, SimpleArchive>>| -> Option<Blob<SimpleArchive>> {
+                let bytes = snapshot.lock().unwrap().as_ref()?.get_blob(&h.raw)?;
+                Some(Blob::new(anybytes::Bytes::from_source(bytes)))
+            },
+        );
+        let Err(e) = result else { return true };
+        match e {
+            VerifyError::Revoked => entry.conn.close(CLOSE_REVOKED.into(), b"revoked"),
+            VerifyError::Expired => entry.conn.close(CLOSE_EXPIRED.into(), b"expired"),
+            _ => entry.conn.close(CLOSE_UNAUTHORIZED.into(), b"unauthorized"),
+        }
+        eprintln!(
+            "[net] capability of {} no longer verifies ({e}); connection closed",
+            hex::encode(&entry.peer.to_bytes()[..4]),
+        );
+        closed += 1;
+        false
+    });
+    closed
+}
+
+/// Rebuild a team-root revocation pair from the `(signature, rev blob)`
+/// form it travels in over gossip and the DHT. Returns the revoked key
+/// and both blobs, or `None` unless the team root signed the rev blob.
+fn decode_revocation(
+    team_root: ed25519_dalek::VerifyingKey,
+    signature: &[u8; 64],
+    rev: &[u8],
+) -> Option<(
+    ed25519_dalek::VerifyingKey,
+    triblespace_core::blob::Blob<triblespace_core::blob::schemas::simplearchive::SimpleArchive>,
+    triblespace_core::blob::Blob<triblespace_core::blob::schemas::simplearchive::SimpleArchive>,
+)> {
+    use triblespace_core::blob::Blob;
+    use triblespace_core::repo::capability::{revocation_sig_blob, verify_revocation};
+
+    if rev.len() > MAX_REVOCATION_LEN {
+        return None;
+    }
+    let rev_blob = Blob::new(anybytes::Bytes::from_source(rev.to_vec()));
+    let signature = ed25519_dalek::Signature::from_bytes(signature);
+    let sig_blob = revocation_sig_blob(team_root, &rev_blob, signature);
+    let (revoker, target) = verify_revocation(rev_blob.clone(), sig_blob.clone()).ok()?;
+    (revoker == team_root).then_some((target, rev_blob, sig_blob))
+}
+
+/// Everything needed to act on a revocation learned from the network:
+/// hot-add the target to the live revoked set, hand the blob pair to
+/// the store, and drop the connections it invalidates.
+#[derive(Clone)]
+struct RevocationSink {
+    team_root: ed25519_dalek::VerifyingKey,
+    revoked: Arc<std::sync::RwLock<HashSet<ed25519_dalek::VerifyingKey>>>,
+    snapshot: Arc<Mutex<Option<Box<dyn AnySnapshot>>>>,
+    connections: AuthedConnections,
+    events: mpsc::Sender<NetEvent>,
+}
+
+impl RevocationSink {
+    /// Apply a revocation received as `(signature, rev blob)`. Returns
+    /// `true` if it verified and its target was not yet revoked.
+    fn receive(&self, signature: &[u8; 64], rev: &[u8]) -> bool {
+        let Some((target, rev_blob, sig_blob)) = decode_revocation(self.team_root, signature, rev) else {
+            return false;
+        };
+        if !self.revoked.write().unwrap().insert(target) {
+            return false;
+        }
+        eprintln!("[net] revocation of {} received", hex::encode(&target.to_bytes()[..4]));
+        let _ = self.events.send(NetEvent::Blob(rev_blob.bytes.to_vec()));
+        let _ = self.events.send(NetEvent::Blob(sig_blob.bytes.to_vec()));
+        self.sweep();
+        true
+    }
+
+    /// Close connections whose chain no longer verifies against the
+    /// current revoked set and clock.
+    fn sweep(&self) -> usize {
+        let revoked = self.revoked.read().unwrap().clone();
+        sweep_revoked_connections(&self.connections, &self.snapshot, self.team_root, &revoked)
     }
 }
 
@@ -667,9 +1021,22 @@ struct SnapshotHandler {
     /// the lock is also written from the sync `NetSender::update_snapshot`
     /// path and the read inside the async `serve_stream` is brief
     /// (read-clone-drop, no guard held across await). Revocations are
-    /// added at runtime by `update_snapshot`'s rescan, so the handler
+    /// added at runtime by `update_snapshot`'s rescan and by
+    /// revocations arriving over gossip or the DHT, so the handler
     /// always sees the latest set without a restart.
     revoked: Arc<std::sync::RwLock<std::collections::HashSet<ed25519_dalek::VerifyingKey>>>,
+    /// Per-branch reachable blob sets backing the blob-level scope
+    /// gate. Shared with `NetSender` so `update_snapshot` can drop
+    /// them whenever the served snapshot changes.
+    reachability: Arc<Mutex<ReachabilityIndex>>,
+    /// This node's own capability sig handle. The blobs of its chain
+    /// are served to every authenticated peer regardless of branch
+    /// scope: they are the credential this node presents anyway, and
+    /// receivers of our gossip need them to check our write permission.
+    self_cap: RawHash,
+    /// Connections that passed `OP_AUTH`, so a revocation arriving at
+    /// runtime can find and close the ones it invalidates.
+    connections: AuthedConnections,
 }
 
 impl std::fmt::Debug for SnapshotHandler {
@@ -683,6 +1050,9 @@ impl iroh::protocol::ProtocolHandler for SnapshotHandler {
         let snap = self.snapshot.clone();
         let team_root = self.team_root;
         let revoked = self.revoked.clone();
+        let reachability = self.reachability.clone();
+        let self_cap = self.self_cap;
+        let connections = self.connections.clone();
 
         // Extract the connecting peer's verified ed25519 identity from
         // iroh's TLS handshake.
@@ -708,6 +1078,9 @@ impl iroh::protocol::ProtocolHandler for SnapshotHandler {
             let snap = snap.clone();
             let auth_state = auth_state.clone();
             let revoked = revoked.clone();
+            let reachability = reachability.clone();
+            let connection = connection.clone();
+            let connections = connections.clone();
             tokio::spawn(async move {
                 if let Err(e) = serve_stream(
                     &snap,
@@ -715,6 +1088,10 @@ impl iroh::protocol::ProtocolHandler for SnapshotHandler {
                     peer_pubkey,
                     auth_state,
                     revoked,
+                    &reachability,
+                    &self_cap,
+                    &connection,
+                    &connections,
                     &mut send,
                     &mut recv,
                 ).await {
@@ -723,10 +1100,12 @@ impl iroh::protocol::ProtocolHandler for SnapshotHandler {
                 let _ = send.finish();
             });
         }
+        connections.lock().unwrap().remove(&connection.stable_id());
         Ok(())
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn serve_stream(
     snap_arc: &Arc<Mutex<Option<Box<dyn AnySnapshot>>>>,
     team_root: ed25519_dalek::VerifyingKey,
@@ -735,6 +1114,10 @@ async fn serve_stream(
         Option<triblespace_core::repo::capability::VerifiedCapability>,
     >>,
     revoked: Arc<std::sync::RwLock<std::collections::HashSet<ed25519_dalek::VerifyingKey>>>,
+    reachability: &Arc<Mutex<ReachabilityIndex>>,
+    self_cap: &RawHash,
+    connection: &iroh::endpoint::Connection,
+    connections: &AuthedConnections,
     send: &mut iroh::endpoint::SendStream,
     recv: &mut iroh::endpoint::RecvStream,
 ) -> anyhow::Result<()> {
@@ -772,6 +1155,11 @@ async fn serve_stream(
         match result {
             Ok(verified) => {
                 *auth_state.write().await = Some(verified);
+                connections.lock().unwrap().insert(connection.stable_id(), AuthedConnection {
+                    peer: peer_pubkey,
+                    cap: cap_handle_raw,
+                    conn: connection.clone(),
+                });
                 send_u8(send, AUTH_OK).await?;
             }
             Err(_) => {
@@ -792,6 +1180,24 @@ async fn serve_stream(
             return Ok(());
         }
     };
+    // Connections outlive caps. Once the chain verified at OP_AUTH time
+    // has run out, close the connection with `CLOSE_EXPIRED` instead of
+    // leaving the stream unanswered: the peer has to reconnect with a
+    // renewed cap. The periodic sweep catches idle connections too.
+    let now = hifitime::Epoch::now().expect("system time");
+    if !verified.is_valid_at(now, triblespace_core::repo::capability::CLOCK_SKEW_TOLERANCE) {
+        // Another stream may have re-authenticated in the meantime.
+        let current = auth_state.read().await.as_ref().map(|current| current.not_after);
+        if current == Some(verified.not_after) {
+            connections.lock().unwrap().remove(&connection.stable_id());
+            connection.close(CLOSE_EXPIRED.into(), b"expired");
+            eprintln!(
+                "[net] capability for {} expired; connection closed",
+                hex::encode(&peer_pubkey.to_bytes()[..4]),
+            );
+        }
+        return Ok(());
+    }
     // Two-tier scope gate:
     //
     //  - branch level: `OP_LIST` and `OP_HEAD` are filtered by
@@ -802,9 +1208,10 @@ async fn serve_stream(
     //    Y reaches, even if they probe by raw hash. Unrestricted caps
     //    (`granted_branches() == None`) skip the reachability filter.
     //
-    // Reachability is recomputed per OP_GET_BLOB / OP_CHILDREN call for
-    // simplicity; for chain-walk-heavy workloads, a per-stream cache
-    // would be the obvious next optimisation.
+    // Reachability comes from the shared `ReachabilityIndex`: each
+    // allowed branch's closure is computed once per served snapshot
+    // and reused by every stream on every connection until the next
+    // `update_snapshot`.
 
     match op {
         OP_LIST => {
@@ -829,16 +1236,32 @@ async fn serve_stream(
 
         OP_HEAD => {
             let id_bytes = recv_branch_id(recv).await?;
-            let allowed = triblespace_core::id::Id::new(id_bytes)
-                .is_some_and(|id| verified.grants_read_on(&id));
-            let hash = if allowed {
-                snap_arc.lock().unwrap().as_ref()
-                    .and_then(|s| s.head(&id_bytes))
-                    .unwrap_or(NIL_HASH)
-            } else {
-                NIL_HASH
+            // The head comes with its signer's capability so the caller
+            // can check the signer's write permission; this node may be
+            // a mirror or a reader that never wrote the branch itself.
+            let (hash, cap) = match triblespace_core::id::Id::new(id_bytes) {
+                Some(id) if verified.grants_read_on(&id) => {
+                    let revoked = revoked.read().unwrap().clone();
+                    let guard = snap_arc.lock().unwrap();
+                    match guard.as_ref().and_then(|snap| Some((snap, snap.head(&id_bytes)?))) {
+                        Some((snap, hash)) => {
+                            let cap = signer_capability(
+                                snap.as_ref(),
+                                &mut reachability.lock().unwrap(),
+                                team_root,
+                                &revoked,
+                                &id,
+                                &hash,
+                            );
+                            (hash, cap.unwrap_or(NIL_HASH))
+                        }
+                        None => (NIL_HASH, NIL_HASH),
+                    }
+                }
+                _ => (NIL_HASH, NIL_HASH),
             };
             send_hash(send, &hash).await?;
+            send_hash(send, &cap).await?;
         }
 
         OP_GET_BLOB => {
@@ -846,7 +1269,11 @@ async fn serve_stream(
             let data = {
                 let guard = snap_arc.lock().unwrap();
                 guard.as_ref().and_then(|snap| {
-                    if !blob_in_scope(snap.as_ref(), &verified, &hash) {
+                    let mut index = reachability.lock().unwrap();
+                    if !blob_in_scope(snap.as_ref(), &mut index, &verified, &hash)
+                        && !credential_blobs(snap.as_ref(), self_cap).contains(&hash)
+                        && !index.credentials(snap.as_ref()).contains(&hash)
+                    {
                         return None;
                     }
                     snap.get_blob(&hash)
@@ -868,12 +1295,12 @@ async fn serve_stream(
                 match guard.as_ref() {
                     None => Vec::new(),
                     Some(snap) => {
-                        // Compute the reachable set once for this op
-                        // and check membership against it for every
-                        // candidate — avoids the previous O(K×N) BFS
-                        // re-walk per child.
-                        let reachable = reachable_set_for(
+                        // Look the allowed closures up once for this op
+                        // and check membership against them for every
+                        // candidate child.
+                        let reachable = readable_closures(
                             snap.as_ref(),
+                            &mut reachability.lock().unwrap(),
                             &verified,
                         );
                         let in_scope = |hash: &RawHash| -> bool {
@@ -882,7 +1309,7 @@ async fn serve_stream(
                             }
                             match &reachable {
                                 None => verified.grants_read(),
-                                Some(set) => set.contains(hash),
+                                Some(sets) => sets.iter().any(|set| set.contains(hash)),
                             }
                         };
                         if !in_scope(&parent_hash) {
@@ -919,41 +1346,154 @@ async fn serve_stream(
     Ok(())
 }
 
-/// Build the reachable set for the given verified cap once. Returns
-/// `None` if the cap is unrestricted (i.e. every present blob is in
-/// scope — caller short-circuits to `snap.has_blob` checks).
-/// Returns `Some(set)` for branch-restricted caps; the BFS walks
-/// from each allowed branch's head following 32-byte child chunks
-/// in blob bytes, just like the OP_CHILDREN handler does.
+/// Reachable blob sets per served branch, computed lazily.
 ///
-/// This is a per-op O(reachable subgraph) computation. Previously
-/// `blob_in_scope` re-did this BFS for every blob a single
-/// `OP_CHILDREN` response had to test (parent + every candidate
-/// child) — worst case `O(K × N)` for K children and N reachable
-/// blobs. Computing the set once amortises the BFS across the
-/// whole response.
-fn reachable_set_for(
-    snap: &dyn AnySnapshot,
-    verified: &triblespace_core::repo::capability::VerifiedCapability,
-) -> Option<HashSet<RawHash>> {
-    if verified.granted_branches().is_none() {
-        // Unrestricted cap: every blob present in the snapshot is in
-        // scope. The cap may still lack read permission entirely; in
-        // that case `grants_read()` is false and the branch-level
-        // gate would have filtered every head — caller cross-checks
-        // via `verified.grants_read()` before consulting this set.
-        return None;
+/// Each entry maps a branch id to the head it was computed from and
+/// the set of snapshot blobs transitively reachable from that head
+/// via 32-byte child chunks (the same walk `OP_CHILDREN` exposes).
+/// Entries are shared by every connection, so a branch's closure is
+/// walked at most once per served snapshot no matter how many peers
+/// or streams consult it. `NetSender::update_snapshot` clears the
+/// index whenever it swaps the snapshot: new blobs can extend an
+/// existing closure even when the head stays the same.
+///
+/// The index also caches the snapshot's [`CredentialIndex`], so
+/// `OP_HEAD` can find a head signer's capability without rescanning
+/// the pile.
+#[derive(Debug, Default)]
+pub(crate) struct ReachabilityIndex {
+    branches: HashMap<RawBranchId, (RawHash, Arc<HashSet<RawHash>>)>,
+    credentials: Option<Arc<CredentialIndex>>,
+}
+
+impl ReachabilityIndex {
+    /// Drop every cached closure and the credential index.
+    pub(crate) fn clear(&mut self) {
+        self.branches.clear();
+        self.credentials = None;
     }
 
-    let mut frontier: Vec<RawHash> = snap
-        .list_branches()
-        .iter()
-        .filter_map(|(bid, head)| {
-            triblespace_core::id::Id::new(*bid)
-                .filter(|id| verified.grants_read_on(id))
-                .map(|_| *head)
+    /// Return the snapshot's credential index, building it on first use.
+    fn credentials(&mut self, snap: &dyn AnySnapshot) -> Arc<CredentialIndex> {
+        self.credentials
+            .get_or_insert_with(|| Arc::new(CredentialIndex::build(snap)))
+            .clone()
+    }
+
+    /// Number of branches with a cached closure.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// Return the closure of `head` for `branch`, walking the snapshot
+    /// only if no entry for this exact `(branch, head)` pair exists.
+    fn closure(
+        &mut self,
+        snap: &dyn AnySnapshot,
+        branch: RawBranchId,
+        head: RawHash,
+    ) -> Arc<HashSet<RawHash>> {
+        if let Some((cached_head, set)) = self.branches.get(&branch)
+            && *cached_head == head
+        {
+            return set.clone();
+        }
+        let set = Arc::new(walk_reachable(snap, head));
+        self.branches.insert(branch, (head, set.clone()));
+        set
+    }
+}
+
+/// The capability sig blobs a snapshot holds, keyed by the subject of
+/// the cap each one attests to, plus every blob of their chains.
+///
+/// A node serving a branch head it did not write (a mirror, a relay, a
+/// read-only teammate) hands out the signer's credential alongside it,
+/// so the puller can check that the signer may write the branch.
+#[derive(Debug, Default)]
+pub(crate) struct CredentialIndex {
+    by_subject: HashMap<ed25519_dalek::VerifyingKey, Vec<RawHash>>,
+    blobs: HashSet<RawHash>,
+}
+
+impl CredentialIndex {
+    fn build(snap: &dyn AnySnapshot) -> Self {
+        use triblespace_core::blob::Blob;
+        use triblespace_core::repo::capability::{chain_successor, inspect_capability};
+        use triblespace_core::value::schemas::hash::Blake3;
+
+        let mut index = CredentialIndex::default();
+        for blob in snap.all_simple_archive_blobs() {
+            // Cap blobs also have a successor (their parent); only sig
+            // blobs point at a cap without being one.
+            if inspect_capability(&blob).is_ok() {
+                continue;
+            }
+            let Some(cap) = chain_successor(&blob) else { continue };
+            let Some(data) = snap.get_blob(&cap.raw) else { continue };
+            let Ok(info) = inspect_capability(&Blob::new(anybytes::Bytes::from_source(data)))
+            else {
+                continue;
+            };
+            let sig = blob.get_handle::<Blake3>().raw;
+            index.blobs.extend(credential_blobs(snap, &sig));
+            index.by_subject.entry(info.subject).or_default().push(sig);
+        }
+        index
+    }
+
+    /// Whether `hash` belongs to one of the indexed credential chains.
+    fn contains(&self, hash: &RawHash) -> bool {
+        self.blobs.contains(hash)
+    }
+}
+
+/// Find the capability of the key that signed the branch metadata at
+/// `head`: the first indexed sig blob for that key whose chain verifies
+/// against `team_root` and grants write on `branch`. Returns `None` if
+/// the metadata is missing or unsigned, or the snapshot holds no such
+/// capability.
+fn signer_capability(
+    snap: &dyn AnySnapshot,
+    index: &mut ReachabilityIndex,
+    team_root: ed25519_dalek::VerifyingKey,
+    revoked: &HashSet<ed25519_dalek::VerifyingKey>,
+    branch: &triblespace_core::id::Id,
+    head: &RawHash,
+) -> Option<RawHash> {
+    use triblespace_core::blob::{Blob, TryFromBlob};
+    use triblespace_core::blob::schemas::simplearchive::SimpleArchive;
+    use triblespace_core::macros::{find, pattern};
+    use triblespace_core::repo::capability::verify_chain;
+    use triblespace_core::trible::TribleSet;
+    use triblespace_core::value::Value;
+    use triblespace_core::value::schemas::hash::{Blake3, Handle};
+
+    let data = snap.get_blob(head)?;
+    let meta: TribleSet = TryFromBlob::<SimpleArchive>::try_from_blob(Blob::new(
+        anybytes::Bytes::from_source(data),
+    ))
+    .ok()?;
+    let (signer,) = find!(
+        (signer: ed25519_dalek::VerifyingKey),
+        pattern!(&meta, [{ _?e @ triblespace_core::repo::signed_by: ?signer }])
+    )
+    .next()?;
+    let credentials = index.credentials(snap);
+    credentials.by_subject.get(&signer)?.iter().copied().find(|sig| {
+        verify_chain(team_root, Value::new(*sig), signer, revoked, |h: Value<Handle<Blake3, SimpleArchive>>| {
+            snap.get_blob(&h.raw)
+                .map(|data| Blob::new(anybytes::Bytes::from_source(data)))
         })
-        .collect();
+        .is_ok_and(|verified| verified.grants_write_on(branch))
+    })
+}
+
+/// Breadth-first walk of every snapshot blob reachable from `head`
+/// through 32-byte child chunks in blob bytes.
+fn walk_reachable(snap: &dyn AnySnapshot, head: RawHash) -> HashSet<RawHash> {
+    let mut frontier: Vec<RawHash> = vec![head];
     let mut reachable: HashSet<RawHash> = HashSet::new();
     while let Some(h) = frontier.pop() {
         if !reachable.insert(h) {
@@ -971,7 +1511,59 @@ fn reachable_set_for(
             }
         }
     }
-    Some(reachable)
+    reachable
+}
+
+/// Collect the cached closures of every branch the `verified` cap may
+/// read. Returns `None` if the cap is unrestricted (i.e. every present
+/// blob is in scope — caller short-circuits to `snap.has_blob`
+/// checks). Returns `Some(sets)` for branch-restricted caps; a blob is
+/// in scope iff at least one of the sets contains it.
+fn readable_closures(
+    snap: &dyn AnySnapshot,
+    index: &mut ReachabilityIndex,
+    verified: &triblespace_core::repo::capability::VerifiedCapability,
+) -> Option<Vec<Arc<HashSet<RawHash>>>> {
+    // Unrestricted cap: every blob present in the snapshot is in
+    // scope. The cap may still lack read permission entirely; in that
+    // case `grants_read()` is false and the branch-level gate would
+    // have filtered every head — caller cross-checks via
+    // `verified.grants_read()` before consulting these sets.
+    verified.granted_branches()?;
+
+    let sets = snap
+        .list_branches()
+        .iter()
+        .filter(|(bid, _)| {
+            triblespace_core::id::Id::new(*bid)
+                .is_some_and(|id| verified.grants_read_on(&id))
+        })
+        .map(|(bid, head)| index.closure(snap, *bid, *head))
+        .collect();
+    Some(sets)
+}
+
+/// Hashes of the capability chain blobs (leaf sig blob and every cap
+/// blob up to the founder's) rooted at `cap`, as far as the snapshot
+/// holds them.
+fn credential_blobs(snap: &dyn AnySnapshot, cap: &RawHash) -> HashSet<RawHash> {
+    use triblespace_core::blob::Blob;
+    use triblespace_core::repo::capability::{MAX_CHAIN_DEPTH, chain_successor};
+
+    let mut chain = HashSet::new();
+    let mut next = Some(*cap);
+    while let Some(hash) = next {
+        if chain.len() > MAX_CHAIN_DEPTH + 1 || !chain.insert(hash) {
+            break;
+        }
+        let Some(data) = snap.get_blob(&hash) else {
+            chain.remove(&hash);
+            break;
+        };
+        next = chain_successor(&Blob::new(anybytes::Bytes::from_source(data)))
+            .map(|h| h.raw);
+    }
+    chain
 }
 
 /// Returns `true` if `hash` is reachable (transitively, via 32-byte-chunk
@@ -979,21 +1571,21 @@ fn reachable_set_for(
 /// grants read access on. Unrestricted caps short-circuit to `true` for
 /// every hash present in the snapshot.
 ///
-/// Convenience wrapper over [`reachable_set_for`] for callers that only
+/// Convenience wrapper over [`readable_closures`] for callers that only
 /// need to test a single hash. Multi-hash callers (e.g. `OP_CHILDREN`)
-/// should compute the set once and check membership directly to avoid
-/// recomputing the BFS per candidate.
+/// should fetch the closures once and check membership directly.
 fn blob_in_scope(
     snap: &dyn AnySnapshot,
+    index: &mut ReachabilityIndex,
     verified: &triblespace_core::repo::capability::VerifiedCapability,
     hash: &RawHash,
 ) -> bool {
     if !snap.has_blob(hash) {
         return false;
     }
-    match reachable_set_for(snap, verified) {
+    match readable_closures(snap, index, verified) {
         None => verified.grants_read(),
-        Some(set) => set.contains(hash),
+        Some(sets) => sets.iter().any(|set| set.contains(hash)),
     }
 }
 
@@ -1164,10 +1756,13 @@ mod tests {
             });
         }
         let dummy_subject = SigningKey::generate(&mut OsRng).verifying_key();
+        let now = Epoch::now().expect("system time");
         triblespace_core::repo::capability::VerifiedCapability {
             subject: dummy_subject,
             scope_root,
             cap_set,
+            not_before: now,
+            not_after: now + hifitime::Duration::from_seconds(24.0 * 3600.0),
         }
     }
 
@@ -1226,20 +1821,22 @@ mod tests {
         let verified =
             manual_verified_cap(scope_root, &[PERM_READ], &[branch_a]);
 
+        let mut index = ReachabilityIndex::default();
+
         assert!(
-            blob_in_scope(snap.as_ref(), &verified, &head_a),
+            blob_in_scope(snap.as_ref(), &mut index, &verified, &head_a),
             "head reachable from allowed branch is in scope",
         );
         assert!(
-            blob_in_scope(snap.as_ref(), &verified, &leaf_a),
+            blob_in_scope(snap.as_ref(), &mut index, &verified, &leaf_a),
             "leaf reachable from allowed branch is in scope",
         );
         assert!(
-            !blob_in_scope(snap.as_ref(), &verified, &head_b),
+            !blob_in_scope(snap.as_ref(), &mut index, &verified, &head_b),
             "head of disallowed branch is out of scope",
         );
         assert!(
-            !blob_in_scope(snap.as_ref(), &verified, &leaf_b),
+            !blob_in_scope(snap.as_ref(), &mut index, &verified, &leaf_b),
             "leaf reachable only from disallowed branch is out of scope",
         );
     }
@@ -1252,14 +1849,16 @@ mod tests {
         // Unrestricted: PERM_READ, no scope_branch tribles.
         let verified = manual_verified_cap(scope_root, &[PERM_READ], &[]);
 
-        assert!(blob_in_scope(snap.as_ref(), &verified, &head_a));
+        let mut index = ReachabilityIndex::default();
+
+        assert!(blob_in_scope(snap.as_ref(), &mut index, &verified, &head_a));
         assert!(
-            blob_in_scope(snap.as_ref(), &verified, &head_b),
+            blob_in_scope(snap.as_ref(), &mut index, &verified, &head_b),
             "unrestricted cap admits all branches' heads",
         );
         let absent = [0xFFu8; 32];
         assert!(
-            !blob_in_scope(snap.as_ref(), &verified, &absent),
+            !blob_in_scope(snap.as_ref(), &mut index, &verified, &absent),
             "blobs absent from the snapshot are never in scope",
         );
     }
@@ -1272,13 +1871,197 @@ mod tests {
         // Cap with branch restriction but no read permission tag.
         let verified = manual_verified_cap(scope_root, &[], &[branch_a]);
 
+        let mut index = ReachabilityIndex::default();
+
         assert!(
-            !blob_in_scope(snap.as_ref(), &verified, &head_a),
+            !blob_in_scope(snap.as_ref(), &mut index, &verified, &head_a),
             "cap without read permission cannot reach any blob, even of \
              a notionally-allowed branch",
         );
     }
 
+    #[test]
+    fn reachability_index_caches_only_allowed_branches() {
+        let (snap, branch_a, _branch_b, head_a, leaf_a, _head_b, _leaf_b) =
+            two_branch_snapshot();
+        let scope_root = *ufoid();
+        let verified =
+            manual_verified_cap(scope_root, &[PERM_READ], &[branch_a]);
+
+        let mut index = ReachabilityIndex::default();
+        assert!(blob_in_scope(snap.as_ref(), &mut index, &verified, &leaf_a));
+        assert_eq!(index.len(), 1, "only the readable branch is walked");
+
+        // A second lookup hands back the very same closure.
+        let first = readable_closures(snap.as_ref(), &mut index, &verified)
+            .expect("restricted cap");
+        let second = readable_closures(snap.as_ref(), &mut index, &verified)
+            .expect("restricted cap");
+        assert!(Arc::ptr_eq(&first[0], &second[0]));
+        assert!(first[0].contains(&head_a));
+        assert!(first[0].contains(&leaf_a));
+    }
+
+    #[test]
+    fn update_snapshot_clears_reachability_index() {
+        use std::sync::mpsc as std_mpsc;
+
+        let (snap, branch_a, _branch_b, _head_a, leaf_a, _head_b, _leaf_b) =
+            two_branch_snapshot();
+        let verified =
+            manual_verified_cap(*ufoid(), &[PERM_READ], &[branch_a]);
+
+        let (cmd_tx, _cmd_rx) = std_mpsc::channel::<NetCommand>();
+        let reachability = Arc::new(Mutex::new(ReachabilityIndex::default()));
+        let dummy_secret = iroh_secret(&SigningKey::generate(&mut OsRng));
+        let sender = NetSender {
+            cmd_tx,
+            snapshot: Arc::new(Mutex::new(None)),
+            revoked: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            reachability: reachability.clone(),
+            team_root: SigningKey::generate(&mut OsRng).verifying_key(),
+            id: dummy_secret.public().into(),
+            scanned: Arc::new(AtomicBool::new(false)),
+        };
+
+        assert!(blob_in_scope(
+            snap.as_ref(),
+            &mut reachability.lock().unwrap(),
+            &verified,
+            &leaf_a,
+        ));
+        assert_eq!(reachability.lock().unwrap().len(), 1);
+
+        sender.update_snapshot(BoxedSnap(snapshot_with_blobs(&[])));
+        assert_eq!(
+            reachability.lock().unwrap().len(),
+            0,
+            "a new snapshot invalidates every cached closure",
+        );
+    }
+
+    #[test]
+    fn credential_blobs_cover_the_whole_chain() {
+        // team_root → alice → bob. Bob's credential is his leaf sig blob
+        // plus both cap blobs; alice's sig is embedded in bob's cap.
+        let team_root = SigningKey::generate(&mut OsRng);
+        let alice = SigningKey::generate(&mut OsRng);
+        let bob = SigningKey::generate(&mut OsRng);
+        let (scope_root, scope_facts) = empty_scope();
+        let (alice_cap, alice_sig) = build_capability(
+            &team_root, alice.verifying_key(), None,
+            scope_root, scope_facts.clone(), now_plus_24h(),
+        ).expect("alice cap");
+        let (bob_cap, bob_sig) = build_capability(
+            &alice, bob.verifying_key(), Some((alice_cap.clone(), alice_sig)),
+            scope_root, scope_facts, now_plus_24h(),
+        ).expect("bob cap");
+        let unrelated: Blob<SimpleArchive> =
+            triblespace_core::blob::ToBlob::to_blob(TribleSet::new());
+
+        let snap = snapshot_with_blobs(&[
+            alice_cap.clone(), bob_cap.clone(), bob_sig.clone(), unrelated.clone(),
+        ]);
+        let chain = credential_blobs(snap.as_ref(), &bob_sig.get_handle::<Blake3>().raw);
+        let expected: HashSet<RawHash> = [bob_sig, bob_cap, alice_cap]
+            .iter()
+            .map(|b| b.get_handle::<Blake3>().raw)
+            .collect();
+        assert_eq!(chain, expected);
+        assert!(!chain.contains(&unrelated.get_handle::<Blake3>().raw));
+
+        // An unknown cap handle yields nothing to serve.
+        assert!(credential_blobs(snap.as_ref(), &[0u8; 32]).is_empty());
+    }
+
+    #[test]
+    fn signer_capability_is_served_by_a_read_only_peer() {
+        // Writer A signed the head; reader B serves it. B's snapshot
+        // holds both caps, and `OP_HEAD` hands out A's, not B's.
+        use triblespace_core::repo::branch::branch_metadata;
+        use triblespace_core::repo::capability::PERM_WRITE;
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let writer = SigningKey::generate(&mut OsRng);
+        let reader = SigningKey::generate(&mut OsRng);
+        let write_root = ufoid();
+        let write_facts = TribleSet::from(entity! { ExclusiveId::force_ref(&write_root) @
+            triblespace_core::metadata::tag: PERM_WRITE,
+        });
+        let (writer_cap, writer_sig) = build_capability(
+            &team_root, writer.verifying_key(), None,
+            *write_root, write_facts, now_plus_24h(),
+        ).expect("writer cap");
+        let (scope_root, scope_facts) = empty_scope();
+        let (reader_cap, reader_sig) = build_capability(
+            &team_root, reader.verifying_key(), None,
+            scope_root, scope_facts, now_plus_24h(),
+        ).expect("reader cap");
+
+        let branch = *ufoid();
+        let commit: Blob<SimpleArchive> =
+            triblespace_core::blob::ToBlob::to_blob(TribleSet::new());
+        let meta: Blob<SimpleArchive> = triblespace_core::blob::ToBlob::to_blob(
+            branch_metadata(&writer, branch, Value::new([1u8; 32]), Some(commit), None),
+        );
+        let head = meta.get_handle::<Blake3>().raw;
+
+        let snap = snapshot_with_blobs(&[
+            writer_cap.clone(), writer_sig.clone(), reader_cap, reader_sig, meta,
+        ]);
+        let mut index = ReachabilityIndex::default();
+        let found = signer_capability(
+            snap.as_ref(), &mut index, team_root.verifying_key(), &HashSet::new(),
+            &branch, &head,
+        );
+        assert_eq!(found, Some(writer_sig.get_handle::<Blake3>().raw));
+
+        // The writer's chain is servable so the puller can verify it.
+        let credentials = index.credentials(snap.as_ref());
+        assert!(credentials.contains(&writer_sig.get_handle::<Blake3>().raw));
+        assert!(credentials.contains(&writer_cap.get_handle::<Blake3>().raw));
+
+        // A revoked writer has no capability to hand out.
+        let revoked = HashSet::from([writer.verifying_key()]);
+        let found = signer_capability(
+            snap.as_ref(), &mut index, team_root.verifying_key(), &revoked,
+            &branch, &head,
+        );
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn gossip_head_messages_roundtrip_with_and_without_cap() {
+        let branch = [1u8; 16];
+        let head = [2u8; 32];
+        let publisher = [3u8; 32];
+        let cap = [4u8; 32];
+
+        let mut msg = vec![GOSSIP_HEAD];
+        msg.extend_from_slice(&branch);
+        msg.extend_from_slice(&head);
+        msg.extend_from_slice(&publisher);
+        msg.extend_from_slice(&cap);
+        assert_eq!(msg.len(), GOSSIP_HEAD_LEN);
+        assert_eq!(
+            parse_gossip_head(&msg),
+            Some(GossipHead { branch, head, publisher, cap: Some(cap) }),
+        );
+
+        // A pre-capability announcement still parses, without a cap,
+        // so the receiver can record it as rejected.
+        let mut legacy = msg[..GOSSIP_HEAD_V1_LEN].to_vec();
+        legacy[0] = GOSSIP_HEAD_V1;
+        assert_eq!(
+            parse_gossip_head(&legacy),
+            Some(GossipHead { branch, head, publisher, cap: None }),
+        );
+
+        // Tag/length mismatches are dropped.
+        assert_eq!(parse_gossip_head(&msg[..GOSSIP_HEAD_V1_LEN]), None);
+        assert_eq!(parse_gossip_head(&[]), None);
+    }
+
     /// `NetSender::update_snapshot` rescans the new snapshot for
     /// revocation pairs signed by the configured team root and unions
     /// them into the live `revoked` set. This is the runtime
@@ -1314,8 +2097,10 @@ mod tests {
             cmd_tx,
             snapshot: snapshot_arc.clone(),
             revoked: revoked_arc.clone(),
+            reachability: Arc::new(Mutex::new(ReachabilityIndex::default())),
             team_root: team_root.verifying_key(),
             id: dummy_id,
+            scanned: Arc::new(AtomicBool::new(false)),
         };
 
         // Snapshot containing the revocation pair (and nothing else
@@ -1370,8 +2155,10 @@ mod tests {
             cmd_tx,
             snapshot: snapshot_arc,
             revoked: revoked_arc.clone(),
+            reachability: Arc::new(Mutex::new(ReachabilityIndex::default())),
             team_root: team_root.verifying_key(),
             id: dummy_id,
+            scanned: Arc::new(AtomicBool::new(false)),
         };
 
         let snap = snapshot_with_blobs(&[rev_blob, rev_sig_blob]);
@@ -1384,6 +2171,148 @@ mod tests {
         );
     }
 
+    /// A revocation written into the pile is published exactly once:
+    /// the first snapshot that reveals it queues a `PublishRevocation`
+    /// whose wire form decodes back to the same target, later
+    /// snapshots containing the same pair stay quiet.
+    #[test]
+    fn update_snapshot_publishes_new_revocations_once() {
+        use std::sync::mpsc as std_mpsc;
+        use triblespace_core::repo::capability::build_revocation;
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let target = SigningKey::generate(&mut OsRng);
+        let (rev_blob, rev_sig_blob) =
+            build_revocation(&team_root, target.verifying_key());
+
+        let (cmd_tx, cmd_rx) = std_mpsc::channel::<NetCommand>();
+        let dummy_id: EndpointId =
+            iroh_secret(&SigningKey::generate(&mut OsRng)).public().into();
+        let sender = NetSender {
+            cmd_tx,
+            snapshot: Arc::new(Mutex::new(None)),
+            revoked: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            reachability: Arc::new(Mutex::new(ReachabilityIndex::default())),
+            team_root: team_root.verifying_key(),
+            id: dummy_id,
+            scanned: Arc::new(AtomicBool::new(false)),
+        };
+
+        let blobs = [rev_blob, rev_sig_blob];
+        sender.update_snapshot(BoxedSnap(snapshot_with_blobs(&blobs)));
+        sender.update_snapshot(BoxedSnap(snapshot_with_blobs(&blobs)));
+
+        let published: Vec<_> = cmd_rx.try_iter().collect();
+        assert_eq!(published.len(), 1, "one publish for one new revocation");
+        let NetCommand::PublishRevocation { rev, signature } = &published[0] else {
+            panic!("expected a PublishRevocation command");
+        };
+        let (decoded, _, _) =
+            decode_revocation(team_root.verifying_key(), signature, rev).expect("decodes");
+        assert_eq!(decoded, target.verifying_key());
+    }
+
+    /// Revocations that seed the live set at boot are still in the
+    /// pile: the first snapshot publishes them even though their
+    /// targets are already revoked, later ones stay quiet.
+    #[test]
+    fn update_snapshot_publishes_boot_time_revocations() {
+        use std::sync::mpsc as std_mpsc;
+        use triblespace_core::repo::capability::build_revocation;
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let target = SigningKey::generate(&mut OsRng);
+        let (rev_blob, rev_sig_blob) =
+            build_revocation(&team_root, target.verifying_key());
+
+        let (cmd_tx, cmd_rx) = std_mpsc::channel::<NetCommand>();
+        let dummy_id: EndpointId =
+            iroh_secret(&SigningKey::generate(&mut OsRng)).public().into();
+        let sender = NetSender {
+            cmd_tx,
+            snapshot: Arc::new(Mutex::new(None)),
+            revoked: Arc::new(std::sync::RwLock::new(
+                [target.verifying_key()].into_iter().collect(),
+            )),
+            reachability: Arc::new(Mutex::new(ReachabilityIndex::default())),
+            team_root: team_root.verifying_key(),
+            id: dummy_id,
+            scanned: Arc::new(AtomicBool::new(false)),
+        };
+
+        let blobs = [rev_blob, rev_sig_blob];
+        sender.update_snapshot(BoxedSnap(snapshot_with_blobs(&blobs)));
+        sender.update_snapshot(BoxedSnap(snapshot_with_blobs(&blobs)));
+
+        let published: Vec<_> = cmd_rx.try_iter().collect();
+        assert_eq!(published.len(), 1, "boot-time revocation published once");
+        let NetCommand::PublishRevocation { rev, signature } = &published[0] else {
+            panic!("expected a PublishRevocation command");
+        };
+        let (decoded, _, _) =
+            decode_revocation(team_root.verifying_key(), signature, rev).expect("decodes");
+        assert_eq!(decoded, target.verifying_key());
+    }
+
+    #[test]
+    fn gossip_revocations_roundtrip_and_require_the_team_root() {
+        use triblespace_core::repo::capability::{build_revocation, revocation_signature};
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let bystander = SigningKey::generate(&mut OsRng);
+        let target = SigningKey::generate(&mut OsRng);
+
+        let (rev_blob, sig_blob) = build_revocation(&team_root, target.verifying_key());
+        let signature = revocation_signature(sig_blob.clone()).unwrap().to_bytes();
+        let msg = encode_gossip_revocation(&signature, &rev_blob.bytes);
+        let (parsed_sig, parsed_rev) = parse_gossip_revocation(&msg).expect("parses");
+        assert_eq!(parsed_sig, signature);
+        assert_eq!(parsed_rev, &rev_blob.bytes[..]);
+
+        let (decoded, rev_out, sig_out) =
+            decode_revocation(team_root.verifying_key(), &parsed_sig, parsed_rev).expect("verifies");
+        assert_eq!(decoded, target.verifying_key());
+        assert_eq!(rev_out.bytes, rev_blob.bytes);
+        assert_eq!(sig_out.bytes, sig_blob.bytes);
+
+        // Head messages are not revocations and vice versa.
+        assert!(parse_gossip_revocation(&[GOSSIP_HEAD; GOSSIP_HEAD_LEN]).is_none());
+        assert!(parse_gossip_head(&msg).is_none());
+
+        // A bystander's revocation does not pass as the team root's.
+        let (rev_blob, sig_blob) = build_revocation(&bystander, target.verifying_key());
+        let signature = revocation_signature(sig_blob).unwrap().to_bytes();
+        assert!(decode_revocation(team_root.verifying_key(), &signature, &rev_blob.bytes).is_none());
+    }
+
+    #[test]
+    fn revocation_sink_applies_each_revocation_once() {
+        use triblespace_core::repo::capability::{build_revocation, revocation_signature};
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let target = SigningKey::generate(&mut OsRng);
+        let (rev_blob, sig_blob) = build_revocation(&team_root, target.verifying_key());
+        let signature = revocation_signature(sig_blob).unwrap().to_bytes();
+
+        let (events, events_rx) = mpsc::channel();
+        let sink = RevocationSink {
+            team_root: team_root.verifying_key(),
+            revoked: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            snapshot: Arc::new(Mutex::new(None)),
+            connections: Arc::new(Mutex::new(HashMap::new())),
+            events,
+        };
+
+        assert!(sink.receive(&signature, &rev_blob.bytes));
+        assert!(sink.revoked.read().unwrap().contains(&target.verifying_key()));
+        // Both halves of the pair are handed to the store.
+        assert_eq!(events_rx.try_iter().count(), 2);
+
+        // Hearing it again (gossip echo, DHT refresh) is a no-op.
+        assert!(!sink.receive(&signature, &rev_blob.bytes));
+        assert_eq!(events_rx.try_iter().count(), 0);
+    }
+
     /// Wrapper letting us pass a pre-boxed `dyn AnySnapshot` through
     /// the `update_snapshot(impl AnySnapshot)` API. The wrapper
     /// implements `AnySnapshot` by delegating every method to its
@@ -1492,8 +2421,9 @@ mod tests {
     /// Build both endpoints up-front (transports allocated on the
     /// shared `TestNetwork` before either endpoint binds), mount
     /// `SnapshotHandler` on the server, dial from the client.
-    /// Returns `(router, client_ep, connection)` — the test holds
-    /// onto **all three**: dropping the router tears down the
+    /// Returns `(router, client_ep, connection, revocations)` — the
+    /// last one shares the server's revoked set, snapshot and
+    /// connection registry. The test holds onto the first **three**: dropping the router tears down the
     /// accept loop, **dropping the client `Endpoint` tears down
     /// every connection it owns** (this was the bug that made an
     /// earlier draft of these tests deadlock — the client endpoint
@@ -1512,6 +2442,7 @@ mod tests {
         iroh::protocol::Router,
         iroh::Endpoint,
         iroh::endpoint::Connection,
+        RevocationSink,
     ) {
         use iroh::test_utils::test_transport::{TestNetwork, to_custom_addr};
 
@@ -1539,10 +2470,21 @@ mod tests {
         let revoked: Arc<
             std::sync::RwLock<HashSet<ed25519_dalek::VerifyingKey>>,
         > = Arc::new(std::sync::RwLock::new(HashSet::new()));
+        let connections: AuthedConnections = Arc::new(Mutex::new(HashMap::new()));
+        let revocations = RevocationSink {
+            team_root,
+            revoked: revoked.clone(),
+            snapshot: snap_arc.clone(),
+            connections: connections.clone(),
+            events: mpsc::channel().0,
+        };
         let handler = SnapshotHandler {
             snapshot: snap_arc,
             team_root,
             revoked,
+            reachability: Arc::new(Mutex::new(ReachabilityIndex::default())),
+            self_cap: [0u8; 32],
+            connections,
         };
         let router = iroh::protocol::Router::builder(server_ep)
             .accept(PILE_SYNC_ALPN, handler)
@@ -1558,7 +2500,7 @@ mod tests {
             .connect(server_addr, PILE_SYNC_ALPN)
             .await
             .expect("client connect");
-        (router, client_ep, conn)
+        (router, client_ep, conn, revocations)
     }
 
     /// Smoke test: echo handler over TestNetwork with the same
@@ -1637,7 +2579,7 @@ mod tests {
         let sig_handle: Value<Handle<Blake3, SimpleArchive>> =
             (&sig_blob).get_handle();
 
-        let (router, _client_ep, conn) = dial_against_auth_server(
+        let (router, _client_ep, conn, _) = dial_against_auth_server(
             team_root.verifying_key(),
             cap_blob,
             sig_blob,
@@ -1669,7 +2611,7 @@ mod tests {
         )
         .expect("cap builds");
 
-        let (router, _client_ep, conn) = dial_against_auth_server(
+        let (router, _client_ep, conn, _) = dial_against_auth_server(
             team_root.verifying_key(),
             cap_blob,
             sig_blob,
@@ -1711,7 +2653,7 @@ mod tests {
         let sig_handle: Value<Handle<Blake3, SimpleArchive>> =
             (&sig_blob).get_handle();
 
-        let (router, _client_ep, conn) = dial_against_auth_server(
+        let (router, _client_ep, conn, _) = dial_against_auth_server(
             real_team_root.verifying_key(),
             cap_blob,
             sig_blob,
@@ -1729,4 +2671,100 @@ mod tests {
 
         let _ = router.shutdown().await;
     }
+
+    #[tokio::test]
+    async fn e2e_revocation_closes_authenticated_connection() {
+        use triblespace_core::repo::capability::{build_revocation, revocation_signature};
+
+        let team_root = SigningKey::generate(&mut OsRng);
+        let founder = SigningKey::generate(&mut OsRng);
+        let (scope_root, scope_facts) = empty_scope();
+        let (cap_blob, sig_blob) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            scope_root,
+            scope_facts,
+            now_plus_24h(),
+        )
+        .expect("cap builds");
+        let sig_handle: Value<Handle<Blake3, SimpleArchive>> = sig_blob.get_handle();
+
+        let (router, _client_ep, conn, revocations) = dial_against_auth_server(
+            team_root.verifying_key(),
+            cap_blob,
+            sig_blob,
+            &founder,
+        )
+        .await;
+        crate::protocol::op_auth(&conn, &sig_handle.raw)
+            .await
+            .expect("server accepts cap before the revocation");
+        assert_eq!(revocations.connections.lock().unwrap().len(), 1);
+
+        // The team root revokes the founder; the revocation arrives in
+        // its wire form, as it would over gossip or the DHT.
+        let (rev_blob, rev_sig_blob) = build_revocation(&team_root, founder.verifying_key());
+        let signature = revocation_signature(rev_sig_blob).unwrap().to_bytes();
+        assert!(revocations.receive(&signature, &rev_blob.bytes));
+
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(5), conn.closed())
+            .await
+            .expect("server closes the connection");
+        match closed {
+            iroh::endpoint::ConnectionError::ApplicationClosed(close) => {
+                assert_eq!(close.error_code, CLOSE_REVOKED.into());
+                assert_eq!(&close.reason[..], b"revoked");
+            }
+            other => panic!("expected an application close, got {other:?}"),
+        }
+        assert!(revocations.connections.lock().unwrap().is_empty());
+
+        let _ = router.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn e2e_sweep_closes_connection_whose_chain_stops_verifying() {
+        let team_root = SigningKey::generate(&mut OsRng);
+        let founder = SigningKey::generate(&mut OsRng);
+        let (scope_root, scope_facts) = empty_scope();
+        let (cap_blob, sig_blob) = build_capability(
+            &team_root,
+            founder.verifying_key(),
+            None,
+            scope_root,
+            scope_facts,
+            now_plus_24h(),
+        )
+        .expect("cap builds");
+        let sig_handle: Value<Handle<Blake3, SimpleArchive>> = sig_blob.get_handle();
+
+        let (router, _client_ep, conn, revocations) = dial_against_auth_server(
+            team_root.verifying_key(),
+            cap_blob,
+            sig_blob,
+            &founder,
+        )
+        .await;
+        crate::protocol::op_auth(&conn, &sig_handle.raw)
+            .await
+            .expect("server accepts cap");
+
+        // The chain can no longer be fetched, so it no longer verifies,
+        // without any key being revoked.
+        *revocations.snapshot.lock().unwrap() = None;
+        assert_eq!(revocations.sweep(), 1);
+
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(5), conn.closed())
+            .await
+            .expect("server closes the connection");
+        match closed {
+            iroh::endpoint::ConnectionError::ApplicationClosed(close) => {
+                assert_eq!(close.error_code, CLOSE_UNAUTHORIZED.into());
+            }
+            other => panic!("expected an application close, got {other:?}"),
+        }
+
+        let _ = router.shutdown().await;
+    }
 }
@@ -23,13 +23,7 @@ pub fn load_or_create_key(
     explicit_path: &Option<std::path::PathBuf>,
     default_dir: &Path,
 ) -> Result<SigningKey> {
-    let key_path = match explicit_path {
-        Some(p) => p.clone(),
-        None => match std::env::var("TRIBLESPACE_KEY") {
-            Ok(s) => std::path::PathBuf::from(s),
-            Err(_) => default_dir.join("self.key"),
-        },
-    };
+    let key_path = resolve_key_path(explicit_path, default_dir);
     if key_path.exists() {
         return load_key_from_file(&key_path);
     }
@@ -41,6 +35,33 @@ pub fn load_or_create_key(
     Ok(key)
 }
 
+/// Load an existing node identity, resolved like
+/// [`load_or_create_key`] but never creating one: a missing key file
+/// is an error.
+pub fn load_key(
+    explicit_path: &Option<std::path::PathBuf>,
+    default_dir: &Path,
+) -> Result<SigningKey> {
+    let key_path = resolve_key_path(explicit_path, default_dir);
+    if !key_path.exists() {
+        return Err(anyhow!("no key at {}", key_path.display()));
+    }
+    load_key_from_file(&key_path)
+}
+
+fn resolve_key_path(
+    explicit_path: &Option<std::path::PathBuf>,
+    default_dir: &Path,
+) -> std::path::PathBuf {
+    match explicit_path {
+        Some(p) => p.clone(),
+        None => match std::env::var("TRIBLESPACE_KEY") {
+            Ok(s) => std::path::PathBuf::from(s),
+            Err(_) => default_dir.join("self.key"),
+        },
+    }
+}
+
 /// Convert an ed25519 signing key to an iroh secret key.
 pub fn iroh_secret(key: &SigningKey) -> SecretKey {
     SecretKey::from(key.to_bytes())
@@ -18,7 +18,7 @@
 //! for single-blob pulls. Set `gossip_topic: None` in [`PeerConfig`] for
 //! pull-only mode where the peer doesn't subscribe to a flood mesh.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use anybytes::Bytes;
 use ed25519_dalek::SigningKey;
@@ -37,6 +37,10 @@ use triblespace_core::value::schemas::hash::{Blake3, Handle};
 use crate::channel::NetEvent;
 use crate::host::{self, NetReceiver, NetSender, StoreSnapshot};
 use crate::protocol::{RawBranchId, RawHash};
+use crate::tracking::RejectedHead;
+
+/// How many refused remote heads [`Peer::rejected_heads`] remembers.
+const REJECTED_HEADS_CAP: usize = 256;
 
 pub use crate::host::PeerConfig;
 
@@ -60,6 +64,15 @@ where
     /// Baseline branch heads for diff-and-publish on `refresh`. Updated on
     /// every Peer-driven write so we don't double-gossip our own changes.
     last_branches: HashMap<Id, RawHash>,
+
+    /// Gossiped or pulled heads refused by the write-capability check,
+    /// oldest first, bounded by [`REJECTED_HEADS_CAP`].
+    rejected_heads: VecDeque<RejectedHead>,
+
+    /// Heads refused since the Peer was created, including those already
+    /// dropped from `rejected_heads`. Lets `pull_branch` spot a refusal
+    /// of its own head.
+    rejected_total: usize,
 }
 
 impl<S> Peer<S>
@@ -89,6 +102,8 @@ where
             receiver,
             last_blob_reader,
             last_branches: HashMap::new(),
+            rejected_heads: VecDeque::new(),
+            rejected_total: 0,
         }
     }
 
@@ -103,7 +118,11 @@ where
     /// Used by `pile net pull` and other "go get this from over there"
     /// workflows. Does not require `gossip_topic` to be set — works in
     /// pull-only mode too. The fetched data lands in the wrapped store
-    /// via the same auto-drain path that `refresh` uses.
+    /// via the same auto-drain path that `refresh` uses. The head is
+    /// only accepted if its signer's capability grants write on the
+    /// branch; `peer` itself may be any peer that holds the head, such
+    /// as a mirror or a read-only teammate. Refused heads show up in
+    /// [`rejected_heads`](Self::rejected_heads).
     ///
     /// Fire-and-forget: returns immediately. Use [`pull_branch`](Self::pull_branch)
     /// if you want to block until the tracking branch is materialized.
@@ -119,8 +138,9 @@ where
     ///
     /// Composes [`list_remote_branches`](Self::list_remote_branches),
     /// [`fetch`](Self::fetch), [`track`](Self::track), and the tracking
-    /// auto-drain on reads. Times out at 30 s if the remote never sends
-    /// the HEAD.
+    /// auto-drain on reads. Fails if the HEAD is refused (see
+    /// [`track`](Self::track)) and times out at 30 s if the remote never
+    /// sends it.
     pub fn pull_branch(
         &mut self,
         remote: EndpointId,
@@ -130,6 +150,7 @@ where
             .ok_or_else(|| anyhow::anyhow!("branch '{name}' not found on remote"))?;
 
         let branch_bytes: [u8; 16] = remote_id.into();
+        let rejected_before = self.rejected_total;
         self.track(remote, branch_bytes);
 
         let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
@@ -137,6 +158,13 @@ where
             if let Some(id) = crate::tracking::find_tracking_branch(self, remote_id) {
                 return Ok(id);
             }
+            let new = (self.rejected_total - rejected_before).min(self.rejected_heads.len());
+            let rejected = self.rejected_heads.iter().rev().take(new).find(|r| {
+                r.branch == remote_id && r.publisher == *remote.as_bytes()
+            });
+            if let Some(rejected) = rejected {
+                return Err(anyhow::anyhow!("remote HEAD refused: {}", rejected.reason));
+            }
             if std::time::Instant::now() > deadline {
                 return Err(anyhow::anyhow!("timed out waiting for remote HEAD"));
             }
@@ -234,17 +262,29 @@ where
                     let bytes: Bytes = data.into();
                     let _ = self.store.put::<UnknownBlob, Bytes>(bytes);
                 }
-                NetEvent::Head { branch, head, publisher } => {
-                    if let Some(remote_id) = Id::new(branch) {
-                        if let Some(name) = read_remote_name(&mut self.store, &head) {
-                            crate::tracking::ensure_tracking_branch(
-                                &mut self.store,
-                                remote_id,
-                                &head,
-                                &name,
-                                &publisher,
-                            );
-                        }
+                NetEvent::Head { branch, head, publisher, origin } => {
+                    let Some(remote_id) = Id::new(branch) else { continue };
+                    let authorized = crate::tracking::authorize_head(
+                        &mut self.store,
+                        self.sender.team_root(),
+                        &self.sender.revoked(),
+                        remote_id,
+                        &head,
+                        &publisher,
+                        origin,
+                    );
+                    if let Err(reason) = authorized {
+                        self.reject_head(RejectedHead { branch: remote_id, head, publisher, reason });
+                        continue;
+                    }
+                    if let Some(name) = read_remote_name(&mut self.store, &head) {
+                        crate::tracking::ensure_tracking_branch(
+                            &mut self.store,
+                            remote_id,
+                            &head,
+                            &name,
+                            &publisher,
+                        );
                     }
                 }
             }
@@ -286,6 +326,33 @@ where
         }
     }
 
+    /// Gossiped or pulled heads that were refused because the publisher
+    /// could not show write permission on the branch, oldest first. Only
+    /// the most recent few hundred are kept.
+    pub fn rejected_heads(&self) -> impl Iterator<Item = &RejectedHead> {
+        self.rejected_heads.iter()
+    }
+
+    /// Drain the refused-heads log, e.g. to report it once per sync tick.
+    pub fn take_rejected_heads(&mut self) -> Vec<RejectedHead> {
+        self.rejected_heads.drain(..).collect()
+    }
+
+    fn reject_head(&mut self, rejected: RejectedHead) {
+        eprintln!(
+            "[net] rejected HEAD {} for branch {} from {}: {}",
+            hex::encode(&rejected.head[..4]),
+            hex::encode(&rejected.branch.raw()[..4]),
+            hex::encode(&rejected.publisher[..4]),
+            rejected.reason,
+        );
+        if self.rejected_heads.len() == REJECTED_HEADS_CAP {
+            self.rejected_heads.pop_front();
+        }
+        self.rejected_heads.push_back(rejected);
+        self.rejected_total += 1;
+    }
+
     /// Force-republish all current non-tracking branches to the gossip
     /// topic, regardless of whether they appear changed since the last
     /// publish.
@@ -9,7 +9,9 @@
 //! the configured team root, and caches the verified scope for the rest of
 //! the connection. Subsequent streams are gated on that cached scope. A
 //! connection whose first stream is not `OP_AUTH`, or whose cap fails to
-//! verify, sees every subsequent op rejected (`AUTH_REJECTED`).
+//! verify, sees every subsequent op rejected (`AUTH_REJECTED`). Once the
+//! cached cap expires the server closes the connection with a dedicated
+//! close code; the client reconnects with a renewed cap.
 //!
 //! Nil sentinels: nil id ([0u8; 16]) and nil hash ([0u8; 32]) terminate
 //! sequences. P(collision) = 2^(-128) / 2^(-256). Content-addressed systems
@@ -18,12 +20,12 @@
 //! Operations:
 //!   AUTH       cap_handle:32 → resp:u8                (0x00 = OK, 0x01 = REJECTED)
 //!   LIST       → (id:16 head:32)* nil_id:16         (48-byte aligned entries)
-//!   HEAD       id:16 → hash:32                      (nil = no head)
+//!   HEAD       id:16 → hash:32 signer_cap:32        (nil = no head / no cap)
 //!   GET_BLOB   hash:32 → len:u64 data                (u64::MAX = missing)
 //!   CHILDREN   parent:32 → hash* nil                  (nil = end)
 //!   (protocol is read-only — no remote writes)
 
-pub const PILE_SYNC_ALPN: &[u8] = b"/triblespace/pile-sync/4";
+pub const PILE_SYNC_ALPN: &[u8] = b"/triblespace/pile-sync/5";
 
 // Operation types — first byte on each stream.
 pub const OP_LIST: u8 = 0x01;
@@ -142,14 +144,24 @@ pub async fn op_list(conn: &Connection) -> Result<Vec<(RawBranchId, RawHash)>> {
 }
 
 /// HEAD: query head hash for a specific branch. Nil hash = no head.
-pub async fn op_head(conn: &Connection, branch_id: &RawBranchId) -> Result<Option<RawHash>> {
+///
+/// The head comes with the capability sig handle of the key that signed
+/// the branch metadata, as far as the serving node holds one (nil =
+/// none), so the caller can check that the signer may write the branch.
+/// The serving node itself needs no write permission.
+pub async fn op_head(
+    conn: &Connection,
+    branch_id: &RawBranchId,
+) -> Result<Option<(RawHash, Option<RawHash>)>> {
     let (mut send, mut recv) = conn.open_bi().await.map_err(|e| anyhow!("open_bi: {e}"))?;
     send_u8(&mut send, OP_HEAD).await?;
     send_branch_id(&mut send, branch_id).await?;
     send.finish().map_err(|e| anyhow!("finish: {e}"))?;
 
     let hash = recv_hash(&mut recv).await?;
-    if hash == NIL_HASH { Ok(None) } else { Ok(Some(hash)) }
+    let cap = recv_hash(&mut recv).await?;
+    if hash == NIL_HASH { return Ok(None); }
+    Ok(Some((hash, (cap != NIL_HASH).then_some(cap))))
 }
 
 /// GET_BLOB: fetch a single blob by hash.
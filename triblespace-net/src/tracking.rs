@@ -7,6 +7,11 @@
 //! The tracking branch has its own local ID. Repository can pull/merge
 //! it like any other branch.
 
+use std::collections::HashSet;
+
+use ed25519_dalek::VerifyingKey;
+
+use triblespace_core::blob::Blob;
 use triblespace_core::blob::schemas::longstring::LongString;
 use triblespace_core::blob::schemas::simplearchive::SimpleArchive;
 use triblespace_core::id::{Id, genid};
@@ -18,8 +23,10 @@ use triblespace_core::value::schemas::hash::{Blake3, Handle};
 use triblespace_core::prelude::valueschemas::{GenId, ED25519PublicKey};
 use triblespace_core::prelude::attributes;
 use triblespace_core::macros::{find, pattern, entity};
+use triblespace_core::repo::branch::{self as branch_meta, ValidationError};
+use triblespace_core::repo::capability::{VerifyError, verify_chain};
 
-use crate::channel::PublisherKey;
+use crate::channel::{HeadOrigin, PublisherKey};
 use crate::protocol::RawHash;
 
 // Minted attribute IDs for tracking branches.
@@ -287,6 +294,128 @@ where
     }
 }
 
+/// Why a remote head was refused by [`authorize_head`].
+#[derive(Debug)]
+pub enum HeadRejection {
+    /// The branch metadata blob (or the commit it points to) is not in
+    /// the local store.
+    MissingMetadata,
+    /// The metadata carries no commit signature.
+    Unsigned,
+    /// The commit signature is ambiguous or does not verify.
+    BadSignature,
+    /// A gossiped head was signed by a key other than the publisher.
+    PublisherMismatch,
+    /// No capability for the signer came with the head (pre-capability
+    /// sender, or a serving peer that does not hold the signer's cap).
+    MissingCapability,
+    /// The signer's capability chain failed verification.
+    Capability(VerifyError),
+    /// The capability is valid but does not grant write on this branch.
+    NotWriter,
+}
+
+impl std::fmt::Display for HeadRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeadRejection::MissingMetadata => write!(f, "branch metadata not available"),
+            HeadRejection::Unsigned => write!(f, "branch metadata is unsigned"),
+            HeadRejection::BadSignature => write!(f, "branch metadata signature invalid"),
+            HeadRejection::PublisherMismatch => {
+                write!(f, "branch metadata not signed by the publisher")
+            }
+            HeadRejection::MissingCapability => write!(f, "no capability for the signer"),
+            HeadRejection::Capability(e) => write!(f, "capability rejected: {e}"),
+            HeadRejection::NotWriter => write!(f, "capability does not grant write"),
+        }
+    }
+}
+
+/// A remote head that was refused, kept by the [`Peer`](crate::peer::Peer)
+/// so operators can see who tried to write what.
+#[derive(Debug)]
+pub struct RejectedHead {
+    /// The remote branch id the head was announced or pulled for.
+    pub branch: Id,
+    /// The branch metadata blob hash.
+    pub head: RawHash,
+    /// The node that published or served the head.
+    pub publisher: PublisherKey,
+    /// Why it was refused.
+    pub reason: HeadRejection,
+}
+
+/// Check that a remote head may advance a tracking branch.
+///
+/// Whatever its origin, the branch metadata at `head` must carry a
+/// valid commit signature, and the capability carried by `origin` (the
+/// signer's capability sig handle) must verify against `team_root` for
+/// that signer and grant write on `remote_branch_id`.
+///
+/// Gossip is a flood mesh: anyone on the topic can announce anything,
+/// so a [`HeadOrigin::Gossip`] head must additionally be signed by the
+/// announcing `publisher` itself. Heads pulled explicitly
+/// ([`HeadOrigin::Pulled`], see [`Peer::track`](crate::peer::Peer::track))
+/// may be served by anyone who holds them, such as a mirror, a relay or
+/// a read-only teammate, so only the signer's authority is checked.
+///
+/// Everything is read from `store`, so the metadata and the capability
+/// chain have to be fetched before calling this.
+pub fn authorize_head<S: BlobStore<Blake3>>(
+    store: &mut S,
+    team_root: VerifyingKey,
+    revoked: &HashSet<VerifyingKey>,
+    remote_branch_id: Id,
+    head: &RawHash,
+    publisher: &PublisherKey,
+    origin: HeadOrigin,
+) -> Result<(), HeadRejection> {
+    let reader = store.reader().map_err(|_| HeadRejection::MissingMetadata)?;
+    let meta_handle = Value::<Handle<Blake3, SimpleArchive>>::new(*head);
+    let meta: TribleSet = reader
+        .get(meta_handle)
+        .map_err(|_| HeadRejection::MissingMetadata)?;
+
+    let (commit_handle, signer) = find!(
+        (h: Value<Handle<Blake3, SimpleArchive>>, signer: VerifyingKey),
+        pattern!(&meta, [{
+            _?e @
+            triblespace_core::repo::head: ?h,
+            triblespace_core::repo::signed_by: ?signer,
+        }])
+    )
+    .next()
+    .ok_or(HeadRejection::Unsigned)?;
+    let commit: Blob<SimpleArchive> = reader
+        .get(commit_handle)
+        .map_err(|_| HeadRejection::MissingMetadata)?;
+    branch_meta::verify(commit, meta).map_err(|e| match e {
+        ValidationError::MissingSignature => HeadRejection::Unsigned,
+        ValidationError::AmbiguousSignature | ValidationError::FailedValidation => {
+            HeadRejection::BadSignature
+        }
+    })?;
+    let cap = match origin {
+        HeadOrigin::Gossip { cap } => {
+            if signer.as_bytes() != publisher {
+                return Err(HeadRejection::PublisherMismatch);
+            }
+            cap
+        }
+        HeadOrigin::Pulled { cap } => cap,
+    };
+
+    let cap = cap.ok_or(HeadRejection::MissingCapability)?;
+    let verified = verify_chain(team_root, Value::new(cap), signer, revoked, |h| {
+        reader.get(h).ok()
+    })
+    .map_err(HeadRejection::Capability)?;
+    if !verified.grants_write_on(&remote_branch_id) {
+        return Err(HeadRejection::NotWriter);
+    }
+    Ok(())
+}
+
 /// Outcome of [`merge_tracking_into_local`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MergeOutcome {
@@ -510,4 +639,151 @@ mod tests {
         assert_eq!(track_head, commit_handle,
             "tracking branch head should be the inner commit, not the branch metadata blob");
     }
+
+    /// A team root, a repo whose signing key publishes one commit on
+    /// `main`, and a capability for that key with the given permission
+    /// scoped to `main`. Returns the repo, team root pubkey, branch id,
+    /// branch metadata hash, publisher key and cap sig handle.
+    fn gossip_fixture(
+        perm: Id,
+    ) -> (Repository<MemoryRepo>, VerifyingKey, Id, RawHash, PublisherKey, RawHash) {
+        use hifitime::Epoch;
+        use triblespace_core::id::ExclusiveId;
+        use triblespace_core::repo::capability::{build_capability, scope_branch};
+        use triblespace_core::value::TryToValue;
+
+        let team_root = SigningKey::from_bytes(&[1u8; 32]);
+        let mut repo = test_repo();
+        let writer = SigningKey::from_bytes(&[7u8; 32]).verifying_key();
+
+        let main_id = repo.ensure_branch("main", None).unwrap();
+        let mut ws = repo.pull(main_id).unwrap();
+        ws.commit(TribleSet::new(), "remote commit");
+        repo.push(&mut ws).unwrap();
+        let head = repo.storage_mut().head(main_id).unwrap().unwrap().raw;
+
+        let scope_root = genid();
+        let scope_facts = TribleSet::from(entity! { ExclusiveId::force_ref(&scope_root) @
+            triblespace_core::metadata::tag: perm,
+            scope_branch: main_id,
+        });
+        let now = Epoch::now().unwrap();
+        let expiry: Value<NsTAIInterval> = (now, now + hifitime::Duration::from_seconds(3600.0))
+            .try_to_value()
+            .unwrap();
+        let (cap, sig) =
+            build_capability(&team_root, writer, None, *scope_root, scope_facts, expiry).unwrap();
+        repo.storage_mut().put::<SimpleArchive, _>(cap).unwrap();
+        let sig_handle = repo.storage_mut().put::<SimpleArchive, _>(sig).unwrap();
+
+        (repo, team_root.verifying_key(), main_id, head, writer.to_bytes(), sig_handle.raw)
+    }
+
+    #[test]
+    fn gossiped_head_from_writer_is_authorized() {
+        use triblespace_core::repo::capability::PERM_WRITE;
+        let (mut repo, team_root, branch, head, publisher, cap) = gossip_fixture(PERM_WRITE);
+        authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &publisher,
+            HeadOrigin::Gossip { cap: Some(cap) },
+        )
+        .expect("writer may publish");
+    }
+
+    #[test]
+    fn gossiped_head_from_reader_is_rejected() {
+        use triblespace_core::repo::capability::PERM_READ;
+        let (mut repo, team_root, branch, head, publisher, cap) = gossip_fixture(PERM_READ);
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &publisher,
+            HeadOrigin::Gossip { cap: Some(cap) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::NotWriter), "got {err:?}");
+    }
+
+    #[test]
+    fn gossiped_head_for_other_branch_is_rejected() {
+        use triblespace_core::repo::capability::PERM_WRITE;
+        let (mut repo, team_root, _, head, publisher, cap) = gossip_fixture(PERM_WRITE);
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), *genid(), &head, &publisher,
+            HeadOrigin::Gossip { cap: Some(cap) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::NotWriter), "got {err:?}");
+    }
+
+    #[test]
+    fn gossiped_head_without_cap_or_from_revoked_writer_is_rejected() {
+        use triblespace_core::repo::capability::PERM_WRITE;
+        let (mut repo, team_root, branch, head, publisher, cap) = gossip_fixture(PERM_WRITE);
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &publisher,
+            HeadOrigin::Gossip { cap: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::MissingCapability), "got {err:?}");
+
+        let revoked = HashSet::from([VerifyingKey::from_bytes(&publisher).unwrap()]);
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &revoked, branch, &head, &publisher,
+            HeadOrigin::Gossip { cap: Some(cap) },
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, HeadRejection::Capability(VerifyError::Revoked)),
+            "got {err:?}"
+        );
+    }
+
+    #[test]
+    fn gossiped_head_relayed_by_someone_else_is_rejected() {
+        // A valid writer's metadata re-announced under another key must
+        // not borrow the writer's signature.
+        use triblespace_core::repo::capability::PERM_WRITE;
+        let (mut repo, team_root, branch, head, _, cap) = gossip_fixture(PERM_WRITE);
+        let impostor = SigningKey::from_bytes(&[9u8; 32]).verifying_key().to_bytes();
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &impostor,
+            HeadOrigin::Gossip { cap: Some(cap) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::PublisherMismatch), "got {err:?}");
+    }
+
+    #[test]
+    fn pulled_head_served_by_read_only_peer_is_authorized() {
+        // Writer A signed the head; read-only peer B serves it. A pull
+        // checks A's authority, not B's.
+        use triblespace_core::repo::capability::PERM_WRITE;
+        let (mut repo, team_root, branch, head, _, writer_cap) = gossip_fixture(PERM_WRITE);
+        let server = SigningKey::from_bytes(&[9u8; 32]).verifying_key().to_bytes();
+        authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &server,
+            HeadOrigin::Pulled { cap: Some(writer_cap) },
+        )
+        .expect("a writer's head may be pulled from any peer");
+
+        // The same head gossiped by B is still refused.
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &server,
+            HeadOrigin::Gossip { cap: Some(writer_cap) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::PublisherMismatch), "got {err:?}");
+    }
+
+    #[test]
+    fn pulled_head_signed_by_reader_is_rejected() {
+        use triblespace_core::repo::capability::PERM_READ;
+        let (mut repo, team_root, branch, head, _, reader_cap) = gossip_fixture(PERM_READ);
+        let server = SigningKey::from_bytes(&[9u8; 32]).verifying_key().to_bytes();
+        let err = authorize_head(
+            repo.storage_mut(), team_root, &HashSet::new(), branch, &head, &server,
+            HeadOrigin::Pulled { cap: Some(reader_cap) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, HeadRejection::NotWriter), "got {err:?}");
+    }
 }
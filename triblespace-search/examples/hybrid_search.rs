@@ -32,6 +32,7 @@ use triblespace_core::macros::{entity, pattern};
 use triblespace_core::macros::attributes;
 
 use triblespace_search::bm25::BM25Builder;
+use triblespace_search::fusion::{Fusion, SourceHit};
 use triblespace_search::hnsw::HNSWBuilder;
 use triblespace_search::schemas::{put_embedding, Embedding};
 use triblespace_search::tokens::hash_tokens;
@@ -169,4 +170,45 @@ fn main() {
     assert!(!got.contains(&id(5)));
 
     println!("\n✓ hybrid AND works — neither constraint alone is sufficient");
+
+    // Ranking instead of filtering: run each retriever on its own
+    // and fuse the scored lists. BM25 is keyed by paper already;
+    // vector hits are embedding handles, mapped back to papers.
+    let by_handle: std::collections::HashMap<_, Id> =
+        handles.iter().map(|(pid, h)| (*h, *pid)).collect();
+    let lexical: Vec<(Id, f32)> = bm25
+        .query_multi(&hash_tokens("graph search"))
+        .into_iter()
+        .map(|(doc, score)| (doc.try_from_value::<Id>().expect("paper id"), score))
+        .collect();
+    let semantic: Vec<(Id, f32)> = hnsw_view
+        .nearest(query_handle, papers.len())
+        .unwrap()
+        .into_iter()
+        .filter_map(|(h, score)| Some((*by_handle.get(&h)?, score)))
+        .collect();
+
+    // Weighted normalized scores rather than RRF: the three front
+    // runners hold ranks 1/2/3 in some order on both sides, which
+    // reciprocal ranks score as a three-way tie. Normalized scores
+    // keep the fact that all three are nearly equally close to the
+    // query vector while paper 2 is the clear lexical winner.
+    println!("\nFused ranking: 'graph search' + embedding near [1,0,0,0] (weighted)");
+    let fused = Fusion::weighted()
+        .source(1.0, lexical)
+        .source(1.0, semantic)
+        .top_k(3);
+    for hit in &fused {
+        let rank = |s: Option<SourceHit>| s.map_or("-".to_string(), |s| s.rank.to_string());
+        println!(
+            "  {:.4}  {}  (bm25 rank {}, vector rank {})",
+            hit.score,
+            hit.key,
+            rank(hit.sources[0]),
+            rank(hit.sources[1]),
+        );
+    }
+    // Paper 2 mentions both "graph" and "search" and sits close to
+    // the query vector, so it leads the fused list.
+    assert_eq!(fused[0].key, id(2));
 }
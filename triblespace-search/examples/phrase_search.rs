@@ -5,6 +5,8 @@
 //! index — the compiler enforces the separation. The pattern is
 //! two [`BM25Builder`]s, one per tokenizer, keyed by the same
 //! doc id; hybrid queries combine both via the query engine.
+//! Phrases longer than two words — and "within N words" — need
+//! the positional companion index, shown last.
 //!
 //! ```sh
 //! cargo run --example phrase_search
@@ -50,6 +52,9 @@ fn main() {
         words_b.insert(doc_id, hash_tokens(text));
         bigrams_b.insert(doc_id, bigram_tokens(text));
     }
+    // Positions come from the same builder; grab them before
+    // `build` consumes it.
+    let positions = words_b.build_positions();
     let words: SuccinctBM25Index<GenId, WordHash> = words_b.build();
     let bigrams: SuccinctBM25Index<GenId, BigramHash> = bigrams_b.build();
     println!(
@@ -138,6 +143,26 @@ fn main() {
         id(1),
         "doc 1 has both 'the quick' AND 'quick brown' bigrams + all three words",
     );
+
+    // ── 5. Positional postings: exact three-word phrases and
+    // proximity windows from the words builder alone. ─
+    println!("\nexact phrase 'quick brown fox' (positional):");
+    let hits = positions.query_phrase(&hash_tokens("quick brown fox"));
+    for (v, s) in &hits {
+        let d = id_from_value(*v);
+        println!("  {d}  score={s:.3}  {}", text_for(&corpus, &d));
+    }
+    assert_eq!(hits.len(), 1);
+    assert_eq!(id_from_value(hits[0].0), id(1));
+
+    println!("\n'quick' and 'brown' within 4 words, any order:");
+    let hits = positions.query_near(&hash_tokens("quick brown"), 4);
+    for (v, s) in &hits {
+        let d = id_from_value(*v);
+        println!("  {d}  score={s:.3}  {}", text_for(&corpus, &d));
+    }
+    // doc 1 (adjacent) and doc 4 ("quick fox and brown").
+    assert_eq!(hits.len(), 2);
 }
 
 fn text_for<'a>(corpus: &'a [(Id, &'a str)], d: &Id) -> &'a str {
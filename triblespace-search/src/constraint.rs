@@ -21,7 +21,9 @@
 //!   are both embedding handles, and `score_floor` is a fixed
 //!   cosine threshold — *not* a bound variable. Callers who
 //!   need the exact score fetch both embeddings and compute
-//!   it directly (no quantisation).
+//!   it directly (no quantisation). `similar_where` adds a
+//!   [`HandleFilter`] (allow-set or predicate) that the walk
+//!   consults while traversing the graph.
 //!
 //! See `docs/QUERY_ENGINE_INTEGRATION.md` for the long-form
 //! design.
@@ -317,6 +319,111 @@ impl<D: triblespace_core::value::ValueSchema>
     }
 }
 
+#[cfg(feature = "succinct")]
+impl<D: triblespace_core::value::ValueSchema, T: triblespace_core::value::ValueSchema>
+    BM25Queryable for crate::segment::BM25Segments<D, T>
+{
+    fn query_term_boxed<'a>(
+        &'a self,
+        term: &RawValue,
+    ) -> Box<dyn Iterator<Item = (RawValue, f32)> + 'a> {
+        let term_val = Value::<T>::new(*term);
+        Box::new(self.query_term(&term_val).map(|(v, s)| (v.raw, s)))
+    }
+}
+
+#[cfg(feature = "succinct")]
+impl<D: triblespace_core::value::ValueSchema, T: triblespace_core::value::ValueSchema>
+    crate::segment::BM25Segments<D, T>
+{
+    /// Segment-stack sibling of [`BM25Index::matches`]. Scores
+    /// use the stack's global statistics, so results agree with
+    /// a single index over the live documents.
+    pub fn matches(
+        &self,
+        doc: Variable<D>,
+        terms: &[Value<T>],
+        score_floor: f32,
+    ) -> BM25Filter<D> {
+        let raw_terms: Vec<RawValue> = terms.iter().map(|t| t.raw).collect();
+        BM25Filter::from_entries(doc, aggregate_above(self, &raw_terms, score_floor))
+    }
+
+    /// Segment-stack sibling of [`BM25Index::score`].
+    pub fn score(&self, doc: &Value<D>, terms: &[Value<T>]) -> f32 {
+        let mut sum = 0.0;
+        for term in terms {
+            for (d, s) in self.query_term(term) {
+                if d.raw == doc.raw {
+                    sum += s;
+                    break;
+                }
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(feature = "succinct")]
+impl<D: triblespace_core::value::ValueSchema, T: triblespace_core::value::ValueSchema>
+    crate::positions::PositionIndex<D, T>
+{
+    /// Exact-phrase constraint. Binds `doc` to documents that
+    /// contain `terms` consecutively and in order, with a
+    /// phrase BM25 score `>= score_floor` (see
+    /// [`query_phrase`][crate::positions::PositionIndex::query_phrase]).
+    pub fn phrase(
+        &self,
+        doc: Variable<D>,
+        terms: &[Value<T>],
+        score_floor: f32,
+    ) -> BM25Filter<D> {
+        let entries = self
+            .query_phrase(terms)
+            .into_iter()
+            .filter_map(|(d, s)| (s >= score_floor).then_some(d.raw))
+            .collect();
+        BM25Filter::from_entries(doc, entries)
+    }
+
+    /// Phrase BM25 score for `doc`, `0.0` if the phrase doesn't
+    /// occur in it.
+    pub fn phrase_score(&self, doc: &Value<D>, terms: &[Value<T>]) -> f32 {
+        self.query_phrase(terms)
+            .into_iter()
+            .find_map(|(d, s)| (d.raw == doc.raw).then_some(s))
+            .unwrap_or(0.0)
+    }
+
+    /// Proximity constraint. Binds `doc` to documents where all
+    /// `terms` fall inside a span of at most `window` tokens,
+    /// with a proximity BM25 score `>= score_floor` (see
+    /// [`query_near`][crate::positions::PositionIndex::query_near]).
+    pub fn near(
+        &self,
+        doc: Variable<D>,
+        terms: &[Value<T>],
+        window: u32,
+        score_floor: f32,
+    ) -> BM25Filter<D> {
+        let entries = self
+            .query_near(terms, window)
+            .into_iter()
+            .filter_map(|(d, s)| (s >= score_floor).then_some(d.raw))
+            .collect();
+        BM25Filter::from_entries(doc, entries)
+    }
+
+    /// Proximity BM25 score for `doc`, `0.0` if no window
+    /// matches.
+    pub fn near_score(&self, doc: &Value<D>, terms: &[Value<T>], window: u32) -> f32 {
+        self.query_near(terms, window)
+            .into_iter()
+            .find_map(|(d, s)| (d.raw == doc.raw).then_some(s))
+            .unwrap_or(0.0)
+    }
+}
+
 impl<'a, S> Constraint<'a> for BM25Filter<S>
 where
     S: triblespace_core::value::ValueSchema + 'a,
@@ -358,6 +465,44 @@ where
 
 // ── Similarity constraint ───────────────────────────────────────────
 
+/// Restricts a similarity search to a subset of the indexed
+/// embedding handles — "only papers from 2024", expressed as the
+/// set (or predicate) of their embedding handles.
+///
+/// Implemented for any `Fn(&Value<EmbHandle>) -> bool` and for
+/// `HashSet<Value<EmbHandle>>` allow-sets. Consulted during
+/// graph traversal by [`SimilaritySearch::neighbours_above_filtered`],
+/// so a selective filter still fills the beam with allowed
+/// handles instead of leaving it to a post-filter.
+pub trait HandleFilter {
+    /// `true` if `handle` may appear in the results.
+    fn allows(&self, handle: &Value<Handle<Blake3, Embedding>>) -> bool;
+
+    /// Upper bound on how many handles [`allows`][Self::allows]
+    /// admits, if known without evaluating it. Lets an index pick
+    /// between walking and exhaustive scoring without probing every
+    /// node. Defaults to `None`; allow-sets report their length.
+    fn size_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<F: Fn(&Value<Handle<Blake3, Embedding>>) -> bool> HandleFilter for F {
+    fn allows(&self, handle: &Value<Handle<Blake3, Embedding>>) -> bool {
+        self(handle)
+    }
+}
+
+impl<S: std::hash::BuildHasher> HandleFilter for HashSet<Value<Handle<Blake3, Embedding>>, S> {
+    fn allows(&self, handle: &Value<Handle<Blake3, Embedding>>) -> bool {
+        self.contains(handle)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
 /// Backing surface a similarity index must expose for the
 /// [`Similar`] binary-relation constraint. Implemented for the
 /// three attached views:
@@ -373,8 +518,10 @@ where
 /// diagnostics belong in the concrete attached view's inherent
 /// methods, not here.
 pub trait SimilaritySearch {
-    /// Return every handle `b` in the index such that the cosine
-    /// similarity `cos(*from, *b) ≥ score_floor`. `from` may or
+    /// Return every handle `b` in the index whose similarity
+    /// score to `*from` under the index's
+    /// [`Metric`][crate::hnsw::Metric] (cosine by default) is
+    /// `≥ score_floor`. `from` may or
     /// may not be in the index (e.g. it could be a query vector
     /// put into the pile for this one call).
     fn neighbours_above(
@@ -383,20 +530,40 @@ pub trait SimilaritySearch {
         score_floor: f32,
     ) -> Vec<Value<Handle<Blake3, Embedding>>>;
 
-    /// Exact cosine similarity between the two handles, or
-    /// [`None`] if either blob can't be fetched / parsed.
-    fn cosine_between(
+    /// Exact similarity score between the two handles under the
+    /// index's metric (see [`Metric::score`][s]), or [`None`] if
+    /// either blob can't be fetched / parsed.
+    ///
+    /// [s]: crate::hnsw::Metric::score
+    fn score_between(
         &self,
         a: Value<Handle<Blake3, Embedding>>,
         b: Value<Handle<Blake3, Embedding>>,
     ) -> Option<f32>;
+
+    /// [`neighbours_above`][Self::neighbours_above] restricted to
+    /// handles `filter` allows. The default post-filters the
+    /// unfiltered walk; graph indexes override it to consult the
+    /// filter during traversal.
+    fn neighbours_above_filtered(
+        &self,
+        from: Value<Handle<Blake3, Embedding>>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> Vec<Value<Handle<Blake3, Embedding>>> {
+        let mut hits = self.neighbours_above(from, score_floor);
+        hits.retain(|h| filter.allows(h));
+        hits
+    }
 }
 
 /// Binary similarity-relation constraint:
 /// `similar(a, b, score_floor)` holds iff `a` and `b` are both
-/// embedding handles with `cosine(*a, *b) ≥ score_floor`.
+/// embedding handles with `cosine(*a, *b) ≥ score_floor` — or,
+/// for indexes built with another [`Metric`][crate::hnsw::Metric],
+/// that metric's score.
 ///
-/// Semantics are symmetric (cosine is symmetric). Operationally,
+/// Semantics are symmetric (every metric is). Operationally,
 /// at least one of `a` / `b` must be bound so the engine can walk
 /// the index from that side; when both are bound, the constraint
 /// fetches both embeddings and checks the threshold directly.
@@ -466,11 +633,21 @@ pub trait SimilaritySearch {
 /// sugar over this constraint —
 /// `view.similar_to(probe, neighbour, floor)` collapses the
 /// `temp!` + `anchor.is(...)` ceremony.
+///
+/// # Filtered variant
+///
+/// `view.similar_where(a, b, floor, &filter)` (or
+/// [`with_filter`][Self::with_filter]) additionally requires
+/// `filter.allows(b)`. Walks from a bound `a` consult the
+/// filter during traversal, so a selective filter doesn't
+/// starve the beam the way `and!(similar(..), pattern!(..))`
+/// can; walks from a bound `b` check it once up front.
 pub struct Similar<'a, I: SimilaritySearch + ?Sized> {
     index: &'a I,
     a: Variable<Handle<Blake3, Embedding>>,
     b: Variable<Handle<Blake3, Embedding>>,
     score_floor: f32,
+    filter: Option<&'a (dyn HandleFilter + Sync)>,
 }
 
 impl<'a, I: SimilaritySearch + ?Sized> Similar<'a, I> {
@@ -487,6 +664,28 @@ impl<'a, I: SimilaritySearch + ?Sized> Similar<'a, I> {
             a,
             b,
             score_floor,
+            filter: None,
+        }
+    }
+
+    /// Restrict `b` to handles `filter` allows.
+    pub fn with_filter(mut self, filter: &'a (dyn HandleFilter + Sync)) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Handles paired with the bound `from` on the side opposite
+    /// `variable`, honouring the filter on `b`.
+    fn neighbours(&self, variable: VariableId, from: RawValue) -> Vec<Value<Handle<Blake3, Embedding>>> {
+        let from = Value::new(from);
+        match self.filter {
+            None => self.index.neighbours_above(from, self.score_floor),
+            Some(filter) if variable == self.b.index => {
+                self.index
+                    .neighbours_above_filtered(from, self.score_floor, filter)
+            }
+            Some(filter) if filter.allows(&from) => self.index.neighbours_above(from, self.score_floor),
+            Some(_) => Vec::new(),
         }
     }
 }
@@ -508,11 +707,7 @@ impl<'a, I: SimilaritySearch + ?Sized + 'a> Constraint<'a> for Similar<'a, I> {
         match binding.get(other).copied() {
             // Other side bound: count the candidates from the
             // walk and report an exact cardinality.
-            Some(from) => Some(
-                self.index
-                    .neighbours_above(Value::new(from), self.score_floor)
-                    .len(),
-            ),
+            Some(from) => Some(self.neighbours(variable, from).len()),
             // Other side unbound: the engine is still ordering
             // the join — signal "expensive" so it picks a
             // cheaper constraint first, rather than `None` which
@@ -535,10 +730,7 @@ impl<'a, I: SimilaritySearch + ?Sized + 'a> Constraint<'a> for Similar<'a, I> {
             // another constraint first.
             return;
         };
-        for h in self
-            .index
-            .neighbours_above(Value::new(from), self.score_floor)
-        {
+        for h in self.neighbours(variable, from) {
             proposals.push(h.raw);
         }
     }
@@ -560,8 +752,7 @@ impl<'a, I: SimilaritySearch + ?Sized + 'a> Constraint<'a> for Similar<'a, I> {
             return;
         };
         let allowed: HashSet<RawValue> = self
-            .index
-            .neighbours_above(Value::new(from), self.score_floor)
+            .neighbours(variable, from)
             .into_iter()
             .map(|h| h.raw)
             .collect();
@@ -571,10 +762,13 @@ impl<'a, I: SimilaritySearch + ?Sized + 'a> Constraint<'a> for Similar<'a, I> {
     fn satisfied(&self, binding: &Binding) -> bool {
         match (binding.get(self.a.index), binding.get(self.b.index)) {
             (Some(a), Some(b)) => {
-                // Both bound: compute cosine directly. No engine
+                if self.filter.is_some_and(|f| !f.allows(&Value::new(*b))) {
+                    return false;
+                }
+                // Both bound: compute the score directly. No engine
                 // reason to prefer the walk here — exact beats
                 // approximate once we've paid the two blob fetches.
-                match self.index.cosine_between(Value::new(*a), Value::new(*b)) {
+                match self.index.score_between(Value::new(*a), Value::new(*b)) {
                     Some(sim) => sim >= self.score_floor,
                     None => false,
                 }
@@ -1121,6 +1315,58 @@ mod tests {
         assert!(!got.contains(&handles[1].raw));
     }
 
+    #[test]
+    fn flat_similar_where_restricts_b_side() {
+        let (flat, _hnsw, mut store, handles) = sample_sim();
+        let reader = store.reader().unwrap();
+        let view = flat.attach(&reader);
+        let allow: HashSet<Value<Handle<Blake3, Embedding>>> = [handles[0]].into_iter().collect();
+
+        let mut ctx = triblespace_core::query::VariableContext::new();
+        let a: Variable<Handle<Blake3, Embedding>> = ctx.next_variable();
+        let b: Variable<Handle<Blake3, Embedding>> = ctx.next_variable();
+        let c = view.similar_where(a, b, 0.8, &allow);
+
+        // a bound: the walk only yields allowed b's.
+        let mut binding = Binding::default();
+        binding.set(a.index, &handles[0].raw);
+        let mut props = Vec::new();
+        c.propose(b.index, &binding, &mut props);
+        assert_eq!(props, vec![handles[0].raw]);
+        assert_eq!(c.estimate(b.index, &binding), Some(1));
+
+        // b bound to a disallowed handle: nothing pairs with it.
+        let mut binding = Binding::default();
+        binding.set(b.index, &handles[2].raw);
+        let mut props = Vec::new();
+        c.propose(a.index, &binding, &mut props);
+        assert!(props.is_empty());
+
+        let mut both = Binding::default();
+        both.set(a.index, &handles[2].raw);
+        both.set(b.index, &handles[0].raw);
+        assert!(c.satisfied(&both));
+        both.set(a.index, &handles[0].raw);
+        both.set(b.index, &handles[2].raw);
+        assert!(!c.satisfied(&both));
+    }
+
+    #[test]
+    fn hnsw_similar_to_where_accepts_predicates() {
+        let (_flat, hnsw, mut store, handles) = sample_sim();
+        let reader = store.reader().unwrap();
+        let view = hnsw.attach(&reader);
+        let excluded = handles[0];
+        let not_probe = move |h: &Value<Handle<Blake3, Embedding>>| *h != excluded;
+
+        let mut ctx = triblespace_core::query::VariableContext::new();
+        let b: Variable<Handle<Blake3, Embedding>> = ctx.next_variable();
+        let c = view.similar_to_where(handles[0], b, 0.8, &not_probe);
+        let mut props = Vec::new();
+        c.propose(b.index, &Binding::default(), &mut props);
+        assert_eq!(props, vec![handles[2].raw]);
+    }
+
     #[test]
     fn similar_estimate_saturates_when_other_unbound() {
         let (flat, _hnsw, mut store, _handles) = sample_sim();
@@ -0,0 +1,552 @@
+//! Term dictionaries: the surface strings behind hashed terms.
+//!
+//! BM25 terms are Blake3 hashes, so a [`SuccinctBM25Index`]
+//! can't answer `graph*`, `gr?ph` or "anything within one typo
+//! of `grpah`" — the strings are gone. A [`TermDictionary`]
+//! (schema [`TermDictionaryBlob`]) is the optional companion
+//! blob that keeps them: a sorted string set with each string's
+//! term hash alongside. Pattern helpers expand a pattern into
+//! the matching terms, which then feed the index's `matches` /
+//! `query_multi` like any other term list.
+//!
+//! The dictionary stores *analyzed* terms (what the tokenizer
+//! emitted, before hashing via [`hash_term`]), so it pairs with
+//! [`hash_tokens`][crate::tokens::hash_tokens],
+//! [`code_tokens`][crate::tokens::code_tokens] and every
+//! [`Analyzer`][crate::tokens::Analyzer]. Patterns are matched
+//! verbatim — normalize them the way the analyzer would
+//! (typically: lowercase) before expanding.
+//!
+//! ```
+//! use triblespace_core::find;
+//! use triblespace_core::id::Id;
+//! use triblespace_search::bm25::BM25Builder;
+//! use triblespace_search::dictionary::TermDictionary;
+//! use triblespace_search::tokens::hash_tokens;
+//!
+//! let docs = ["graph search", "graphs and grapes", "paragraph"];
+//! let mut b = BM25Builder::new();
+//! for (i, text) in docs.iter().enumerate() {
+//!     b.insert(Id::new([i as u8 + 1; 16]).unwrap(), hash_tokens(text));
+//! }
+//! let idx = b.build();
+//! let dict: TermDictionary = TermDictionary::build(
+//!     docs.iter().flat_map(|d| d.split_whitespace()),
+//! );
+//!
+//! let terms = dict.prefix("graph"); // graph, graphs
+//! assert_eq!(terms.len(), 2);
+//! let hits: Vec<(Id,)> = find!((doc: Id), idx.matches(doc, &terms, 0.0)).collect();
+//! assert_eq!(hits.len(), 2);
+//!
+//! assert_eq!(dict.fuzzy("grpah", 2).len(), 1); // "graph"
+//! assert_eq!(dict.wildcard("gr?pe*").len(), 1); // "grapes"
+//! ```
+//!
+//! [`SuccinctBM25Index`]: crate::succinct::SuccinctBM25Index
+
+use anybytes::Bytes;
+use jerky::int_vectors::CompactVector;
+use triblespace_core::blob::{Blob, BlobSchema, ToBlob, TryFromBlob};
+use triblespace_core::id::Id;
+use triblespace_core::id_hex;
+use triblespace_core::metadata::{ConstDescribe, ConstId};
+use triblespace_core::value::{RawValue, Value, ValueSchema};
+use zerocopy::{FromBytes, IntoBytes};
+
+use crate::segment::pack;
+use crate::succinct::{CompactVectorMetaOnDisk, FixedBytesTable, SuccinctLoadError};
+use crate::tokens::hash_term;
+
+/// Sorted set of term strings, each paired with its hashed
+/// [`Value<T>`].
+///
+/// Strings are concatenated in lexicographic byte order with a
+/// [`CompactVector`] of offsets, so prefix and range scans are a
+/// binary search plus a linear walk. A second permutation sorted
+/// by hash answers the reverse lookup ([`Self::term`]).
+pub struct TermDictionary<T: ValueSchema = crate::tokens::WordHash> {
+    strings: Bytes,
+    /// `len + 1` offsets into `strings`.
+    offsets: CompactVector,
+    /// Hash of each string, parallel to the string order.
+    hashes: FixedBytesTable<32>,
+    /// String positions ordered by hash.
+    by_hash: CompactVector,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: ValueSchema> std::fmt::Debug for TermDictionary<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TermDictionary")
+            .field("n_terms", &self.len())
+            .field("string_bytes", &self.strings.len())
+            .finish()
+    }
+}
+
+impl<T: ValueSchema> TermDictionary<T> {
+    /// Build from analyzed term strings. Duplicates collapse;
+    /// each string is hashed with [`hash_term`].
+    pub fn build<I, S>(terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut rows: Vec<String> = terms.into_iter().map(|t| t.as_ref().to_owned()).collect();
+        rows.sort_unstable();
+        rows.dedup();
+
+        let mut strings = Vec::new();
+        let mut offsets = Vec::with_capacity(rows.len() + 1);
+        offsets.push(0u32);
+        let mut hashes: Vec<RawValue> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            strings.extend_from_slice(row.as_bytes());
+            offsets.push(strings.len() as u32);
+            hashes.push(hash_term::<T>(row).raw);
+        }
+        let mut by_hash: Vec<u32> = (0..rows.len() as u32).collect();
+        by_hash.sort_unstable_by_key(|&i| hashes[i as usize]);
+
+        let load = |(bytes, meta)| CompactVector::from_bytes(meta, bytes).expect("round-trip");
+        Self {
+            strings: Bytes::from_source(strings),
+            offsets: load(pack(&offsets)),
+            hashes: FixedBytesTable::from_bytes(FixedBytesTable::<32>::build(&hashes), rows.len())
+                .expect("round-trip hashes"),
+            by_hash: load(pack(&by_hash)),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of terms.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// `true` if the dictionary holds no terms.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// String of term `i` in sorted order.
+    fn string(&self, i: usize) -> &str {
+        let start = self.offsets.get_int(i).unwrap();
+        let end = self.offsets.get_int(i + 1).unwrap();
+        // Built from `&str`s and validated on load.
+        std::str::from_utf8(&self.strings[start..end]).expect("utf-8 term")
+    }
+
+    fn value(&self, i: usize) -> Value<T> {
+        Value::new(*self.hashes.get(i).unwrap())
+    }
+
+    /// First position whose string is `>= needle`.
+    fn lower_bound(&self, needle: &str) -> usize {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.string(mid) < needle {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Iterate `(term, hash)` pairs in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Value<T>)> + '_ {
+        (0..self.len()).map(|i| (self.string(i), self.value(i)))
+    }
+
+    /// Hash of `term`, if it is in the dictionary.
+    pub fn get(&self, term: &str) -> Option<Value<T>> {
+        let i = self.lower_bound(term);
+        (i < self.len() && self.string(i) == term).then(|| self.value(i))
+    }
+
+    /// Surface string of a hashed term, if it is in the
+    /// dictionary.
+    pub fn term(&self, value: &Value<T>) -> Option<&str> {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let i = self.by_hash.get_int(mid).unwrap();
+            match self.hashes.get(i).unwrap().cmp(&value.raw) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(self.string(i)),
+            }
+        }
+        None
+    }
+
+    /// `(term, hash)` pairs for every term starting with
+    /// `prefix`, in sorted order.
+    pub fn prefix_terms<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, Value<T>)> + 'a {
+        (self.lower_bound(prefix)..self.len())
+            .map(|i| (self.string(i), self.value(i)))
+            .take_while(move |(s, _)| s.starts_with(prefix))
+    }
+
+    /// Hashes of every term starting with `prefix` — the
+    /// expansion of `prefix*`.
+    pub fn prefix(&self, prefix: &str) -> Vec<Value<T>> {
+        self.prefix_terms(prefix).map(|(_, v)| v).collect()
+    }
+
+    /// Hashes of every term matching a glob `pattern`: `*`
+    /// matches any run of characters, `?` exactly one. The
+    /// literal prefix before the first wildcard narrows the scan.
+    pub fn wildcard(&self, pattern: &str) -> Vec<Value<T>> {
+        let literal = pattern.find(['*', '?']).map_or(pattern, |i| &pattern[..i]);
+        let pattern: Vec<char> = pattern.chars().collect();
+        self.prefix_terms(literal)
+            .filter(|(s, _)| glob_match(&pattern, &s.chars().collect::<Vec<_>>()))
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// `(term, hash, distance)` for every term within
+    /// `max_edits` Levenshtein edits (insert, delete, substitute
+    /// one character) of `term`, closest first.
+    pub fn fuzzy_terms(&self, term: &str, max_edits: usize) -> Vec<(&str, Value<T>, usize)> {
+        let query: Vec<char> = term.chars().collect();
+        let mut out: Vec<(&str, Value<T>, usize)> = self
+            .iter()
+            .filter_map(|(s, v)| {
+                let len = s.chars().count();
+                if len.abs_diff(query.len()) > max_edits {
+                    return None;
+                }
+                let chars: Vec<char> = s.chars().collect();
+                bounded_levenshtein(&query, &chars, max_edits).map(|d| (s, v, d))
+            })
+            .collect();
+        out.sort_by_key(|&(s, _, d)| (d, s));
+        out
+    }
+
+    /// Hashes of every term within `max_edits` Levenshtein edits
+    /// of `term` — typo-tolerant expansion. Scans the whole
+    /// dictionary; keep `max_edits` small (1–2).
+    pub fn fuzzy(&self, term: &str, max_edits: usize) -> Vec<Value<T>> {
+        self.fuzzy_terms(term, max_edits)
+            .into_iter()
+            .map(|(_, v, _)| v)
+            .collect()
+    }
+
+    /// Serialize to a self-contained blob:
+    ///
+    /// ```text
+    /// [header  ] DICTIONARY_HEADER_LEN B
+    /// [strings ] concatenated UTF-8, sorted
+    /// [offsets ] CompactVector body (n_terms + 1)
+    /// [hashes  ] n_terms × 32 B
+    /// [by_hash ] CompactVector body (n_terms)
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let repack = |cv: &CompactVector| {
+            let values: Vec<u32> = cv.to_vec().into_iter().map(|n| n as u32).collect();
+            pack(&values)
+        };
+        let (offsets_region, offsets_meta) = repack(&self.offsets);
+        let (by_hash_region, by_hash_meta) = repack(&self.by_hash);
+        let hashes: Vec<u8> = (0..self.len())
+            .flat_map(|i| *self.hashes.get(i).unwrap())
+            .collect();
+
+        fn align8(n: u64) -> u64 {
+            (n + 7) & !7
+        }
+        let regions: [&[u8]; 4] = [
+            self.strings.as_ref(),
+            offsets_region.as_ref(),
+            &hashes,
+            by_hash_region.as_ref(),
+        ];
+        let mut sections = [(0u64, 0u64); 4];
+        let mut off = 0u64;
+        for (section, region) in sections.iter_mut().zip(regions) {
+            off = align8(off);
+            *section = (off, region.len() as u64);
+            off += region.len() as u64;
+        }
+
+        let mut buf = Vec::with_capacity(DICTIONARY_HEADER_LEN + off as usize);
+        buf.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.strings.len() as u64).to_le_bytes());
+        let offsets_meta: CompactVectorMetaOnDisk = offsets_meta.into();
+        let by_hash_meta: CompactVectorMetaOnDisk = by_hash_meta.into();
+        buf.extend_from_slice(offsets_meta.as_bytes());
+        buf.extend_from_slice(by_hash_meta.as_bytes());
+        for (off, len) in sections {
+            buf.extend_from_slice(&off.to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+        debug_assert_eq!(buf.len(), DICTIONARY_HEADER_LEN);
+
+        for ((off, _), region) in sections.iter().zip(regions) {
+            buf.resize(DICTIONARY_HEADER_LEN + *off as usize, 0);
+            buf.extend_from_slice(region);
+        }
+        buf
+    }
+
+    /// Reload from bytes previously produced by [`Self::to_bytes`].
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, SuccinctLoadError> {
+        if bytes.len() < DICTIONARY_HEADER_LEN {
+            return Err(SuccinctLoadError::ShortHeader);
+        }
+        let read_u64 = |off: usize| u64::from_le_bytes(bytes[off..off + 8].try_into().unwrap());
+        let n_terms = read_u64(0) as usize;
+        let offsets_meta = CompactVectorMetaOnDisk::read_from_bytes(&bytes[16..48])
+            .map_err(|_| SuccinctLoadError::BadMeta("offsets"))?
+            .to_jerky();
+        let by_hash_meta = CompactVectorMetaOnDisk::read_from_bytes(&bytes[48..80])
+            .map_err(|_| SuccinctLoadError::BadMeta("by_hash"))?
+            .to_jerky();
+
+        const SECTIONS: [&str; 4] = ["strings", "offsets", "hashes", "by_hash"];
+        let body = Bytes::from_source(bytes[DICTIONARY_HEADER_LEN..].to_vec());
+        let mut regions = Vec::with_capacity(SECTIONS.len());
+        for (i, name) in SECTIONS.iter().enumerate() {
+            let off = read_u64(80 + i * 16) as usize;
+            let len = read_u64(88 + i * 16) as usize;
+            match off.checked_add(len) {
+                Some(end) if end <= body.len() => regions.push(body.slice(off..end)),
+                _ => return Err(SuccinctLoadError::TruncatedSection(name)),
+            }
+        }
+
+        let offsets = CompactVector::from_bytes(offsets_meta, regions[1].clone())
+            .map_err(|_| SuccinctLoadError::TruncatedSection("offsets"))?;
+        let by_hash = CompactVector::from_bytes(by_hash_meta, regions[3].clone())
+            .map_err(|_| SuccinctLoadError::TruncatedSection("by_hash"))?;
+        let hashes = FixedBytesTable::<32>::from_bytes(regions[2].clone(), n_terms)
+            .map_err(|_| SuccinctLoadError::TruncatedSection("hashes"))?;
+        let strings = regions[0].clone();
+
+        // Offsets must be monotone, in bounds and on UTF-8
+        // boundaries; `string` relies on it.
+        if offsets.len() != n_terms + 1 || by_hash.len() != n_terms {
+            return Err(SuccinctLoadError::BadMeta("offsets"));
+        }
+        let text = std::str::from_utf8(&strings)
+            .map_err(|_| SuccinctLoadError::TruncatedSection("strings"))?;
+        let mut prev = 0;
+        for i in 0..=n_terms {
+            let off = offsets.get_int(i).unwrap();
+            if off < prev || off > text.len() || !text.is_char_boundary(off) {
+                return Err(SuccinctLoadError::BadMeta("offsets"));
+            }
+            prev = off;
+        }
+        if (0..n_terms).any(|i| by_hash.get_int(i).unwrap() >= n_terms) {
+            return Err(SuccinctLoadError::BadMeta("by_hash"));
+        }
+
+        Ok(Self {
+            strings,
+            offsets,
+            hashes,
+            by_hash,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Glob match with `*` (any run) and `?` (one char).
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bt)) => {
+                    p = bp + 1;
+                    t = bt + 1;
+                    backtrack = Some((bp, bt + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Levenshtein distance between `a` and `b`, or `None` once it
+/// is certain to exceed `max`.
+fn bounded_levenshtein(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    (prev[b.len()] <= max).then_some(prev[b.len()])
+}
+
+/// Header length in bytes for a `TermDictionary` blob.
+///
+/// Layout: 8 n_terms, 8 strings_len, 2×32 CompactVectorMeta
+/// (offsets, by_hash), 4×16 section (offset, len) = 144.
+const DICTIONARY_HEADER_LEN: usize = 144;
+
+/// Content-addressed [`BlobSchema`] marker for a term
+/// dictionary — 144 B header + sorted strings + hashes.
+///
+/// Schema id minted fresh via `trible genid`:
+/// `794EFBEC4B05442094093433AAF5D269`.
+pub enum TermDictionaryBlob {}
+
+impl ConstId for TermDictionaryBlob {
+    const ID: Id = id_hex!("794EFBEC4B05442094093433AAF5D269");
+}
+
+impl BlobSchema for TermDictionaryBlob {}
+
+impl ConstDescribe for TermDictionaryBlob {}
+
+impl<T: ValueSchema> ToBlob<TermDictionaryBlob> for &TermDictionary<T> {
+    fn to_blob(self) -> Blob<TermDictionaryBlob> {
+        Blob::new(Bytes::from_source(self.to_bytes()))
+    }
+}
+
+impl<T: ValueSchema> ToBlob<TermDictionaryBlob> for TermDictionary<T> {
+    fn to_blob(self) -> Blob<TermDictionaryBlob> {
+        (&self).to_blob()
+    }
+}
+
+impl<T: ValueSchema> TryFromBlob<TermDictionaryBlob> for TermDictionary<T> {
+    type Error = SuccinctLoadError;
+
+    fn try_from_blob(blob: Blob<TermDictionaryBlob>) -> Result<Self, Self::Error> {
+        TermDictionary::try_from_bytes(blob.bytes.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{hash_tokens, Analyzer, EnglishStemHash, WordHash};
+
+    fn dict(words: &[&str]) -> TermDictionary {
+        TermDictionary::build(words.iter().copied())
+    }
+
+    fn strings(dict: &TermDictionary, values: &[Value<WordHash>]) -> Vec<String> {
+        values
+            .iter()
+            .map(|v| dict.term(v).unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn hashes_agree_with_tokenizers() {
+        let d = dict(&["fox", "quick", "brown"]);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d.get("fox"), Some(hash_tokens("fox")[0]));
+        assert_eq!(d.get("wolf"), None);
+        assert_eq!(d.term(&hash_tokens("quick")[0]), Some("quick"));
+        assert_eq!(d.term(&hash_tokens("wolf")[0]), None);
+
+        let en = Analyzer::<EnglishStemHash>::english();
+        let stems: TermDictionary<EnglishStemHash> =
+            TermDictionary::build(en.terms("running dogs"));
+        assert_eq!(stems.get("run"), Some(en.analyze("runs")[0]));
+    }
+
+    #[test]
+    fn prefix_expansion() {
+        let d = dict(&["graph", "graphs", "grape", "paragraph", "gr", "graph"]);
+        assert_eq!(strings(&d, &d.prefix("graph")), vec!["graph", "graphs"]);
+        assert_eq!(
+            strings(&d, &d.prefix("gr")),
+            vec!["gr", "grape", "graph", "graphs"]
+        );
+        assert!(d.prefix("zzz").is_empty());
+        assert_eq!(d.prefix("").len(), 5);
+    }
+
+    #[test]
+    fn wildcard_expansion() {
+        let d = dict(&["graph", "graphs", "grape", "paragraph", "grip"]);
+        assert_eq!(
+            strings(&d, &d.wildcard("gr?p*")),
+            vec!["grape", "graph", "graphs", "grip"]
+        );
+        assert_eq!(
+            strings(&d, &d.wildcard("*graph")),
+            vec!["graph", "paragraph"]
+        );
+        assert_eq!(strings(&d, &d.wildcard("g*h*")), vec!["graph", "graphs"]);
+        assert_eq!(strings(&d, &d.wildcard("grip")), vec!["grip"]);
+    }
+
+    #[test]
+    fn fuzzy_expansion() {
+        let d = dict(&["graph", "grape", "giraffe", "paragraph", "café"]);
+        let hits = d.fuzzy_terms("grpah", 2);
+        assert_eq!(
+            hits.iter().map(|h| (h.0, h.2)).collect::<Vec<_>>(),
+            vec![("graph", 2)]
+        );
+        assert_eq!(strings(&d, &d.fuzzy("grap", 1)), vec!["grape", "graph"]);
+        assert_eq!(strings(&d, &d.fuzzy("cafe", 1)), vec!["café"]);
+        assert!(d.fuzzy("zebra", 1).is_empty());
+    }
+
+    #[test]
+    fn dictionary_blob_round_trip() {
+        let d = dict(&["äpfel", "birnen", "kirschen", "birne"]);
+        let blob: Blob<TermDictionaryBlob> = (&d).to_blob();
+        let loaded: TermDictionary = TermDictionary::try_from_blob(blob).unwrap();
+        assert_eq!(
+            loaded.iter().collect::<Vec<_>>(),
+            d.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.to_bytes(), d.to_bytes());
+        assert_eq!(
+            strings(&loaded, &loaded.prefix("birn")),
+            vec!["birne", "birnen"]
+        );
+
+        let empty = dict(&[]);
+        let loaded: TermDictionary = TermDictionary::try_from_bytes(&empty.to_bytes()).unwrap();
+        assert!(loaded.is_empty());
+
+        assert_eq!(
+            TermDictionary::<WordHash>::try_from_bytes(&[0u8; 8]).unwrap_err(),
+            SuccinctLoadError::ShortHeader
+        );
+        let mut bytes = d.to_bytes();
+        bytes.truncate(DICTIONARY_HEADER_LEN + 4);
+        assert!(TermDictionary::<WordHash>::try_from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Rank fusion for hybrid retrieval.
+//!
+//! The constraints in [`crate::constraint`] deliberately keep
+//! scores out of bindings: a `find!` can intersect "BM25 matches"
+//! with "close in embedding space", but it can't rank by both.
+//! [`Fusion`] is the ranking layer on the other side of that
+//! line. Each retriever runs on its own and hands over a scored
+//! list — [`BM25Index::query_multi`][bm] for the lexical side,
+//! [`AttachedSuccinctHNSWIndex::nearest`][nn] for the vector
+//! side — keyed by whatever the caller ranks (usually the doc
+//! id; vector hits are embedding handles and get mapped back to
+//! their documents first). The fuser combines them under a
+//! [`FusionMethod`] and returns the top-k keys, each with a
+//! per-source breakdown.
+//!
+//! ```
+//! use triblespace_search::fusion::Fusion;
+//!
+//! let lexical = vec![("a", 7.5), ("b", 3.0), ("c", 1.0)];
+//! let semantic = vec![("c", 0.92), ("a", 0.90), ("d", 0.40)];
+//!
+//! let hits = Fusion::rrf()
+//!     .source(1.0, lexical)
+//!     .source(1.0, semantic)
+//!     .top_k(3);
+//! assert_eq!(hits[0].key, "a"); // ranked 1st and 2nd
+//! assert_eq!(hits[0].sources[0].unwrap().rank, 1);
+//! assert_eq!(hits[0].sources[1].unwrap().rank, 2);
+//! ```
+//!
+//! [bm]: crate::bm25::BM25Index::query_multi
+//! [nn]: crate::succinct::AttachedSuccinctHNSWIndex::nearest
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// How per-source ranked lists are combined into one score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion: a key at 1-based rank `r` in a
+    /// source of weight `w` contributes `w / (k + r)`. Ignores
+    /// raw score magnitudes entirely, so BM25 and similarity
+    /// scores need no calibration against each other. `k = 60`
+    /// is the customary constant; smaller values favour the top
+    /// of each list more sharply.
+    Rrf {
+        /// Rank offset; must be non-negative.
+        k: f32,
+    },
+    /// Weighted normalized scores: each source's scores are
+    /// min-max normalized to `[0, 1]` over the list it returned
+    /// (a list whose scores are all equal normalizes to `1.0`),
+    /// and a key contributes `w × normalized`. Keeps score gaps
+    /// that RRF flattens, at the cost of depending on how deep
+    /// each source's list goes.
+    Weighted,
+}
+
+impl Default for FusionMethod {
+    fn default() -> Self {
+        FusionMethod::Rrf { k: 60.0 }
+    }
+}
+
+/// One source's view of a fused hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceHit {
+    /// 1-based rank within the source's list.
+    pub rank: usize,
+    /// The score the source returned for this key.
+    pub score: f32,
+    /// What this source added to [`FusedHit::score`].
+    pub contribution: f32,
+}
+
+/// A fused result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedHit<K> {
+    /// The ranked key (doc id, handle, …).
+    pub key: K,
+    /// Sum of the per-source contributions.
+    pub score: f32,
+    /// Breakdown in the order sources were added; `None` where
+    /// the source didn't return this key.
+    pub sources: Vec<Option<SourceHit>>,
+}
+
+/// Fuses scored lists from independent retrievers.
+///
+/// Each [`source`][Self::source] is ranked by descending score
+/// (ties keep input order). If a key appears more than once in
+/// one source — several embeddings mapping back to the same doc,
+/// say — only its best entry counts. Results are sorted by fused
+/// score; ties keep first-seen order, so output is deterministic
+/// for a given input.
+#[derive(Debug, Clone)]
+pub struct Fusion<K> {
+    method: FusionMethod,
+    sources: Vec<(f32, Vec<(K, f32)>)>,
+}
+
+impl<K: Eq + Hash + Clone> Fusion<K> {
+    /// Fuse with `method`.
+    pub fn new(method: FusionMethod) -> Self {
+        Self {
+            method,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Reciprocal rank fusion with the customary `k = 60`.
+    pub fn rrf() -> Self {
+        Self::new(FusionMethod::default())
+    }
+
+    /// Weighted min-max normalized score fusion.
+    pub fn weighted() -> Self {
+        Self::new(FusionMethod::Weighted)
+    }
+
+    /// Add a retriever's scored hits with the given `weight`.
+    /// Higher scores rank first.
+    pub fn source(mut self, weight: f32, hits: impl IntoIterator<Item = (K, f32)>) -> Self {
+        let mut hits: Vec<(K, f32)> = hits.into_iter().collect();
+        hits.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let mut seen = std::collections::HashSet::new();
+        hits.retain(|(key, _)| seen.insert(key.clone()));
+        self.sources.push((weight, hits));
+        self
+    }
+
+    /// Every fused key, best first.
+    pub fn fuse(&self) -> Vec<FusedHit<K>> {
+        let n = self.sources.len();
+        let mut slots: HashMap<K, usize> = HashMap::new();
+        let mut out: Vec<FusedHit<K>> = Vec::new();
+        for (s, (weight, hits)) in self.sources.iter().enumerate() {
+            let (lo, hi) = hits.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| {
+                (lo.min(h.1), hi.max(h.1))
+            });
+            for (r, (key, score)) in hits.iter().enumerate() {
+                let rank = r + 1;
+                let contribution = weight
+                    * match self.method {
+                        FusionMethod::Rrf { k } => 1.0 / (k + rank as f32),
+                        FusionMethod::Weighted if hi > lo => (score - lo) / (hi - lo),
+                        FusionMethod::Weighted => 1.0,
+                    };
+                let slot = *slots.entry(key.clone()).or_insert_with(|| {
+                    out.push(FusedHit {
+                        key: key.clone(),
+                        score: 0.0,
+                        sources: vec![None; n],
+                    });
+                    out.len() - 1
+                });
+                let hit = &mut out[slot];
+                hit.score += contribution;
+                hit.sources[s] = Some(SourceHit {
+                    rank,
+                    score: *score,
+                    contribution,
+                });
+            }
+        }
+        out.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        out
+    }
+
+    /// The `k` best fused keys.
+    pub fn top_k(&self, k: usize) -> Vec<FusedHit<K>> {
+        let mut out = self.fuse();
+        out.truncate(k);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rrf_sums_reciprocal_ranks() {
+        let hits = Fusion::rrf()
+            .source(1.0, vec![("a", 10.0), ("b", 5.0)])
+            .source(1.0, vec![("b", 0.9), ("c", 0.8)])
+            .fuse();
+        assert_eq!(hits[0].key, "b");
+        let expect = 1.0 / 62.0 + 1.0 / 61.0;
+        assert!((hits[0].score - expect).abs() < 1e-6);
+        assert_eq!(hits[0].sources[0].unwrap().rank, 2);
+        assert_eq!(hits[0].sources[1].unwrap().score, 0.9);
+        // "a" outranks "c": same rank, but from the first source
+        // — ties keep first-seen order.
+        assert_eq!(hits[1].key, "a");
+        assert_eq!(hits[2].key, "c");
+        assert!(hits[1].sources[1].is_none());
+    }
+
+    #[test]
+    fn rrf_ranks_unsorted_input_by_score() {
+        let hits = Fusion::new(FusionMethod::Rrf { k: 0.0 })
+            .source(2.0, vec![("low", 1.0), ("high", 3.0), ("mid", 2.0)])
+            .top_k(2);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].key, "high");
+        assert_eq!(hits[0].score, 2.0);
+        assert_eq!(hits[1].key, "mid");
+        assert_eq!(hits[1].score, 1.0);
+    }
+
+    #[test]
+    fn weighted_normalizes_each_source() {
+        // BM25-scale and cosine-scale scores: normalization puts
+        // them on the same footing before weighting.
+        let hits = Fusion::weighted()
+            .source(0.5, vec![("a", 12.0), ("b", 4.0), ("c", 2.0)])
+            .source(0.5, vec![("c", 0.95), ("b", 0.90), ("a", 0.15)])
+            .fuse();
+        let score = |key| hits.iter().find(|h| h.key == key).unwrap().score;
+        assert!((score("a") - 0.5).abs() < 1e-6);
+        assert!((score("b") - (0.5 * 0.2 + 0.5 * 0.9375)).abs() < 1e-6);
+        assert!((score("c") - 0.5).abs() < 1e-6);
+        assert_eq!(hits[0].key, "b");
+    }
+
+    #[test]
+    fn weighted_flat_source_counts_fully() {
+        let hits = Fusion::weighted()
+            .source(1.0, vec![("a", 3.0), ("b", 3.0)])
+            .fuse();
+        assert!(hits.iter().all(|h| h.score == 1.0));
+    }
+
+    #[test]
+    fn duplicate_keys_keep_their_best_entry() {
+        let hits = Fusion::rrf()
+            .source(1.0, vec![("a", 0.2), ("b", 0.5), ("a", 0.9)])
+            .fuse();
+        assert_eq!(hits.len(), 2);
+        let a = hits[0].sources[0].unwrap();
+        assert_eq!((hits[0].key, a.rank, a.score), ("a", 1, 0.9));
+        assert_eq!(hits[1].sources[0].unwrap().rank, 2);
+    }
+}
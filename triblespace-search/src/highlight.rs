@@ -0,0 +1,373 @@
+//! Query-time snippets and match highlighting.
+//!
+//! Index terms are hashes, so a BM25 hit says *that* a document
+//! matched, not *where*. The [`Highlighter`] re-analyzes the
+//! document text with the same tokenizer that built the index —
+//! through its span-aware form, [`SpanAnalyzer`] — and returns
+//! the best windows around query-term occurrences as
+//! [`Snippet`]s with byte offsets into the original text.
+//!
+//! Span-aware analyzers ship for every word tokenizer:
+//! [`hash_token_spans`] (pairs with [`hash_tokens`]) and
+//! [`Analyzer::analyze_spans`] (pairs with [`Analyzer::analyze`],
+//! including [`english_tokens`] / [`german_tokens`] via
+//! `Analyzer::english()` / `Analyzer::german()`). Any
+//! `Fn(&str) -> Vec<(Range<usize>, Value<S>)>` works too.
+//!
+//! ```
+//! use triblespace_search::highlight::Highlighter;
+//! use triblespace_search::tokens::{hash_token_spans, hash_tokens};
+//!
+//! let text = "The quick brown fox. Later, the fox met a lazy dog.";
+//! let query = hash_tokens("fox dog");
+//! let snippets = Highlighter::new()
+//!     .window(6)
+//!     .snippets(text, &hash_token_spans, &query);
+//!
+//! // Best window holds both terms.
+//! assert_eq!(snippets[0].text(text), "the fox met a lazy dog");
+//! assert_eq!(
+//!     snippets[0].highlight(text, "[", "]"),
+//!     "the [fox] met a lazy [dog]"
+//! );
+//! ```
+//!
+//! Documents stored as `LongString` blobs load as a
+//! `View<str>`, which derefs to `&str` — pass it straight in.
+//!
+//! [`hash_token_spans`]: crate::tokens::hash_token_spans
+//! [`hash_tokens`]: crate::tokens::hash_tokens
+//! [`english_tokens`]: crate::tokens::english_tokens
+//! [`german_tokens`]: crate::tokens::german_tokens
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use triblespace_core::value::{RawValue, Value, ValueSchema};
+
+use crate::tokens::Analyzer;
+
+/// An analyzer that reports where each term came from: the terms
+/// must equal the ones the index was built with, each paired with
+/// its byte range in the analyzed text.
+pub trait SpanAnalyzer<S: ValueSchema> {
+    /// Terms of `text` in order, with source byte ranges.
+    fn analyze_spans(&self, text: &str) -> Vec<(Range<usize>, Value<S>)>;
+}
+
+impl<S: ValueSchema> SpanAnalyzer<S> for Analyzer<S> {
+    fn analyze_spans(&self, text: &str) -> Vec<(Range<usize>, Value<S>)> {
+        Analyzer::analyze_spans(self, text)
+    }
+}
+
+impl<S: ValueSchema, F: Fn(&str) -> Vec<(Range<usize>, Value<S>)>> SpanAnalyzer<S> for F {
+    fn analyze_spans(&self, text: &str) -> Vec<(Range<usize>, Value<S>)> {
+        self(text)
+    }
+}
+
+/// A window of the document around query matches.
+///
+/// All ranges are byte offsets into the text passed to the
+/// [`Highlighter`]; they always fall on `char` boundaries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    /// Extent of the snippet: first to last token of the window.
+    pub range: Range<usize>,
+    /// Query-term occurrences inside `range`, ascending.
+    pub matches: Vec<Range<usize>>,
+    /// Window score; higher is better. Comparable only between
+    /// snippets of the same query.
+    pub score: f32,
+}
+
+impl Snippet {
+    /// The snippet's slice of `text`.
+    pub fn text<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.range.clone()]
+    }
+
+    /// The snippet's slice of `text` with every match wrapped in
+    /// `open` / `close` markers (e.g. `"<mark>"`, `"</mark>"`).
+    /// Markers are inserted verbatim — escape `text` first if
+    /// the output is HTML.
+    pub fn highlight(&self, text: &str, open: &str, close: &str) -> String {
+        let mut out = String::with_capacity(
+            self.range.len() + self.matches.len() * (open.len() + close.len()),
+        );
+        let mut cursor = self.range.start;
+        for m in &self.matches {
+            out.push_str(&text[cursor..m.start]);
+            out.push_str(open);
+            out.push_str(&text[m.clone()]);
+            out.push_str(close);
+            cursor = m.end;
+        }
+        out.push_str(&text[cursor..self.range.end]);
+        out
+    }
+}
+
+/// Picks the best-scoring snippets of a document for a query.
+///
+/// A window of [`window`][Self::window] consecutive terms scores
+/// the summed weight of the distinct query terms it contains,
+/// plus a tenth of that weight for every repeat — covering more
+/// of the query beats repeating one word. Among equal scores the
+/// window whose first match sits a quarter window in wins, so
+/// snippets carry some lead-in context. Up to
+/// [`max_snippets`][Self::max_snippets] non-overlapping windows
+/// are returned, best first.
+#[derive(Debug, Clone, Copy)]
+pub struct Highlighter {
+    window: usize,
+    max_snippets: usize,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Highlighter {
+    /// 24-term windows, up to 3 snippets.
+    pub fn new() -> Self {
+        Self {
+            window: 24,
+            max_snippets: 3,
+        }
+    }
+
+    /// Snippet length in analyzed terms (not counting removed
+    /// stopwords, which still show up in the text between them).
+    pub fn window(mut self, terms: usize) -> Self {
+        self.window = terms.max(1);
+        self
+    }
+
+    /// Maximum number of snippets returned.
+    pub fn max_snippets(mut self, n: usize) -> Self {
+        self.max_snippets = n;
+        self
+    }
+
+    /// Byte ranges of every occurrence of `terms` in `text`,
+    /// ascending — for highlighting a whole document.
+    pub fn matches<S: ValueSchema>(
+        &self,
+        text: &str,
+        analyzer: &impl SpanAnalyzer<S>,
+        terms: &[Value<S>],
+    ) -> Vec<Range<usize>> {
+        analyzer
+            .analyze_spans(text)
+            .into_iter()
+            .filter(|(_, v)| terms.contains(v))
+            .map(|(span, _)| span)
+            .collect()
+    }
+
+    /// Best snippets of `text` for `terms`, every query term
+    /// weighted equally. Empty if no term occurs.
+    pub fn snippets<S: ValueSchema>(
+        &self,
+        text: &str,
+        analyzer: &impl SpanAnalyzer<S>,
+        terms: &[Value<S>],
+    ) -> Vec<Snippet> {
+        let weighted: Vec<(Value<S>, f32)> = terms.iter().map(|t| (*t, 1.0)).collect();
+        self.snippets_weighted(text, analyzer, &weighted)
+    }
+
+    /// [`snippets`][Self::snippets] with a weight per query term —
+    /// typically its IDF or BM25 contribution, so a window holding
+    /// the rare term beats one holding two common ones.
+    pub fn snippets_weighted<S: ValueSchema>(
+        &self,
+        text: &str,
+        analyzer: &impl SpanAnalyzer<S>,
+        terms: &[(Value<S>, f32)],
+    ) -> Vec<Snippet> {
+        let weights: HashMap<RawValue, f32> = terms.iter().map(|(t, w)| (t.raw, *w)).collect();
+        let tokens = analyzer.analyze_spans(text);
+        let hits: Vec<usize> = (0..tokens.len())
+            .filter(|&i| weights.contains_key(&tokens[i].1.raw))
+            .collect();
+        if hits.is_empty() {
+            return Vec::new();
+        }
+
+        let n = tokens.len();
+        let window = self.window.min(n);
+        let lead = window / 4;
+        // (score, distance of the first match from the lead-in
+        // offset, start) for every window holding a match.
+        let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+        let mut counts: HashMap<RawValue, usize> = HashMap::new();
+        let mut next_hit = 0;
+        for (i, (_, v)) in tokens.iter().enumerate() {
+            if weights.contains_key(&v.raw) {
+                *counts.entry(v.raw).or_insert(0) += 1;
+            }
+            if i + 1 < window {
+                continue;
+            }
+            let start = i + 1 - window;
+            if start > 0 {
+                let left = &tokens[start - 1].1.raw;
+                if let Some(count) = counts.get_mut(left) {
+                    *count -= 1;
+                    if *count == 0 {
+                        counts.remove(left);
+                    }
+                }
+            }
+            while next_hit < hits.len() && hits[next_hit] < start {
+                next_hit += 1;
+            }
+            if counts.is_empty() {
+                continue;
+            }
+            let score = counts
+                .iter()
+                .map(|(t, &count)| weights[t] * (1.0 + 0.1 * (count - 1) as f32))
+                .sum();
+            candidates.push((score, (hits[next_hit] - start).abs_diff(lead), start));
+        }
+        candidates.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+                .then(a.2.cmp(&b.2))
+        });
+
+        let mut picked: Vec<(f32, usize)> = Vec::new();
+        for (score, _, start) in candidates {
+            if picked.len() >= self.max_snippets {
+                break;
+            }
+            let overlaps = picked
+                .iter()
+                .any(|&(_, s)| start < s + window && s < start + window);
+            if !overlaps {
+                picked.push((score, start));
+            }
+        }
+
+        picked
+            .into_iter()
+            .map(|(score, start)| {
+                let slice = &tokens[start..start + window];
+                Snippet {
+                    range: slice[0].0.start..slice[window - 1].0.end,
+                    matches: slice
+                        .iter()
+                        .filter(|(_, v)| weights.contains_key(&v.raw))
+                        .map(|(span, _)| span.clone())
+                        .collect(),
+                    score,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{hash_token_spans, hash_tokens, EnglishStemHash, WordHash};
+
+    #[test]
+    fn snippets_prefer_query_coverage() {
+        let text = "fox fox fox. Nothing here at all, nothing at all. \
+                    Then a fox and a dog walked by.";
+        let query = hash_tokens("fox dog");
+        let snippets = Highlighter::new()
+            .window(4)
+            .snippets(text, &hash_token_spans, &query);
+        assert_eq!(snippets[0].text(text), "fox and a dog");
+        assert!(snippets[0].score > snippets[1].score);
+        assert_eq!(snippets[1].text(text), "fox fox fox. Nothing");
+        assert_eq!(snippets[1].matches.len(), 3);
+        for s in &snippets {
+            for m in &s.matches {
+                assert!(s.range.start <= m.start && m.end <= s.range.end);
+            }
+        }
+    }
+
+    #[test]
+    fn snippets_do_not_overlap_and_respect_limit() {
+        let text = "a b fox c d e f g h fox i j k l m n fox o";
+        let query = hash_tokens("fox");
+        let snippets = Highlighter::new()
+            .window(3)
+            .max_snippets(2)
+            .snippets(text, &hash_token_spans, &query);
+        assert_eq!(snippets.len(), 2);
+        assert!(
+            snippets[0].range.end <= snippets[1].range.start
+                || snippets[1].range.end <= snippets[0].range.start
+        );
+        assert!(Highlighter::new()
+            .snippets(text, &hash_token_spans, &hash_tokens("wolf"))
+            .is_empty());
+    }
+
+    #[test]
+    fn weights_pick_the_rare_term() {
+        let text = "common common words here. rare appears later on.";
+        let query = [
+            (hash_tokens("common")[0], 0.1),
+            (hash_tokens("rare")[0], 2.0),
+        ];
+        let snippets = Highlighter::new()
+            .window(2)
+            .snippets_weighted(text, &hash_token_spans, &query);
+        assert_eq!(snippets[0].text(text), "rare appears");
+    }
+
+    #[test]
+    fn analyzer_offsets_point_into_original_text() {
+        let en = Analyzer::<EnglishStemHash>::english();
+        let text = "The RUNNERS were running, Café-style — ﬁne!";
+        let query = en.analyze("run café fine");
+        let matches = Highlighter::new().matches(text, &en, &query);
+        let words: Vec<&str> = matches.iter().map(|r| &text[r.clone()]).collect();
+        assert_eq!(words, vec!["running", "Café", "ﬁne"]);
+
+        let snippet = &Highlighter::new().snippets(text, &en, &query)[0];
+        assert_eq!(
+            snippet.highlight(text, "<b>", "</b>"),
+            "RUNNERS were <b>running</b>, <b>Café</b>-style — <b>ﬁne</b>"
+        );
+    }
+
+    #[test]
+    fn span_analyzers_agree_with_plain_ones() {
+        let texts = [
+            "Hello, WORLD — hello.",
+            "  don't   stop (me) now!! ",
+            "e\u{301}te\u{301} Ｆｕｌｌ　ｗｉｄｔｈ naïve",
+            "",
+        ];
+        let en = Analyzer::<EnglishStemHash>::english();
+        let plain = Analyzer::<WordHash>::new();
+        for text in texts {
+            let spans: Vec<_> = hash_token_spans(text).into_iter().map(|(_, v)| v).collect();
+            assert_eq!(spans, hash_tokens(text), "{text:?}");
+            let spans: Vec<_> = en.analyze_spans(text).into_iter().map(|(_, v)| v).collect();
+            assert_eq!(spans, en.analyze(text), "{text:?}");
+            assert_eq!(
+                plain.term_spans(text).into_iter().map(|(_, t)| t).collect::<Vec<_>>(),
+                plain.terms(text),
+                "{text:?}"
+            );
+            for (span, _) in plain.term_spans(text) {
+                assert!(text.is_char_boundary(span.start) && text.is_char_boundary(span.end));
+            }
+        }
+    }
+}
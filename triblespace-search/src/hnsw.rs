@@ -43,8 +43,9 @@
 
 use triblespace_core::query::Variable;
 use triblespace_core::value::schemas::hash::{Blake3, Handle};
-use triblespace_core::value::Value;
+use triblespace_core::value::{RawValue, Value};
 
+use crate::constraint::HandleFilter;
 use crate::schemas::{EmbHandle, Embedding};
 
 // ── HNSW blob byte format ────────────────────────────────────────────
@@ -90,6 +91,11 @@ pub struct HNSWBuilder {
     ef_construction: u16,
     /// Level-sampling multiplier `m_L = 1 / ln(M)`.
     level_mult: f32,
+    metric: Metric,
+    /// Scheme for the per-node codes trained at
+    /// [`build`][Self::build]; `None` keeps the walk on
+    /// full-precision embeddings.
+    quantization: Option<crate::quantize::Quantization>,
     /// SplitMix64 state for deterministic level sampling.
     rng: u64,
     /// Per-node state, inclusive of the inline vector used for
@@ -108,6 +114,10 @@ pub struct HNSWBuilder {
     handles: Vec<Value<Handle<Blake3, Embedding>>>,
     entry_point: Option<u32>,
     max_level: u8,
+    /// Tombstones from [`remove`][Self::remove]: handle → node
+    /// count at removal time. Nodes below that index carrying
+    /// the handle are dropped at build; later re-inserts survive.
+    removed: std::collections::HashMap<RawValue, u32>,
 }
 
 impl HNSWBuilder {
@@ -126,14 +136,77 @@ impl HNSWBuilder {
             m0: m * 2,
             ef_construction: 200,
             level_mult: 1.0 / (m as f32).ln(),
+            metric: Metric::Cosine,
+            quantization: None,
             rng: 0xC0FFEEu64,
             nodes: Vec::new(),
             handles: Vec::new(),
             entry_point: None,
             max_level: 0,
+            removed: std::collections::HashMap::new(),
         }
     }
 
+    /// Reopen a persisted index for incremental updates.
+    ///
+    /// Copies the graph out of `index` and fetches every node's
+    /// embedding from `store`, so further [`insert`][Self::insert]s
+    /// link into the existing graph instead of rebuilding it.
+    /// `M` / `M0`, the metric and the quantization scheme come
+    /// from the index (codes are retrained at the next build);
+    /// `ef_construction` and the
+    /// seed are not persisted and take the builder defaults (the
+    /// seed is offset by the node count so a reopened builder
+    /// doesn't replay the original level sequence). Node levels
+    /// are recovered as the highest layer with neighbours.
+    pub fn from_succinct<B>(
+        index: &crate::succinct::SuccinctHNSWIndex,
+        store: &B,
+    ) -> Result<Self, HNSWLoadError<B::GetError<anybytes::view::ViewError>>>
+    where
+        B: triblespace_core::repo::BlobStoreGet<Blake3>,
+    {
+        let mut builder = Self::new(index.dim())
+            .m(index.m())
+            .m0(index.m0())
+            .metric(index.metric());
+        builder.quantization = index.quantizer().map(|q| q.kind());
+        let n = index.doc_count();
+        builder.rng = builder.rng.wrapping_add(n as u64);
+        for i in 0..n {
+            let handle = index.handle(i).expect("node in range");
+            let view: anybytes::View<[f32]> = store.get(handle).map_err(HNSWLoadError::Blob)?;
+            let mut vector = view.as_ref().to_vec();
+            if vector.len() != builder.dim {
+                return Err(HNSWLoadError::Dim(DimMismatch {
+                    expected: builder.dim,
+                    got: vector.len(),
+                }));
+            }
+            if builder.metric.normalizes() {
+                normalize(&mut vector);
+            }
+            let mut neighbors: Vec<Vec<u32>> = (0..=index.max_level())
+                .map(|layer| index.node_neighbours(i, layer))
+                .collect();
+            let level = if index.entry_point() == Some(i as u32) {
+                index.max_level()
+            } else {
+                neighbors.iter().rposition(|l| !l.is_empty()).unwrap_or(0) as u8
+            };
+            neighbors.truncate(level as usize + 1);
+            builder.nodes.push(HNSWNode {
+                vector,
+                level,
+                neighbors,
+            });
+            builder.handles.push(handle);
+        }
+        builder.entry_point = index.entry_point();
+        builder.max_level = index.max_level();
+        Ok(builder)
+    }
+
     /// Override `M` (max neighbours on non-zero layers). `M0`
     /// defaults to `2 * M` unless overridden separately.
     pub fn m(mut self, m: u16) -> Self {
@@ -158,6 +231,26 @@ impl HNSWBuilder {
         self
     }
 
+    /// Select the distance [`Metric`]. Default
+    /// [`Metric::Cosine`]; with [`Metric::Dot`] / [`Metric::L2`]
+    /// vectors are indexed un-normalized, so put their embedding
+    /// blobs with `store.put::<Embedding, _>(vec)` rather than
+    /// [`put_embedding`][crate::schemas::put_embedding].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Store a quantized code per node in the built index. The
+    /// quantizer is trained on the inserted vectors at
+    /// [`build`][Self::build]; queries then walk the graph on
+    /// the codes and rerank the final beam against the
+    /// full-precision embeddings. See [`crate::quantize`].
+    pub fn quantization(mut self, quantization: crate::quantize::Quantization) -> Self {
+        self.quantization = Some(quantization);
+        self
+    }
+
     /// Override the level-sampling PRNG seed for reproducibility
     /// across runs.
     pub fn with_seed(mut self, seed: u64) -> Self {
@@ -187,11 +280,12 @@ impl HNSWBuilder {
     /// carries the handle, so embeddings live in the pile's
     /// blob store and dedupe across indexes.
     ///
-    /// The vector is L2-normalized in place before distance
-    /// computation, so the index treats its metric as cosine
-    /// similarity; the stored `handle` is expected to point at
-    /// an already-normalized embedding (the [`put_embedding`]
-    /// helper normalizes before put).
+    /// Under [`Metric::Cosine`] (the default) the vector is
+    /// L2-normalized in place before distance computation; the
+    /// stored `handle` is expected to point at an
+    /// already-normalized embedding (the [`put_embedding`]
+    /// helper normalizes before put). Other metrics index the
+    /// vector as given.
     ///
     /// Note: the index stores handles only — the caller's
     /// mapping from doc / entity to embedding handle is a
@@ -210,7 +304,9 @@ impl HNSWBuilder {
                 got: vec.len(),
             });
         }
-        normalize(&mut vec);
+        if self.metric.normalizes() {
+            normalize(&mut vec);
+        }
         let new_level = self.sample_level();
         let new_idx = self.nodes.len() as u32;
 
@@ -271,14 +367,50 @@ impl HNSWBuilder {
         Ok(())
     }
 
+    /// Tombstone every node inserted so far under `handle`.
+    ///
+    /// Tombstoned nodes keep routing inserts until the next
+    /// [`build`][Self::build], which drops them and reconnects
+    /// their neighbours through them. Re-inserting the handle
+    /// afterwards adds a fresh node that is kept.
+    pub fn remove(&mut self, handle: Value<Handle<Blake3, Embedding>>) {
+        self.removed.insert(handle.raw, self.nodes.len() as u32);
+    }
+
+    /// Number of nodes, including ones tombstoned since the last
+    /// build.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// `true` if no nodes were inserted.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
     /// Consume the builder and produce a succinct HNSW index,
     /// ready to `put` into a pile or query directly. This is
     /// the production path — the naive in-memory [`HNSWIndex`]
     /// is kept only as a reference oracle (see
     /// [`build_naive`][Self::build_naive]).
-    pub fn build(self) -> crate::succinct::SuccinctHNSWIndex {
-        crate::succinct::SuccinctHNSWIndex::from_naive(&self.build_naive())
-            .expect("from_naive cannot fail on a valid HNSWIndex built by HNSWBuilder")
+    ///
+    /// With a [`quantization`][Self::quantization] scheme set,
+    /// the quantizer is trained on the surviving nodes' vectors
+    /// here and their codes are stored in the index.
+    pub fn build(mut self) -> crate::succinct::SuccinctHNSWIndex {
+        self.purge_removed();
+        let quantized = self.quantization.map(|kind| {
+            let rows: Vec<&[f32]> = self.nodes.iter().map(|n| &n.vector[..]).collect();
+            let quantizer = crate::quantize::Quantizer::train(kind, self.dim, &rows);
+            let codes: Vec<u8> = rows.iter().flat_map(|v| quantizer.encode(v)).collect();
+            (quantizer, codes)
+        });
+        let index = crate::succinct::SuccinctHNSWIndex::from_naive(&self.build_naive())
+            .expect("from_naive cannot fail on a valid HNSWIndex built by HNSWBuilder");
+        match quantized {
+            Some((quantizer, codes)) => index.with_codes(quantizer, codes),
+            None => index,
+        }
     }
 
     /// Naive layered-graph reference index. Strips the inline
@@ -289,7 +421,8 @@ impl HNSWBuilder {
     /// callers already hold a naive index and want
     /// [`SuccinctHNSWIndex::from_naive`][crate::succinct::SuccinctHNSWIndex::from_naive]
     /// directly. Most callers want [`build`][Self::build].
-    pub fn build_naive(self) -> HNSWIndex {
+    pub fn build_naive(mut self) -> HNSWIndex {
+        self.purge_removed();
         let nodes: Vec<HNSWIndexNode> = self
             .nodes
             .into_iter()
@@ -302,6 +435,7 @@ impl HNSWBuilder {
             dim: self.dim,
             m: self.m,
             m0: self.m0,
+            metric: self.metric,
             nodes,
             handles: self.handles,
             entry_point: self.entry_point,
@@ -309,6 +443,124 @@ impl HNSWBuilder {
         }
     }
 
+    /// Drop tombstoned nodes and repair the graph around them.
+    ///
+    /// Each live neighbour list that pointed at a dead node is
+    /// refilled from the live nodes reachable through dead ones
+    /// (closest first, up to the layer cap). Nodes left without
+    /// any layer-0 neighbour are relinked by a fresh search from
+    /// the entry point.
+    fn purge_removed(&mut self) {
+        use std::collections::HashSet;
+
+        let removed = std::mem::take(&mut self.removed);
+        let n = self.nodes.len();
+        let dead: Vec<bool> = (0..n)
+            .map(|i| {
+                removed
+                    .get(&self.handles[i].raw)
+                    .is_some_and(|&cut| (i as u32) < cut)
+            })
+            .collect();
+        if !dead.contains(&true) {
+            return;
+        }
+
+        for i in (0..n).filter(|&i| !dead[i]) {
+            for layer in 0..self.nodes[i].neighbors.len() {
+                let list = &self.nodes[i].neighbors[layer];
+                if !list.iter().any(|&j| dead[j as usize]) {
+                    continue;
+                }
+                let mut stack: Vec<u32> = list.clone();
+                let mut seen: HashSet<u32> = stack.iter().copied().collect();
+                seen.insert(i as u32);
+                let mut candidates = Vec::new();
+                while let Some(j) = stack.pop() {
+                    if !dead[j as usize] {
+                        candidates.push(j);
+                    } else if candidates.len() < self.ef_construction as usize {
+                        for &k in self.nodes[j as usize].neighbors.get(layer).into_iter().flatten() {
+                            if seen.insert(k) {
+                                stack.push(k);
+                            }
+                        }
+                    }
+                }
+                let q = &self.nodes[i].vector;
+                let scored: Vec<(u32, f32)> = candidates
+                    .into_iter()
+                    .map(|j| (j, self.metric.distance(q, &self.nodes[j as usize].vector)))
+                    .collect();
+                let cap = if layer == 0 { self.m0 } else { self.m } as usize;
+                let mut selected = Self::select_neighbours(&scored, cap);
+                selected.sort_unstable();
+                self.nodes[i].neighbors[layer] = selected;
+            }
+        }
+
+        // Compact: renumber the survivors.
+        let mut remap = vec![u32::MAX; n];
+        let mut next = 0u32;
+        for (i, slot) in remap.iter_mut().enumerate() {
+            if !dead[i] {
+                *slot = next;
+                next += 1;
+            }
+        }
+        let nodes = std::mem::take(&mut self.nodes);
+        let handles = std::mem::take(&mut self.handles);
+        for ((mut node, handle), is_dead) in nodes.into_iter().zip(handles).zip(&dead) {
+            if *is_dead {
+                continue;
+            }
+            for list in &mut node.neighbors {
+                list.retain(|&j| !dead[j as usize]);
+                for j in list.iter_mut() {
+                    *j = remap[*j as usize];
+                }
+            }
+            self.nodes.push(node);
+            self.handles.push(handle);
+        }
+
+        self.entry_point = match self.entry_point {
+            Some(ep) if !dead[ep as usize] => Some(remap[ep as usize]),
+            _ => (0..self.nodes.len() as u32).max_by_key(|&i| (self.nodes[i as usize].level, u32::MAX - i)),
+        };
+        self.max_level = self
+            .entry_point
+            .map(|ep| self.nodes[ep as usize].level)
+            .unwrap_or(0);
+
+        // Relink nodes the purge cut off entirely.
+        let Some(entry) = self.entry_point else {
+            return;
+        };
+        let m0 = self.m0 as usize;
+        for i in 0..self.nodes.len() as u32 {
+            if self.nodes.len() < 2 || !self.nodes[i as usize].neighbors[0].is_empty() {
+                continue;
+            }
+            let q = self.nodes[i as usize].vector.clone();
+            let mut curr = entry;
+            for lvl in (1..=self.max_level).rev() {
+                curr = self.greedy_search_layer(&q, curr, lvl);
+            }
+            let candidates: Vec<(u32, f32)> = self
+                .search_layer(&q, curr, self.ef_construction as usize, 0)
+                .into_iter()
+                .filter(|&(j, _)| j != i)
+                .collect();
+            for n in Self::select_neighbours(&candidates, m0) {
+                self.nodes[i as usize].neighbors[0].push(n);
+                self.nodes[n as usize].neighbors[0].push(i);
+                self.prune_neighbours(n, 0, m0);
+            }
+            self.prune_neighbours(i, 0, m0);
+        }
+    }
+
     // ── HNSW primitives (shared with the immutable index) ────────
 
     /// Walk greedily to the node with minimum distance to `q` on
@@ -317,7 +569,7 @@ impl HNSWBuilder {
     /// and search.
     fn greedy_search_layer(&self, q: &[f32], entry: u32, layer: u8) -> u32 {
         let mut curr = entry;
-        let mut curr_dist = cosine_dist(q, &self.nodes[curr as usize].vector);
+        let mut curr_dist = self.metric.distance(q, &self.nodes[curr as usize].vector);
         loop {
             let mut changed = false;
             let node = &self.nodes[curr as usize];
@@ -325,7 +577,7 @@ impl HNSWBuilder {
                 return curr;
             };
             for &n in neigh {
-                let d = cosine_dist(q, &self.nodes[n as usize].vector);
+                let d = self.metric.distance(q, &self.nodes[n as usize].vector);
                 if d < curr_dist {
                     curr_dist = d;
                     curr = n;
@@ -345,7 +597,7 @@ impl HNSWBuilder {
 
         let mut visited: std::collections::HashSet<u32> = std::collections::HashSet::new();
         visited.insert(entry);
-        let d0 = cosine_dist(q, &self.nodes[entry as usize].vector);
+        let d0 = self.metric.distance(q, &self.nodes[entry as usize].vector);
         let mut candidates: BinaryHeap<MinDist> = BinaryHeap::new();
         candidates.push(MinDist {
             idx: entry,
@@ -369,7 +621,7 @@ impl HNSWBuilder {
                 if !visited.insert(n) {
                     continue;
                 }
-                let d = cosine_dist(q, &self.nodes[n as usize].vector);
+                let d = self.metric.distance(q, &self.nodes[n as usize].vector);
                 let farthest = results.peek().map(|r| r.dist).unwrap_or(f32::INFINITY);
                 if d < farthest || results.len() < ef {
                     candidates.push(MinDist { idx: n, dist: d });
@@ -411,7 +663,7 @@ impl HNSWBuilder {
         let q = self.nodes[node as usize].vector.clone();
         let mut scored: Vec<(u32, f32)> = list_snapshot
             .iter()
-            .map(|&n| (n, cosine_dist(&q, &self.nodes[n as usize].vector)))
+            .map(|&n| (n, self.metric.distance(&q, &self.nodes[n as usize].vector)))
             .collect();
         scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
         let list = &mut self.nodes[node as usize].neighbors[layer as usize];
@@ -438,6 +690,7 @@ pub struct HNSWIndex {
     dim: usize,
     m: u16,
     m0: u16,
+    metric: Metric,
     /// Post-build per-node state. Neighbour lists survive; the
     /// vectors were stripped — distance evaluations resolve
     /// handles through a caller-supplied blob store.
@@ -478,6 +731,10 @@ impl HNSWIndex {
     pub fn m0(&self) -> u16 {
         self.m0
     }
+    /// Distance metric the graph was built under.
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
     /// Highest layer a node was inserted at.
     pub fn max_level(&self) -> u8 {
         self.max_level
@@ -589,7 +846,8 @@ where
     }
 
     /// Build a symmetric similarity constraint over two handle
-    /// variables, gated by a fixed cosine `score_floor`. Both
+    /// variables, gated by a fixed `score_floor` (cosine under
+    /// the default metric; see [`Metric::score`]). Both
     /// variables bind to `Handle<Blake3, Embedding>` values —
     /// callers typically get `a` from a trible pattern
     /// (e.g. "embedding attached to this doc") and leave `b`
@@ -606,7 +864,7 @@ where
 
     /// Convenience wrapper for the common
     /// "search from a known handle" case. Binds `var` to every
-    /// handle whose cosine similarity to `probe` clears
+    /// handle whose similarity score to `probe` clears
     /// `score_floor`. Equivalent to
     /// `temp!((a), and!(a.is(probe), self.similar(a, var, floor)))`
     /// but without the temp-variable ceremony at the call site;
@@ -628,8 +886,42 @@ where
         crate::constraint::SimilarTo::from_candidates(var, candidates)
     }
 
+    /// Filtered counterpart of [`similar`][Self::similar]:
+    /// additionally requires `filter.allows(b)`. The naive
+    /// oracle post-filters its walk; the production
+    /// [`AttachedSuccinctHNSWIndex::similar_where`][w] consults
+    /// the filter during traversal.
+    ///
+    /// [w]: crate::succinct::AttachedSuccinctHNSWIndex::similar_where
+    pub fn similar_where<'f>(
+        &'f self,
+        a: Variable<EmbHandle>,
+        b: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &'f (dyn HandleFilter + Sync),
+    ) -> crate::constraint::Similar<'f, Self> {
+        crate::constraint::Similar::new(self, a, b, score_floor).with_filter(filter)
+    }
+
+    /// Filtered counterpart of [`similar_to`][Self::similar_to].
+    pub fn similar_to_where(
+        &self,
+        probe: Value<EmbHandle>,
+        var: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> crate::constraint::SimilarTo {
+        use crate::constraint::SimilaritySearch;
+        let candidates = self
+            .neighbours_above_filtered(probe, score_floor, filter)
+            .into_iter()
+            .map(|h| h.raw)
+            .collect();
+        crate::constraint::SimilarTo::from_candidates(var, candidates)
+    }
+
     /// Walk the graph from `from_handle`'s embedding and return
-    /// every handle whose cosine similarity is at least
+    /// every handle whose [`Metric::score`] is at least
     /// `score_floor`. The core primitive that the similarity
     /// constraint calls; exposed for tests and for callers who
     /// want the walk without the constraint wrapper.
@@ -650,9 +942,10 @@ where
         if query.len() != self.index.dim {
             return Ok(Vec::new());
         }
-        // The stored vectors are pre-normalised, and embeddings
-        // that land here came from `put_embedding` which also
-        // L2-normalises, so the query is unit-length already.
+        // Under cosine the stored vectors are pre-normalised, and
+        // embeddings that land here came from `put_embedding`
+        // which also L2-normalises, so the query is unit-length
+        // already.
         let mut curr = entry;
         for lvl in (1..=self.index.max_level).rev() {
             curr = self.greedy_search_layer(&query, curr, lvl)?;
@@ -660,7 +953,7 @@ where
         let candidates = self.search_layer(&query, curr, self.ef_search, 0)?;
         Ok(candidates
             .into_iter()
-            .filter(|(_, dist)| 1.0 - dist >= score_floor)
+            .filter(|(_, dist)| self.index.metric.score(*dist) >= score_floor)
             .map(|(i, _)| self.index.handles[i as usize])
             .collect())
     }
@@ -672,7 +965,7 @@ where
     ) -> Result<f32, B::GetError<anybytes::view::ViewError>> {
         let handle = self.index.handles[i as usize];
         let view = self.cache.get(handle)?;
-        Ok(cosine_dist(q, view.as_ref().as_ref()))
+        Ok(self.index.metric.distance(q, view.as_ref().as_ref()))
     }
 
     fn greedy_search_layer(
@@ -756,9 +1049,82 @@ where
     }
 }
 
-/// Cosine distance = 1 - dot(a, b) for pre-normalized vectors.
-pub(crate) fn cosine_dist(a: &[f32], b: &[f32]) -> f32 {
-    1.0 - dot(a, b)
+/// An embedding handle paired with its [`Metric::score`] against
+/// a probe — one entry of a ranked `nearest` result.
+pub type ScoredHandle = (Value<EmbHandle>, f32);
+
+/// Distance function an index ranks neighbours by. Chosen per
+/// index on [`HNSWBuilder::metric`] / [`FlatBuilder::metric`]
+/// and persisted in the succinct HNSW blob header.
+///
+/// Similarity floors (`score_floor` on `similar` /
+/// `candidates_above`) compare against [`score`][Self::score],
+/// which is "larger is closer" for every metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    /// Cosine similarity. Vectors are L2-normalized at insert,
+    /// so the distance is `1 - dot(a, b)` and the score is the
+    /// cosine itself, in `[-1, 1]`.
+    #[default]
+    Cosine,
+    /// Raw inner product (maximum inner-product search).
+    /// Vectors are indexed as given; the score is `dot(a, b)`.
+    Dot,
+    /// Euclidean distance. Vectors are indexed as given; the
+    /// score is the *negated* distance, so a floor of `-r`
+    /// keeps everything within radius `r`.
+    L2,
+}
+
+impl Metric {
+    /// Distance between `a` and `b` — smaller is closer.
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Self::Cosine => 1.0 - dot(a, b),
+            Self::Dot => -dot(a, b),
+            Self::L2 => {
+                debug_assert_eq!(a.len(), b.len());
+                a.iter()
+                    .zip(b)
+                    .map(|(&x, &y)| (x - y) * (x - y))
+                    .sum::<f32>()
+                    .sqrt()
+            }
+        }
+    }
+
+    /// Map a [`distance`][Self::distance] to the score that
+    /// similarity floors compare against.
+    pub fn score(self, dist: f32) -> f32 {
+        match self {
+            Self::Cosine => 1.0 - dist,
+            Self::Dot | Self::L2 => -dist,
+        }
+    }
+
+    /// `true` if inserted vectors are L2-normalized first.
+    pub fn normalizes(self) -> bool {
+        self == Self::Cosine
+    }
+
+    /// Header tag.
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            Self::Cosine => 0,
+            Self::Dot => 1,
+            Self::L2 => 2,
+        }
+    }
+
+    /// Inverse of [`code`][Self::code].
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Cosine),
+            1 => Some(Self::Dot),
+            2 => Some(Self::L2),
+            _ => None,
+        }
+    }
 }
 
 /// Min-heap wrapper: smaller distance = higher priority.
@@ -834,6 +1200,34 @@ impl std::fmt::Display for DimMismatch {
 
 impl std::error::Error for DimMismatch {}
 
+/// Failure reopening a persisted index with
+/// [`HNSWBuilder::from_succinct`].
+#[derive(Debug)]
+pub enum HNSWLoadError<E> {
+    /// A node's embedding couldn't be fetched from the store.
+    Blob(E),
+    /// A stored embedding disagrees with the index dimensionality.
+    Dim(DimMismatch),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for HNSWLoadError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Blob(e) => write!(f, "failed to load embedding: {e}"),
+            Self::Dim(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for HNSWLoadError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Blob(e) => Some(e),
+            Self::Dim(e) => Some(e),
+        }
+    }
+}
+
 /// L2-normalize `v` in place. Zero vectors are left untouched.
 pub(crate) fn normalize(v: &mut [f32]) {
     let norm_sq: f32 = v.iter().map(|&x| x * x).sum();
@@ -846,7 +1240,7 @@ pub(crate) fn normalize(v: &mut [f32]) {
 }
 
 /// Dot product. Assumes both slices have equal length.
-fn dot(a: &[f32], b: &[f32]) -> f32 {
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
     debug_assert_eq!(a.len(), b.len());
     a.iter().zip(b.iter()).map(|(&x, &y)| x * y).sum()
 }
@@ -862,6 +1256,7 @@ fn dot(a: &[f32], b: &[f32]) -> f32 {
 #[doc(hidden)]
 pub struct FlatBuilder {
     dim: usize,
+    metric: Metric,
     handles: Vec<Value<Handle<Blake3, Embedding>>>,
 }
 
@@ -873,10 +1268,19 @@ impl FlatBuilder {
         assert!(dim > 0, "FlatBuilder: dim must be > 0");
         Self {
             dim,
+            metric: Metric::Cosine,
             handles: Vec::new(),
         }
     }
 
+    /// Select the distance [`Metric`]. Default
+    /// [`Metric::Cosine`] — the exact oracle for an
+    /// [`HNSWBuilder`] configured with the same metric.
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
     /// Insert an embedding by its `handle` — the handle points
     /// at an [`Embedding`] blob in the pile's blob store. The
     /// builder stores neither the raw vector nor any copy of
@@ -894,6 +1298,7 @@ impl FlatBuilder {
     pub fn build(self) -> FlatIndex {
         FlatIndex {
             dim: self.dim,
+            metric: self.metric,
             handles: self.handles,
         }
     }
@@ -922,16 +1327,17 @@ impl FlatBuilder {
 /// [`BlobStoreGet`][g] at query time, so two indexes that
 /// embed the same entity share storage.
 ///
-/// Scores are cosine similarity in `[-1, 1]` **iff** the
-/// stored embeddings are L2-normalized (the convention — see
-/// [`Embedding`]'s docs). `similar()` L2-normalizes the query
-/// itself so the dot product reads back as cosine.
+/// Under the default [`Metric::Cosine`], scores are cosine
+/// similarity in `[-1, 1]` **iff** the stored embeddings are
+/// L2-normalized (the convention — see [`Embedding`]'s docs).
+/// Other metrics score per [`Metric::score`].
 ///
 /// [g]: triblespace_core::repo::BlobStoreGet
 #[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct FlatIndex {
     dim: usize,
+    metric: Metric,
     handles: Vec<Value<Handle<Blake3, Embedding>>>,
 }
 
@@ -941,6 +1347,11 @@ impl FlatIndex {
         self.dim
     }
 
+    /// Distance metric candidates are scored under.
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
     /// Number of indexed embeddings.
     pub fn doc_count(&self) -> usize {
         self.handles.len()
@@ -999,7 +1410,7 @@ where
     }
 
     /// Build a symmetric similarity constraint over two handle
-    /// variables, gated by a fixed cosine `score_floor`. Mirrors
+    /// variables, gated by a fixed `score_floor`. Mirrors
     /// [`AttachedHNSWIndex::similar`][a] for the brute-force
     /// case — O(N) in the corpus. See [`crate::constraint::Similar`].
     ///
@@ -1033,8 +1444,36 @@ where
         crate::constraint::SimilarTo::from_candidates(var, candidates)
     }
 
-    /// Walk every stored handle and return those with cosine
-    /// similarity ≥ `score_floor` to the embedding referenced by
+    /// Filtered counterpart of [`similar`][Self::similar]:
+    /// additionally requires `filter.allows(b)`. See
+    /// [`crate::constraint::Similar`].
+    pub fn similar_where<'f>(
+        &'f self,
+        a: Variable<EmbHandle>,
+        b: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &'f (dyn HandleFilter + Sync),
+    ) -> crate::constraint::Similar<'f, Self> {
+        crate::constraint::Similar::new(self, a, b, score_floor).with_filter(filter)
+    }
+
+    /// Filtered counterpart of [`similar_to`][Self::similar_to].
+    pub fn similar_to_where(
+        &self,
+        probe: Value<EmbHandle>,
+        var: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> crate::constraint::SimilarTo {
+        let candidates = self
+            .candidates_above_filtered(probe, score_floor, filter)
+            .map(|v| v.into_iter().map(|h| h.raw).collect())
+            .unwrap_or_default();
+        crate::constraint::SimilarTo::from_candidates(var, candidates)
+    }
+
+    /// Walk every stored handle and return those whose
+    /// [`Metric::score`] is ≥ `score_floor` to the embedding referenced by
     /// `from_handle`. Mirrors [`AttachedHNSWIndex::candidates_above`][a]
     /// for the brute-force case — O(N) in the corpus.
     ///
@@ -1049,17 +1488,70 @@ where
         if query.len() != self.index.dim {
             return Ok(Vec::new());
         }
-        // Already-normalised by put_embedding, so dot = cosine.
+        // Under cosine, already-normalised by put_embedding, so
+        // the metric reduces to a dot product.
+        let metric = self.index.metric;
         let mut out = Vec::new();
         for &handle in self.index.handles.iter() {
             let view = self.cache.get(handle)?;
-            let score = dot(query, view.as_ref().as_ref());
+            let score = metric.score(metric.distance(query, view.as_ref().as_ref()));
             if score >= score_floor {
                 out.push(handle);
             }
         }
         Ok(out)
     }
+
+    /// [`candidates_above`][Self::candidates_above] restricted to
+    /// handles `filter` allows. Disallowed handles are skipped
+    /// before their embeddings are fetched.
+    pub fn candidates_above_filtered(
+        &self,
+        from_handle: Value<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> Result<Vec<Value<EmbHandle>>, B::GetError<anybytes::view::ViewError>> {
+        let from = self.cache.get(from_handle)?;
+        let query = from.as_ref().as_ref();
+        if query.len() != self.index.dim {
+            return Ok(Vec::new());
+        }
+        let metric = self.index.metric;
+        let mut out = Vec::new();
+        for &handle in self.index.handles.iter().filter(|h| filter.allows(h)) {
+            let view = self.cache.get(handle)?;
+            if metric.score(metric.distance(query, view.as_ref().as_ref())) >= score_floor {
+                out.push(handle);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Exact top-`k` counterpart of
+    /// [`AttachedSuccinctHNSWIndex::nearest`][n]: every stored
+    /// handle scored, best first, truncated to `k`.
+    ///
+    /// [n]: crate::succinct::AttachedSuccinctHNSWIndex::nearest
+    pub fn nearest(
+        &self,
+        from_handle: Value<EmbHandle>,
+        k: usize,
+    ) -> Result<Vec<ScoredHandle>, B::GetError<anybytes::view::ViewError>> {
+        let from = self.cache.get(from_handle)?;
+        let query = from.as_ref().as_ref();
+        if query.len() != self.index.dim {
+            return Ok(Vec::new());
+        }
+        let metric = self.index.metric;
+        let mut out = Vec::with_capacity(self.index.handles.len());
+        for &handle in self.index.handles.iter() {
+            let view = self.cache.get(handle)?;
+            out.push((handle, metric.score(metric.distance(query, view.as_ref().as_ref()))));
+        }
+        out.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        out.truncate(k);
+        Ok(out)
+    }
 }
 
 impl FlatIndex {
@@ -1085,7 +1577,7 @@ where
         self.candidates_above(from, score_floor).unwrap_or_default()
     }
 
-    fn cosine_between(
+    fn score_between(
         &self,
         a: Value<Handle<Blake3, Embedding>>,
         b: Value<Handle<Blake3, Embedding>>,
@@ -1097,7 +1589,8 @@ where
         if a_slice.len() != b_slice.len() {
             return None;
         }
-        Some(dot(a_slice, b_slice))
+        let metric = self.index.metric;
+        Some(metric.score(metric.distance(a_slice, b_slice)))
     }
 }
 
@@ -1113,7 +1606,7 @@ where
         self.candidates_above(from, score_floor).unwrap_or_default()
     }
 
-    fn cosine_between(
+    fn score_between(
         &self,
         a: Value<Handle<Blake3, Embedding>>,
         b: Value<Handle<Blake3, Embedding>>,
@@ -1125,7 +1618,18 @@ where
         if a_slice.len() != b_slice.len() {
             return None;
         }
-        Some(dot(a_slice, b_slice))
+        let metric = self.index.metric;
+        Some(metric.score(metric.distance(a_slice, b_slice)))
+    }
+
+    fn neighbours_above_filtered(
+        &self,
+        from: Value<Handle<Blake3, Embedding>>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> Vec<Value<Handle<Blake3, Embedding>>> {
+        self.candidates_above_filtered(from, score_floor, filter)
+            .unwrap_or_default()
     }
 }
 
@@ -1134,7 +1638,7 @@ mod tests {
     use super::*;
 
     use triblespace_core::blob::MemoryBlobStore;
-    use triblespace_core::repo::BlobStore;
+    use triblespace_core::repo::{BlobStore, BlobStorePut};
     use triblespace_core::value::schemas::hash::Blake3;
 
     /// Put `vec` into `store` as a normalized [`Embedding`] blob
@@ -1370,6 +1874,134 @@ mod tests {
         assert!(recall >= 0.7, "HNSW recall {recall:.2} below 0.7 threshold");
     }
 
+    fn random_vecs(seed: u64, n: usize, dim: usize) -> Vec<Vec<f32>> {
+        let mut rng = seed;
+        let mut next = || {
+            rng = rng.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = rng;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        (0..n)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| (next() as i32 as f32) / (i32::MAX as f32))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Fraction of the flat oracle's above-`floor` hits that the
+    /// HNSW index also returns, summed over `probes`.
+    fn recall_against_flat(
+        hnsw: &crate::succinct::SuccinctHNSWIndex,
+        live: &[Value<EmbHandle>],
+        store: &mut MemoryBlobStore<Blake3>,
+        probes: &[Value<EmbHandle>],
+        floor: f32,
+    ) -> f32 {
+        let mut flat = FlatBuilder::new(hnsw.dim()).metric(hnsw.metric());
+        for h in live {
+            flat.insert(*h);
+        }
+        let flat = flat.build();
+        let reader = reader_of(store);
+        let flat_view = flat.attach(&reader);
+        let hnsw_view = hnsw.attach(&reader).with_ef_search(50);
+        let mut total_hits = 0usize;
+        let mut total_overlap = 0usize;
+        for probe in probes {
+            let truth: std::collections::HashSet<_> =
+                flat_view.candidates_above(*probe, floor).unwrap().into_iter().collect();
+            let got: std::collections::HashSet<_> =
+                hnsw_view.candidates_above(*probe, floor).unwrap().into_iter().collect();
+            total_hits += truth.len();
+            total_overlap += truth.intersection(&got).count();
+        }
+        assert!(total_hits > 0, "test fixture: floor excluded everything");
+        total_overlap as f32 / total_hits as f32
+    }
+
+    #[test]
+    fn hnsw_reopened_index_accepts_inserts() {
+        let dim = 16;
+        let vecs = random_vecs(0xFEED, 300, dim);
+        let (base, mut store, mut handles) = build_hnsw(dim, 42, &vecs[..150]);
+        let persisted =
+            crate::succinct::SuccinctHNSWIndex::try_from_bytes(&base.to_bytes()).unwrap();
+
+        let mut b = HNSWBuilder::from_succinct(&persisted, &reader_of(&mut store)).unwrap();
+        assert_eq!(b.len(), 150);
+        for v in &vecs[150..] {
+            let h = put_emb(&mut store, v.clone());
+            b.insert(h, v.clone()).unwrap();
+            handles.push(h);
+        }
+        let grown = b.build();
+        assert_eq!(grown.doc_count(), 300);
+        assert_eq!(grown.m(), base.m());
+        for (i, h) in handles.iter().enumerate() {
+            assert_eq!(grown.handle(i), Some(*h));
+        }
+
+        // Probe both the original and the freshly inserted nodes.
+        let probes: Vec<_> = handles.iter().step_by(30).copied().collect();
+        let recall = recall_against_flat(&grown, &handles, &mut store, &probes, 0.6);
+        assert!(recall >= 0.7, "reopened HNSW recall {recall:.2} below 0.7 threshold");
+    }
+
+    #[test]
+    fn hnsw_removed_handles_are_purged() {
+        let dim = 16;
+        let vecs = random_vecs(0xD00D, 250, dim);
+        let (base, mut store, handles) = build_hnsw(dim, 7, &vecs);
+        let mut b = HNSWBuilder::from_succinct(&base, &reader_of(&mut store)).unwrap();
+        // Tombstone every third node, the entry point included.
+        let entry = base.handle(base.entry_point().unwrap() as usize).unwrap();
+        b.remove(entry);
+        let mut removed: std::collections::HashSet<_> = [entry].into_iter().collect();
+        for h in handles.iter().step_by(3) {
+            b.remove(*h);
+            removed.insert(*h);
+        }
+        let pruned = b.build();
+        let live: Vec<_> = handles.iter().copied().filter(|h| !removed.contains(h)).collect();
+        assert_eq!(pruned.doc_count(), live.len());
+        for i in 0..pruned.doc_count() {
+            assert!(!removed.contains(&pruned.handle(i).unwrap()));
+        }
+
+        let reader = reader_of(&mut store);
+        let view = pruned.attach(&reader);
+        for probe in live.iter().take(10) {
+            for hit in view.candidates_above(*probe, 0.0).unwrap() {
+                assert!(!removed.contains(&hit));
+            }
+        }
+        drop(view);
+        let probes: Vec<_> = live.iter().step_by(25).copied().collect();
+        let recall = recall_against_flat(&pruned, &live, &mut store, &probes, 0.6);
+        assert!(recall >= 0.7, "pruned HNSW recall {recall:.2} below 0.7 threshold");
+    }
+
+    #[test]
+    fn hnsw_reinsert_after_remove_survives() {
+        let vecs = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]];
+        let (base, mut store, handles) = build_hnsw(2, 42, &vecs);
+        let mut b = HNSWBuilder::from_succinct(&base, &reader_of(&mut store)).unwrap();
+        b.remove(handles[2]);
+        b.insert(handles[2], vecs[2].clone()).unwrap();
+        b.remove(handles[0]);
+        let idx = b.build();
+        assert_eq!(idx.doc_count(), 2);
+        let hits = idx
+            .attach(&reader_of(&mut store))
+            .candidates_above(handles[2], 0.999)
+            .unwrap();
+        assert_eq!(hits, vec![handles[2]]);
+    }
+
     #[test]
     fn hnsw_deterministic_seed_reproduces_structure() {
         let vecs: Vec<Vec<f32>> = (1u8..=20)
@@ -1450,4 +2082,287 @@ mod tests {
         let larger = b.build_naive().byte_size();
         assert!(larger > small);
     }
+
+    /// Build an HNSW index configured by `configure`, putting
+    /// the vectors as given (no normalization) so non-cosine
+    /// metrics see the raw geometry.
+    fn build_raw_hnsw(
+        vecs: &[Vec<f32>],
+        configure: impl FnOnce(HNSWBuilder) -> HNSWBuilder,
+    ) -> (
+        crate::succinct::SuccinctHNSWIndex,
+        MemoryBlobStore<Blake3>,
+        Vec<Value<EmbHandle>>,
+    ) {
+        let mut store = MemoryBlobStore::<Blake3>::new();
+        let mut b = configure(HNSWBuilder::new(vecs[0].len()).with_seed(42));
+        let mut handles = Vec::with_capacity(vecs.len());
+        for v in vecs {
+            let h = store.put::<Embedding, _>(v.clone()).unwrap();
+            b.insert(h, v.clone()).unwrap();
+            handles.push(h);
+        }
+        (b.build(), store, handles)
+    }
+
+    #[test]
+    fn hnsw_dot_and_l2_metrics_match_flat() {
+        let vecs: Vec<Vec<f32>> = random_vecs(0xD07, 300, 16)
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| v.into_iter().map(|x| x * (1 + i % 3) as f32).collect())
+            .collect();
+        for (metric, floor) in [(Metric::Dot, 4.0), (Metric::L2, -3.0)] {
+            let (idx, mut store, handles) = build_raw_hnsw(&vecs, |b| b.metric(metric));
+            assert_eq!(idx.metric(), metric);
+            let probes: Vec<_> = handles.iter().step_by(30).copied().collect();
+            let recall = recall_against_flat(&idx, &handles, &mut store, &probes, floor);
+            assert!(recall >= 0.7, "{metric:?} recall {recall:.2} below 0.7 threshold");
+        }
+    }
+
+    #[test]
+    fn metric_scores_are_larger_when_closer() {
+        let (a, b, c) = ([1.0f32, 0.0], [2.0f32, 0.0], [0.0f32, 3.0]);
+        assert_eq!(Metric::L2.score(Metric::L2.distance(&a, &b)), -1.0);
+        assert_eq!(Metric::Dot.score(Metric::Dot.distance(&a, &b)), 2.0);
+        assert_eq!(Metric::Cosine.score(Metric::Cosine.distance(&a, &c)), 0.0);
+        for m in [Metric::Cosine, Metric::Dot, Metric::L2] {
+            assert_eq!(Metric::from_code(m.code()), Some(m));
+        }
+    }
+
+    #[test]
+    fn hnsw_quantized_walk_reranks_against_full_precision() {
+        use crate::quantize::Quantization;
+
+        let dim = 16;
+        let vecs = random_vecs(0x0B17, 300, dim);
+        let kinds = [
+            Quantization::Int8,
+            Quantization::Binary,
+            Quantization::Product {
+                subspaces: 4,
+                centroids: 32,
+            },
+        ];
+        for kind in kinds {
+            let mut store = MemoryBlobStore::<Blake3>::new();
+            let mut b = HNSWBuilder::new(dim).with_seed(42).quantization(kind);
+            let mut handles = Vec::new();
+            for v in &vecs {
+                let h = put_emb(&mut store, v.clone());
+                b.insert(h, v.clone()).unwrap();
+                handles.push(h);
+            }
+            let idx = b.build();
+            let quantizer = idx.quantizer().expect("quantized");
+            assert_eq!(idx.code(0).unwrap().len(), quantizer.code_len());
+            assert_eq!(idx.code(vecs.len()), None);
+
+            // Reranked hits clear the floor on exact scores.
+            let reader = reader_of(&mut store);
+            let view = idx.attach(&reader).with_ef_search(50);
+            for probe in handles.iter().take(5) {
+                for hit in view.candidates_above(*probe, 0.6).unwrap() {
+                    let exact = crate::constraint::SimilaritySearch::score_between(&view, *probe, hit);
+                    assert!(exact.unwrap() >= 0.6);
+                }
+            }
+            drop(view);
+            let probes: Vec<_> = handles.iter().step_by(30).copied().collect();
+            let recall = recall_against_flat(&idx, &handles, &mut store, &probes, 0.6);
+            assert!(recall >= 0.7, "{kind:?} recall {recall:.2} below 0.7 threshold");
+        }
+    }
+
+    #[test]
+    fn hnsw_metric_and_codes_persist() {
+        use crate::quantize::Quantization;
+        use crate::succinct::SuccinctHNSWIndex;
+
+        let vecs = random_vecs(0x5EED, 120, 8);
+        let kind = Quantization::Product {
+            subspaces: 2,
+            centroids: 16,
+        };
+        let (idx, mut store, handles) =
+            build_raw_hnsw(&vecs, |b| b.metric(Metric::L2).quantization(kind));
+        let back = SuccinctHNSWIndex::try_from_bytes(&idx.to_bytes()).unwrap();
+        assert_eq!(back.metric(), Metric::L2);
+        assert_eq!(back.quantizer(), idx.quantizer());
+        for i in 0..idx.doc_count() {
+            assert_eq!(back.code(i), idx.code(i));
+        }
+        assert_eq!(back.to_bytes(), idx.to_bytes());
+
+        // Reopening keeps the metric and retrains the same scheme.
+        let mut b = HNSWBuilder::from_succinct(&back, &reader_of(&mut store)).unwrap();
+        let extra = vec![0.5f32; 8];
+        let h = store.put::<Embedding, _>(extra.clone()).unwrap();
+        b.insert(h, extra).unwrap();
+        let grown = b.build();
+        assert_eq!(grown.metric(), Metric::L2);
+        assert_eq!(grown.quantizer().map(|q| q.kind()), Some(kind));
+        assert_eq!(grown.doc_count(), handles.len() + 1);
+
+        let unquantized = SuccinctHNSWIndex::try_from_bytes(&build_raw_hnsw(&vecs, |b| b).0.to_bytes()).unwrap();
+        assert_eq!(unquantized.metric(), Metric::Cosine);
+        assert!(unquantized.quantizer().is_none());
+        assert_eq!(unquantized.code(0), None);
+    }
+
+    #[test]
+    fn hnsw_rejects_product_codes_past_the_centroids() {
+        use crate::quantize::Quantization;
+        use crate::succinct::{SuccinctHNSWIndex, SuccinctLoadError};
+
+        let vecs = random_vecs(0xC0DE, 40, 8);
+        let kind = Quantization::Product {
+            subspaces: 2,
+            centroids: 16,
+        };
+        let (idx, _, _) = build_raw_hnsw(&vecs, |b| b.quantization(kind));
+        // The codes section closes the blob.
+        let mut bytes = idx.to_bytes();
+        *bytes.last_mut().unwrap() = 16;
+        let err = SuccinctHNSWIndex::try_from_bytes(&bytes).unwrap_err();
+        assert_eq!(err, SuccinctLoadError::BadMeta("codes"));
+    }
+
+    #[test]
+    fn hnsw_filtered_walk_keeps_recall_under_a_filter() {
+        let dim = 16;
+        let vecs = random_vecs(0xF117, 400, dim);
+        let (idx, mut store, handles) = build_hnsw(dim, 42, &vecs);
+        // Half the corpus: too broad for the exhaustive fallback
+        // (200² > 50 × 400), so this exercises the filtered walk.
+        let allow: std::collections::HashSet<_> = handles.iter().step_by(2).copied().collect();
+        let mut flat = FlatBuilder::new(dim);
+        for h in &handles {
+            flat.insert(*h);
+        }
+        let flat = flat.build();
+        let reader = reader_of(&mut store);
+        let flat_view = flat.attach(&reader);
+        let view = idx.attach(&reader).with_ef_search(50);
+
+        let floor = 0.5;
+        let mut total_hits = 0usize;
+        let mut total_overlap = 0usize;
+        for probe in handles.iter().skip(1).step_by(40) {
+            let truth: std::collections::HashSet<_> = flat_view
+                .candidates_above_filtered(*probe, floor, &allow)
+                .unwrap()
+                .into_iter()
+                .collect();
+            let got = view.candidates_above_filtered(*probe, floor, &allow).unwrap();
+            assert!(got.iter().all(|h| allow.contains(h)));
+            total_hits += truth.len();
+            total_overlap += got.iter().filter(|h| truth.contains(*h)).count();
+        }
+        assert!(total_hits > 0, "test fixture: floor excluded everything");
+        let recall = total_overlap as f32 / total_hits as f32;
+        assert!(recall >= 0.7, "filtered HNSW recall {recall:.2} below 0.7 threshold");
+    }
+
+    #[test]
+    fn hnsw_selective_filter_falls_back_to_exact_scan() {
+        let dim = 16;
+        let vecs = random_vecs(0x5E1E, 400, dim);
+        let (idx, mut store, handles) = build_hnsw(dim, 42, &vecs);
+        let allow: std::collections::HashSet<_> = handles.iter().step_by(50).copied().collect();
+        let mut flat = FlatBuilder::new(dim);
+        for h in &handles {
+            flat.insert(*h);
+        }
+        let flat = flat.build();
+        let reader = reader_of(&mut store);
+        let view = idx.attach(&reader);
+        for probe in handles.iter().take(5) {
+            let mut want = flat
+                .attach(&reader)
+                .candidates_above_filtered(*probe, -1.0, &allow)
+                .unwrap();
+            let mut got = view.candidates_above_filtered(*probe, -1.0, &allow).unwrap();
+            want.sort_by_key(|h| h.raw);
+            got.sort_by_key(|h| h.raw);
+            assert_eq!(got, want);
+            assert_eq!(got.len(), allow.len());
+        }
+        let none = |_: &Value<EmbHandle>| false;
+        assert!(view.candidates_above_filtered(handles[0], -1.0, &none).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hnsw_permissive_filter_is_not_evaluated_on_every_node() {
+        struct Counting<'a> {
+            calls: std::cell::Cell<usize>,
+            hint: Option<usize>,
+            allow: &'a dyn Fn(&Value<EmbHandle>) -> bool,
+        }
+        impl HandleFilter for Counting<'_> {
+            fn allows(&self, handle: &Value<EmbHandle>) -> bool {
+                self.calls.set(self.calls.get() + 1);
+                (self.allow)(handle)
+            }
+            fn size_hint(&self) -> Option<usize> {
+                self.hint
+            }
+        }
+
+        let dim = 16;
+        let vecs = random_vecs(0xC0DE, 400, dim);
+        let (idx, mut store, handles) = build_hnsw(dim, 42, &vecs);
+        let reader = reader_of(&mut store);
+        let view = idx.attach(&reader).with_ef_search(10);
+        let everything = |_: &Value<EmbHandle>| true;
+        // Counted lazily: 64² > 10 × 400 settles on the walk long
+        // before the last node; with a hint nothing is counted.
+        for hint in [None, Some(handles.len())] {
+            let filter = Counting {
+                calls: std::cell::Cell::new(0),
+                hint,
+                allow: &everything,
+            };
+            let got = view.candidates_above_filtered(handles[0], -1.0, &filter).unwrap();
+            assert!(!got.is_empty());
+            assert!(
+                filter.calls.get() < handles.len(),
+                "{} filter calls for {} nodes",
+                filter.calls.get(),
+                handles.len(),
+            );
+        }
+    }
+
+    #[test]
+    fn hnsw_nearest_ranks_like_flat() {
+        let dim = 16;
+        let vecs = random_vecs(0x4EA2, 300, dim);
+        let (idx, mut store, handles) = build_raw_hnsw(&vecs, |b| b);
+        let mut flat = FlatBuilder::new(dim);
+        for h in &handles {
+            flat.insert(*h);
+        }
+        let flat = flat.build();
+        let reader = reader_of(&mut store);
+        let flat_view = flat.attach(&reader);
+        let view = idx.attach(&reader).with_ef_search(16);
+
+        let mut overlap = 0usize;
+        for probe in handles.iter().step_by(30) {
+            let truth = flat_view.nearest(*probe, 10).unwrap();
+            // k above ef_search widens the beam rather than
+            // truncating the answer.
+            let got = view.nearest(*probe, 40).unwrap();
+            assert_eq!(got.len(), 40);
+            assert!(got.windows(2).all(|w| w[0].1 >= w[1].1));
+            assert_eq!(got[0].0, *probe);
+            let got: std::collections::HashSet<_> =
+                got.iter().take(10).map(|(h, _)| *h).collect();
+            overlap += truth.iter().filter(|(h, _)| got.contains(h)).count();
+        }
+        assert!(overlap >= 90, "top-10 overlap {overlap}/100");
+    }
 }
@@ -9,7 +9,10 @@
 //!   entity ids, tags, anything).
 //! - [`succinct::SuccinctHNSWIndex`] (schema
 //!   [`succinct::SuccinctHNSWBlob`]) — approximate
-//!   k-nearest-neighbour over caller-supplied embeddings.
+//!   k-nearest-neighbour over caller-supplied embeddings, under
+//!   a per-index [`hnsw::Metric`] (cosine, dot product or L2)
+//!   and optionally walking [`quantize`]d codes that are
+//!   reranked against the full-precision vectors.
 //!
 //! [`bm25::BM25Builder::build`] goes direct-to-succinct
 //! (sorts keys into a `CompressedUniverse` first, then
@@ -28,7 +31,25 @@
 //! Both indexes are rebuilt-and-replaced (no mutation); the
 //! caller persists the resulting handle wherever appropriate
 //! (branch metadata, commit metadata, a plain trible, or an
-//! in-memory cache).
+//! in-memory cache) — or declares them on a
+//! [`maintain::SearchIndexes`] and lets it refresh them from
+//! commit deltas after each push, recording the handles in
+//! commit metadata on a separate index branch. A persisted HNSW graph can be reopened
+//! with [`hnsw::HNSWBuilder::from_succinct`] to insert and
+//! [`remove`][hnsw::HNSWBuilder::remove] handles before
+//! re-emitting the blob. Corpora that grow incrementally can stack
+//! BM25 delta segments instead — see [`segment::BM25Segments`]
+//! (schema [`segment::BM25SegmentBlob`]), which scores across
+//! segments with global statistics and compacts them back into
+//! one blob. Since BM25 terms are hashes, prefix / wildcard /
+//! fuzzy queries go through an optional companion
+//! [`dictionary::TermDictionary`] (schema
+//! [`dictionary::TermDictionaryBlob`]) that expands a pattern
+//! into the matching term hashes. Exact phrases and proximity
+//! windows use the positional companion
+//! [`positions::PositionIndex`] (schema
+//! [`positions::PositionIndexBlob`]), built from the same
+//! `BM25Builder`.
 //!
 //! # Query surface
 //!
@@ -52,15 +73,36 @@
 //! - [`AttachedHNSWIndex::similar_to`][sth] — unary
 //!   convenience for the common "search from a known handle"
 //!   case; pins the probe on the call.
+//! - [`AttachedSuccinctHNSWIndex::similar_where`][sw] /
+//!   `similar_to_where` — the same relations restricted by a
+//!   [`HandleFilter`][constraint::HandleFilter] (allow-set or
+//!   predicate over embedding handles) that the graph walk
+//!   consults while traversing, falling back to an exact scan
+//!   when the filter is very selective.
+//! - [`PositionIndex::phrase`][ph] / [`near`][nr] — exact
+//!   phrase and proximity filters over positional postings,
+//!   same [`BM25Filter`][constraint::BM25Filter] shape as
+//!   `matches`.
+//!
+//! Ranking happens outside the engine: [`fusion::Fusion`] takes
+//! the scored lists from [`BM25Index::query_multi`][qm] and
+//! [`AttachedSuccinctHNSWIndex::nearest`][nn], fuses them by
+//! reciprocal rank or weighted normalized scores, and returns the
+//! top-k keys with a per-source breakdown.
 //!
 //! [m]: bm25::BM25Index::matches
+//! [qm]: bm25::BM25Index::query_multi
+//! [nn]: succinct::AttachedSuccinctHNSWIndex::nearest
 //! [s]: bm25::BM25Index::score
 //! [sbm25]: succinct::SuccinctBM25Index
 //! [sh]: hnsw::AttachedHNSWIndex::similar
 //! [sth]: hnsw::AttachedHNSWIndex::similar_to
 //! [sf]: hnsw::AttachedFlatIndex::similar
 //! [ssh]: succinct::AttachedSuccinctHNSWIndex::similar
+//! [sw]: succinct::AttachedSuccinctHNSWIndex::similar_where
 //! [emb]: schemas::EmbHandle
+//! [ph]: positions::PositionIndex::phrase
+//! [nr]: positions::PositionIndex::near
 //!
 //! # Quickstart
 //!
@@ -91,22 +133,37 @@
 //! assert_eq!(docs.len(), 2);
 //! ```
 //!
+//! [`highlight::Highlighter`] turns hits back into text:
+//! re-analyzing a document with the index's tokenizer, it returns
+//! the best-scoring snippets with byte offsets of every match.
+//!
 //! See the `examples/` directory for runnable walkthroughs:
 //! `compose_bm25_and_pattern` / `multi_term_bm25_search`
 //! (BM25 + pattern joins), `compose_hnsw_and_pattern`
 //! (vector similarity + pattern), `hybrid_search` (all
-//! three composed in one `find!`), and `phrase_search` for
-//! the typed-tokenizer pattern.
+//! three composed in one `find!`, then ranked by fusion), and
+//! `phrase_search` for the typed-tokenizer pattern.
 //!
 //! [`jerky`]: https://docs.rs/jerky
 
 pub mod bm25;
 pub mod constraint;
+#[cfg(feature = "succinct")]
+pub mod dictionary;
+pub mod fusion;
+pub mod highlight;
 pub mod hnsw;
 #[cfg(feature = "succinct")]
+pub mod maintain;
+#[cfg(feature = "succinct")]
+pub mod positions;
+pub mod quantize;
+#[cfg(feature = "succinct")]
 pub mod ring;
 pub mod schemas;
 #[cfg(feature = "succinct")]
+pub mod segment;
+#[cfg(feature = "succinct")]
 pub mod succinct;
 pub mod tokens;
 
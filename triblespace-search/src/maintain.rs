@@ -0,0 +1,692 @@
+#![allow(clippy::type_complexity)]
+//! Search indexes kept current with a branch.
+//!
+//! Building an index is the caller's job everywhere else in this
+//! crate; so is deciding where its handle lives. [`SearchIndexes`]
+//! packages the common case: declare an [`IndexSpec`] per index —
+//! BM25 over a `LongString` attribute, HNSW over an
+//! [`EmbHandle`] attribute — and call
+//! [`refresh`][SearchIndexes::refresh] after
+//! [`Repository::push`]. The indexes of a branch are recorded on a
+//! separate *index branch* the caller creates once, so the
+//! branch's own history only ever holds the user's commits. Each
+//! refresh
+//!
+//! 1. [`locate`]s the newest record of every declared index at the
+//!    index branch head,
+//! 2. checks out only the commits of the indexed branch the record
+//!    doesn't cover yet (`ws.checkout(indexed_head..)`),
+//! 3. folds the new tribles of the indexed attribute into the
+//!    index — a new [`BM25Segment`] on the record's segment stack
+//!    (compacted per [`BM25Segments::merge_plan`]), or new nodes
+//!    linked into the HNSW graph via
+//!    [`HNSWBuilder::from_succinct`] — and
+//! 4. pushes an empty commit to the index branch whose *commit
+//!    metadata* carries the updated records.
+//!
+//! Readers do the first step themselves: [`locate`] the record
+//! from a pull of the index branch, then load
+//! [`bm25`][IndexRecord::bm25] / [`hnsw`][IndexRecord::hnsw]. The
+//! record names the indexed commit, so
+//! [`is_current`][IndexRecord::is_current] tells whether commits
+//! landed on the indexed branch since the last refresh.
+//!
+//! Records live in commit metadata rather than branch metadata so
+//! the index branch keeps their history: every earlier record
+//! stays reachable, and merges of concurrent refreshes don't drop
+//! them.
+//!
+//! Tribles are append-only, so a refresh only ever adds: a BM25
+//! document is keyed by the entity and re-indexed from the values
+//! a delta carries for it (a newer body replaces an older one),
+//! and HNSW nodes are embedding handles, each inserted once.
+//!
+//! ```
+//! use ed25519_dalek::SigningKey;
+//! use triblespace_core::blob::schemas::longstring::LongString;
+//! use triblespace_core::id::{id_hex, ExclusiveId, Id};
+//! use triblespace_core::macros::{attributes, entity};
+//! use triblespace_core::repo::memoryrepo::MemoryRepo;
+//! use triblespace_core::repo::Repository;
+//! use triblespace_core::trible::TribleSet;
+//! use triblespace_core::value::schemas::hash::{Blake3, Handle};
+//! use triblespace_search::maintain::{locate, IndexSpec, SearchIndexes};
+//! use triblespace_search::tokens::hash_tokens;
+//!
+//! attributes! {
+//!     "501F0B54BD1240CEA95B8D228C014A58" as body: Handle<Blake3, LongString>;
+//! }
+//! const BODY_INDEX: Id = id_hex!("4C13E07BB3C546B8AE0868BAD4019A04");
+//!
+//! let mut repo = Repository::new(
+//!     MemoryRepo::default(),
+//!     SigningKey::from_bytes(&[7; 32]),
+//!     TribleSet::new(),
+//! )
+//! .unwrap();
+//! let branch = *repo.create_branch("main", None).unwrap();
+//! let index_branch = *repo.create_branch("main-search", None).unwrap();
+//! let indexes = SearchIndexes::new().with(IndexSpec::bm25(BODY_INDEX, &body));
+//!
+//! let mut ws = repo.pull(branch).unwrap();
+//! let doc = ExclusiveId::force(Id::new([1; 16]).unwrap());
+//! let text = ws.put("the quick brown fox".to_string());
+//! ws.commit(entity! { &doc @ body: text }, "add doc");
+//! repo.push(&mut ws).unwrap();
+//! indexes.refresh(&mut repo, branch, index_branch).unwrap();
+//!
+//! let mut index_ws = repo.pull(index_branch).unwrap();
+//! let record = locate(&mut index_ws, BODY_INDEX).unwrap().expect("refreshed");
+//! assert!(record.is_current(repo.pull(branch).unwrap().head()));
+//! let segments = record.bm25(&mut index_ws).unwrap();
+//! assert_eq!(segments.query_multi(&hash_tokens("fox")).len(), 1);
+//! ```
+//!
+//! [`Repository::push`]: triblespace_core::repo::Repository::push
+//! [`EmbHandle`]: crate::schemas::EmbHandle
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use anybytes::View;
+use triblespace_core::attribute::Attribute;
+use triblespace_core::blob::schemas::longstring::LongString;
+use triblespace_core::blob::schemas::simplearchive::{SimpleArchive, UnarchiveError};
+use triblespace_core::find;
+use triblespace_core::id::{ExclusiveId, Id};
+use triblespace_core::macros::{attributes, entity, pattern};
+use triblespace_core::repo::{
+    self, BlobStore, BlobStoreGet, BranchStore, CommitHandle, PullError, PushError,
+    Repository, Workspace, WorkspaceCheckoutError,
+};
+use triblespace_core::trible::TribleSet;
+use triblespace_core::value::schemas::genid::GenId;
+use triblespace_core::value::schemas::hash::{Blake3, Handle};
+use triblespace_core::value::schemas::iu256::U256BE;
+use triblespace_core::value::{TryFromValue, Value};
+
+use crate::hnsw::{DimMismatch, HNSWBuilder, HNSWLoadError};
+use crate::schemas::{EmbHandle, Embedding};
+use crate::segment::{BM25Segment, BM25SegmentBlob, BM25SegmentBuilder, BM25Segments};
+use crate::succinct::{SuccinctHNSWBlob, SuccinctHNSWIndex, SuccinctLoadError};
+use crate::tokens::{hash_tokens, WordHash};
+
+attributes! {
+    /// The commit whose history an index record covers. The record
+    /// entity's id is the [`IndexSpec::id`].
+    "CB7D8A77BF964BC9BCF64ADAE2A2FF03" as pub indexed_head: Handle<Blake3, SimpleArchive>;
+    /// The current graph of an HNSW index record.
+    "BD3FC2D0AF1C4273BB66D41A343FB704" as pub hnsw_index: Handle<Blake3, SuccinctHNSWBlob>;
+    /// The index record a BM25 segment belongs to.
+    "1A977B4A50544C5D87E34A151F882947" as pub segment_of: GenId;
+    /// One delta segment of a BM25 index record.
+    "48B789C32B4E4289932F4B5395302F17" as pub bm25_segment: Handle<Blake3, BM25SegmentBlob>;
+    /// Position of a segment in its stack, oldest first.
+    "3919709D6FBA45A59CABF5BCC279B976" as pub segment_rank: U256BE;
+}
+
+/// What an [`IndexSpec`] builds.
+#[derive(Clone, Copy)]
+enum IndexKind {
+    Text {
+        tokenize: fn(&str) -> Vec<Value<WordHash>>,
+    },
+    Vector {
+        builder: fn() -> HNSWBuilder,
+    },
+}
+
+/// Declaration of one maintained index: which attribute it
+/// covers, how it is built, and the id its records are filed
+/// under. Mint the id once (`trible genid`) and keep it stable —
+/// readers [`locate`] the index by it.
+#[derive(Clone, Copy)]
+pub struct IndexSpec {
+    id: Id,
+    attribute: Id,
+    kind: IndexKind,
+}
+
+impl IndexSpec {
+    /// BM25 over a text attribute, documents keyed by entity id
+    /// and tokenized with [`hash_tokens`].
+    pub fn bm25(id: Id, attribute: &Attribute<Handle<Blake3, LongString>>) -> Self {
+        Self::bm25_with_tokenizer(id, attribute, hash_tokens)
+    }
+
+    /// [`bm25`][Self::bm25] with a custom tokenizer. Queries must
+    /// tokenize the same way.
+    pub fn bm25_with_tokenizer(
+        id: Id,
+        attribute: &Attribute<Handle<Blake3, LongString>>,
+        tokenize: fn(&str) -> Vec<Value<WordHash>>,
+    ) -> Self {
+        Self {
+            id,
+            attribute: attribute.id(),
+            kind: IndexKind::Text { tokenize },
+        }
+    }
+
+    /// HNSW over an embedding-handle attribute. `builder`
+    /// configures the first build (dimension, metric, `M`, …);
+    /// later refreshes reopen the persisted graph, which keeps
+    /// those parameters.
+    pub fn hnsw(
+        id: Id,
+        attribute: &Attribute<EmbHandle>,
+        builder: fn() -> HNSWBuilder,
+    ) -> Self {
+        Self {
+            id,
+            attribute: attribute.id(),
+            kind: IndexKind::Vector { builder },
+        }
+    }
+
+    /// The id records of this index are filed under.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+}
+
+/// Where one index stood as of a commit, read from the commit
+/// metadata of an index branch by [`locate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexRecord {
+    /// The [`IndexSpec::id`].
+    pub id: Id,
+    /// The index branch commit whose metadata holds this record.
+    pub commit: CommitHandle,
+    /// The index covers every commit of the indexed branch
+    /// reachable from here.
+    pub indexed_head: CommitHandle,
+    /// BM25 segment stack, oldest first; empty for HNSW indexes.
+    pub segments: Vec<Value<Handle<Blake3, BM25SegmentBlob>>>,
+    /// HNSW graph; `None` for BM25 indexes.
+    pub hnsw: Option<Value<Handle<Blake3, SuccinctHNSWBlob>>>,
+}
+
+impl IndexRecord {
+    /// `true` if the record covers `head`, the head of the indexed
+    /// branch.
+    pub fn is_current(&self, head: Option<CommitHandle>) -> bool {
+        head == Some(self.indexed_head)
+    }
+
+    /// Load the BM25 segment stack.
+    pub fn bm25<B: BlobStore<Blake3>>(
+        &self,
+        ws: &mut Workspace<B>,
+    ) -> Result<BM25Segments, <B::Reader as BlobStoreGet<Blake3>>::GetError<SuccinctLoadError>>
+    {
+        let mut segments = BM25Segments::new();
+        for &h in &self.segments {
+            segments.push(ws.get::<BM25Segment, BM25SegmentBlob>(h)?);
+        }
+        Ok(segments)
+    }
+
+    /// Load the HNSW graph, if this is an HNSW record.
+    pub fn hnsw<B: BlobStore<Blake3>>(
+        &self,
+        ws: &mut Workspace<B>,
+    ) -> Result<
+        Option<SuccinctHNSWIndex>,
+        <B::Reader as BlobStoreGet<Blake3>>::GetError<SuccinctLoadError>,
+    > {
+        self.hnsw
+            .map(|h| ws.get::<SuccinctHNSWIndex, SuccinctHNSWBlob>(h))
+            .transpose()
+    }
+
+    fn from_metadata(meta: &TribleSet, id: Id, commit: CommitHandle) -> Option<Self> {
+        let (covered,) = find!(
+            (h: Value<Handle<Blake3, SimpleArchive>>),
+            pattern!(meta, [{ id @ indexed_head: ?h }])
+        )
+        .next()?;
+        let hnsw = find!(
+            (h: Value<Handle<Blake3, SuccinctHNSWBlob>>),
+            pattern!(meta, [{ id @ hnsw_index: ?h }])
+        )
+        .next()
+        .map(|(h,)| h);
+        let mut ranked: Vec<(u64, Value<Handle<Blake3, BM25SegmentBlob>>)> = find!(
+            (s: Value<Handle<Blake3, BM25SegmentBlob>>, r: Value<U256BE>),
+            pattern!(meta, [{ segment_of: id, bm25_segment: ?s, segment_rank: ?r }])
+        )
+        .filter_map(|(s, r)| Some((u64::try_from_value(&r).ok()?, s)))
+        .collect();
+        ranked.sort_unstable_by_key(|&(r, _)| r);
+        Some(Self {
+            id,
+            commit,
+            indexed_head: covered,
+            segments: ranked.into_iter().map(|(_, s)| s).collect(),
+            hnsw,
+        })
+    }
+
+    fn to_tribles(&self) -> TribleSet {
+        let mut set: TribleSet = entity! { ExclusiveId::force_ref(&self.id) @
+            indexed_head: self.indexed_head,
+            hnsw_index?: self.hnsw,
+        }
+        .into();
+        for (rank, &segment) in self.segments.iter().enumerate() {
+            set += entity! {
+                segment_of: self.id,
+                bm25_segment: segment,
+                segment_rank: rank as u64,
+            };
+        }
+        set
+    }
+}
+
+/// Find the newest record of index `id` reachable from the head
+/// of `ws`, a pull of the index branch.
+///
+/// Walks the commit graph breadth-first from the head, reading
+/// commit metadata until a record turns up. Every refresh commit
+/// carries the records of all indexes it refreshed, so on an index
+/// branch the walk stops at the head (or, after a merge of
+/// concurrent refreshes, one commit further). Only an index that
+/// was never refreshed there makes it read the whole history and
+/// return `None`; each distinct metadata blob is read once.
+pub fn locate<B: BlobStore<Blake3>>(
+    ws: &mut Workspace<B>,
+    id: Id,
+) -> Result<Option<IndexRecord>, <B::Reader as BlobStoreGet<Blake3>>::GetError<UnarchiveError>> {
+    let head = ws.head();
+    find_record(|h| ws.get(h), head, id)
+}
+
+fn find_record<E>(
+    mut get: impl FnMut(Value<Handle<Blake3, SimpleArchive>>) -> Result<TribleSet, E>,
+    head: Option<CommitHandle>,
+    id: Id,
+) -> Result<Option<IndexRecord>, E> {
+    let mut queue: VecDeque<CommitHandle> = head.into_iter().collect();
+    let mut seen: HashSet<CommitHandle> = queue.iter().copied().collect();
+    let mut checked: HashSet<Value<Handle<Blake3, SimpleArchive>>> = HashSet::new();
+    while let Some(commit) = queue.pop_front() {
+        let meta = get(commit)?;
+        let metadata: Vec<_> = find!(
+            (m: Value<Handle<Blake3, SimpleArchive>>),
+            pattern!(&meta, [{ repo::metadata: ?m }])
+        )
+        .map(|(m,)| m)
+        .collect();
+        for m in metadata {
+            if checked.insert(m) {
+                if let Some(record) = IndexRecord::from_metadata(&get(m)?, id, commit) {
+                    return Ok(Some(record));
+                }
+            }
+        }
+        for (parent,) in find!(
+            (p: Value<Handle<Blake3, SimpleArchive>>),
+            pattern!(&meta, [{ repo::parent: ?p }])
+        ) {
+            if seen.insert(parent) {
+                queue.push_back(parent);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Error returned by [`SearchIndexes::refresh`].
+#[derive(Debug)]
+pub enum RefreshError<Storage: BranchStore<Blake3> + BlobStore<Blake3>> {
+    /// Could not pull the indexed branch or the index branch.
+    Pull(
+        PullError<
+            Storage::HeadError,
+            <Storage as BlobStore<Blake3>>::ReaderError,
+            <<Storage as BlobStore<Blake3>>::Reader as BlobStoreGet<Blake3>>::GetError<UnarchiveError>,
+        >,
+    ),
+    /// Could not check out the commits a record doesn't cover.
+    Checkout(
+        WorkspaceCheckoutError<
+            <<Storage as BlobStore<Blake3>>::Reader as BlobStoreGet<Blake3>>::GetError<UnarchiveError>,
+        >,
+    ),
+    /// A text, embedding or previously persisted index blob
+    /// couldn't be read or decoded.
+    Blob(Box<dyn std::error::Error + Send + Sync>),
+    /// An embedding disagrees with the HNSW index dimensionality.
+    Dim(DimMismatch),
+    /// Storing index blobs or pushing the index commit failed.
+    Push(PushError<Storage>),
+}
+
+/// The set of indexes maintained on a branch. See the
+/// [module docs](self).
+#[derive(Clone, Default)]
+pub struct SearchIndexes {
+    specs: Vec<IndexSpec>,
+}
+
+impl SearchIndexes {
+    /// No indexes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare another index.
+    pub fn with(mut self, spec: IndexSpec) -> Self {
+        self.specs.push(spec);
+        self
+    }
+
+    /// The declared indexes.
+    pub fn specs(&self) -> &[IndexSpec] {
+        &self.specs
+    }
+
+    /// Bring every declared index up to date with `branch_id`'s
+    /// head and push the updated records as a commit on
+    /// `index_branch_id`.
+    ///
+    /// The index branch must exist; create it once next to the
+    /// indexed branch and keep it to this set of indexes, since each
+    /// index commit carries only the records declared here. The
+    /// indexed branch is only read.
+    ///
+    /// Returns the index commit, or `None` when there was nothing
+    /// to do (an empty branch, or every record already current).
+    /// The push merges on conflict like [`Repository::push`], so
+    /// concurrent refreshes never lose records.
+    pub fn refresh<Storage>(
+        &self,
+        repo: &mut Repository<Storage>,
+        branch_id: Id,
+        index_branch_id: Id,
+    ) -> Result<Option<CommitHandle>, RefreshError<Storage>>
+    where
+        Storage: BlobStore<Blake3> + BranchStore<Blake3>,
+    {
+        let mut ws = repo.pull(branch_id).map_err(RefreshError::Pull)?;
+        let Some(head) = ws.head() else {
+            return Ok(None);
+        };
+        let mut index_ws = repo.pull(index_branch_id).map_err(RefreshError::Pull)?;
+        let reader = repo
+            .storage_mut()
+            .reader()
+            .map_err(|e| RefreshError::Push(PushError::StorageReader(e)))?;
+
+        let mut previous = Vec::with_capacity(self.specs.len());
+        for spec in &self.specs {
+            let record = find_record(|h| reader.get(h), index_ws.head(), spec.id)
+                .map_err(|e| RefreshError::Push(PushError::StorageGet(e)))?;
+            previous.push(record);
+        }
+        if previous
+            .iter()
+            .all(|r| r.as_ref().is_some_and(|r| r.is_current(Some(head))))
+        {
+            return Ok(None);
+        }
+
+        let mut metadata: TribleSet = reader
+            .get(index_ws.metadata())
+            .map_err(|e| RefreshError::Push(PushError::StorageGet(e)))?;
+        for (spec, previous) in self.specs.iter().zip(previous) {
+            let delta = match &previous {
+                Some(r) => ws.checkout(r.indexed_head..),
+                None => ws.checkout(..),
+            }
+            .map_err(RefreshError::Checkout)?
+            .into_facts();
+            let mut record = previous.unwrap_or(IndexRecord {
+                id: spec.id,
+                commit: head,
+                indexed_head: head,
+                segments: Vec::new(),
+                hnsw: None,
+            });
+            record.indexed_head = head;
+            match spec.kind {
+                IndexKind::Text { tokenize } => {
+                    apply_bm25(repo, &reader, spec.attribute, tokenize, &delta, &mut record)?
+                }
+                IndexKind::Vector { builder } => {
+                    apply_hnsw(repo, &reader, spec.attribute, builder, &delta, &mut record)?
+                }
+            }
+            metadata += record.to_tribles();
+        }
+
+        let metadata = repo
+            .storage_mut()
+            .put(metadata)
+            .map_err(|e| RefreshError::Push(PushError::StoragePut(e)))?;
+        index_ws.commit_with_metadata(TribleSet::new(), metadata, "refresh search indexes");
+        repo.push(&mut index_ws).map_err(RefreshError::Push)?;
+        Ok(index_ws.head())
+    }
+}
+
+fn blob_error<E: std::error::Error + Send + Sync + 'static, S>(e: E) -> RefreshError<S>
+where
+    S: BranchStore<Blake3> + BlobStore<Blake3>,
+{
+    RefreshError::Blob(Box::new(e))
+}
+
+/// Values of `attribute` in `delta`, grouped by entity.
+fn values_of<V>(delta: &TribleSet, attribute: Id) -> BTreeMap<Id, Vec<Value<V>>>
+where
+    V: triblespace_core::value::ValueSchema,
+{
+    let mut out: BTreeMap<Id, Vec<Value<V>>> = BTreeMap::new();
+    for t in delta.iter().filter(|t| *t.a() == attribute) {
+        out.entry(*t.e()).or_default().push(*t.v::<V>());
+    }
+    out
+}
+
+fn apply_bm25<Storage>(
+    repo: &mut Repository<Storage>,
+    reader: &Storage::Reader,
+    attribute: Id,
+    tokenize: fn(&str) -> Vec<Value<WordHash>>,
+    delta: &TribleSet,
+    record: &mut IndexRecord,
+) -> Result<(), RefreshError<Storage>>
+where
+    Storage: BlobStore<Blake3> + BranchStore<Blake3>,
+{
+    let docs = values_of::<Handle<Blake3, LongString>>(delta, attribute);
+    if docs.is_empty() {
+        return Ok(());
+    }
+    let mut batch = BM25SegmentBuilder::new();
+    for (entity, texts) in docs {
+        let mut terms = Vec::new();
+        for text in texts {
+            let text: View<str> = reader.get(text).map_err(blob_error)?;
+            terms.extend(tokenize(text.as_ref()));
+        }
+        batch.insert(entity, terms);
+    }
+    let mut segments = BM25Segments::new();
+    for &h in &record.segments {
+        segments.push(reader.get::<BM25Segment, BM25SegmentBlob>(h).map_err(blob_error)?);
+    }
+    segments.push(batch.build());
+    while let Some(range) = segments.merge_plan() {
+        let merged = segments.compact(range.clone());
+        segments.replace(range, merged);
+    }
+    record.segments = segments
+        .segments()
+        .iter()
+        .map(|s| repo.storage_mut().put::<BM25SegmentBlob, _>(s))
+        .collect::<Result<_, _>>()
+        .map_err(|e| RefreshError::Push(PushError::StoragePut(e)))?;
+    Ok(())
+}
+
+fn apply_hnsw<Storage>(
+    repo: &mut Repository<Storage>,
+    reader: &Storage::Reader,
+    attribute: Id,
+    builder: fn() -> HNSWBuilder,
+    delta: &TribleSet,
+    record: &mut IndexRecord,
+) -> Result<(), RefreshError<Storage>>
+where
+    Storage: BlobStore<Blake3> + BranchStore<Blake3>,
+{
+    let mut handles: Vec<Value<EmbHandle>> = values_of::<EmbHandle>(delta, attribute)
+        .into_values()
+        .flatten()
+        .collect();
+    if handles.is_empty() {
+        return Ok(());
+    }
+    let (mut builder, mut present) = match record.hnsw {
+        Some(h) => {
+            let index: SuccinctHNSWIndex = reader.get(h).map_err(blob_error)?;
+            let present: HashSet<Value<EmbHandle>> =
+                (0..index.doc_count()).filter_map(|i| index.handle(i)).collect();
+            let builder = HNSWBuilder::from_succinct(&index, reader).map_err(|e| match e {
+                HNSWLoadError::Blob(e) => blob_error(e),
+                HNSWLoadError::Dim(e) => RefreshError::Dim(e),
+            })?;
+            (builder, present)
+        }
+        None => (builder(), HashSet::new()),
+    };
+    // The delta can revisit commits an earlier refresh already
+    // covered (through the far side of a merge); re-inserting is
+    // skipped rather than duplicated.
+    handles.retain(|h| present.insert(*h));
+    if handles.is_empty() {
+        return Ok(());
+    }
+    for h in handles {
+        let vector: View<[f32]> = reader.get::<View<[f32]>, Embedding>(h).map_err(blob_error)?;
+        builder
+            .insert(h, vector.as_ref().to_vec())
+            .map_err(RefreshError::Dim)?;
+    }
+    let index = builder.build();
+    let handle = repo
+        .storage_mut()
+        .put::<SuccinctHNSWBlob, _>(&index)
+        .map_err(|e| RefreshError::Push(PushError::StoragePut(e)))?;
+    record.hnsw = Some(handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use triblespace_core::repo::memoryrepo::MemoryRepo;
+
+    attributes! {
+        "132EE63E9D89477CB0702801BDDDDBE7" as body: Handle<Blake3, LongString>;
+        "F76744B769CA4DD68A34F3A754BD248D" as embedding: EmbHandle;
+    }
+
+    const BODY: Id = triblespace_core::id::id_hex!("BC9ABBFA5E2045E2BF0C941C99697E28");
+    const EMB: Id = triblespace_core::id::id_hex!("E1E460994D7A47AFBBC4D519450206AC");
+
+    fn repo() -> (Repository<MemoryRepo>, Id, Id) {
+        let mut repo = Repository::new(
+            MemoryRepo::default(),
+            SigningKey::from_bytes(&[3; 32]),
+            TribleSet::new(),
+        )
+        .unwrap();
+        let branch = *repo.create_branch("main", None).unwrap();
+        let index_branch = *repo.create_branch("main-search", None).unwrap();
+        (repo, branch, index_branch)
+    }
+
+    fn indexes() -> SearchIndexes {
+        SearchIndexes::new()
+            .with(IndexSpec::bm25(BODY, &body))
+            .with(IndexSpec::hnsw(EMB, &embedding, || HNSWBuilder::new(2).with_seed(1)))
+    }
+
+    fn doc(byte: u8) -> ExclusiveId {
+        ExclusiveId::force(Id::new([byte; 16]).unwrap())
+    }
+
+    fn add(repo: &mut Repository<MemoryRepo>, branch: Id, byte: u8, text: &str, vector: [f32; 2]) {
+        let mut ws = repo.pull(branch).unwrap();
+        let text = ws.put(text.to_string());
+        let emb = ws.put::<Embedding, _>(vector.to_vec());
+        ws.commit(entity! { &doc(byte) @ body: text, embedding: emb }, "add doc");
+        repo.push(&mut ws).unwrap();
+    }
+
+    #[test]
+    fn refresh_indexes_incrementally() {
+        let (mut repo, branch, index_branch) = repo();
+        let indexes = indexes();
+        assert_eq!(indexes.refresh(&mut repo, branch, index_branch).unwrap(), None);
+
+        add(&mut repo, branch, 1, "the quick brown fox", [1.0, 0.0]);
+        add(&mut repo, branch, 2, "the lazy brown dog", [0.0, 1.0]);
+        let first = indexes
+            .refresh(&mut repo, branch, index_branch)
+            .unwrap()
+            .expect("index commit");
+        // Already current: no second index commit.
+        assert_eq!(indexes.refresh(&mut repo, branch, index_branch).unwrap(), None);
+
+        add(&mut repo, branch, 3, "quick silver fox", [0.8, 0.6]);
+        let head = repo.pull(branch).unwrap().head();
+        let mut ws = repo.pull(index_branch).unwrap();
+        let stale = locate(&mut ws, BODY).unwrap().unwrap();
+        assert_eq!(stale.commit, first);
+        assert!(!stale.is_current(head));
+
+        indexes
+            .refresh(&mut repo, branch, index_branch)
+            .unwrap()
+            .expect("index commit");
+        // The indexed branch holds only the user's commits.
+        let mut source = repo.pull(branch).unwrap();
+        assert_eq!(source.head(), head);
+        assert_eq!(source.checkout(..).unwrap().into_facts().len(), 6);
+        let mut ws = repo.pull(index_branch).unwrap();
+        let bm25 = locate(&mut ws, BODY).unwrap().unwrap();
+        assert!(bm25.is_current(head));
+        assert!(bm25.hnsw.is_none());
+        let segments = bm25.bm25(&mut ws).unwrap();
+        assert_eq!(segments.doc_count(), 3);
+        assert_eq!(segments.query_multi(&hash_tokens("fox")).len(), 2);
+
+        let hnsw = locate(&mut ws, EMB).unwrap().unwrap();
+        assert!(hnsw.segments.is_empty());
+        let graph = hnsw.hnsw(&mut ws).unwrap().expect("hnsw record");
+        assert_eq!(graph.doc_count(), 3);
+    }
+
+    #[test]
+    fn records_round_trip_through_metadata() {
+        let (mut repo, branch, _) = repo();
+        add(&mut repo, branch, 1, "alpha", [1.0, 0.0]);
+        let head = repo.pull(branch).unwrap().head().unwrap();
+        let record = IndexRecord {
+            id: BODY,
+            commit: head,
+            indexed_head: head,
+            segments: (1..4u8).rev().map(|i| Value::new([i; 32])).collect(),
+            hnsw: None,
+        };
+        let back = IndexRecord::from_metadata(&record.to_tribles(), BODY, head).unwrap();
+        assert_eq!(back, record);
+        assert!(IndexRecord::from_metadata(&record.to_tribles(), EMB, head).is_none());
+    }
+}
@@ -0,0 +1,787 @@
+//! Positional postings: exact phrases and proximity windows.
+//!
+//! A [`SuccinctBM25Index`] only knows *how often* a term occurs
+//! in a document, not *where*, so phrase search has had to
+//! approximate adjacency with a second [`BigramHash`] index —
+//! which can't express three-word phrases or "within five
+//! words". [`PositionIndex`] (schema [`PositionIndexBlob`]) is
+//! the optional companion that keeps the token offsets. Build
+//! it from the same [`BM25Builder`] as the main index:
+//!
+//! - [`PositionIndex::query_phrase`] — documents where the terms
+//!   occur consecutively, in order.
+//! - [`PositionIndex::query_near`] — documents where every term
+//!   occurs inside a span of at most `window` tokens, in any
+//!   order.
+//!
+//! Both score with BM25 over a *pseudo-term*: the phrase (or
+//! window) occurrences stand in for term frequency, and the
+//! number of documents containing it for document frequency.
+//! Results therefore rank like an ordinary single-term query and
+//! combine with bag-of-words scores by plain addition. The
+//! `phrase` / `near` constraints plug the same filters into
+//! `find!`.
+//!
+//! Positions are indices into the term vector passed to
+//! [`BM25Builder::insert`], so they count *analyzed* tokens: a
+//! stopword removed by an [`Analyzer`] closes the gap it leaves.
+//! Analyze phrase queries with the same pipeline.
+//!
+//! ```
+//! use triblespace_core::find;
+//! use triblespace_core::id::Id;
+//! use triblespace_search::bm25::BM25Builder;
+//! use triblespace_search::tokens::hash_tokens;
+//!
+//! let mut b = BM25Builder::new();
+//! b.insert(Id::new([1; 16]).unwrap(), hash_tokens("the quick brown fox jumps"));
+//! b.insert(Id::new([2; 16]).unwrap(), hash_tokens("quick fox and brown dog"));
+//! let positions = b.build_positions();
+//! let index = b.build();
+//!
+//! // Exact phrase: only doc 1 has "quick brown fox" in order.
+//! let phrase = hash_tokens("quick brown fox");
+//! assert_eq!(positions.query_phrase(&phrase).len(), 1);
+//!
+//! // Proximity: "fox" and "brown" within three tokens — both.
+//! let near = hash_tokens("fox brown");
+//! let hits: Vec<(Id,)> = find!((doc: Id), positions.near(doc, &near, 3, 0.0)).collect();
+//! assert_eq!(hits.len(), 2);
+//! # let _ = index;
+//! ```
+//!
+//! [`SuccinctBM25Index`]: crate::succinct::SuccinctBM25Index
+//! [`BigramHash`]: crate::tokens::BigramHash
+//! [`Analyzer`]: crate::tokens::Analyzer
+
+use std::collections::{BTreeMap, HashMap};
+
+use anybytes::Bytes;
+use jerky::int_vectors::compact_vector::CompactVectorMeta;
+use jerky::int_vectors::CompactVector;
+use triblespace_core::blob::{Blob, BlobSchema, ToBlob, TryFromBlob};
+use triblespace_core::id::Id;
+use triblespace_core::id_hex;
+use triblespace_core::metadata::{ConstDescribe, ConstId};
+use triblespace_core::value::schemas::genid::GenId;
+use triblespace_core::value::{RawValue, Value, ValueSchema};
+use zerocopy::{FromBytes, IntoBytes};
+
+use crate::bm25::BM25Builder;
+use crate::segment::{pack, table_bytes, to_u32s};
+use crate::succinct::{CompactVectorMetaOnDisk, FixedBytesTable, SuccinctLoadError};
+
+/// Token positions of every term in every document, plus the
+/// document lengths and BM25 parameters needed to score phrase
+/// and proximity matches.
+///
+/// Documents are keyed by their position in the sorted key
+/// table, the same order the [`SuccinctBM25Index`] universe
+/// uses. Produce via [`BM25Builder::build_positions`].
+///
+/// [`SuccinctBM25Index`]: crate::succinct::SuccinctBM25Index
+pub struct PositionIndex<D: ValueSchema = GenId, T: ValueSchema = crate::tokens::WordHash> {
+    keys: FixedBytesTable<32>,
+    doc_lens: CompactVector,
+    terms: FixedBytesTable<32>,
+    /// `n_terms + 1` cumulative offsets into the entry columns.
+    term_offsets: CompactVector,
+    /// Per-(term, doc) entry: document index, ascending within
+    /// each term.
+    entry_docs: CompactVector,
+    /// `n_entries + 1` cumulative offsets into `positions`.
+    entry_offsets: CompactVector,
+    /// Token positions, ascending within each entry.
+    positions: CompactVector,
+    avg_doc_len: f32,
+    k1: f32,
+    b: f32,
+    _phantom: std::marker::PhantomData<(D, T)>,
+}
+
+impl<D: ValueSchema, T: ValueSchema> std::fmt::Debug for PositionIndex<D, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PositionIndex")
+            .field("n_docs", &self.keys.len())
+            .field("n_terms", &self.terms.len())
+            .field("n_positions", &self.positions.len())
+            .finish()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> BM25Builder<D, T> {
+    /// Build the positional companion of [`Self::build`] from the
+    /// documents inserted so far. Borrows the builder, so the
+    /// usual sequence is `build_positions()` then `build()`.
+    /// Duplicate keys resolve the same way (last insert wins).
+    pub fn build_positions(&self) -> PositionIndex<D, T> {
+        let docs: BTreeMap<RawValue, &[RawValue]> = self
+            .docs
+            .iter()
+            .map(|(key, terms)| (*key, terms.as_slice()))
+            .collect();
+        PositionIndex::assemble(docs, self.k1, self.b)
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> PositionIndex<D, T> {
+    fn assemble(docs: BTreeMap<RawValue, &[RawValue]>, k1: f32, b: f32) -> Self {
+        let doc_lens: Vec<u32> = docs.values().map(|t| t.len() as u32).collect();
+        let avg_doc_len = if docs.is_empty() {
+            0.0
+        } else {
+            doc_lens.iter().map(|&n| n as f64).sum::<f64>() as f32 / docs.len() as f32
+        };
+
+        // term → [(doc, [positions])], docs visited in key order
+        // so every list comes out ascending.
+        let mut by_term: HashMap<RawValue, Vec<(u32, Vec<u32>)>> = HashMap::new();
+        for (doc, terms) in docs.values().enumerate() {
+            let mut local: HashMap<RawValue, Vec<u32>> = HashMap::new();
+            for (pos, term) in terms.iter().enumerate() {
+                local.entry(*term).or_default().push(pos as u32);
+            }
+            for (term, positions) in local {
+                by_term.entry(term).or_default().push((doc as u32, positions));
+            }
+        }
+        let mut term_rows: Vec<RawValue> = by_term.keys().copied().collect();
+        term_rows.sort_unstable();
+
+        let mut term_offsets = Vec::with_capacity(term_rows.len() + 1);
+        let mut entry_docs = Vec::new();
+        let mut entry_offsets = vec![0u32];
+        let mut positions = Vec::new();
+        term_offsets.push(0u32);
+        for term in &term_rows {
+            for (doc, list) in &by_term[term] {
+                entry_docs.push(*doc);
+                positions.extend_from_slice(list);
+                entry_offsets.push(positions.len() as u32);
+            }
+            term_offsets.push(entry_docs.len() as u32);
+        }
+
+        let key_rows: Vec<RawValue> = docs.keys().copied().collect();
+        let load = |(bytes, meta): (Bytes, CompactVectorMeta)| {
+            CompactVector::from_bytes(meta, bytes).expect("round-trip compact vector")
+        };
+        Self {
+            keys: FixedBytesTable::from_bytes(FixedBytesTable::<32>::build(&key_rows), key_rows.len())
+                .expect("round-trip keys"),
+            doc_lens: load(pack(&doc_lens)),
+            terms: FixedBytesTable::from_bytes(
+                FixedBytesTable::<32>::build(&term_rows),
+                term_rows.len(),
+            )
+            .expect("round-trip terms"),
+            term_offsets: load(pack(&term_offsets)),
+            entry_docs: load(pack(&entry_docs)),
+            entry_offsets: load(pack(&entry_offsets)),
+            positions: load(pack(&positions)),
+            avg_doc_len,
+            k1,
+            b,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of documents.
+    pub fn doc_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Number of distinct terms.
+    pub fn term_count(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Average document length used for scoring.
+    pub fn avg_doc_len(&self) -> f32 {
+        self.avg_doc_len
+    }
+
+    /// BM25 `k1` copied from the builder.
+    pub fn k1(&self) -> f32 {
+        self.k1
+    }
+
+    /// BM25 `b` copied from the builder.
+    pub fn b(&self) -> f32 {
+        self.b
+    }
+
+    /// Entry range of `term`, empty if absent.
+    fn entries(&self, term: &RawValue) -> std::ops::Range<usize> {
+        match self.terms.binary_search(term) {
+            Ok(t) => self.term_offsets.get_int(t).unwrap()..self.term_offsets.get_int(t + 1).unwrap(),
+            Err(_) => 0..0,
+        }
+    }
+
+    /// Positions stored for entry `e`.
+    fn entry_positions(&self, e: usize) -> Vec<u32> {
+        let start = self.entry_offsets.get_int(e).unwrap();
+        let end = self.entry_offsets.get_int(e + 1).unwrap();
+        (start..end)
+            .map(|p| self.positions.get_int(p).unwrap() as u32)
+            .collect()
+    }
+
+    /// Positions of `term` in document index `doc`, if any.
+    fn doc_positions(&self, term: &RawValue, doc: u32) -> Option<Vec<u32>> {
+        let range = self.entries(term);
+        let (mut lo, mut hi) = (range.start, range.end);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match (self.entry_docs.get_int(mid).unwrap() as u32).cmp(&doc) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(self.entry_positions(mid)),
+            }
+        }
+        None
+    }
+
+    /// Token positions of `term` in `doc`, ascending. Empty if
+    /// either is absent.
+    pub fn positions(&self, doc: &Value<D>, term: &Value<T>) -> Vec<u32> {
+        match self.keys.binary_search(&doc.raw) {
+            Ok(d) => self.doc_positions(&term.raw, d as u32).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Per-document occurrence counts of a positional pattern.
+    /// `count` receives the positions of each query term (in
+    /// query order) and returns how often the pattern occurs.
+    /// Candidates come from the rarest term's postings.
+    fn occurrences(
+        &self,
+        terms: &[RawValue],
+        count: impl Fn(&[Vec<u32>]) -> u32,
+    ) -> Vec<(u32, u32)> {
+        let Some(rarest) = terms.iter().min_by_key(|t| self.entries(t).len()) else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        'docs: for e in self.entries(rarest) {
+            let doc = self.entry_docs.get_int(e).unwrap() as u32;
+            let mut lists = Vec::with_capacity(terms.len());
+            for term in terms {
+                match self.doc_positions(term, doc) {
+                    Some(list) => lists.push(list),
+                    None => continue 'docs,
+                }
+            }
+            let n = count(&lists);
+            if n > 0 {
+                out.push((doc, n));
+            }
+        }
+        out
+    }
+
+    /// Score `(doc, pseudo-tf)` pairs as one BM25 term and sort
+    /// descending.
+    fn score_occurrences(&self, hits: Vec<(u32, u32)>) -> Vec<(Value<D>, f32)> {
+        let n = self.doc_count() as f32;
+        let df = hits.len() as f32;
+        let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+        let mut out: Vec<(Value<D>, f32)> = hits
+            .into_iter()
+            .map(|(doc, tf)| {
+                let tf = tf as f32;
+                let dl = self.doc_lens.get_int(doc as usize).unwrap() as f32;
+                let norm = if self.avg_doc_len > 0.0 {
+                    1.0 - self.b + self.b * (dl / self.avg_doc_len)
+                } else {
+                    1.0
+                };
+                let score = idf * (tf * (self.k1 + 1.0)) / (tf + self.k1 * norm);
+                (Value::new(*self.keys.get(doc as usize).unwrap()), score)
+            })
+            .collect();
+        out.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        out
+    }
+
+    /// Documents containing `terms` as a consecutive, in-order
+    /// phrase, scored by BM25 with the phrase frequency as term
+    /// frequency. Sorted descending by score. A single-term
+    /// phrase degenerates to that term's postings; an empty
+    /// phrase matches nothing.
+    pub fn query_phrase(&self, terms: &[Value<T>]) -> Vec<(Value<D>, f32)> {
+        let raw: Vec<RawValue> = terms.iter().map(|t| t.raw).collect();
+        self.score_occurrences(self.occurrences(&raw, count_phrase))
+    }
+
+    /// Documents where every distinct term of `terms` occurs
+    /// inside a span of at most `window` consecutive tokens, in
+    /// any order. Scored by BM25 with the number of minimal
+    /// windows as term frequency; sorted descending. Consecutive
+    /// windows may share tokens — "a b a" holds two for {a, b}. `window` equal to the number of terms means
+    /// "adjacent, any order".
+    pub fn query_near(&self, terms: &[Value<T>], window: u32) -> Vec<(Value<D>, f32)> {
+        let mut raw: Vec<RawValue> = terms.iter().map(|t| t.raw).collect();
+        raw.sort_unstable();
+        raw.dedup();
+        self.score_occurrences(self.occurrences(&raw, |lists| count_near(lists, window)))
+    }
+
+    /// Serialize to a self-contained blob:
+    ///
+    /// ```text
+    /// [header       ] POSITIONS_HEADER_LEN B
+    /// [keys         ] n_docs × 32 B
+    /// [terms        ] n_terms × 32 B
+    /// [doc_lens     ] CompactVector body
+    /// [term_offsets ] CompactVector body (n_terms + 1)
+    /// [entry_docs   ] CompactVector body
+    /// [entry_offsets] CompactVector body (n_entries + 1)
+    /// [positions    ] CompactVector body
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let vectors = [
+            pack(&to_u32s(&self.doc_lens)),
+            pack(&to_u32s(&self.term_offsets)),
+            pack(&to_u32s(&self.entry_docs)),
+            pack(&to_u32s(&self.entry_offsets)),
+            pack(&to_u32s(&self.positions)),
+        ];
+        let tables = [table_bytes(&self.keys), table_bytes(&self.terms)];
+
+        fn align8(n: u64) -> u64 {
+            (n + 7) & !7
+        }
+        let regions: Vec<&[u8]> = tables
+            .iter()
+            .map(|t| t.as_slice())
+            .chain(vectors.iter().map(|(bytes, _)| bytes.as_ref()))
+            .collect();
+        let mut sections = Vec::with_capacity(regions.len());
+        let mut off = 0u64;
+        for region in &regions {
+            off = align8(off);
+            sections.push((off, region.len() as u64));
+            off += region.len() as u64;
+        }
+
+        let mut buf = Vec::with_capacity(POSITIONS_HEADER_LEN + off as usize);
+        buf.extend_from_slice(&self.avg_doc_len.to_le_bytes());
+        buf.extend_from_slice(&self.k1.to_le_bytes());
+        buf.extend_from_slice(&self.b.to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&(self.keys.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.terms.len() as u64).to_le_bytes());
+        for (_, meta) in &vectors {
+            let on_disk: CompactVectorMetaOnDisk = (*meta).into();
+            buf.extend_from_slice(on_disk.as_bytes());
+        }
+        for (off, len) in &sections {
+            buf.extend_from_slice(&off.to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+        debug_assert_eq!(buf.len(), POSITIONS_HEADER_LEN);
+
+        for ((off, _), region) in sections.iter().zip(&regions) {
+            buf.resize(POSITIONS_HEADER_LEN + *off as usize, 0);
+            buf.extend_from_slice(region);
+        }
+        buf
+    }
+
+    /// Reload from bytes previously produced by [`Self::to_bytes`].
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, SuccinctLoadError> {
+        if bytes.len() < POSITIONS_HEADER_LEN {
+            return Err(SuccinctLoadError::ShortHeader);
+        }
+        let read_f32 = |off: usize| f32::from_le_bytes(bytes[off..off + 4].try_into().unwrap());
+        let read_u64 = |off: usize| u64::from_le_bytes(bytes[off..off + 8].try_into().unwrap());
+        let avg_doc_len = read_f32(0);
+        let k1 = read_f32(4);
+        let b = read_f32(8);
+        let n_docs = read_u64(16) as usize;
+        let n_terms = read_u64(24) as usize;
+
+        const VECTORS: [&str; 5] = [
+            "doc_lens",
+            "term_offsets",
+            "entry_docs",
+            "entry_offsets",
+            "positions",
+        ];
+        const SECTIONS: [&str; 7] = [
+            "keys",
+            "terms",
+            "doc_lens",
+            "term_offsets",
+            "entry_docs",
+            "entry_offsets",
+            "positions",
+        ];
+        let mut metas = Vec::with_capacity(VECTORS.len());
+        for (i, name) in VECTORS.iter().enumerate() {
+            let start = 32 + i * 32;
+            let meta = CompactVectorMetaOnDisk::read_from_bytes(&bytes[start..start + 32])
+                .map_err(|_| SuccinctLoadError::BadMeta(name))?
+                .to_jerky();
+            metas.push(meta);
+        }
+
+        let body = Bytes::from_source(bytes[POSITIONS_HEADER_LEN..].to_vec());
+        let mut regions = Vec::with_capacity(SECTIONS.len());
+        for (i, name) in SECTIONS.iter().enumerate() {
+            let off = read_u64(192 + i * 16) as usize;
+            let len = read_u64(200 + i * 16) as usize;
+            match off.checked_add(len) {
+                Some(end) if end <= body.len() => regions.push(body.slice(off..end)),
+                _ => return Err(SuccinctLoadError::TruncatedSection(name)),
+            }
+        }
+
+        let table = |i: usize, len: usize| {
+            FixedBytesTable::<32>::from_bytes(regions[i].clone(), len)
+                .map_err(|_| SuccinctLoadError::TruncatedSection(SECTIONS[i]))
+        };
+        let vector = |i: usize| {
+            CompactVector::from_bytes(metas[i], regions[2 + i].clone())
+                .map_err(|_| SuccinctLoadError::TruncatedSection(VECTORS[i]))
+        };
+        let index = Self {
+            keys: table(0, n_docs)?,
+            terms: table(1, n_terms)?,
+            doc_lens: vector(0)?,
+            term_offsets: vector(1)?,
+            entry_docs: vector(2)?,
+            entry_offsets: vector(3)?,
+            positions: vector(4)?,
+            avg_doc_len,
+            k1,
+            b,
+            _phantom: std::marker::PhantomData,
+        };
+        if index.doc_lens.len() != n_docs {
+            return Err(SuccinctLoadError::BadMeta("doc_lens"));
+        }
+        // Offsets must be monotone and in bounds, and entries must name
+        // existing docs; `entries`, `entry_positions` and the scorers
+        // rely on it.
+        let monotone_within = |offsets: &CompactVector, bound: usize| {
+            let mut prev = 0;
+            (0..offsets.len()).all(|i| {
+                let off = offsets.get_int(i).unwrap();
+                let ok = off >= prev && off <= bound;
+                prev = off;
+                ok
+            })
+        };
+        let n_entries = index.entry_docs.len();
+        if index.term_offsets.len() != n_terms + 1
+            || !monotone_within(&index.term_offsets, n_entries)
+        {
+            return Err(SuccinctLoadError::BadMeta("term_offsets"));
+        }
+        if index.entry_offsets.len() != n_entries + 1
+            || !monotone_within(&index.entry_offsets, index.positions.len())
+        {
+            return Err(SuccinctLoadError::BadMeta("entry_offsets"));
+        }
+        if (0..n_entries).any(|e| index.entry_docs.get_int(e).unwrap() >= n_docs) {
+            return Err(SuccinctLoadError::BadMeta("entry_docs"));
+        }
+        Ok(index)
+    }
+}
+
+/// Occurrences of `lists[0][p], lists[1][p+1], …` — the phrase
+/// starts at `p`.
+fn count_phrase(lists: &[Vec<u32>]) -> u32 {
+    let Some((first, rest)) = lists.split_first() else {
+        return 0;
+    };
+    first
+        .iter()
+        .filter(|&&p| {
+            rest.iter()
+                .enumerate()
+                .all(|(i, list)| {
+                    // A phrase starting near `u32::MAX` can't fit.
+                    p.checked_add(i as u32 + 1)
+                        .is_some_and(|q| list.binary_search(&q).is_ok())
+                })
+        })
+        .count() as u32
+}
+
+/// Number of minimal spans covering one position from every list
+/// with `last - first < window`. Spans are told apart by their
+/// first position and may overlap: "a b a" holds `[a b]` and `[b a]`.
+fn count_near(lists: &[Vec<u32>], window: u32) -> u32 {
+    let mut events: Vec<(u32, usize)> = lists
+        .iter()
+        .enumerate()
+        .flat_map(|(t, list)| list.iter().map(move |&p| (p, t)))
+        .collect();
+    events.sort_unstable();
+
+    let mut seen = vec![0u32; lists.len()];
+    let mut covered = 0;
+    let mut left = 0;
+    let mut count = 0;
+    for right in 0..events.len() {
+        let t = events[right].1;
+        if seen[t] == 0 {
+            covered += 1;
+        }
+        seen[t] += 1;
+        if covered < lists.len() {
+            continue;
+        }
+        // Shrink to the minimal span ending at `right`.
+        while seen[events[left].1] > 1 {
+            seen[events[left].1] -= 1;
+            left += 1;
+        }
+        if events[right].0 - events[left].0 < window {
+            count += 1;
+        }
+        // Release the leftmost term so the next span starts
+        // further right.
+        seen[events[left].1] -= 1;
+        covered -= 1;
+        left += 1;
+    }
+    count
+}
+
+/// Header length in bytes for a `PositionIndex` blob.
+///
+/// Layout: 4 avg_doc_len, 4 k1, 4 b, 4 reserved, 8 n_docs,
+/// 8 n_terms, 5×32 CompactVectorMeta (doc_lens, term_offsets,
+/// entry_docs, entry_offsets, positions), 7×16 section
+/// (offset, len) = 304. A multiple of 8, so jerky's u64 views
+/// stay aligned.
+const POSITIONS_HEADER_LEN: usize = 304;
+
+/// Content-addressed [`BlobSchema`] marker for positional
+/// postings — 304 B header + per-(term, doc) position lists.
+///
+/// Schema id minted fresh via `trible genid`:
+/// `859C227733C54FF5A0524C7DA39F6313`.
+pub enum PositionIndexBlob {}
+
+impl ConstId for PositionIndexBlob {
+    const ID: Id = id_hex!("859C227733C54FF5A0524C7DA39F6313");
+}
+
+impl BlobSchema for PositionIndexBlob {}
+
+impl ConstDescribe for PositionIndexBlob {}
+
+impl<D: ValueSchema, T: ValueSchema> ToBlob<PositionIndexBlob> for &PositionIndex<D, T> {
+    fn to_blob(self) -> Blob<PositionIndexBlob> {
+        Blob::new(Bytes::from_source(self.to_bytes()))
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> ToBlob<PositionIndexBlob> for PositionIndex<D, T> {
+    fn to_blob(self) -> Blob<PositionIndexBlob> {
+        (&self).to_blob()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> TryFromBlob<PositionIndexBlob> for PositionIndex<D, T> {
+    type Error = SuccinctLoadError;
+
+    fn try_from_blob(blob: Blob<PositionIndexBlob>) -> Result<Self, Self::Error> {
+        PositionIndex::try_from_bytes(blob.bytes.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{bigram_tokens, hash_tokens, BigramHash, WordHash};
+    use triblespace_core::value::ToValue;
+
+    fn id(byte: u8) -> Id {
+        Id::new([byte; 16]).unwrap()
+    }
+
+    fn key(byte: u8) -> Value<GenId> {
+        (&id(byte)).to_value()
+    }
+
+    const CORPUS: [(u8, &str); 4] = [
+        (1, "the quick brown fox jumps over the lazy dog"),
+        (2, "a quick silver fox"),
+        (3, "the brown fox runs and the brown fox hides"),
+        (4, "quick fox and brown dog"),
+    ];
+
+    fn corpus() -> BM25Builder {
+        let mut b = BM25Builder::new();
+        for (byte, text) in CORPUS {
+            b.insert(id(byte), hash_tokens(text));
+        }
+        b
+    }
+
+    fn docs(hits: &[(Value<GenId>, f32)]) -> Vec<Value<GenId>> {
+        let mut out: Vec<_> = hits.iter().map(|(d, _)| *d).collect();
+        out.sort_unstable_by_key(|d| d.raw);
+        out
+    }
+
+    #[test]
+    fn positions_follow_token_order() {
+        let idx = corpus().build_positions();
+        assert_eq!(idx.doc_count(), 4);
+        let the = &hash_tokens("the")[0];
+        assert_eq!(idx.positions(&key(1), the), vec![0, 6]);
+        assert_eq!(idx.positions(&key(2), the), Vec::<u32>::new());
+        assert_eq!(idx.positions(&key(9), the), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn phrase_requires_order_and_adjacency() {
+        let idx = corpus().build_positions();
+        let hits = idx.query_phrase(&hash_tokens("quick brown fox"));
+        assert_eq!(docs(&hits), vec![key(1)]);
+
+        let hits = idx.query_phrase(&hash_tokens("brown fox"));
+        assert_eq!(docs(&hits), vec![key(1), key(3)]);
+        // Doc 3 has the phrase twice in a doc of similar length.
+        assert_eq!(hits[0].0, key(3));
+
+        assert!(idx.query_phrase(&hash_tokens("fox brown")).is_empty());
+        assert!(idx.query_phrase(&hash_tokens("brown unicorn")).is_empty());
+        assert!(idx.query_phrase(&[]).is_empty());
+        assert_eq!(idx.query_phrase(&hash_tokens("fox")).len(), 4);
+    }
+
+    #[test]
+    fn phrase_matches_bigram_index() {
+        // A two-word phrase must select exactly the docs a bigram
+        // index finds for the same pair.
+        let idx = corpus().build_positions();
+        let mut bigrams: BM25Builder<GenId, BigramHash> = BM25Builder::new();
+        for (byte, text) in CORPUS {
+            bigrams.insert(id(byte), bigram_tokens(text));
+        }
+        let bigrams = bigrams.build();
+        for pair in ["quick brown", "brown fox", "the brown", "fox and"] {
+            let mut expected: Vec<_> = bigrams
+                .query_term(&bigram_tokens(pair)[0])
+                .map(|(d, _)| d)
+                .collect();
+            expected.sort_unstable_by_key(|d| d.raw);
+            assert_eq!(docs(&idx.query_phrase(&hash_tokens(pair))), expected, "{pair}");
+        }
+    }
+
+    #[test]
+    fn near_windows() {
+        let idx = corpus().build_positions();
+        let terms = hash_tokens("fox quick");
+        assert_eq!(docs(&idx.query_near(&terms, 2)), vec![key(4)]);
+        // Doc 1 and 2 both read "quick <word> fox": a 3-token span.
+        assert_eq!(docs(&idx.query_near(&terms, 3)), vec![key(1), key(2), key(4)]);
+
+        // Duplicated query terms collapse.
+        let terms = hash_tokens("dog dog brown");
+        assert_eq!(docs(&idx.query_near(&terms, 3)), vec![key(4)]);
+
+        assert_eq!(count_near(&[vec![1, 7], vec![2, 8]], 2), 2);
+        assert_eq!(count_near(&[vec![1, 2, 3], vec![10]], 3), 0);
+        // "a b a": both minimal windows count, sharing the `b`.
+        assert_eq!(count_near(&[vec![0, 2], vec![1]], 2), 2);
+    }
+
+    #[test]
+    fn phrase_at_the_end_of_the_position_range() {
+        assert_eq!(count_phrase(&[vec![u32::MAX], vec![0]]), 0);
+        assert_eq!(count_phrase(&[vec![u32::MAX - 1], vec![u32::MAX]]), 1);
+    }
+
+    #[test]
+    fn constraints_bind_docs() {
+        use triblespace_core::find;
+        let idx = corpus().build_positions();
+        let phrase = hash_tokens("brown fox");
+        let mut hits: Vec<Id> = find!((doc: Id), idx.phrase(doc, &phrase, 0.0))
+            .map(|(d,)| d)
+            .collect();
+        hits.sort();
+        assert_eq!(hits, vec![id(1), id(3)]);
+        assert!(idx.phrase_score(&key(3), &phrase) > idx.phrase_score(&key(1), &phrase));
+        assert_eq!(idx.phrase_score(&key(2), &phrase), 0.0);
+
+        let near = hash_tokens("quick fox");
+        let hits: Vec<(Id,)> = find!((doc: Id), idx.near(doc, &near, 2, 0.0)).collect();
+        assert_eq!(hits, vec![(id(4),)]);
+        assert!(idx.near_score(&key(4), &near, 2) > 0.0);
+    }
+
+    #[test]
+    fn positions_blob_round_trip() {
+        let idx = corpus().build_positions();
+        let blob: Blob<PositionIndexBlob> = (&idx).to_blob();
+        let loaded: PositionIndex<GenId, WordHash> = PositionIndex::try_from_blob(blob).unwrap();
+        assert_eq!(loaded.to_bytes(), idx.to_bytes());
+        let phrase = hash_tokens("brown fox");
+        assert_eq!(loaded.query_phrase(&phrase), idx.query_phrase(&phrase));
+        assert_eq!(loaded.k1(), idx.k1());
+        assert_eq!(loaded.avg_doc_len(), idx.avg_doc_len());
+
+        let empty: PositionIndex = BM25Builder::new().build_positions();
+        let loaded: PositionIndex = PositionIndex::try_from_bytes(&empty.to_bytes()).unwrap();
+        assert_eq!(loaded.doc_count(), 0);
+        assert!(loaded.query_phrase(&phrase).is_empty());
+
+        assert_eq!(
+            PositionIndex::<GenId, WordHash>::try_from_bytes(&[0u8; 16]).unwrap_err(),
+            SuccinctLoadError::ShortHeader
+        );
+        let mut bytes = idx.to_bytes();
+        bytes.truncate(POSITIONS_HEADER_LEN + 8);
+        assert!(PositionIndex::<GenId, WordHash>::try_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn positions_with_bad_offsets_are_rejected() {
+        let load = |values: &[u32]| {
+            let (bytes, meta) = pack(values);
+            CompactVector::from_bytes(meta, bytes).unwrap()
+        };
+        let reload = |idx: &PositionIndex| {
+            PositionIndex::<GenId, WordHash>::try_from_bytes(&idx.to_bytes()).unwrap_err()
+        };
+        let n_docs = corpus().build_positions().doc_count() as u32;
+
+        let mut bad = corpus().build_positions();
+        let mut lens = to_u32s(&bad.doc_lens);
+        lens.pop();
+        bad.doc_lens = load(&lens);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("doc_lens"));
+
+        let mut bad = corpus().build_positions();
+        let mut offsets = to_u32s(&bad.term_offsets);
+        *offsets.last_mut().unwrap() += 1;
+        bad.term_offsets = load(&offsets);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("term_offsets"));
+
+        let mut bad = corpus().build_positions();
+        let mut offsets = to_u32s(&bad.entry_offsets);
+        offsets.swap(1, 2);
+        bad.entry_offsets = load(&offsets);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("entry_offsets"));
+
+        let mut bad = corpus().build_positions();
+        let mut docs = to_u32s(&bad.entry_docs);
+        docs[0] = n_docs;
+        bad.entry_docs = load(&docs);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("entry_docs"));
+    }
+}
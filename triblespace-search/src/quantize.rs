@@ -0,0 +1,587 @@
+//! Compressed embedding codes for HNSW traversal.
+//!
+//! At tens of millions of vectors the graph walk is dominated by
+//! embedding fetches: every visited node resolves its
+//! full-precision [`Embedding`][crate::schemas::Embedding] blob
+//! through the store. A [`Quantizer`] trained at build time
+//! stores a short code per node inside the
+//! [`SuccinctHNSWIndex`][crate::succinct::SuccinctHNSWIndex]
+//! blob instead, so the walk scores candidates from the codes
+//! alone and only the final beam is reranked against the
+//! full-precision vectors.
+//!
+//! Three schemes, picked via [`Quantization`]:
+//!
+//! - [`Int8`][Quantization::Int8] — per-dimension min / step
+//!   scalar quantization, one byte per dimension (4× smaller
+//!   than f32).
+//! - [`Binary`][Quantization::Binary] — one sign bit per
+//!   dimension with a per-dimension magnitude (32× smaller).
+//! - [`Product`][Quantization::Product] — product quantization:
+//!   the vector is split into `subspaces` slices, each encoded
+//!   as the index of its nearest of up to 256 k-means
+//!   centroids, one byte per subspace.
+//!
+//! Distances are asymmetric: the query stays full-precision and
+//! is compared against decoded codes (for product quantization
+//! through a per-query lookup table, see [`QuantizedQuery`]).
+//!
+//! ```
+//! use triblespace_search::hnsw::Metric;
+//! use triblespace_search::quantize::{Quantization, Quantizer};
+//!
+//! let vecs = [[0.9f32, 0.1, 0.0, 0.4], [0.0, 1.0, 0.5, 0.0], [0.3, 0.3, 0.3, 0.3]];
+//! let rows: Vec<&[f32]> = vecs.iter().map(|v| &v[..]).collect();
+//! let q = Quantizer::train(Quantization::Int8, 4, &rows);
+//! assert_eq!(q.code_len(), 4);
+//!
+//! let code = q.encode(&vecs[0]);
+//! let approx = q.query(Metric::L2, &vecs[0]).distance(&code);
+//! assert!(approx < 0.01);
+//! ```
+
+use crate::hnsw::{dot, Metric};
+
+/// Cap on the number of vectors product quantization trains its
+/// centroids on. Larger corpora are subsampled with a fixed
+/// stride so training cost stays bounded and deterministic.
+const PQ_TRAINING_SAMPLE: usize = 1 << 16;
+
+/// Lloyd iterations per subspace during product-quantization
+/// training.
+const PQ_ITERATIONS: usize = 10;
+
+/// Quantization scheme, chosen on
+/// [`HNSWBuilder::quantization`][crate::hnsw::HNSWBuilder::quantization]
+/// and recorded in the succinct HNSW blob header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantization {
+    /// One byte per dimension: `x ≈ min + code × step`, with
+    /// `min` / `step` fitted per dimension.
+    Int8,
+    /// One bit per dimension: `x ≈ ±scale`, with `scale` the
+    /// mean absolute value of the dimension.
+    Binary,
+    /// One byte per subspace, indexing that subspace's k-means
+    /// codebook. `subspaces` is clamped to `1..=dim` and
+    /// `centroids` to `1..=256` (and to the training-set size).
+    Product { subspaces: u16, centroids: u16 },
+}
+
+impl Quantization {
+    /// Header tag; `0` is reserved for "not quantized".
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            Self::Int8 => 1,
+            Self::Binary => 2,
+            Self::Product { .. } => 3,
+        }
+    }
+
+    /// Inverse of [`code`][Self::code]; the product parameters
+    /// travel separately.
+    pub(crate) fn from_code(code: u8, subspaces: u16, centroids: u16) -> Option<Option<Self>> {
+        match code {
+            0 => Some(None),
+            1 => Some(Some(Self::Int8)),
+            2 => Some(Some(Self::Binary)),
+            3 => Some(Some(Self::Product {
+                subspaces,
+                centroids,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// A trained quantizer: the scheme plus its fitted parameters.
+///
+/// Produced by [`Quantizer::train`] (usually indirectly, by
+/// [`HNSWBuilder::build`][crate::hnsw::HNSWBuilder::build]) and
+/// persisted alongside the codes in the succinct HNSW blob.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantizer {
+    /// Scheme with the *effective* product parameters (after
+    /// clamping), so the header round-trips exactly.
+    kind: Quantization,
+    dim: usize,
+    /// Fitted parameters, layout depending on `kind`:
+    /// - `Int8`: `(min, step)` per dimension, interleaved.
+    /// - `Binary`: `scale` per dimension.
+    /// - `Product`: each subspace's `centroids × sub_dim` block,
+    ///   subspace-major — subspace `s` spanning dimensions
+    ///   `lo..hi` starts at `centroids × lo`.
+    params: Vec<f32>,
+}
+
+impl Quantizer {
+    /// Fit a `kind` quantizer for `dim`-dimensional vectors on
+    /// `vectors`. Rows whose length isn't `dim` are skipped.
+    /// An empty training set yields a quantizer that encodes
+    /// everything to zeros.
+    pub fn train(kind: Quantization, dim: usize, vectors: &[&[f32]]) -> Self {
+        assert!(dim > 0, "Quantizer: dim must be > 0");
+        let rows: Vec<&[f32]> = vectors.iter().copied().filter(|v| v.len() == dim).collect();
+        match kind {
+            Quantization::Int8 => {
+                let mut params = Vec::with_capacity(dim * 2);
+                for d in 0..dim {
+                    let lo = rows.iter().map(|v| v[d]).fold(f32::INFINITY, f32::min);
+                    let hi = rows.iter().map(|v| v[d]).fold(f32::NEG_INFINITY, f32::max);
+                    if lo.is_finite() && hi.is_finite() {
+                        params.extend_from_slice(&[lo, (hi - lo) / 255.0]);
+                    } else {
+                        params.extend_from_slice(&[0.0, 0.0]);
+                    }
+                }
+                Self { kind, dim, params }
+            }
+            Quantization::Binary => {
+                let n = rows.len().max(1) as f32;
+                let params = (0..dim)
+                    .map(|d| rows.iter().map(|v| v[d].abs()).sum::<f32>() / n)
+                    .collect();
+                Self { kind, dim, params }
+            }
+            Quantization::Product {
+                subspaces,
+                centroids,
+            } => {
+                let subspaces = (subspaces as usize).clamp(1, dim);
+                let stride = rows.len().div_ceil(PQ_TRAINING_SAMPLE).max(1);
+                let sample: Vec<&[f32]> = rows.iter().copied().step_by(stride).collect();
+                let k = (centroids as usize).clamp(1, 256).min(sample.len().max(1));
+                let mut params = vec![0.0f32; k * dim];
+                for s in 0..subspaces {
+                    let (lo, hi) = subspace(dim, subspaces, s);
+                    let book = kmeans(&sample, lo..hi, k);
+                    params[k * lo..k * hi].copy_from_slice(&book);
+                }
+                Self {
+                    kind: Quantization::Product {
+                        subspaces: subspaces as u16,
+                        centroids: k as u16,
+                    },
+                    dim,
+                    params,
+                }
+            }
+        }
+    }
+
+    /// Rebuild a quantizer from persisted parameters, or `None`
+    /// if `params` has the wrong length for `kind` / `dim`.
+    pub(crate) fn from_params(kind: Quantization, dim: usize, params: Vec<f32>) -> Option<Self> {
+        let expected = match kind {
+            Quantization::Int8 => dim * 2,
+            Quantization::Binary => dim,
+            Quantization::Product {
+                subspaces,
+                centroids,
+            } => {
+                if subspaces == 0 || subspaces as usize > dim || !(1..=256).contains(&centroids) {
+                    return None;
+                }
+                centroids as usize * dim
+            }
+        };
+        (dim > 0 && params.len() == expected).then_some(Self { kind, dim, params })
+    }
+
+    /// The fitted parameters, in the layout documented on the
+    /// field.
+    pub(crate) fn params(&self) -> &[f32] {
+        &self.params
+    }
+
+    /// Scheme, with the effective product parameters.
+    pub fn kind(&self) -> Quantization {
+        self.kind
+    }
+
+    /// Vector dimensionality.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Bytes per encoded vector.
+    pub fn code_len(&self) -> usize {
+        match self.kind {
+            Quantization::Int8 => self.dim,
+            Quantization::Binary => self.dim.div_ceil(8),
+            Quantization::Product { subspaces, .. } => subspaces as usize,
+        }
+    }
+
+    /// Encode `v` (length [`dim`][Self::dim]) into a
+    /// [`code_len`][Self::code_len]-byte code.
+    pub fn encode(&self, v: &[f32]) -> Vec<u8> {
+        debug_assert_eq!(v.len(), self.dim);
+        match self.kind {
+            Quantization::Int8 => v
+                .iter()
+                .zip(self.params.chunks_exact(2))
+                .map(|(&x, p)| {
+                    if p[1] > 0.0 {
+                        ((x - p[0]) / p[1]).round().clamp(0.0, 255.0) as u8
+                    } else {
+                        0
+                    }
+                })
+                .collect(),
+            Quantization::Binary => {
+                let mut code = vec![0u8; self.code_len()];
+                for (d, &x) in v.iter().enumerate() {
+                    if x > 0.0 {
+                        code[d / 8] |= 1 << (d % 8);
+                    }
+                }
+                code
+            }
+            Quantization::Product {
+                subspaces,
+                centroids,
+            } => {
+                let k = centroids as usize;
+                (0..subspaces as usize)
+                    .map(|s| {
+                        let (lo, hi) = subspace(self.dim, subspaces as usize, s);
+                        let book = &self.params[k * lo..k * hi];
+                        nearest(book, &v[lo..hi]) as u8
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Reconstruct the approximate vector behind `code`.
+    pub fn decode(&self, code: &[u8]) -> Vec<f32> {
+        match self.kind {
+            Quantization::Int8 => code
+                .iter()
+                .zip(self.params.chunks_exact(2))
+                .map(|(&c, p)| p[0] + c as f32 * p[1])
+                .collect(),
+            Quantization::Binary => (0..self.dim)
+                .map(|d| {
+                    if bit(code, d) {
+                        self.params[d]
+                    } else {
+                        -self.params[d]
+                    }
+                })
+                .collect(),
+            Quantization::Product {
+                subspaces,
+                centroids,
+            } => {
+                let k = centroids as usize;
+                let mut out = Vec::with_capacity(self.dim);
+                for (s, &c) in code.iter().enumerate().take(subspaces as usize) {
+                    let (lo, hi) = subspace(self.dim, subspaces as usize, s);
+                    let w = hi - lo;
+                    let at = k * lo + c as usize * w;
+                    out.extend_from_slice(&self.params[at..at + w]);
+                }
+                out
+            }
+        }
+    }
+
+    /// Prepare `query` (full precision) for repeated asymmetric
+    /// distance evaluation against codes under `metric`.
+    pub fn query(&self, metric: Metric, query: &[f32]) -> QuantizedQuery<'_> {
+        let mut table = Vec::new();
+        let mut bias = 0.0;
+        let inner = metric != Metric::L2;
+        match self.kind {
+            Quantization::Int8 if inner => {
+                for (&x, p) in query.iter().zip(self.params.chunks_exact(2)) {
+                    table.push(x * p[1]);
+                    bias += x * p[0];
+                }
+            }
+            Quantization::Binary if inner => {
+                table.extend(query.iter().zip(&self.params).map(|(&x, &s)| x * s));
+                bias = -table.iter().sum::<f32>();
+            }
+            Quantization::Product {
+                subspaces,
+                centroids,
+            } => {
+                let k = centroids as usize;
+                for s in 0..subspaces as usize {
+                    let (lo, hi) = subspace(self.dim, subspaces as usize, s);
+                    let q = &query[lo..hi];
+                    for c in self.params[k * lo..k * hi].chunks_exact(hi - lo) {
+                        table.push(if inner { dot(q, c) } else { sq_l2(q, c) });
+                    }
+                }
+            }
+            _ => {}
+        }
+        QuantizedQuery {
+            quantizer: self,
+            metric,
+            query: query.to_vec(),
+            table,
+            bias,
+        }
+    }
+}
+
+/// A full-precision query prepared against a [`Quantizer`] —
+/// produced by [`Quantizer::query`]. Holds the per-query lookup
+/// tables so each [`distance`][Self::distance] is a single pass
+/// over the code.
+#[derive(Debug, Clone)]
+pub struct QuantizedQuery<'a> {
+    quantizer: &'a Quantizer,
+    metric: Metric,
+    query: Vec<f32>,
+    /// Inner-product metrics: per-dimension weights (`Int8`,
+    /// `Binary`). Product quantization: per-subspace,
+    /// per-centroid partial dot products or squared distances.
+    table: Vec<f32>,
+    /// Code-independent part of the inner product.
+    bias: f32,
+}
+
+impl QuantizedQuery<'_> {
+    /// Approximate [`Metric::distance`] between the query and
+    /// the vector behind `code`.
+    pub fn distance(&self, code: &[u8]) -> f32 {
+        let q = self.quantizer;
+        let inner = self.metric != Metric::L2;
+        let raw = match q.kind {
+            Quantization::Int8 if inner => {
+                self.bias
+                    + code
+                        .iter()
+                        .zip(&self.table)
+                        .map(|(&c, &w)| c as f32 * w)
+                        .sum::<f32>()
+            }
+            Quantization::Int8 => self
+                .query
+                .iter()
+                .zip(code)
+                .zip(q.params.chunks_exact(2))
+                .map(|((&x, &c), p)| {
+                    let e = x - (p[0] + c as f32 * p[1]);
+                    e * e
+                })
+                .sum(),
+            Quantization::Binary if inner => {
+                let set: f32 = (0..q.dim)
+                    .filter(|&d| bit(code, d))
+                    .map(|d| self.table[d])
+                    .sum();
+                self.bias + 2.0 * set
+            }
+            Quantization::Binary => (0..q.dim)
+                .map(|d| {
+                    let s = q.params[d];
+                    let e = self.query[d] - if bit(code, d) { s } else { -s };
+                    e * e
+                })
+                .sum(),
+            Quantization::Product { centroids, .. } => code
+                .iter()
+                .enumerate()
+                .map(|(s, &c)| self.table[s * centroids as usize + c as usize])
+                .sum(),
+        };
+        match self.metric {
+            Metric::Cosine => 1.0 - raw,
+            Metric::Dot => -raw,
+            Metric::L2 => raw.sqrt(),
+        }
+    }
+}
+
+/// Dimension range `lo..hi` of subspace `s` out of `m` over
+/// `dim` dimensions. Remainders spread evenly, so subspaces
+/// differ in width by at most one.
+fn subspace(dim: usize, m: usize, s: usize) -> (usize, usize) {
+    (s * dim / m, (s + 1) * dim / m)
+}
+
+fn bit(code: &[u8], d: usize) -> bool {
+    code[d / 8] & (1 << (d % 8)) != 0
+}
+
+fn sq_l2(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(&x, &y)| (x - y) * (x - y)).sum()
+}
+
+/// Index of the row of `book` (rows of `v.len()` floats)
+/// closest to `v` in squared L2.
+fn nearest(book: &[f32], v: &[f32]) -> usize {
+    book.chunks_exact(v.len())
+        .map(|c| sq_l2(c, v))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Lloyd's k-means over the `dims` slice of `rows`, seeded with
+/// evenly strided rows so training is deterministic. Returns
+/// `k × dims.len()` centroid coordinates; centroids that lose
+/// all their members keep their previous position.
+fn kmeans(rows: &[&[f32]], dims: std::ops::Range<usize>, k: usize) -> Vec<f32> {
+    let w = dims.len();
+    let mut book = vec![0.0f32; k * w];
+    if rows.is_empty() {
+        return book;
+    }
+    for (c, slot) in book.chunks_exact_mut(w).enumerate() {
+        slot.copy_from_slice(&rows[c * rows.len() / k][dims.clone()]);
+    }
+    let mut assign = vec![usize::MAX; rows.len()];
+    for _ in 0..PQ_ITERATIONS {
+        let mut changed = false;
+        for (row, a) in rows.iter().zip(assign.iter_mut()) {
+            let c = nearest(&book, &row[dims.clone()]);
+            changed |= *a != c;
+            *a = c;
+        }
+        if !changed {
+            break;
+        }
+        let mut sums = vec![0.0f32; k * w];
+        let mut counts = vec![0usize; k];
+        for (row, &a) in rows.iter().zip(&assign) {
+            counts[a] += 1;
+            for (s, &x) in sums[a * w..(a + 1) * w].iter_mut().zip(&row[dims.clone()]) {
+                *s += x;
+            }
+        }
+        for c in (0..k).filter(|&c| counts[c] > 0) {
+            for (slot, &s) in book[c * w..(c + 1) * w].iter_mut().zip(&sums[c * w..(c + 1) * w]) {
+                *slot = s / counts[c] as f32;
+            }
+        }
+    }
+    book
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_vecs(seed: u64, n: usize, dim: usize) -> Vec<Vec<f32>> {
+        let mut rng = seed;
+        let mut next = || {
+            rng = rng.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = rng;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        (0..n)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| (next() as i32 as f32) / (i32::MAX as f32))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn train(kind: Quantization, vecs: &[Vec<f32>]) -> Quantizer {
+        let rows: Vec<&[f32]> = vecs.iter().map(|v| &v[..]).collect();
+        Quantizer::train(kind, vecs[0].len(), &rows)
+    }
+
+    #[test]
+    fn int8_round_trip_is_within_half_a_step() {
+        let vecs = random_vecs(1, 50, 8);
+        let q = train(Quantization::Int8, &vecs);
+        assert_eq!(q.code_len(), 8);
+        for v in &vecs {
+            let back = q.decode(&q.encode(v));
+            for (d, (&x, &y)) in v.iter().zip(&back).enumerate() {
+                assert!((x - y).abs() <= q.params()[2 * d + 1] / 2.0 + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_codes_keep_signs() {
+        let vecs = random_vecs(2, 20, 11);
+        let q = train(Quantization::Binary, &vecs);
+        assert_eq!(q.code_len(), 2);
+        for v in &vecs {
+            let back = q.decode(&q.encode(v));
+            for (&x, &y) in v.iter().zip(&back) {
+                assert_eq!(x > 0.0, y > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn product_codes_hit_their_own_centroids() {
+        // Fewer vectors than centroids: every vector becomes its
+        // own centroid and decodes exactly.
+        let vecs = random_vecs(3, 10, 6);
+        let q = train(
+            Quantization::Product {
+                subspaces: 4,
+                centroids: 256,
+            },
+            &vecs,
+        );
+        assert_eq!(
+            q.kind(),
+            Quantization::Product {
+                subspaces: 4,
+                centroids: 10
+            }
+        );
+        assert_eq!(q.code_len(), 4);
+        for v in &vecs {
+            assert_eq!(&q.decode(&q.encode(v)), v);
+        }
+    }
+
+    #[test]
+    fn prepared_distances_match_decoded_vectors() {
+        let vecs = random_vecs(4, 64, 12);
+        let kinds = [
+            Quantization::Int8,
+            Quantization::Binary,
+            Quantization::Product {
+                subspaces: 3,
+                centroids: 8,
+            },
+        ];
+        for kind in kinds {
+            let q = train(kind, &vecs);
+            for metric in [Metric::Cosine, Metric::Dot, Metric::L2] {
+                let prepared = q.query(metric, &vecs[0]);
+                for v in &vecs[1..] {
+                    let code = q.encode(v);
+                    let want = metric.distance(&vecs[0], &q.decode(&code));
+                    let got = prepared.distance(&code);
+                    assert!((want - got).abs() < 1e-4, "{kind:?}/{metric:?}: {want} vs {got}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn params_round_trip() {
+        let vecs = random_vecs(5, 30, 5);
+        let q = train(
+            Quantization::Product {
+                subspaces: 2,
+                centroids: 4,
+            },
+            &vecs,
+        );
+        let back = Quantizer::from_params(q.kind(), q.dim(), q.params().to_vec()).unwrap();
+        assert_eq!(back, q);
+        assert!(Quantizer::from_params(Quantization::Int8, 5, vec![0.0; 3]).is_none());
+    }
+}
@@ -0,0 +1,1017 @@
+//! Incremental BM25: immutable delta segments searched as one
+//! index, compacted in the background.
+//!
+//! [`SuccinctBM25Index`] bakes corpus-wide statistics (doc
+//! count, average length, document frequency) into its
+//! quantised scores, so adding a single document means
+//! rebuilding the whole blob. For corpora that grow commit by
+//! commit this module offers the LSM-shaped alternative:
+//!
+//! - [`BM25SegmentBuilder`] turns a batch of new, changed or
+//!   deleted documents into a small [`BM25Segment`] (schema
+//!   [`BM25SegmentBlob`]). Segments store raw term frequencies
+//!   instead of scores, so nothing in them depends on the rest
+//!   of the corpus.
+//! - [`BM25Segments`] stacks segments oldest → newest and
+//!   scores across all of them with *global* statistics. A key
+//!   in a newer segment supersedes every older version; a
+//!   tombstone hides it without replacing it.
+//! - [`BM25Segments::merge_plan`] + [`BM25Segments::compact`] +
+//!   [`BM25Segments::replace`] fold runs of segments into one,
+//!   keeping the stack logarithmic. [`BM25Segments::to_index`]
+//!   collapses everything into a plain [`SuccinctBM25Index`]
+//!   when the corpus settles.
+//!
+//! Scores from a segment stack equal those of a
+//! [`BM25Builder::build`] over the same live documents (up to
+//! the succinct index's quantisation).
+//!
+//! ```
+//! use triblespace_core::id::Id;
+//! use triblespace_search::segment::{BM25SegmentBuilder, BM25Segments};
+//! use triblespace_search::tokens::hash_tokens;
+//!
+//! let mut first = BM25SegmentBuilder::new();
+//! first.insert(&Id::new([1; 16]).unwrap(), hash_tokens("the quick brown fox"));
+//! first.insert(&Id::new([2; 16]).unwrap(), hash_tokens("the lazy brown dog"));
+//!
+//! let mut second = BM25SegmentBuilder::new();
+//! second.insert(&Id::new([3; 16]).unwrap(), hash_tokens("quick silver fox"));
+//! second.delete(&Id::new([1; 16]).unwrap());
+//!
+//! let mut segments = BM25Segments::new();
+//! segments.push(first.build());
+//! segments.push(second.build());
+//! assert_eq!(segments.doc_count(), 2);
+//!
+//! let hits = segments.query_multi(&hash_tokens("fox"));
+//! assert_eq!(hits.len(), 1);
+//!
+//! // Fold both segments into one; scores are unchanged.
+//! let merged = segments.compact(0..2);
+//! segments.replace(0..2, merged);
+//! assert_eq!(segments.segment_count(), 1);
+//! ```
+//!
+//! [`SuccinctBM25Index`]: crate::succinct::SuccinctBM25Index
+//! [`BM25Builder::build`]: crate::bm25::BM25Builder::build
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use anybytes::{ByteArea, Bytes};
+use jerky::int_vectors::compact_vector::CompactVectorMeta;
+use jerky::int_vectors::{CompactVector, CompactVectorBuilder};
+use triblespace_core::blob::{Blob, BlobSchema, ToBlob, TryFromBlob};
+use triblespace_core::id::Id;
+use triblespace_core::id_hex;
+use triblespace_core::metadata::{ConstDescribe, ConstId};
+use triblespace_core::value::schemas::genid::GenId;
+use triblespace_core::value::{RawValue, ToValue, Value, ValueSchema};
+use zerocopy::{FromBytes, IntoBytes};
+
+use crate::bm25::BM25Builder;
+use crate::succinct::{
+    CompactVectorMetaOnDisk, FixedBytesTable, SuccinctBM25Index, SuccinctLoadError,
+};
+
+/// Accumulates one batch of index changes. Each
+/// [`insert`][Self::insert] adds or replaces a document, each
+/// [`delete`][Self::delete] records a tombstone; the last
+/// operation on a key within the batch wins.
+pub struct BM25SegmentBuilder<D: ValueSchema = GenId, T: ValueSchema = crate::tokens::WordHash> {
+    ops: HashMap<RawValue, Option<Vec<RawValue>>>,
+    _phantom: std::marker::PhantomData<(D, T)>,
+}
+
+impl<D: ValueSchema, T: ValueSchema> Default for BM25SegmentBuilder<D, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> BM25SegmentBuilder<D, T> {
+    /// Empty batch.
+    pub fn new() -> Self {
+        Self {
+            ops: HashMap::new(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Add a document, superseding any older version of `key`.
+    /// Same key/term conventions as
+    /// [`BM25Builder::insert`][crate::bm25::BM25Builder::insert].
+    pub fn insert<K: ToValue<D>>(&mut self, key: K, terms: Vec<Value<T>>) {
+        let key: Value<D> = key.to_value();
+        self.ops
+            .insert(key.raw, Some(terms.into_iter().map(|v| v.raw).collect()));
+    }
+
+    /// Remove `key` from the index. Hides every older version;
+    /// a later segment may insert it again.
+    pub fn delete<K: ToValue<D>>(&mut self, key: K) {
+        let key: Value<D> = key.to_value();
+        self.ops.insert(key.raw, None);
+    }
+
+    /// `true` if the batch records no changes.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Freeze the batch into an immutable segment.
+    pub fn build(self) -> BM25Segment<D, T> {
+        let mut docs = Vec::new();
+        let mut deleted = Vec::new();
+        for (key, op) in self.ops {
+            match op {
+                Some(terms) => docs.push((key, terms)),
+                None => deleted.push(key),
+            }
+        }
+        BM25Segment::assemble(docs, deleted)
+    }
+}
+
+/// Pack `values` into a [`CompactVector`] in its own byte region,
+/// so the returned meta is relative to the region start.
+pub(crate) fn pack(values: &[u32]) -> (Bytes, CompactVectorMeta) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let width = (32 - max.leading_zeros() as usize).max(1);
+    let mut area = ByteArea::new().expect("alloc ByteArea");
+    let mut sections = area.sections();
+    let mut builder = CompactVectorBuilder::with_capacity(values.len(), width, &mut sections)
+        .expect("compact vector capacity");
+    builder
+        .set_ints(0..values.len(), values.iter().map(|&n| n as usize))
+        .expect("values fit the computed width");
+    let meta = builder.freeze().metadata();
+    let _ = sections;
+    (area.freeze().expect("freeze ByteArea"), meta)
+}
+
+/// One immutable batch of BM25 documents and tombstones.
+///
+/// Unlike [`SuccinctBM25Index`], postings carry raw term
+/// frequencies: scoring needs corpus-wide statistics that only
+/// the [`BM25Segments`] stack knows. Documents are keyed by
+/// their position in the sorted key table.
+pub struct BM25Segment<D: ValueSchema = GenId, T: ValueSchema = crate::tokens::WordHash> {
+    keys: FixedBytesTable<32>,
+    doc_lens: CompactVector,
+    terms: FixedBytesTable<32>,
+    /// Per-posting document index, grouped by term.
+    posting_docs: CompactVector,
+    /// `n_terms + 1` cumulative offsets into the posting columns.
+    posting_offsets: CompactVector,
+    /// Per-posting term frequency.
+    posting_tfs: CompactVector,
+    deleted: FixedBytesTable<32>,
+    _phantom: std::marker::PhantomData<(D, T)>,
+}
+
+impl<D: ValueSchema, T: ValueSchema> std::fmt::Debug for BM25Segment<D, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BM25Segment")
+            .field("n_docs", &self.keys.len())
+            .field("n_terms", &self.terms.len())
+            .field("n_deleted", &self.deleted.len())
+            .finish()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> BM25Segment<D, T> {
+    /// Build from deduplicated documents and tombstones. A key
+    /// must not appear in both.
+    fn assemble(mut docs: Vec<(RawValue, Vec<RawValue>)>, mut deleted: Vec<RawValue>) -> Self {
+        docs.sort_unstable_by_key(|d| d.0);
+        deleted.sort_unstable();
+
+        let doc_lens: Vec<u32> = docs.iter().map(|(_, t)| t.len() as u32).collect();
+        let mut term_to_tfs: HashMap<RawValue, Vec<(u32, u32)>> = HashMap::new();
+        for (doc, (_, terms)) in docs.iter().enumerate() {
+            let mut tfs: HashMap<RawValue, u32> = HashMap::new();
+            for term in terms {
+                *tfs.entry(*term).or_insert(0) += 1;
+            }
+            for (term, tf) in tfs {
+                term_to_tfs.entry(term).or_default().push((doc as u32, tf));
+            }
+        }
+        let mut term_rows: Vec<RawValue> = term_to_tfs.keys().copied().collect();
+        term_rows.sort_unstable();
+
+        let mut posting_docs = Vec::new();
+        let mut posting_tfs = Vec::new();
+        let mut posting_offsets = Vec::with_capacity(term_rows.len() + 1);
+        posting_offsets.push(0u32);
+        for term in &term_rows {
+            // Docs were enumerated in order, so each list is
+            // already ascending by doc index.
+            for &(doc, tf) in &term_to_tfs[term] {
+                posting_docs.push(doc);
+                posting_tfs.push(tf);
+            }
+            posting_offsets.push(posting_docs.len() as u32);
+        }
+
+        let key_rows: Vec<RawValue> = docs.iter().map(|(k, _)| *k).collect();
+        let load = |(bytes, meta): (Bytes, CompactVectorMeta)| {
+            CompactVector::from_bytes(meta, bytes).expect("round-trip compact vector")
+        };
+        Self {
+            keys: FixedBytesTable::from_bytes(FixedBytesTable::<32>::build(&key_rows), key_rows.len())
+                .expect("round-trip keys"),
+            doc_lens: load(pack(&doc_lens)),
+            terms: FixedBytesTable::from_bytes(
+                FixedBytesTable::<32>::build(&term_rows),
+                term_rows.len(),
+            )
+            .expect("round-trip terms"),
+            posting_docs: load(pack(&posting_docs)),
+            posting_offsets: load(pack(&posting_offsets)),
+            posting_tfs: load(pack(&posting_tfs)),
+            deleted: FixedBytesTable::from_bytes(FixedBytesTable::<32>::build(&deleted), deleted.len())
+                .expect("round-trip tombstones"),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of documents stored in this segment (live or not).
+    pub fn doc_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Number of tombstones.
+    pub fn deleted_count(&self) -> usize {
+        self.deleted.len()
+    }
+
+    /// Number of distinct terms.
+    pub fn term_count(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Key of document `i`, or `None` if out of range.
+    pub fn key(&self, i: usize) -> Option<Value<D>> {
+        self.keys.get(i).map(|k| Value::new(*k))
+    }
+
+    /// Length of document `i`, or `None` if out of range.
+    pub fn doc_len(&self, i: usize) -> Option<u32> {
+        self.doc_lens.get_int(i).map(|n| n as u32)
+    }
+
+    /// `true` if this segment stores `key`.
+    pub fn contains(&self, key: &Value<D>) -> bool {
+        self.keys.binary_search(&key.raw).is_ok()
+    }
+
+    /// `true` if this segment carries a tombstone for `key`.
+    pub fn is_deleted(&self, key: &Value<D>) -> bool {
+        self.deleted.binary_search(&key.raw).is_ok()
+    }
+
+    /// Iterate the tombstoned keys in ascending order.
+    pub fn deleted(&self) -> impl Iterator<Item = Value<D>> + '_ {
+        (0..self.deleted.len()).map(|i| Value::new(*self.deleted.get(i).unwrap()))
+    }
+
+    /// `(doc index, term frequency)` postings for `term`.
+    fn postings(&self, term: &RawValue) -> impl Iterator<Item = (usize, u32)> + '_ {
+        let range = match self.terms.binary_search(term) {
+            Ok(t) => {
+                let start = self.posting_offsets.get_int(t).unwrap();
+                let end = self.posting_offsets.get_int(t + 1).unwrap();
+                start..end
+            }
+            Err(_) => 0..0,
+        };
+        range.map(move |p| {
+            (
+                self.posting_docs.get_int(p).unwrap(),
+                self.posting_tfs.get_int(p).unwrap() as u32,
+            )
+        })
+    }
+
+    /// Serialize to a self-contained blob:
+    ///
+    /// ```text
+    /// [header         ] SEGMENT_HEADER_LEN B
+    /// [keys           ] n_docs × 32 B
+    /// [terms          ] n_terms × 32 B
+    /// [deleted        ] n_deleted × 32 B
+    /// [doc_lens       ] CompactVector body
+    /// [posting_docs   ] CompactVector body
+    /// [posting_offsets] CompactVector body
+    /// [posting_tfs    ] CompactVector body
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Re-pack every vector so the metas point into regions
+        // that start at offset zero.
+        let vectors = [
+            pack(&to_u32s(&self.doc_lens)),
+            pack(&to_u32s(&self.posting_docs)),
+            pack(&to_u32s(&self.posting_offsets)),
+            pack(&to_u32s(&self.posting_tfs)),
+        ];
+        let tables = [
+            table_bytes(&self.keys),
+            table_bytes(&self.terms),
+            table_bytes(&self.deleted),
+        ];
+
+        fn align8(n: u64) -> u64 {
+            (n + 7) & !7
+        }
+        let regions: Vec<&[u8]> = tables
+            .iter()
+            .map(|t| t.as_slice())
+            .chain(vectors.iter().map(|(bytes, _)| bytes.as_ref()))
+            .collect();
+        let mut sections = Vec::with_capacity(regions.len());
+        let mut off = 0u64;
+        for region in &regions {
+            off = align8(off);
+            sections.push((off, region.len() as u64));
+            off += region.len() as u64;
+        }
+
+        let mut buf = Vec::with_capacity(SEGMENT_HEADER_LEN + off as usize);
+        buf.extend_from_slice(&(self.keys.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.terms.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.deleted.len() as u64).to_le_bytes());
+        for (_, meta) in &vectors {
+            let on_disk: CompactVectorMetaOnDisk = (*meta).into();
+            buf.extend_from_slice(on_disk.as_bytes());
+        }
+        for (off, len) in &sections {
+            buf.extend_from_slice(&off.to_le_bytes());
+            buf.extend_from_slice(&len.to_le_bytes());
+        }
+        debug_assert_eq!(buf.len(), SEGMENT_HEADER_LEN);
+
+        for ((off, _), region) in sections.iter().zip(&regions) {
+            buf.resize(SEGMENT_HEADER_LEN + *off as usize, 0);
+            buf.extend_from_slice(region);
+        }
+        buf
+    }
+
+    /// Reload from bytes previously produced by [`Self::to_bytes`].
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, SuccinctLoadError> {
+        if bytes.len() < SEGMENT_HEADER_LEN {
+            return Err(SuccinctLoadError::ShortHeader);
+        }
+        let read_u64 = |off: usize| u64::from_le_bytes(bytes[off..off + 8].try_into().unwrap());
+        let n_docs = read_u64(0) as usize;
+        let n_terms = read_u64(8) as usize;
+        let n_deleted = read_u64(16) as usize;
+
+        const VECTORS: [&str; 4] = ["doc_lens", "posting_docs", "posting_offsets", "posting_tfs"];
+        const SECTIONS: [&str; 7] = [
+            "keys",
+            "terms",
+            "deleted",
+            "doc_lens",
+            "posting_docs",
+            "posting_offsets",
+            "posting_tfs",
+        ];
+        let mut metas = Vec::with_capacity(VECTORS.len());
+        for (i, name) in VECTORS.iter().enumerate() {
+            let start = 24 + i * 32;
+            let meta = CompactVectorMetaOnDisk::read_from_bytes(&bytes[start..start + 32])
+                .map_err(|_| SuccinctLoadError::BadMeta(name))?
+                .to_jerky();
+            metas.push(meta);
+        }
+
+        let body = Bytes::from_source(bytes[SEGMENT_HEADER_LEN..].to_vec());
+        let mut regions = Vec::with_capacity(SECTIONS.len());
+        for (i, name) in SECTIONS.iter().enumerate() {
+            let off = read_u64(152 + i * 16) as usize;
+            let len = read_u64(160 + i * 16) as usize;
+            match off.checked_add(len) {
+                Some(end) if end <= body.len() => regions.push(body.slice(off..end)),
+                _ => return Err(SuccinctLoadError::TruncatedSection(name)),
+            }
+        }
+
+        let table = |i: usize, len: usize| {
+            FixedBytesTable::<32>::from_bytes(regions[i].clone(), len)
+                .map_err(|_| SuccinctLoadError::TruncatedSection(SECTIONS[i]))
+        };
+        let vector = |i: usize| {
+            CompactVector::from_bytes(metas[i], regions[3 + i].clone())
+                .map_err(|_| SuccinctLoadError::TruncatedSection(VECTORS[i]))
+        };
+        let segment = Self {
+            keys: table(0, n_docs)?,
+            terms: table(1, n_terms)?,
+            deleted: table(2, n_deleted)?,
+            doc_lens: vector(0)?,
+            posting_docs: vector(1)?,
+            posting_offsets: vector(2)?,
+            posting_tfs: vector(3)?,
+            _phantom: std::marker::PhantomData,
+        };
+        if segment.doc_lens.len() != n_docs {
+            return Err(SuccinctLoadError::BadMeta("doc_lens"));
+        }
+        if segment.posting_docs.len() != segment.posting_tfs.len() {
+            return Err(SuccinctLoadError::BadMeta("posting_tfs"));
+        }
+        // Offsets must be monotone and in bounds, and postings must
+        // name existing docs; `postings` and the scorers rely on it.
+        let n_postings = segment.posting_docs.len();
+        if segment.posting_offsets.len() != n_terms + 1 {
+            return Err(SuccinctLoadError::BadMeta("posting_offsets"));
+        }
+        let mut prev = 0;
+        for t in 0..=n_terms {
+            let off = segment.posting_offsets.get_int(t).unwrap();
+            if off < prev || off > n_postings {
+                return Err(SuccinctLoadError::BadMeta("posting_offsets"));
+            }
+            prev = off;
+        }
+        if (0..n_postings).any(|p| segment.posting_docs.get_int(p).unwrap() >= n_docs) {
+            return Err(SuccinctLoadError::BadMeta("posting_docs"));
+        }
+        Ok(segment)
+    }
+}
+
+pub(crate) fn to_u32s(cv: &CompactVector) -> Vec<u32> {
+    cv.to_vec().into_iter().map(|n| n as u32).collect()
+}
+
+pub(crate) fn table_bytes(table: &FixedBytesTable<32>) -> Vec<u8> {
+    (0..table.len()).flat_map(|i| *table.get(i).unwrap()).collect()
+}
+
+/// Header length in bytes for a `BM25Segment` blob.
+///
+/// Layout: 8 n_docs, 8 n_terms, 8 n_deleted, 4×32
+/// CompactVectorMeta (doc_lens, posting_docs,
+/// posting_offsets, posting_tfs), 7×16 section (offset, len)
+/// = 264. A multiple of 8, so jerky's u64 views stay aligned.
+const SEGMENT_HEADER_LEN: usize = 264;
+
+/// Content-addressed [`BlobSchema`] marker for a BM25 delta
+/// segment — 264 B header + raw-tf postings + tombstones.
+///
+/// Schema id minted fresh via `trible genid`:
+/// `9234C3FECEAF4C05863DB36A904B51CD`.
+pub enum BM25SegmentBlob {}
+
+impl ConstId for BM25SegmentBlob {
+    const ID: Id = id_hex!("9234C3FECEAF4C05863DB36A904B51CD");
+}
+
+impl BlobSchema for BM25SegmentBlob {}
+
+impl ConstDescribe for BM25SegmentBlob {}
+
+impl<D: ValueSchema, T: ValueSchema> ToBlob<BM25SegmentBlob> for &BM25Segment<D, T> {
+    fn to_blob(self) -> Blob<BM25SegmentBlob> {
+        Blob::new(Bytes::from_source(self.to_bytes()))
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> ToBlob<BM25SegmentBlob> for BM25Segment<D, T> {
+    fn to_blob(self) -> Blob<BM25SegmentBlob> {
+        (&self).to_blob()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> TryFromBlob<BM25SegmentBlob> for BM25Segment<D, T> {
+    type Error = SuccinctLoadError;
+
+    fn try_from_blob(blob: Blob<BM25SegmentBlob>) -> Result<Self, Self::Error> {
+        BM25Segment::try_from_bytes(blob.bytes.as_ref())
+    }
+}
+
+/// A stack of [`BM25Segment`]s searched as one BM25 index.
+///
+/// Segments are ordered oldest → newest. A document is live
+/// when no newer segment stores or tombstones its key; only
+/// live documents count towards the doc count, average length
+/// and document frequencies used for scoring.
+///
+/// Compaction never blocks readers: [`Self::compact`] takes
+/// `&self`, so a background thread can fold a range while
+/// queries continue, and [`Self::replace`] swaps the result
+/// in. Segments pushed in the meantime land after the range
+/// and stay valid.
+pub struct BM25Segments<D: ValueSchema = GenId, T: ValueSchema = crate::tokens::WordHash> {
+    segments: Vec<BM25Segment<D, T>>,
+    /// Per segment, per document: not superseded or deleted.
+    live: Vec<Vec<bool>>,
+    live_docs: usize,
+    live_len: u64,
+    k1: f32,
+    b: f32,
+}
+
+impl<D: ValueSchema, T: ValueSchema> std::fmt::Debug for BM25Segments<D, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BM25Segments")
+            .field("segments", &self.segments)
+            .field("live_docs", &self.live_docs)
+            .field("k1", &self.k1)
+            .field("b", &self.b)
+            .finish()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> Default for BM25Segments<D, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: ValueSchema, T: ValueSchema> BM25Segments<D, T> {
+    /// Empty stack with the BM25 defaults `k1 = 1.5`, `b = 0.75`.
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            live: Vec::new(),
+            live_docs: 0,
+            live_len: 0,
+            k1: 1.5,
+            b: 0.75,
+        }
+    }
+
+    /// Override the BM25 `k1` parameter.
+    pub fn k1(mut self, k1: f32) -> Self {
+        self.k1 = k1;
+        self
+    }
+
+    /// Override the BM25 `b` parameter.
+    pub fn b(mut self, b: f32) -> Self {
+        self.b = b;
+        self
+    }
+
+    /// Append `segment` as the newest layer.
+    pub fn push(&mut self, segment: BM25Segment<D, T>) {
+        self.segments.push(segment);
+        self.refresh();
+    }
+
+    /// The stacked segments, oldest first.
+    pub fn segments(&self) -> &[BM25Segment<D, T>] {
+        &self.segments
+    }
+
+    /// Number of stacked segments.
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Number of live documents.
+    pub fn doc_count(&self) -> usize {
+        self.live_docs
+    }
+
+    /// Average length of the live documents.
+    pub fn avg_doc_len(&self) -> f32 {
+        if self.live_docs == 0 {
+            0.0
+        } else {
+            self.live_len as f64 as f32 / self.live_docs as f32
+        }
+    }
+
+    /// Number of live documents containing `term`.
+    pub fn doc_frequency(&self, term: &Value<T>) -> usize {
+        self.segments
+            .iter()
+            .zip(&self.live)
+            .map(|(segment, live)| {
+                segment
+                    .postings(&term.raw)
+                    .filter(|&(doc, _)| live[doc])
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Iterate `(Value<D>, f32)` for the live documents
+    /// containing `term`, scored with the stack's global
+    /// statistics. Empty if the term is absent.
+    pub fn query_term<'a>(
+        &'a self,
+        term: &Value<T>,
+    ) -> Box<dyn Iterator<Item = (Value<D>, f32)> + 'a> {
+        let df = self.doc_frequency(term);
+        if df == 0 {
+            return Box::new(std::iter::empty());
+        }
+        let n = self.live_docs as f32;
+        let df = df as f32;
+        let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+        let avg_doc_len = self.avg_doc_len();
+        let (k1, b) = (self.k1, self.b);
+        let raw = term.raw;
+        Box::new(
+            self.segments
+                .iter()
+                .zip(&self.live)
+                .flat_map(move |(segment, live)| {
+                    segment
+                        .postings(&raw)
+                        .filter(move |&(doc, _)| live[doc])
+                        .map(move |(doc, tf)| {
+                            let tf = tf as f32;
+                            let dl = segment.doc_len(doc).unwrap() as f32;
+                            let norm = if avg_doc_len > 0.0 {
+                                1.0 - b + b * (dl / avg_doc_len)
+                            } else {
+                                1.0
+                            };
+                            let score = idf * (tf * (k1 + 1.0)) / (tf + k1 * norm);
+                            (segment.key(doc).unwrap(), score)
+                        })
+                }),
+        )
+    }
+
+    /// Score a multi-term query as the sum of per-term BM25
+    /// weights, sorted descending by score. Same semantics as
+    /// [`SuccinctBM25Index::query_multi`].
+    pub fn query_multi(&self, terms: &[Value<T>]) -> Vec<(Value<D>, f32)> {
+        let mut acc: HashMap<RawValue, f32> = HashMap::new();
+        for term in terms {
+            for (key, score) in self.query_term(term) {
+                *acc.entry(key.raw).or_insert(0.0) += score;
+            }
+        }
+        let mut out: Vec<(Value<D>, f32)> =
+            acc.into_iter().map(|(raw, s)| (Value::new(raw), s)).collect();
+        out.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        out
+    }
+
+    /// Suggest a run of segments to compact, or `None` if the
+    /// stack is already balanced.
+    ///
+    /// Walks back from the newest segment, absorbing older
+    /// segments while each is no larger than everything
+    /// absorbed so far. Repeatedly merging the suggested run
+    /// keeps segment sizes roughly geometric, so a stack of
+    /// `n` documents holds `O(log n)` segments and every
+    /// document is rewritten `O(log n)` times.
+    pub fn merge_plan(&self) -> Option<Range<usize>> {
+        let size = |s: &BM25Segment<D, T>| s.doc_count() + s.deleted_count();
+        let end = self.segments.len();
+        let mut start = end.checked_sub(1)?;
+        let mut absorbed = size(&self.segments[start]);
+        while start > 0 && size(&self.segments[start - 1]) <= absorbed {
+            start -= 1;
+            absorbed += size(&self.segments[start]);
+        }
+        (end - start >= 2).then_some(start..end)
+    }
+
+    /// Fold `range` into a single segment holding its live
+    /// documents. Superseded versions and dead tombstones are
+    /// dropped; tombstones survive only if older segments
+    /// remain below the range.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds.
+    pub fn compact(&self, range: Range<usize>) -> BM25Segment<D, T> {
+        let docs = self.live_docs_in(range.clone());
+        let deleted = if range.start == 0 {
+            Vec::new()
+        } else {
+            let live: HashSet<RawValue> = docs.iter().map(|(k, _)| *k).collect();
+            let mut deleted: Vec<RawValue> = self.segments[range]
+                .iter()
+                .flat_map(|s| s.deleted().map(|k| k.raw))
+                .filter(|k| !live.contains(k))
+                .collect();
+            deleted.sort_unstable();
+            deleted.dedup();
+            deleted
+        };
+        BM25Segment::assemble(docs, deleted)
+    }
+
+    /// Swap the segments in `range` for `segment`, typically
+    /// the output of [`Self::compact`] over the same range.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds.
+    pub fn replace(&mut self, range: Range<usize>, segment: BM25Segment<D, T>) {
+        self.segments.splice(range, std::iter::once(segment));
+        self.refresh();
+    }
+
+    /// Collapse every live document into one
+    /// [`SuccinctBM25Index`] with this stack's `k1` / `b`. The
+    /// result answers queries like the stack did, but can no
+    /// longer take deltas.
+    pub fn to_index(&self) -> SuccinctBM25Index<D, T> {
+        let mut builder = BM25Builder::<D, T>::new().k1(self.k1).b(self.b);
+        builder.docs = self.live_docs_in(0..self.segments.len());
+        builder.build()
+    }
+
+    /// Rebuild each live document in `range` as `(key, terms)`.
+    /// Term order isn't preserved — BM25 only needs counts.
+    fn live_docs_in(&self, range: Range<usize>) -> Vec<(RawValue, Vec<RawValue>)> {
+        let mut docs = Vec::new();
+        for (segment, live) in self.segments[range.clone()].iter().zip(&self.live[range]) {
+            let base = docs.len();
+            let mut slot = vec![usize::MAX; segment.doc_count()];
+            for doc in (0..segment.doc_count()).filter(|&d| live[d]) {
+                slot[doc] = docs.len();
+                docs.push((segment.keys.get(doc).copied().unwrap(), Vec::new()));
+            }
+            if docs.len() == base {
+                continue;
+            }
+            for t in 0..segment.term_count() {
+                let term = *segment.terms.get(t).unwrap();
+                for (doc, tf) in segment.postings(&term) {
+                    if live[doc] {
+                        let terms = &mut docs[slot[doc]].1;
+                        terms.extend(std::iter::repeat(term).take(tf as usize));
+                    }
+                }
+            }
+        }
+        docs
+    }
+
+    /// Recompute liveness and the global statistics.
+    fn refresh(&mut self) {
+        let mut shadowed: HashSet<RawValue> = HashSet::new();
+        let mut live = Vec::with_capacity(self.segments.len());
+        self.live_docs = 0;
+        self.live_len = 0;
+        for segment in self.segments.iter().rev() {
+            let flags: Vec<bool> = (0..segment.doc_count())
+                .map(|i| !shadowed.contains(segment.keys.get(i).unwrap()))
+                .collect();
+            for (i, _) in flags.iter().enumerate().filter(|(_, &l)| l) {
+                self.live_docs += 1;
+                self.live_len += segment.doc_len(i).unwrap() as u64;
+            }
+            shadowed.extend((0..segment.doc_count()).map(|i| *segment.keys.get(i).unwrap()));
+            shadowed.extend(segment.deleted().map(|k| k.raw));
+            live.push(flags);
+        }
+        live.reverse();
+        self.live = live;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::hash_tokens;
+    use triblespace_core::blob::ToBlob;
+
+    fn id(byte: u8) -> Id {
+        Id::new([byte; 16]).unwrap()
+    }
+
+    fn id_key(byte: u8) -> Value<GenId> {
+        (id(byte)).to_value()
+    }
+
+    fn assert_same_scores(segments: &BM25Segments, reference: &SuccinctBM25Index, query: &str) {
+        let terms = hash_tokens(query);
+        let got: HashMap<RawValue, f32> = segments
+            .query_multi(&terms)
+            .into_iter()
+            .map(|(k, s)| (k.raw, s))
+            .collect();
+        let want: HashMap<RawValue, f32> = reference
+            .query_multi(&terms)
+            .into_iter()
+            .map(|(k, s)| (k.raw, s))
+            .collect();
+        assert_eq!(got.len(), want.len(), "query {query:?}");
+        let tol = reference.score_tolerance() * terms.len() as f32 + 1e-5;
+        for (key, score) in &want {
+            let diff = (got[key] - score).abs();
+            assert!(diff <= tol, "query {query:?}: {} vs {score}", got[key]);
+        }
+    }
+
+    #[test]
+    fn segmented_scores_match_a_single_build() {
+        let corpus = [
+            (1, "the quick brown fox jumps"),
+            (2, "the lazy brown dog sleeps"),
+            (3, "quick silver fox"),
+            (4, "a dog and a fox and a dog"),
+            (5, "brown bread"),
+        ];
+        let mut segments = BM25Segments::new();
+        for chunk in corpus.chunks(2) {
+            let mut batch = BM25SegmentBuilder::new();
+            for &(k, text) in chunk {
+                batch.insert(id(k), hash_tokens(text));
+            }
+            segments.push(batch.build());
+        }
+        let mut single = BM25Builder::new();
+        for &(k, text) in &corpus {
+            single.insert(id(k), hash_tokens(text));
+        }
+        let reference = single.build();
+
+        assert_eq!(segments.segment_count(), 3);
+        assert_eq!(segments.doc_count(), reference.doc_count());
+        assert!((segments.avg_doc_len() - reference.avg_doc_len()).abs() < 1e-6);
+        for term in hash_tokens("fox dog brown") {
+            assert_eq!(segments.doc_frequency(&term), reference.doc_frequency(&term));
+        }
+        for query in ["fox", "brown dog", "quick fox sleeps", "absent"] {
+            assert_same_scores(&segments, &reference, query);
+        }
+    }
+
+    #[test]
+    fn newer_segments_supersede_and_delete() {
+        let mut old = BM25SegmentBuilder::new();
+        old.insert(id(1), hash_tokens("apples and pears"));
+        old.insert(id(2), hash_tokens("pears only"));
+        old.insert(id(3), hash_tokens("plums"));
+        let mut new = BM25SegmentBuilder::new();
+        new.insert(id(1), hash_tokens("cherries"));
+        new.delete(id(2));
+
+        let mut segments = BM25Segments::new();
+        segments.push(old.build());
+        segments.push(new.build());
+
+        assert_eq!(segments.doc_count(), 2);
+        assert!(segments.query_multi(&hash_tokens("pears")).is_empty());
+        let hits = segments.query_multi(&hash_tokens("cherries"));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, id_key(1));
+
+        let mut live = BM25Builder::new();
+        live.insert(id(1), hash_tokens("cherries"));
+        live.insert(id(3), hash_tokens("plums"));
+        let reference = live.build();
+        for query in ["cherries", "plums", "pears"] {
+            assert_same_scores(&segments, &reference, query);
+        }
+    }
+
+    #[test]
+    fn compaction_preserves_scores() {
+        let mut segments = BM25Segments::new();
+        for (round, text) in ["red green", "green blue", "blue red red", "violet"]
+            .into_iter()
+            .enumerate()
+        {
+            let mut batch = BM25SegmentBuilder::new();
+            batch.insert(id(round as u8 + 1), hash_tokens(text));
+            if round == 3 {
+                batch.delete(id(2));
+            }
+            segments.push(batch.build());
+        }
+        let before = segments.query_multi(&hash_tokens("red blue violet"));
+
+        // Compacting the top keeps the tombstone (segment 0
+        // still holds an older doc); compacting all drops it.
+        let top = segments.compact(2..4);
+        assert_eq!(top.deleted_count(), 1);
+        segments.replace(2..4, top);
+        assert_eq!(segments.segment_count(), 3);
+        let all = segments.compact(0..3);
+        assert_eq!(all.deleted_count(), 0);
+        assert_eq!(all.doc_count(), 3);
+        segments.replace(0..3, all);
+
+        let mut after = segments.query_multi(&hash_tokens("red blue violet"));
+        let mut before = before;
+        before.sort_by_key(|a| a.0.raw);
+        after.sort_by_key(|a| a.0.raw);
+        assert_eq!(before.len(), after.len());
+        for ((k0, s0), (k1, s1)) in before.iter().zip(&after) {
+            assert_eq!(k0, k1);
+            assert!((s0 - s1).abs() < 1e-5);
+        }
+
+        let index = segments.to_index();
+        assert_eq!(index.doc_count(), 3);
+        assert_same_scores(&segments, &index, "red blue violet");
+    }
+
+    #[test]
+    fn merge_plan_keeps_sizes_geometric() {
+        let mut segments: BM25Segments = BM25Segments::new();
+        assert_eq!(segments.merge_plan(), None);
+        for i in 0..16u8 {
+            let mut batch = BM25SegmentBuilder::new();
+            batch.insert(id(i + 1), hash_tokens("word"));
+            segments.push(batch.build());
+            while let Some(range) = segments.merge_plan() {
+                let merged = segments.compact(range.clone());
+                segments.replace(range, merged);
+            }
+        }
+        assert_eq!(segments.segment_count(), 1);
+        assert_eq!(segments.doc_count(), 16);
+
+        let mut batch = BM25SegmentBuilder::new();
+        batch.insert(id(100), hash_tokens("word"));
+        segments.push(batch.build());
+        assert_eq!(segments.merge_plan(), None);
+    }
+
+    #[test]
+    fn matches_filters_live_documents() {
+        use triblespace_core::find;
+
+        let mut old = BM25SegmentBuilder::new();
+        old.insert(id(1), hash_tokens("graph search"));
+        old.insert(id(2), hash_tokens("graph theory"));
+        let mut new = BM25SegmentBuilder::new();
+        new.delete(id(2));
+        new.insert(id(3), hash_tokens("search engines"));
+        let mut segments = BM25Segments::new();
+        segments.push(old.build());
+        segments.push(new.build());
+
+        let terms = hash_tokens("graph search");
+        let mut docs: Vec<Id> = find!((doc: Id), segments.matches(doc, &terms, 0.0))
+            .map(|(d,)| d)
+            .collect();
+        docs.sort();
+        assert_eq!(docs, vec![id(1), id(3)]);
+        assert!(segments.score(&id_key(1), &terms) > segments.score(&id_key(3), &terms));
+        assert_eq!(segments.score(&id_key(2), &terms), 0.0);
+    }
+
+    #[test]
+    fn segment_blob_round_trip() {
+        let mut batch = BM25SegmentBuilder::<GenId, crate::tokens::WordHash>::new();
+        batch.insert(id(1), hash_tokens("one two two"));
+        batch.insert(id(2), hash_tokens("three"));
+        batch.delete(id(9));
+        let segment = batch.build();
+
+        let blob: Blob<BM25SegmentBlob> = (&segment).to_blob();
+        let loaded: BM25Segment = BM25Segment::try_from_blob(blob).unwrap();
+        assert_eq!(loaded.doc_count(), 2);
+        assert_eq!(loaded.term_count(), 3);
+        assert!(loaded.is_deleted(&id_key(9)));
+        assert!(loaded.contains(&id_key(1)));
+        assert_eq!(loaded.doc_len(0), segment.doc_len(0));
+        assert_eq!(loaded.to_bytes(), segment.to_bytes());
+
+        assert_eq!(
+            BM25Segment::<GenId>::try_from_bytes(&[0u8; 10]).unwrap_err(),
+            SuccinctLoadError::ShortHeader
+        );
+    }
+
+    #[test]
+    fn segment_with_bad_postings_is_rejected() {
+        let build = || {
+            let mut batch = BM25SegmentBuilder::<GenId, crate::tokens::WordHash>::new();
+            batch.insert(id(1), hash_tokens("one two"));
+            batch.insert(id(2), hash_tokens("two"));
+            batch.build()
+        };
+        let load = |values: &[u32]| {
+            let (bytes, meta) = pack(values);
+            CompactVector::from_bytes(meta, bytes).unwrap()
+        };
+        let reload = |segment: &BM25Segment| {
+            BM25Segment::<GenId>::try_from_bytes(&segment.to_bytes()).unwrap_err()
+        };
+
+        // Postings naming a doc past the end of the segment.
+        let mut bad = build();
+        bad.posting_docs = load(&[0, 1, 7]);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("posting_docs"));
+
+        // Offsets running backwards, and past the postings.
+        let mut bad = build();
+        bad.posting_offsets = load(&[0, 2, 1]);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("posting_offsets"));
+        bad.posting_offsets = load(&[0, 1, 4]);
+        assert_eq!(reload(&bad), SuccinctLoadError::BadMeta("posting_offsets"));
+    }
+}
@@ -54,7 +54,9 @@ use crate::schemas::{EmbHandle, Embedding};
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use std::collections::HashMap;
-use crate::hnsw::HNSWIndex;
+use crate::hnsw::{HNSWIndex, Metric, ScoredHandle};
+use crate::constraint::HandleFilter;
+use crate::quantize::{Quantization, QuantizedQuery, Quantizer};
 
 /// Byte-layout mirror of [`CompactVectorMeta`] that's safe to
 /// serialize through our own blob format.
@@ -67,7 +69,7 @@ use crate::hnsw::HNSWIndex;
 /// with four `u64`-sized fields on 64-bit platforms).
 #[derive(Debug, Clone, Copy, FromBytes, IntoBytes, KnownLayout, Immutable)]
 #[repr(C)]
-struct CompactVectorMetaOnDisk {
+pub(crate) struct CompactVectorMetaOnDisk {
     len: u64,
     width: u64,
     handle_offset: u64,
@@ -169,7 +171,7 @@ impl CompactVectorMetaOnDisk {
     /// with a struct literal — but its layout is identical to
     /// `(usize, usize)` and it's `#[repr(C)]` with a known layout,
     /// so we transmute through a matching byte layout.
-    fn to_jerky(self) -> CompactVectorMeta {
+    pub(crate) fn to_jerky(self) -> CompactVectorMeta {
         // Build a 32-byte scratch in the on-disk layout, then
         // `read_from_bytes` it into `CompactVectorMeta` — sound
         // because both are `repr(C)`, both are 32 bytes, and
@@ -835,6 +837,7 @@ pub struct SuccinctHNSWIndex {
     dim: usize,
     m: u16,
     m0: u16,
+    metric: Metric,
     max_level: u8,
     entry_point: Option<u32>,
     /// Content-addressed pointer to each node's [`Embedding`]
@@ -845,6 +848,11 @@ pub struct SuccinctHNSWIndex {
     /// [g]: triblespace_core::repo::BlobStoreGet
     handles: FixedBytesTable<32>,
     graph: SuccinctGraph,
+    /// Trained quantizer, if the index carries per-node codes.
+    quantizer: Option<Quantizer>,
+    /// `n_nodes × code_len` bytes, node-major; empty when
+    /// `quantizer` is `None`.
+    codes: Bytes,
 }
 
 impl std::fmt::Debug for SuccinctHNSWIndex {
@@ -852,6 +860,8 @@ impl std::fmt::Debug for SuccinctHNSWIndex {
         f.debug_struct("SuccinctHNSWIndex")
             .field("n_nodes", &self.handles.len())
             .field("dim", &self.dim)
+            .field("metric", &self.metric)
+            .field("quantization", &self.quantizer.as_ref().map(|q| q.kind()))
             .field("max_level", &self.max_level)
             .finish()
     }
@@ -892,13 +902,25 @@ impl SuccinctHNSWIndex {
             dim,
             m: idx.m(),
             m0: idx.m0(),
+            metric: idx.metric(),
             max_level,
             entry_point: idx.entry_point(),
             handles,
             graph,
+            quantizer: None,
+            codes: Bytes::empty(),
         })
     }
 
+    /// Attach per-node quantized codes (`doc_count × code_len`
+    /// bytes, node order) produced by `quantizer`.
+    pub(crate) fn with_codes(mut self, quantizer: Quantizer, codes: Vec<u8>) -> Self {
+        debug_assert_eq!(codes.len(), self.doc_count() * quantizer.code_len());
+        self.quantizer = Some(quantizer);
+        self.codes = Bytes::from_source(codes);
+        self
+    }
+
     /// Vector dimensionality.
     pub fn dim(&self) -> usize {
         self.dim
@@ -915,11 +937,42 @@ impl SuccinctHNSWIndex {
     pub fn m0(&self) -> u16 {
         self.m0
     }
+    /// Distance metric the graph was built and is queried under.
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+    /// Quantizer behind the per-node codes, or `None` if the
+    /// walk runs on full-precision embeddings.
+    pub fn quantizer(&self) -> Option<&Quantizer> {
+        self.quantizer.as_ref()
+    }
+    /// Quantized code of node `i`, or `None` if out of range or
+    /// the index isn't quantized.
+    pub fn code(&self, i: usize) -> Option<&[u8]> {
+        let len = self.quantizer.as_ref()?.code_len();
+        self.codes.get(i * len..(i + 1) * len)
+    }
     /// Highest layer any node was promoted to.
     pub fn max_level(&self) -> u8 {
         self.max_level
     }
 
+    /// Node the search starts from, or `None` if empty.
+    pub fn entry_point(&self) -> Option<u32> {
+        self.entry_point
+    }
+
+    /// Embedding handle of node `i`, or `None` if out of range.
+    pub fn handle(&self, i: usize) -> Option<Value<EmbHandle>> {
+        self.handles.get(i).map(|raw| Value::new(*raw))
+    }
+
+    /// Neighbours of node `i` on `layer`. Empty for nodes below
+    /// the layer.
+    pub fn node_neighbours(&self, i: usize, layer: u8) -> Vec<u32> {
+        self.graph.neighbours(i, layer as usize).collect()
+    }
+
     /// Attach a blob store to this index, returning a queryable
     /// view. Paired with the typical load flow:
     ///
@@ -951,45 +1004,44 @@ impl SuccinctHNSWIndex {
     /// Serialize to a self-contained blob. Layout:
     ///
     /// ```text
-    /// [header 152 B]
-    ///   magic u32     = "SH25"
-    ///   version u16   (FORMAT_VERSION)
-    ///   reserved u16
+    /// [header 168 B]
     ///   dim u32                  ; embedding dimensionality
     ///   M u16, M0 u16            ; HNSW degree caps
     ///   max_level u8
-    ///   reserved u8
+    ///   metric u8                ; 0 cosine, 1 dot, 2 L2
     ///   has_entry_point u8
-    ///   reserved u8
+    ///   quantization u8          ; 0 none, 1 int8, 2 binary, 3 product
     ///   entry_point u32
     ///   n_nodes u64
     ///   n_layers u64
     ///   graph_neighbours_meta  32 B   ; CompactVectorMetaOnDisk
     ///   graph_offsets_meta     32 B   ; CompactVectorMetaOnDisk
-    ///   (section_offset, section_len) × 3 = 48 B
+    ///   pq_subspaces u16, pq_centroids u16, reserved u32
+    ///   (section_offset, section_len) × 4 = 64 B
     ///
-    /// [keys    ] n_nodes × 32 B    ; FixedBytesTable<32> raw;
-    ///                                each entry is the doc's
-    ///                                `RawValue` identifier
-    ///                                (GenId / ShortString / …).
-    /// [handles ] n_nodes × 32 B    ; Value<Handle<Blake3, Embedding>>;
+    /// [handles  ] n_nodes × 32 B   ; Value<Handle<Blake3, Embedding>>;
     ///                                content-addressed pointer
-    ///                                to the doc's embedding blob
+    ///                                to the node's embedding blob
     ///                                in the pile's blob store.
-    /// [graph   ] variable          ; SuccinctGraph body = two
+    /// [graph    ] variable         ; SuccinctGraph body = two
     ///                                jerky CompactVectors in one
     ///                                ByteArea:
     ///                                  neighbours (width log₂(n+1))
     ///                                  offsets    (width log₂(E+1))
+    /// [quantizer] params × f32 LE  ; fitted quantizer parameters
+    ///                                (empty when not quantized)
+    /// [codes    ] n_nodes × code_len B ; per-node quantized codes
     /// ```
     ///
-    /// The header carries scalar HNSW parameters, the graph's
-    /// two `CompactVectorMeta` structures, and `(offset, length)`
-    /// pairs for each body section.
+    /// The header carries scalar HNSW parameters, the metric and
+    /// quantization scheme, the graph's two `CompactVectorMeta`
+    /// structures, and `(offset, length)` pairs for each body
+    /// section.
     ///
-    /// Embeddings are **not** in this blob — they live in the
-    /// pile's blob store, referenced by handle. Queries resolve
-    /// handles at traversal time through
+    /// Full-precision embeddings are **not** in this blob — they
+    /// live in the pile's blob store, referenced by handle.
+    /// Queries resolve handles at traversal time (or only for
+    /// reranking, when quantized) through
     /// [`AttachedSuccinctHNSWIndex`]'s internal `BlobCache`.
     pub fn to_bytes(&self) -> Vec<u8> {
         let n_nodes = self.doc_count() as u64;
@@ -1022,39 +1074,68 @@ impl SuccinctHNSWIndex {
         let graph_neighbours_meta: CompactVectorMetaOnDisk = graph_meta.neighbours.into();
         let graph_offsets_meta: CompactVectorMetaOnDisk = graph_meta.offsets.into();
 
+        let quantizer_flat: Vec<u8> = self
+            .quantizer
+            .iter()
+            .flat_map(|q| q.params())
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let kind = self.quantizer.as_ref().map(|q| q.kind());
+        let quant_code = kind.map_or(0, |k| k.code());
+        let (pq_subspaces, pq_centroids) = match kind {
+            Some(Quantization::Product {
+                subspaces,
+                centroids,
+            }) => (subspaces, centroids),
+            _ => (0u16, 0u16),
+        };
+
         // Section offsets inside the body (relative to end of
         // header).
         let handles_off = 0u64;
         let handles_len = handles_flat.len() as u64;
         let graph_off = handles_off + handles_len;
         let graph_len = graph_region.len() as u64;
+        let quantizer_off = graph_off + graph_len;
+        let quantizer_len = quantizer_flat.len() as u64;
+        let codes_off = quantizer_off + quantizer_len;
+        let codes_len = self.codes.len() as u64;
 
-        let body_len = graph_off + graph_len;
+        let body_len = codes_off + codes_len;
         let mut buf = Vec::with_capacity(SH25_HEADER_LEN + body_len as usize);
 
-        // ── header (128 B) ────────────────────────────────────
+        // ── header (168 B) ────────────────────────────────────
         buf.extend_from_slice(&(self.dim as u32).to_le_bytes()); // 4
         buf.extend_from_slice(&self.m.to_le_bytes()); // 2
         buf.extend_from_slice(&self.m0.to_le_bytes()); // 2
         buf.push(self.max_level); // 1
-        buf.push(0u8); // reserved, 1
+        buf.push(self.metric.code()); // 1
         buf.push(self.entry_point.is_some() as u8); // 1
-        buf.push(0u8); // reserved, 1
+        buf.push(quant_code); // 1
         let ep = self.entry_point.unwrap_or(u32::MAX);
         buf.extend_from_slice(&ep.to_le_bytes()); // 4
         buf.extend_from_slice(&n_nodes.to_le_bytes()); // 8
         buf.extend_from_slice(&n_layers.to_le_bytes()); // 8
         buf.extend_from_slice(graph_neighbours_meta.as_bytes()); // 32
         buf.extend_from_slice(graph_offsets_meta.as_bytes()); // 32
+        buf.extend_from_slice(&pq_subspaces.to_le_bytes()); // 2
+        buf.extend_from_slice(&pq_centroids.to_le_bytes()); // 2
+        buf.extend_from_slice(&0u32.to_le_bytes()); // reserved, 4
         buf.extend_from_slice(&handles_off.to_le_bytes()); // 8
         buf.extend_from_slice(&handles_len.to_le_bytes()); // 8
         buf.extend_from_slice(&graph_off.to_le_bytes()); // 8
         buf.extend_from_slice(&graph_len.to_le_bytes()); // 8
+        buf.extend_from_slice(&quantizer_off.to_le_bytes()); // 8
+        buf.extend_from_slice(&quantizer_len.to_le_bytes()); // 8
+        buf.extend_from_slice(&codes_off.to_le_bytes()); // 8
+        buf.extend_from_slice(&codes_len.to_le_bytes()); // 8
         debug_assert_eq!(buf.len(), SH25_HEADER_LEN);
 
         // ── body ──────────────────────────────────────────────
         buf.extend_from_slice(&handles_flat);
         buf.extend_from_slice(&graph_region);
+        buf.extend_from_slice(&quantizer_flat);
+        buf.extend_from_slice(&self.codes);
         buf
     }
 
@@ -1067,6 +1148,7 @@ impl SuccinctHNSWIndex {
         let m = u16::from_le_bytes(bytes[4..6].try_into().unwrap());
         let m0 = u16::from_le_bytes(bytes[6..8].try_into().unwrap());
         let max_level = bytes[8];
+        let metric = Metric::from_code(bytes[9]).ok_or(SuccinctLoadError::BadMeta("metric"))?;
         let has_ep = bytes[10] != 0;
         let ep_raw = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
         let entry_point = if has_ep { Some(ep_raw) } else { None };
@@ -1079,26 +1161,34 @@ impl SuccinctHNSWIndex {
         let graph_offsets_meta = CompactVectorMetaOnDisk::read_from_bytes(&bytes[64..96])
             .map_err(|_| SuccinctLoadError::BadMeta("graph.offsets"))?
             .to_jerky();
+        let pq_subspaces = u16::from_le_bytes(bytes[96..98].try_into().unwrap());
+        let pq_centroids = u16::from_le_bytes(bytes[98..100].try_into().unwrap());
+        let quantization = Quantization::from_code(bytes[11], pq_subspaces, pq_centroids)
+            .ok_or(SuccinctLoadError::BadMeta("quantization"))?;
 
         let read_u64 = |off: usize| u64::from_le_bytes(bytes[off..off + 8].try_into().unwrap());
-        let handles_off = read_u64(96) as usize;
-        let handles_len = read_u64(104) as usize;
-        let graph_off = read_u64(112) as usize;
-        let graph_len = read_u64(120) as usize;
-        debug_assert_eq!(SH25_HEADER_LEN, 128);
-        let _ = dim; // dim stays header-only; no inline vectors.
+        let handles_off = read_u64(104) as usize;
+        let handles_len = read_u64(112) as usize;
+        let graph_off = read_u64(120) as usize;
+        let graph_len = read_u64(128) as usize;
+        let quantizer_off = read_u64(136) as usize;
+        let quantizer_len = read_u64(144) as usize;
+        let codes_off = read_u64(152) as usize;
+        let codes_len = read_u64(160) as usize;
+        debug_assert_eq!(SH25_HEADER_LEN, 168);
 
         let body_start = SH25_HEADER_LEN;
         let body = &bytes[body_start..];
-        let check = |end: usize, name: &'static str| -> Result<(), SuccinctLoadError> {
-            if end > body.len() {
-                Err(SuccinctLoadError::TruncatedSection(name))
-            } else {
-                Ok(())
+        let check = |off: usize, len: usize, name: &'static str| -> Result<(), SuccinctLoadError> {
+            match off.checked_add(len) {
+                Some(end) if end <= body.len() => Ok(()),
+                _ => Err(SuccinctLoadError::TruncatedSection(name)),
             }
         };
-        check(handles_off + handles_len, "handles")?;
-        check(graph_off + graph_len, "graph")?;
+        check(handles_off, handles_len, "handles")?;
+        check(graph_off, graph_len, "graph")?;
+        check(quantizer_off, quantizer_len, "quantizer")?;
+        check(codes_off, codes_len, "codes")?;
 
         let body_bytes = Bytes::from_source(body.to_vec());
         let handles_bytes = body_bytes.slice(handles_off..handles_off + handles_len);
@@ -1116,17 +1206,45 @@ impl SuccinctHNSWIndex {
         let graph = SuccinctGraph::from_bytes(graph_meta, graph_bytes)
             .map_err(|_| SuccinctLoadError::TruncatedSection("graph"))?;
 
+        let quantizer = match quantization {
+            None => None,
+            Some(kind) => {
+                let params: Vec<f32> = body[quantizer_off..quantizer_off + quantizer_len]
+                    .chunks_exact(4)
+                    .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+                    .collect();
+                let q = Quantizer::from_params(kind, dim, params)
+                    .ok_or(SuccinctLoadError::BadMeta("quantizer"))?;
+                Some(q)
+            }
+        };
+        let code_len = quantizer.as_ref().map_or(0, |q| q.code_len());
+        if n_nodes.checked_mul(code_len) != Some(codes_len) {
+            return Err(SuccinctLoadError::TruncatedSection("codes"));
+        }
+        let codes = body_bytes.slice(codes_off..codes_off + codes_len);
+        // Product codes index the per-subspace centroid tables; an
+        // out-of-range byte would read another subspace's entry or
+        // run off the end of the last one.
+        if let Some(Quantization::Product { centroids, .. }) = quantizer.as_ref().map(|q| q.kind()) {
+            if codes.iter().any(|&c| u16::from(c) >= centroids) {
+                return Err(SuccinctLoadError::BadMeta("codes"));
+            }
+        }
+
         Ok(Self {
             dim,
             m,
             m0,
+            metric,
             max_level,
             entry_point,
             handles,
             graph,
+            quantizer,
+            codes,
         })
     }
-
 }
 
 /// A [`SuccinctHNSWIndex`] paired with the blob store its
@@ -1169,7 +1287,8 @@ where
     }
 
     /// Build a symmetric similarity constraint over two handle
-    /// variables, gated by a fixed cosine `score_floor`. See
+    /// variables, gated by a fixed `score_floor` (cosine under
+    /// the default metric; see [`Metric::score`]). See
     /// [`crate::constraint::Similar`] for semantics and the full
     /// `find!` / `pattern!` integration.
     pub fn similar(
@@ -1202,11 +1321,16 @@ where
     }
 
     /// Walk the graph from `from_handle`'s embedding and return
-    /// every handle whose cosine similarity is at least
+    /// every handle whose [`Metric::score`] is at least
     /// `score_floor`. The core primitive the similarity
     /// constraint calls; exposed for tests and for callers who
     /// want the walk without the constraint wrapper.
     ///
+    /// On a quantized index the walk scores nodes from their
+    /// codes alone; only the final beam is fetched and reranked
+    /// against the full-precision embeddings, so the floor is
+    /// always applied to exact scores.
+    ///
     /// Bound by the view's `ef_search` (default 200) — callers
     /// pushing lots of above-threshold results need a wider
     /// beam via [`with_ef_search`][Self::with_ef_search].
@@ -1215,6 +1339,129 @@ where
         from_handle: Value<EmbHandle>,
         score_floor: f32,
     ) -> Result<Vec<Value<EmbHandle>>, B::GetError<anybytes::view::ViewError>> {
+        let hits = self.walk(from_handle, score_floor, None, self.ef_search)?;
+        Ok(hits.into_iter().map(|(h, _)| h).collect())
+    }
+
+    /// Ranked retrieval: the `k` handles most similar to
+    /// `from_handle`'s embedding, best first, each paired with its
+    /// exact [`Metric::score`]. The beam is widened to `k` when
+    /// `k > ef_search`. This is the vector-side input to
+    /// [`crate::fusion::Fusion`].
+    pub fn nearest(
+        &self,
+        from_handle: Value<EmbHandle>,
+        k: usize,
+    ) -> Result<Vec<ScoredHandle>, B::GetError<anybytes::view::ViewError>> {
+        let mut hits = self.walk(from_handle, f32::NEG_INFINITY, None, self.ef_search.max(k))?;
+        hits.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(k);
+        Ok(hits)
+    }
+
+    /// [`candidates_above`][Self::candidates_above] restricted to
+    /// handles `filter` allows.
+    ///
+    /// The walk still routes through disallowed nodes but only
+    /// admits allowed ones into the beam, so it keeps expanding
+    /// until `ef_search` allowed candidates are found. When the
+    /// filter is so selective that this would visit more nodes than
+    /// there are allowed ones — `allowed² ≤ ef_search × n`, the
+    /// walk needing roughly `ef_search × n / allowed` visits —
+    /// the allowed nodes are scored exhaustively instead.
+    ///
+    /// The choice comes from the filter's
+    /// [`size_hint`][HandleFilter::size_hint] when it has one.
+    /// Otherwise allowed nodes are counted only until the walk is
+    /// known to win, so a permissive filter isn't evaluated on
+    /// every node up front.
+    pub fn candidates_above_filtered(
+        &self,
+        from_handle: Value<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> Result<Vec<Value<EmbHandle>>, B::GetError<anybytes::view::ViewError>> {
+        let n = self.index.doc_count();
+        let budget = self.ef_search.saturating_mul(n);
+        let walkable = |count: usize| count.saturating_mul(count) > budget;
+        let hinted = filter.size_hint().map(|hint| hint.min(n));
+        if hinted == Some(0) {
+            return Ok(Vec::new());
+        }
+        let mut allowed = Vec::new();
+        if !hinted.is_some_and(walkable) {
+            for i in 0..n {
+                if filter.allows(&self.index.handle(i).expect("in range")) {
+                    allowed.push(i);
+                    if walkable(allowed.len()) {
+                        break;
+                    }
+                }
+            }
+        }
+        if hinted.is_some_and(walkable) || walkable(allowed.len()) {
+            let hits = self.walk(from_handle, score_floor, Some(filter), self.ef_search)?;
+            return Ok(hits.into_iter().map(|(h, _)| h).collect());
+        }
+        if allowed.is_empty() {
+            return Ok(Vec::new());
+        }
+        let from = self.cache.get(from_handle)?;
+        let query: &[f32] = from.as_ref().as_ref();
+        if query.len() != self.index.dim {
+            return Ok(Vec::new());
+        }
+        let metric = self.index.metric;
+        let mut out = Vec::new();
+        for i in allowed {
+            if metric.score(self.exact_dist(query, i as u32)?) >= score_floor {
+                out.push(self.index.handle(i).expect("in range"));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Filtered counterpart of [`similar`][Self::similar]:
+    /// additionally requires `filter.allows(b)`, consulted during
+    /// the walk. See [`crate::constraint::Similar`].
+    pub fn similar_where<'f>(
+        &'f self,
+        a: Variable<EmbHandle>,
+        b: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &'f (dyn HandleFilter + Sync),
+    ) -> crate::constraint::Similar<'f, Self> {
+        crate::constraint::Similar::new(self, a, b, score_floor).with_filter(filter)
+    }
+
+    /// Filtered counterpart of [`similar_to`][Self::similar_to],
+    /// walking via
+    /// [`candidates_above_filtered`][Self::candidates_above_filtered].
+    pub fn similar_to_where(
+        &self,
+        probe: Value<EmbHandle>,
+        var: Variable<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> crate::constraint::SimilarTo {
+        let candidates = self
+            .candidates_above_filtered(probe, score_floor, filter)
+            .map(|v| v.into_iter().map(|h| h.raw).collect())
+            .unwrap_or_default();
+        crate::constraint::SimilarTo::from_candidates(var, candidates)
+    }
+
+    /// Graph walk shared by the plain, filtered and ranked
+    /// searches; `allowed` (per node) gates which nodes enter the
+    /// layer-0 beam of width `ef`. Returns each surviving handle
+    /// with its exact score.
+    fn walk(
+        &self,
+        from_handle: Value<EmbHandle>,
+        score_floor: f32,
+        filter: Option<&dyn HandleFilter>,
+        ef: usize,
+    ) -> Result<Vec<ScoredHandle>, B::GetError<anybytes::view::ViewError>> {
         let Some(entry) = self.index.entry_point else {
             return Ok(Vec::new());
         };
@@ -1223,22 +1470,47 @@ where
         if query.len() != self.index.dim {
             return Ok(Vec::new());
         }
+        let metric = self.index.metric;
+        let quantized = self.index.quantizer.as_ref().map(|qz| qz.query(metric, &query));
+        let probe = Probe {
+            vector: query,
+            quantized,
+        };
         let mut curr = entry;
         for lvl in (1..=self.index.max_level).rev() {
-            curr = self.greedy_search_layer(&query, curr, lvl)?;
+            curr = self.greedy_search_layer(&probe, curr, lvl)?;
         }
-        let candidates = self.search_layer(&query, curr, self.ef_search, 0)?;
-        Ok(candidates
-            .into_iter()
-            .filter(|(_, dist)| 1.0 - dist >= score_floor)
-            .map(|(i, _)| {
+        let candidates = self.search_layer(&probe, curr, ef, 0, filter)?;
+        let mut out = Vec::new();
+        for (i, dist) in candidates {
+            let dist = if probe.quantized.is_some() {
+                self.exact_dist(&probe.vector, i)?
+            } else {
+                dist
+            };
+            let score = metric.score(dist);
+            if score >= score_floor {
                 let raw = *self.index.handles.get(i as usize).expect("in range");
-                Value::new(raw)
-            })
-            .collect())
+                out.push((Value::new(raw), score));
+            }
+        }
+        Ok(out)
     }
 
+    /// Walk-time distance: from the node's code when the index
+    /// is quantized, from its full-precision embedding otherwise.
     fn dist_to(
+        &self,
+        q: &Probe<'_>,
+        i: u32,
+    ) -> Result<f32, B::GetError<anybytes::view::ViewError>> {
+        match &q.quantized {
+            Some(quantized) => Ok(quantized.distance(self.index.code(i as usize).expect("in range"))),
+            None => self.exact_dist(&q.vector, i),
+        }
+    }
+
+    fn exact_dist(
         &self,
         q: &[f32],
         i: u32,
@@ -1246,12 +1518,12 @@ where
         let raw = *self.index.handles.get(i as usize).expect("in range");
         let handle: Value<EmbHandle> = Value::new(raw);
         let view = self.cache.get(handle)?;
-        Ok(crate::hnsw::cosine_dist(q, view.as_ref().as_ref()))
+        Ok(self.index.metric.distance(q, view.as_ref().as_ref()))
     }
 
     fn greedy_search_layer(
         &self,
-        q: &[f32],
+        q: &Probe<'_>,
         entry: u32,
         layer: u8,
     ) -> Result<u32, B::GetError<anybytes::view::ViewError>> {
@@ -1283,12 +1555,20 @@ where
 
     fn search_layer(
         &self,
-        q: &[f32],
+        q: &Probe<'_>,
         entry: u32,
         ef: usize,
         layer: u8,
+        filter: Option<&dyn HandleFilter>,
     ) -> Result<Vec<(u32, f32)>, B::GetError<anybytes::view::ViewError>> {
         use std::collections::{BinaryHeap, HashSet};
+        // Disallowed nodes still route the walk (they enter
+        // `candidates`) but never take a beam slot in `results`.
+        let admits = |i: u32| {
+            filter.map_or(true, |f| {
+                f.allows(&self.index.handle(i as usize).expect("in range"))
+            })
+        };
         let mut visited: HashSet<u32> = HashSet::new();
         visited.insert(entry);
         let d0 = self.dist_to(q, entry)?;
@@ -1346,10 +1626,12 @@ where
             dist: d0,
         });
         let mut results: BinaryHeap<MaxD> = BinaryHeap::new();
-        results.push(MaxD {
-            idx: entry,
-            dist: d0,
-        });
+        if admits(entry) {
+            results.push(MaxD {
+                idx: entry,
+                dist: d0,
+            });
+        }
         while let Some(c) = candidates.pop() {
             let farthest = results.peek().map(|r| r.dist).unwrap_or(f32::INFINITY);
             if c.dist > farthest && results.len() >= ef {
@@ -1368,9 +1650,11 @@ where
                 let farthest = results.peek().map(|r| r.dist).unwrap_or(f32::INFINITY);
                 if d < farthest || results.len() < ef {
                     candidates.push(MinD { idx: n, dist: d });
-                    results.push(MaxD { idx: n, dist: d });
-                    if results.len() > ef {
-                        results.pop();
+                    if admits(n) {
+                        results.push(MaxD { idx: n, dist: d });
+                        if results.len() > ef {
+                            results.pop();
+                        }
                     }
                 }
             }
@@ -1391,7 +1675,7 @@ where
         self.candidates_above(from, score_floor).unwrap_or_default()
     }
 
-    fn cosine_between(
+    fn score_between(
         &self,
         a: Value<EmbHandle>,
         b: Value<EmbHandle>,
@@ -1403,12 +1687,27 @@ where
         if a_slice.len() != b_slice.len() {
             return None;
         }
-        let mut sum = 0.0f32;
-        for (x, y) in a_slice.iter().zip(b_slice.iter()) {
-            sum += x * y;
-        }
-        Some(sum)
+        let metric = self.index.metric;
+        Some(metric.score(metric.distance(a_slice, b_slice)))
     }
+
+    fn neighbours_above_filtered(
+        &self,
+        from: Value<EmbHandle>,
+        score_floor: f32,
+        filter: &dyn HandleFilter,
+    ) -> Vec<Value<EmbHandle>> {
+        self.candidates_above_filtered(from, score_floor, filter)
+            .unwrap_or_default()
+    }
+}
+
+/// A query vector on its way through an
+/// [`AttachedSuccinctHNSWIndex`] walk, plus its prepared form
+/// when the index scores nodes from quantized codes.
+struct Probe<'q> {
+    vector: Vec<f32>,
+    quantized: Option<QuantizedQuery<'q>>,
 }
 
 /// Zero-copy, jerky-backed BM25 index.
@@ -1985,29 +2284,34 @@ impl<D: ValueSchema, T: ValueSchema> TryFromBlob<SuccinctBM25Blob> for SuccinctB
 
 /// Header length in bytes for a `SuccinctHNSWIndex` blob.
 ///
-/// Layout: 4 dim + 2 m + 2 m0 + 1 max_level + 1 reserved +
-/// 1 has_entry + 1 reserved + 4 entry_point + 8 n_nodes +
-/// 8 n_layers + 2 × 32 CompactVectorMeta + 4 × 8 section
-/// (offset, len) = 128.
+/// Layout: 4 dim + 2 m + 2 m0 + 1 max_level + 1 metric +
+/// 1 has_entry + 1 quantization + 4 entry_point + 8 n_nodes +
+/// 8 n_layers + 2 × 32 CompactVectorMeta + 2 pq_subspaces +
+/// 2 pq_centroids + 4 reserved + 8 × 8 section (offset, len)
+/// = 168.
 ///
 /// No magic, no version — schema identity is carried by the
 /// typed `BlobSchema` handle. Breaking format changes mint a
 /// new schema id.
-const SH25_HEADER_LEN: usize = 128;
+const SH25_HEADER_LEN: usize = 168;
 
 /// Content-addressed [`BlobSchema`] marker for the succinct
-/// HNSW blob format — 128 B header + handles + jerky-packed
-/// graph. Embeddings themselves live as separate blobs in the
-/// pile, referenced by handle.
+/// HNSW blob format — 168 B header + handles + jerky-packed
+/// graph + optional quantizer and per-node codes. Embeddings
+/// themselves live as separate blobs in the pile, referenced
+/// by handle.
 ///
 /// Schema id minted via `trible genid`:
-/// `A96890DE5F85A4F2285C365549B21BC2`. Any breaking format
+/// `A1623E83D791410FA3D631873568D9CA`. Any breaking format
 /// change mints a new id (i.e. a new type), so the compiler
 /// rules out mismatched-layout deserialization.
 ///
 /// Retired IDs (bytes in the wild under these tags have the
 /// old layouts and can't be loaded by the current code):
 ///
+/// - `A96890DE5F85A4F2285C365549B21BC2` — 128 B header,
+///   cosine only, no quantized codes. The metric and
+///   quantization scheme now live in the header.
 /// - `27D71A473EF22DA4D916F61810AC5D86` — carried a keys
 ///   section alongside handles (schema-tagged doc keys). The
 ///   keys table was redundant with the caller's own
@@ -2017,7 +2321,7 @@ const SH25_HEADER_LEN: usize = 128;
 pub enum SuccinctHNSWBlob {}
 
 impl ConstId for SuccinctHNSWBlob {
-    const ID: Id = id_hex!("A96890DE5F85A4F2285C365549B21BC2");
+    const ID: Id = id_hex!("A1623E83D791410FA3D631873568D9CA");
 }
 
 impl BlobSchema for SuccinctHNSWBlob {}
@@ -18,12 +18,23 @@
 //!   (same output space — both lowercase + Blake3).
 //! - [`bigram_tokens`] → [`Value<BigramHash>`].
 //! - [`ngram_tokens`] → [`Value<NgramHash>`].
+//! - [`english_tokens`] / [`german_tokens`] → [`Value<EnglishStemHash>`]
+//!   / [`Value<GermanStemHash>`] — Unicode-normalized, stopword-filtered
+//!   and stemmed via the composable [`Analyzer`] pipeline
+//!   ([`Normalizer`] → [`Tokenizer`] → [`TokenFilter`]s → hash).
+//!   Custom analyzers declare their own schema.
+//!
+//! For highlighting, [`hash_token_spans`] and
+//! [`Analyzer::analyze_spans`] produce the same terms paired with
+//! their byte ranges in the original text (see
+//! [`crate::highlight`]).
 //!
 //! An index that needs multiple tokenizer flavors becomes
 //! multiple indexes, one per schema, joined via `and!` / `or!`
 //! at query time — see `examples/phrase_search.rs`.
 
 use std::convert::Infallible;
+use std::ops::Range;
 
 use triblespace_core::id::Id;
 use triblespace_core::id_hex;
@@ -111,6 +122,26 @@ pub fn hash_tokens(text: &str) -> Vec<Value<WordHash>> {
         .collect()
 }
 
+/// [`hash_tokens`] with the byte range of `text` behind each
+/// term, for highlighting matches in the original string. The
+/// terms are exactly those of `hash_tokens(text)`.
+///
+/// ```
+/// # use triblespace_search::tokens::{hash_token_spans, hash_tokens};
+/// let text = "Hello, (WORLD)!";
+/// let spans = hash_token_spans(text);
+/// assert_eq!(&text[spans[1].0.clone()], "WORLD");
+/// assert_eq!(spans[1].1, hash_tokens("world")[0]);
+/// ```
+pub fn hash_token_spans(text: &str) -> Vec<(Range<usize>, Value<WordHash>)> {
+    text.split_ascii_whitespace()
+        .filter_map(|raw| {
+            let (span, word) = normalize_word(text, raw)?;
+            Some((span, Value::<WordHash>::new(*blake3::hash(word.as_bytes()).as_bytes())))
+        })
+        .collect()
+}
+
 /// Shared word-normalization pipeline used by [`hash_tokens`]
 /// and [`bigram_tokens`]: split on ASCII whitespace, trim
 /// leading/trailing ASCII punctuation, drop tokens with no
@@ -120,21 +151,28 @@ pub fn hash_tokens(text: &str) -> Vec<Value<WordHash>> {
 /// source text, which we can't express across the
 /// per-character lowercase step without allocating anyway.
 fn normalize_words(text: &str) -> impl Iterator<Item = String> + '_ {
-    text.split_ascii_whitespace().filter_map(|raw| {
-        let trimmed = raw.trim_matches(|c: char| c.is_ascii_punctuation());
-        // Drop tokens with no alphanumeric content at all
-        // (em-dashes, pure-symbol clusters). Pure-punctuation
-        // tokens would otherwise all hash to the same value
-        // and poison the term list.
-        if !trimmed.chars().any(|c| c.is_alphanumeric()) {
-            return None;
-        }
-        let mut lower = String::with_capacity(trimmed.len());
-        for c in trimmed.chars() {
-            lower.push(c.to_ascii_lowercase());
-        }
-        Some(lower)
-    })
+    text.split_ascii_whitespace()
+        .filter_map(|raw| normalize_word(text, raw).map(|(_, word)| word))
+}
+
+/// One step of [`normalize_words`]: `raw` is a whitespace-split
+/// piece borrowed from `text`; returns its trimmed byte range in
+/// `text` and the lowercased word.
+fn normalize_word(text: &str, raw: &str) -> Option<(Range<usize>, String)> {
+    let trimmed = raw.trim_matches(|c: char| c.is_ascii_punctuation());
+    // Drop tokens with no alphanumeric content at all
+    // (em-dashes, pure-symbol clusters). Pure-punctuation
+    // tokens would otherwise all hash to the same value
+    // and poison the term list.
+    if !trimmed.chars().any(|c| c.is_alphanumeric()) {
+        return None;
+    }
+    let mut lower = String::with_capacity(trimmed.len());
+    for c in trimmed.chars() {
+        lower.push(c.to_ascii_lowercase());
+    }
+    let start = trimmed.as_ptr() as usize - text.as_ptr() as usize;
+    Some((start..start + trimmed.len(), lower))
 }
 
 /// Word-level bigram tokenizer for phrase-aware retrieval.
@@ -392,10 +430,529 @@ pub fn ngram_tokens(text: &str, n: usize) -> Vec<Value<NgramHash>> {
     out
 }
 
+// ── Analyzer pipeline ───────────────────────────────────────────────
+
+/// Term schema for [`english_tokens`] / [`Analyzer::english`] —
+/// Blake3 hash of an NFKC-normalized, lowercased, stopword-filtered,
+/// Snowball-English-stemmed word.
+///
+/// Schema id minted via `trible genid`:
+/// `D4FB2D8886474AD7B69939C85571EEE4`.
+pub enum EnglishStemHash {}
+
+impl ConstId for EnglishStemHash {
+    const ID: Id = id_hex!("D4FB2D8886474AD7B69939C85571EEE4");
+}
+
+impl ValueSchema for EnglishStemHash {
+    type ValidationError = Infallible;
+}
+
+/// Term schema for [`german_tokens`] / [`Analyzer::german`] —
+/// Blake3 hash of an NFKC-normalized, lowercased, stopword-filtered,
+/// Snowball-German-stemmed word.
+///
+/// Schema id minted via `trible genid`:
+/// `2F8628169CE04C80BA727E35AC987A8C`.
+pub enum GermanStemHash {}
+
+impl ConstId for GermanStemHash {
+    const ID: Id = id_hex!("2F8628169CE04C80BA727E35AC987A8C");
+}
+
+impl ValueSchema for GermanStemHash {
+    type ValidationError = Infallible;
+}
+
+/// First stage of an [`Analyzer`]: rewrite the whole input before
+/// it is split. Implemented for any `Fn(&str) -> String`.
+pub trait Normalizer {
+    /// Return the normalized text.
+    fn normalize(&self, text: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> Normalizer for F {
+    fn normalize(&self, text: &str) -> String {
+        self(text)
+    }
+}
+
+/// Second stage of an [`Analyzer`]: split normalized text into
+/// tokens. Implemented for any `Fn(&str) -> Vec<String>`.
+pub trait Tokenizer {
+    /// Split `text` into tokens, in order, duplicates kept.
+    fn tokenize(&self, text: &str) -> Vec<String>;
+
+    /// Same tokens as [`tokenize`][Self::tokenize], each with the
+    /// byte range of `text` it came from. The default locates
+    /// each token by searching forward from the previous one
+    /// (empty range if the token isn't a substring);
+    /// tokenizers that know their offsets should override it.
+    fn tokenize_spans(&self, text: &str) -> Vec<(Range<usize>, String)> {
+        let mut cursor = 0;
+        self.tokenize(text)
+            .into_iter()
+            .map(|token| {
+                let span = match text[cursor..].find(token.as_str()) {
+                    Some(i) => cursor + i..cursor + i + token.len(),
+                    None => cursor..cursor,
+                };
+                cursor = span.end;
+                (span, token)
+            })
+            .collect()
+    }
+}
+
+impl<F: Fn(&str) -> Vec<String>> Tokenizer for F {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self(text)
+    }
+}
+
+/// Third stage of an [`Analyzer`]: rewrite or drop one token at a
+/// time. Implemented for any `Fn(String) -> Option<String>`.
+pub trait TokenFilter {
+    /// The rewritten token, or `None` to drop it.
+    fn filter(&self, token: String) -> Option<String>;
+}
+
+impl<F: Fn(String) -> Option<String>> TokenFilter for F {
+    fn filter(&self, token: String) -> Option<String> {
+        self(token)
+    }
+}
+
+/// Unicode [`Normalizer`]: NFKC composition (so full-width forms,
+/// ligatures and decomposed accents compare equal), full Unicode
+/// lowercasing, and optionally diacritic stripping (`"café"` →
+/// `"cafe"`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnicodeNormalizer {
+    strip_diacritics: bool,
+}
+
+impl UnicodeNormalizer {
+    /// NFKC + lowercase.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also remove combining marks after canonical decomposition.
+    pub fn strip_diacritics(mut self) -> Self {
+        self.strip_diacritics = true;
+        self
+    }
+}
+
+impl Normalizer for UnicodeNormalizer {
+    fn normalize(&self, text: &str) -> String {
+        use unicode_normalization::char::is_combining_mark;
+        use unicode_normalization::UnicodeNormalization;
+
+        let lower: String = text.nfkc().flat_map(char::to_lowercase).collect();
+        if self.strip_diacritics {
+            lower.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+        } else {
+            lower
+        }
+    }
+}
+
+/// [`Tokenizer`] that splits on every character that is not
+/// alphanumeric (in the Unicode sense), so punctuation, dashes and
+/// whitespace all separate words. Apostrophes inside a word are
+/// kept (`"don't"`) because English stemmers and stopword lists
+/// expect them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordTokenizer;
+
+impl Tokenizer for WordTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokenize_spans(text).into_iter().map(|(_, w)| w).collect()
+    }
+
+    fn tokenize_spans(&self, text: &str) -> Vec<(Range<usize>, String)> {
+        let mut out = Vec::new();
+        let mut word = String::new();
+        let mut start = 0;
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let inner_apostrophe = matches!(c, '\'' | '\u{2019}')
+                && !word.is_empty()
+                && chars.peek().is_some_and(|(_, n)| n.is_alphanumeric());
+            if c.is_alphanumeric() || inner_apostrophe {
+                if word.is_empty() {
+                    start = i;
+                }
+                word.push(if inner_apostrophe { '\'' } else { c });
+            } else if !word.is_empty() {
+                out.push((start..i, std::mem::take(&mut word)));
+            }
+        }
+        if !word.is_empty() {
+            out.push((start..text.len(), word));
+        }
+        out
+    }
+}
+
+/// [`TokenFilter`] that drops tokens found in a stopword list.
+/// Compare after normalization: the built-in lists are lowercase.
+#[derive(Debug, Clone, Default)]
+pub struct StopWords {
+    words: std::collections::HashSet<String>,
+}
+
+impl StopWords {
+    /// Custom stopword list.
+    pub fn new<I, W>(words: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: Into<String>,
+    {
+        Self {
+            words: words.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Common English function words.
+    pub fn english() -> Self {
+        Self::new(ENGLISH_STOPWORDS.iter().copied())
+    }
+
+    /// Common German function words.
+    pub fn german() -> Self {
+        Self::new(GERMAN_STOPWORDS.iter().copied())
+    }
+
+    /// `true` if `word` is on the list.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+}
+
+impl TokenFilter for StopWords {
+    fn filter(&self, token: String) -> Option<String> {
+        (!self.words.contains(&token)).then_some(token)
+    }
+}
+
+/// Snowball stemming [`TokenFilter`] (via `rust-stemmers`), so
+/// `"running"`, `"runs"` and `"run"` share a term.
+pub struct Stemmer {
+    inner: rust_stemmers::Stemmer,
+}
+
+impl Stemmer {
+    /// Snowball English (Porter2).
+    pub fn english() -> Self {
+        Self {
+            inner: rust_stemmers::Stemmer::create(rust_stemmers::Algorithm::English),
+        }
+    }
+
+    /// Snowball German.
+    pub fn german() -> Self {
+        Self {
+            inner: rust_stemmers::Stemmer::create(rust_stemmers::Algorithm::German),
+        }
+    }
+
+    /// Stem a single word.
+    pub fn stem<'a>(&self, word: &'a str) -> std::borrow::Cow<'a, str> {
+        self.inner.stem(word)
+    }
+}
+
+impl TokenFilter for Stemmer {
+    fn filter(&self, token: String) -> Option<String> {
+        Some(self.inner.stem(&token).into_owned())
+    }
+}
+
+/// A composable text-analysis pipeline: [`Normalizer`] →
+/// [`Tokenizer`] → [`TokenFilter`]s → Blake3 hash into
+/// [`Value<S>`].
+///
+/// The term schema `S` names the pipeline. Two analyzers that
+/// could disagree on a word's term (different stemmer, different
+/// stopwords) should use different schemas, so an index built
+/// with one can't be queried with the other by accident — the
+/// same guarantee [`WordHash`] / [`BigramHash`] / [`NgramHash`]
+/// give the fixed tokenizers. Declare a custom schema the same
+/// way those are declared (`ConstId` + `ValueSchema`).
+///
+/// ```
+/// use triblespace_search::tokens::{Analyzer, EnglishStemHash, StopWords, Stemmer};
+///
+/// let en = Analyzer::<EnglishStemHash>::english();
+/// assert_eq!(en.terms("The runners were running!"), vec!["runner", "run"]);
+/// assert_eq!(en.analyze("running"), en.analyze("runs"));
+///
+/// // Custom pipelines compose from parts (closures work too).
+/// let custom = Analyzer::<EnglishStemHash>::new()
+///     .with_filter(StopWords::english())
+///     .with_filter(|t: String| (t.chars().count() > 2).then_some(t))
+///     .with_filter(Stemmer::english());
+/// assert_eq!(custom.terms("an ox is running"), vec!["run"]);
+/// ```
+pub struct Analyzer<S: ValueSchema> {
+    normalizer: Box<dyn Normalizer + Send + Sync>,
+    tokenizer: Box<dyn Tokenizer + Send + Sync>,
+    filters: Vec<Box<dyn TokenFilter + Send + Sync>>,
+    _schema: std::marker::PhantomData<S>,
+}
+
+impl<S: ValueSchema> Default for Analyzer<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: ValueSchema> Analyzer<S> {
+    /// [`UnicodeNormalizer`] + [`WordTokenizer`], no filters.
+    pub fn new() -> Self {
+        Self {
+            normalizer: Box::new(UnicodeNormalizer::new()),
+            tokenizer: Box::new(WordTokenizer),
+            filters: Vec::new(),
+            _schema: std::marker::PhantomData,
+        }
+    }
+
+    /// Replace the normalizer.
+    pub fn with_normalizer(mut self, normalizer: impl Normalizer + Send + Sync + 'static) -> Self {
+        self.normalizer = Box::new(normalizer);
+        self
+    }
+
+    /// Replace the tokenizer.
+    pub fn with_tokenizer(mut self, tokenizer: impl Tokenizer + Send + Sync + 'static) -> Self {
+        self.tokenizer = Box::new(tokenizer);
+        self
+    }
+
+    /// Append a filter; filters run in insertion order.
+    pub fn with_filter(mut self, filter: impl TokenFilter + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Run the pipeline up to (not including) hashing. Useful
+    /// for debugging and for keeping the surface forms of terms.
+    pub fn terms(&self, text: &str) -> Vec<String> {
+        let normalized = self.normalizer.normalize(text);
+        self.tokenizer
+            .tokenize(&normalized)
+            .into_iter()
+            .filter_map(|token| {
+                self.filters
+                    .iter()
+                    .try_fold(token, |token, f| f.filter(token))
+            })
+            .collect()
+    }
+
+    /// Analyze `text` into hashed terms, duplicates preserved
+    /// (BM25 uses term frequency).
+    pub fn analyze(&self, text: &str) -> Vec<Value<S>> {
+        self.terms(text).iter().map(|t| hash_term(t)).collect()
+    }
+
+    /// [`terms`][Self::terms] with the byte range of the original
+    /// `text` each term came from — what highlighting needs.
+    ///
+    /// Offsets survive normalization because the normalizer runs
+    /// on one character (plus its combining marks) at a time
+    /// here, with the output mapped back to that character's
+    /// range. For [`UnicodeNormalizer`] that yields the same
+    /// terms as [`terms`][Self::terms]; a custom normalizer that
+    /// looks across characters may not.
+    pub fn term_spans(&self, text: &str) -> Vec<(Range<usize>, String)> {
+        use unicode_normalization::char::is_combining_mark;
+
+        // normalized byte → original range of its source cluster.
+        let mut normalized = String::with_capacity(text.len());
+        let mut origin: Vec<Range<usize>> = Vec::with_capacity(text.len());
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, m)) = chars.peek() {
+                if !is_combining_mark(m) {
+                    break;
+                }
+                end = i + m.len_utf8();
+                chars.next();
+            }
+            let piece = self.normalizer.normalize(&text[start..end]);
+            origin.extend(std::iter::repeat(start..end).take(piece.len()));
+            normalized.push_str(&piece);
+        }
+
+        self.tokenizer
+            .tokenize_spans(&normalized)
+            .into_iter()
+            .filter_map(|(span, token)| {
+                let token = self
+                    .filters
+                    .iter()
+                    .try_fold(token, |token, f| f.filter(token))?;
+                let span = if span.is_empty() {
+                    let at = origin.get(span.start).map_or(text.len(), |r| r.start);
+                    at..at
+                } else {
+                    origin[span.start].start..origin[span.end - 1].end
+                };
+                Some((span, token))
+            })
+            .collect()
+    }
+
+    /// [`analyze`][Self::analyze] with the byte range of the
+    /// original `text` behind each term. See
+    /// [`term_spans`][Self::term_spans].
+    pub fn analyze_spans(&self, text: &str) -> Vec<(Range<usize>, Value<S>)> {
+        self.term_spans(text)
+            .into_iter()
+            .map(|(span, t)| (span, hash_term(&t)))
+            .collect()
+    }
+}
+
+impl Analyzer<EnglishStemHash> {
+    /// English pipeline: NFKC + lowercase, word split, English
+    /// stopwords, Snowball English stemming.
+    pub fn english() -> Self {
+        Self::new()
+            .with_filter(StopWords::english())
+            .with_filter(Stemmer::english())
+    }
+}
+
+impl Analyzer<GermanStemHash> {
+    /// German pipeline: NFKC + lowercase, word split, German
+    /// stopwords, Snowball German stemming.
+    pub fn german() -> Self {
+        Self::new()
+            .with_filter(StopWords::german())
+            .with_filter(Stemmer::german())
+    }
+}
+
+/// Hash one already-analyzed term into schema `S` — the final
+/// stage of every [`Analyzer`], exposed for callers that keep the
+/// term strings around.
+pub fn hash_term<S: ValueSchema>(term: &str) -> Value<S> {
+    Value::new(*blake3::hash(term.as_bytes()).as_bytes())
+}
+
+/// Shorthand for `Analyzer::english().analyze(text)`. Build the
+/// [`Analyzer`] once and reuse it when analyzing many documents.
+pub fn english_tokens(text: &str) -> Vec<Value<EnglishStemHash>> {
+    Analyzer::english().analyze(text)
+}
+
+/// Shorthand for `Analyzer::german().analyze(text)`. Build the
+/// [`Analyzer`] once and reuse it when analyzing many documents.
+pub fn german_tokens(text: &str) -> Vec<Value<GermanStemHash>> {
+    Analyzer::german().analyze(text)
+}
+
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "am", "an", "and", "any", "are",
+    "as", "at", "be", "because", "been", "before", "being", "below", "between", "both", "but",
+    "by", "can", "could", "did", "do", "does", "doing", "don't", "down", "during", "each", "few",
+    "for", "from", "further", "had", "has", "have", "having", "he", "her", "here", "hers",
+    "herself", "him", "himself", "his", "how", "i", "if", "in", "into", "is", "it", "it's", "its",
+    "itself", "just", "me", "more", "most", "my", "myself", "no", "nor", "not", "now", "of",
+    "off", "on", "once", "only", "or", "other", "our", "ours", "ourselves", "out", "over", "own",
+    "same", "she", "should", "so", "some", "such", "than", "that", "the", "their", "theirs",
+    "them", "themselves", "then", "there", "these", "they", "this", "those", "through", "to",
+    "too", "under", "until", "up", "very", "was", "we", "were", "what", "when", "where", "which",
+    "while", "who", "whom", "why", "will", "with", "would", "you", "your", "yours", "yourself",
+    "yourselves",
+];
+
+const GERMAN_STOPWORDS: &[&str] = &[
+    "aber", "alle", "allem", "allen", "aller", "alles", "als", "also", "am", "an", "ander",
+    "andere", "anderem", "anderen", "anderer", "anderes", "auch", "auf", "aus", "bei", "bin",
+    "bis", "bist", "da", "damit", "dann", "das", "dass", "daß", "dem", "den", "denn", "der",
+    "des", "dich", "die", "dies", "diese", "diesem", "diesen", "dieser", "dieses", "dir", "doch",
+    "dort", "du", "durch", "ein", "eine", "einem", "einen", "einer", "eines", "er", "es", "euch",
+    "euer", "für", "hab", "habe", "haben", "hat", "hatte", "hier", "hin", "ich", "ihm", "ihn",
+    "ihnen", "ihr", "ihre", "im", "in", "indem", "ins", "ist", "jede", "jedem", "jeden", "jeder",
+    "jedes", "kein", "keine", "man", "mein", "meine", "mich", "mir", "mit", "nach", "nicht",
+    "noch", "nun", "nur", "ob", "oder", "ohne", "sehr", "sein", "seine", "sich", "sie", "sind",
+    "so", "solche", "um", "und", "uns", "unser", "unter", "viel", "vom", "von", "vor", "war",
+    "waren", "was", "weil", "wenn", "wer", "wie", "wieder", "will", "wir", "wird", "wo", "zu",
+    "zum", "zur", "über",
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn english_analyzer_stems_and_drops_stopwords() {
+        let en = Analyzer::<EnglishStemHash>::english();
+        assert_eq!(en.terms("Running, runs & RUN."), vec!["run", "run", "run"]);
+        assert_eq!(en.terms("the fox and the hound"), vec!["fox", "hound"]);
+        assert_eq!(en.terms("don't stop"), vec!["stop"]);
+        assert_eq!(english_tokens("connections"), english_tokens("connected"));
+    }
+
+    #[test]
+    fn german_analyzer_stems_and_drops_stopwords() {
+        let de = Analyzer::<GermanStemHash>::german();
+        assert_eq!(de.terms("Die Häuser und das Haus"), vec!["haus", "haus"]);
+        assert_eq!(german_tokens("Häusern"), german_tokens("Haus"));
+    }
+
+    #[test]
+    fn unicode_normalizer_folds_forms() {
+        let norm = UnicodeNormalizer::new();
+        // Decomposed "é" (e + U+0301) composes to the same text as "é".
+        assert_eq!(norm.normalize("Cafe\u{301}"), norm.normalize("CAFÉ"));
+        // Full-width letters fold under NFKC.
+        assert_eq!(norm.normalize("ＡＢＣ"), "abc");
+        assert_eq!(UnicodeNormalizer::new().strip_diacritics().normalize("Crème Brûlée"), "creme brulee");
+    }
+
+    #[test]
+    fn word_tokenizer_splits_on_punctuation() {
+        assert_eq!(
+            WordTokenizer.tokenize("state-of-the-art, naïve 'quote' it's"),
+            vec!["state", "of", "the", "art", "naïve", "quote", "it's"]
+        );
+    }
+
+    #[test]
+    fn analyzer_schemas_stay_separate() {
+        // Same surface word, same hash bytes — but different types,
+        // so an English-stem index can't be probed with WordHash
+        // terms. The raw bytes agree only where the pipelines do.
+        let word = hash_tokens("fox");
+        let stem = english_tokens("fox");
+        assert_eq!(word[0].raw, stem[0].raw);
+        assert_ne!(hash_tokens("running")[0].raw, english_tokens("running")[0].raw);
+    }
+
+    #[test]
+    fn stemmed_bm25_matches_inflections() {
+        use crate::bm25::BM25Builder;
+        use triblespace_core::id::Id;
+        use triblespace_core::value::schemas::genid::GenId;
+
+        let en = Analyzer::<EnglishStemHash>::english();
+        let mut b: BM25Builder<GenId, EnglishStemHash> = BM25Builder::new();
+        b.insert(Id::new([1; 16]).unwrap(), en.analyze("She was running late"));
+        b.insert(Id::new([2; 16]).unwrap(), en.analyze("A quiet evening"));
+        let idx = b.build();
+        let hits = idx.query_multi(&en.analyze("run"));
+        assert_eq!(hits.len(), 1);
+    }
+
     #[test]
     fn splits_on_whitespace() {
         let tokens = hash_tokens("one two three");
@@ -227,6 +227,58 @@ fn find_hnsw_similar_on_succinct() {
     assert_eq!(got, expected);
 }
 
+/// `similar_where` restricts the enumerated side to an
+/// allow-set inside the same `find!` — the filter rides along
+/// with the walk instead of being joined in afterwards.
+#[test]
+fn find_hnsw_similar_where_on_succinct() {
+    use triblespace_core::blob::MemoryBlobStore;
+    use triblespace_core::and;
+    use triblespace_core::query::temp;
+    use triblespace_core::repo::BlobStore;
+    use triblespace_core::value::schemas::hash::{Blake3, Handle};
+    use triblespace_core::value::Value;
+    use triblespace_search::hnsw::HNSWBuilder;
+    use triblespace_search::schemas::{put_embedding, Embedding};
+
+    let mut store = MemoryBlobStore::<Blake3>::new();
+    let mut b = HNSWBuilder::new(4).with_seed(23);
+    let mut handles = Vec::new();
+    for i in 1..=16u8 {
+        let f = i as f32;
+        let v = vec![f.sin(), f.cos(), (f * 0.5).sin(), (f * 0.3).cos()];
+        let h = put_embedding::<_, Blake3>(&mut store, v.clone()).unwrap();
+        b.insert(h, v).unwrap();
+        handles.push(h);
+    }
+    let succinct = b.build();
+    let reader = store.reader().unwrap();
+    let view = succinct.attach(&reader);
+    let probe = handles[0];
+    let floor = 0.0f32;
+    let allow: HashSet<Value<Handle<Blake3, Embedding>>> =
+        handles.iter().skip(1).step_by(3).copied().collect();
+
+    let rows: Vec<(Value<Handle<Blake3, Embedding>>,)> = find!(
+        (neighbour: Value<Handle<Blake3, Embedding>>),
+        temp!(
+            (anchor),
+            and!(anchor.is(probe), view.similar_where(anchor, neighbour, floor, &allow))
+        )
+    )
+    .collect();
+    let got: HashSet<_> = rows.into_iter().map(|(h,)| h).collect();
+
+    let expected: HashSet<_> = view
+        .candidates_above(probe, floor)
+        .unwrap()
+        .into_iter()
+        .filter(|h| allow.contains(h))
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(got, expected);
+}
+
 /// `idx.score` (rescore helper) and `idx.matches` (engine
 /// constraint) operate on the same posting lists, so for any
 /// query the docs the constraint binds must be exactly the docs